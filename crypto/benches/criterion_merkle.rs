@@ -1,7 +1,10 @@
 use core::time::Duration;
 use criterion::{criterion_group, criterion_main, Criterion};
 use lambdaworks_crypto::merkle_tree::{
-    backends::field_element::FieldElementBackend, merkle::MerkleTree,
+    backends::{
+        field_element::FieldElementBackend, field_element_vector::FieldElementVectorBackend,
+    },
+    merkle::MerkleTree,
 };
 use lambdaworks_math::{
     field::element::FieldElement,
@@ -12,6 +15,7 @@ type F = Stark252PrimeField;
 type FE = FieldElement<F>;
 
 type TreeBackend = FieldElementBackend<F, Keccak256, 32>;
+type BatchedTreeBackend = FieldElementVectorBackend<F, Keccak256, 32>;
 
 fn merkle_tree_benchmarks(c: &mut Criterion) {
     let mut group = c.benchmark_group("Merkle Tree");
@@ -31,6 +35,19 @@ fn merkle_tree_benchmarks(c: &mut Criterion) {
             bench.iter_with_large_drop(|| MerkleTree::<TreeBackend>::build(unhashed_leaves));
         },
     );
+
+    // The rows of a 50 column trace, committed in a single tree as the STARK prover does.
+    let rows: Vec<Vec<FE>> = (0..1u64 << 16)
+        .map(|row| {
+            (0..50u64)
+                .map(|column| FE::from(row * 50 + column))
+                .collect()
+        })
+        .collect();
+
+    group.bench_with_input("build 50 columns", rows.as_slice(), |bench, rows| {
+        bench.iter_with_large_drop(|| MerkleTree::<BatchedTreeBackend>::build(rows));
+    });
 }
 
 criterion_group!(merkle_tree, merkle_tree_benchmarks);
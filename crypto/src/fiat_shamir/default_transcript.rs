@@ -1,65 +1,170 @@
 use super::is_transcript::IsTranscript;
-use crate::alloc::borrow::ToOwned;
+use crate::hash::poseidon::{starknet::PoseidonCairoStark252, Poseidon};
+use alloc::vec::Vec;
 use core::marker::PhantomData;
 use lambdaworks_math::{
-    field::{element::FieldElement, traits::IsField},
-    traits::ByteConversion,
+    field::{
+        element::FieldElement, fields::fft_friendly::stark_252_prime_field::Stark252PrimeField,
+        traits::IsField,
+    },
+    traits::{ByteConversion, ByteOrder},
 };
-use sha3::{Digest, Keccak256};
+use sha2::Sha256;
+use sha3::{Digest, Keccak256, Sha3_256};
 
-pub struct DefaultTranscript<F: IsField> {
-    hasher: Keccak256,
+/// A hash function with a 32 byte output that a `DefaultTranscript` can be built on.
+/// The prover and the verifier must use the same one to derive the same challenges.
+pub trait TranscriptHasher: Clone + Default {
+    /// Absorbs `data` into the state of the hasher.
+    fn update(&mut self, data: &[u8]);
+
+    /// Returns the digest of the data absorbed so far and resets the hasher.
+    fn finalize_reset(&mut self) -> [u8; 32];
+}
+
+macro_rules! impl_transcript_hasher_for_digest {
+    ($($hasher:ty),*) => {
+        $(
+            impl TranscriptHasher for $hasher {
+                fn update(&mut self, data: &[u8]) {
+                    Digest::update(self, data);
+                }
+
+                fn finalize_reset(&mut self) -> [u8; 32] {
+                    Digest::finalize_reset(self).into()
+                }
+            }
+        )*
+    };
+}
+
+impl_transcript_hasher_for_digest!(Keccak256, Sha3_256, Sha256);
+
+/// A `TranscriptHasher` built on the Poseidon hash over the Stark252 field used by StarkNet.
+/// The absorbed bytes are split in chunks of 31 bytes, each read as a big-endian field
+/// element, followed by the number of bytes so that zero padding is not ambiguous. The digest
+/// is the big-endian representation of `hash_many` of these elements.
+#[derive(Clone, Default)]
+pub struct PoseidonStark252Hasher {
+    data: Vec<u8>,
+}
+
+impl TranscriptHasher for PoseidonStark252Hasher {
+    fn update(&mut self, data: &[u8]) {
+        self.data.extend_from_slice(data);
+    }
+
+    fn finalize_reset(&mut self) -> [u8; 32] {
+        let mut elements: Vec<FieldElement<Stark252PrimeField>> = self
+            .data
+            .chunks(31)
+            .map(|chunk| {
+                let mut bytes = [0u8; 32];
+                bytes[32 - chunk.len()..].copy_from_slice(chunk);
+                FieldElement::from_bytes_be(&bytes).unwrap()
+            })
+            .collect();
+        elements.push(FieldElement::from(self.data.len() as u64));
+        self.data.clear();
+
+        PoseidonCairoStark252::hash_many(&elements)
+            .to_bytes_be()
+            .try_into()
+            .unwrap()
+    }
+}
+
+/// A Fiat-Shamir transcript hashing the data it receives with `H`, which is Keccak256
+/// by default. Field elements are appended in big-endian order unless another one is set
+/// with [`DefaultTranscript::with_byte_order`].
+pub struct DefaultTranscript<F: IsField, H: TranscriptHasher = Keccak256> {
+    hasher: H,
+    byte_order: ByteOrder,
     phantom: PhantomData<F>,
 }
 
-impl<F> DefaultTranscript<F>
+impl<F, H> DefaultTranscript<F, H>
 where
     F: IsField,
     FieldElement<F>: ByteConversion,
+    H: TranscriptHasher,
 {
     pub fn new(data: &[u8]) -> Self {
         let mut res = Self {
-            hasher: Keccak256::new(),
+            hasher: H::default(),
+            byte_order: ByteOrder::default(),
             phantom: PhantomData,
         };
         res.append_bytes(data);
         res
     }
 
+    /// Sets the order of the bytes of the field elements appended to the transcript.
+    pub fn with_byte_order(mut self, byte_order: ByteOrder) -> Self {
+        self.byte_order = byte_order;
+        self
+    }
+
+    /// Returns an independent copy of the transcript. Both copies produce the same
+    /// challenges as long as they receive the same data.
+    pub fn fork(&self) -> Self {
+        Self {
+            hasher: self.hasher.clone(),
+            byte_order: self.byte_order,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Appends the state of `other` to the transcript, so that the challenges sampled
+    /// from now on depend on all the data `other` received. This allows chaining the
+    /// transcript of a sub-proof into the transcript of a parent proof.
+    pub fn absorb_transcript_state(&mut self, other: &Self) {
+        self.append_bytes(&other.state());
+    }
+
     pub fn sample(&mut self) -> [u8; 32] {
-        let mut result_hash = [0_u8; 32];
-        result_hash.copy_from_slice(&self.hasher.finalize_reset());
+        let mut result_hash = self.hasher.finalize_reset();
         result_hash.reverse();
-        self.hasher.update(result_hash);
+        self.hasher.update(&result_hash);
         result_hash
     }
 }
 
-impl<F> Default for DefaultTranscript<F>
+impl<F, H> Default for DefaultTranscript<F, H>
 where
     F: IsField,
     FieldElement<F>: ByteConversion,
+    H: TranscriptHasher,
 {
     fn default() -> Self {
         Self::new(&[])
     }
 }
 
-impl<F> IsTranscript<F> for DefaultTranscript<F>
+impl<F, H> IsTranscript<F> for DefaultTranscript<F, H>
 where
     F: IsField,
     FieldElement<F>: ByteConversion,
+    H: TranscriptHasher,
 {
     fn append_bytes(&mut self, new_bytes: &[u8]) {
-        self.hasher.update(&mut new_bytes.to_owned());
+        self.hasher.update(new_bytes);
     }
 
     fn append_field_element(&mut self, element: &FieldElement<F>) {
-        self.append_bytes(&element.to_bytes_be());
+        self.append_bytes(&self.byte_order.to_bytes(element));
+    }
+
+    fn byte_order(&self) -> ByteOrder {
+        self.byte_order
     }
 
     fn state(&self) -> [u8; 32] {
-        self.hasher.clone().finalize().into()
+        self.hasher.clone().finalize_reset()
+    }
+
+    fn try_fork(&self) -> Option<Self> {
+        Some(self.fork())
     }
 
     fn sample_field_element(&mut self) -> FieldElement<F> {
@@ -79,6 +184,22 @@ mod tests {
     use alloc::vec::Vec;
     use lambdaworks_math::elliptic_curve::short_weierstrass::curves::bls12_381::default_types::FrField;
 
+    #[test]
+    fn append_many_prefixes_each_item_with_its_length() {
+        let mut transcript_a = DefaultTranscript::<FrField>::default();
+        transcript_a.append_many(&[&[0xFF, 0xAB], &[0xDD]]);
+
+        let mut transcript_b = DefaultTranscript::<FrField>::default();
+        transcript_b.append_bytes(&[
+            0, 0, 0, 0, 0, 0, 0, 2, 0xFF, 0xAB, 0, 0, 0, 0, 0, 0, 0, 1, 0xDD,
+        ]);
+        assert_eq!(transcript_a.state(), transcript_b.state());
+
+        let mut transcript_c = DefaultTranscript::<FrField>::default();
+        transcript_c.append_many(&[&[0xFF], &[0xAB, 0xDD]]);
+        assert_ne!(transcript_a.state(), transcript_c.state());
+    }
+
     #[test]
     fn basic_challenge() {
         let mut transcript = DefaultTranscript::<FrField>::default();
@@ -116,4 +237,149 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn forked_transcripts_produce_the_same_challenges() {
+        let mut transcript = DefaultTranscript::<FrField>::new(&[0x01, 0x02]);
+        transcript.append_bytes(&[0xFF, 0xAB]);
+        let _ = transcript.sample_field_element();
+
+        let mut fork = transcript.fork();
+        transcript.append_bytes(&[0xDD, 0x8C, 0x9D]);
+        fork.append_bytes(&[0xDD, 0x8C, 0x9D]);
+
+        assert_eq!(
+            transcript.sample_field_element(),
+            fork.sample_field_element()
+        );
+        assert_eq!(transcript.sample_u64(1 << 20), fork.sample_u64(1 << 20));
+    }
+
+    #[test]
+    fn forked_transcripts_diverge_on_different_data() {
+        let mut transcript = DefaultTranscript::<FrField>::new(&[0x01, 0x02]);
+        let mut fork = transcript.fork();
+        transcript.append_bytes(&[0x00]);
+        fork.append_bytes(&[0x01]);
+
+        assert_ne!(
+            transcript.sample_field_element(),
+            fork.sample_field_element()
+        );
+    }
+
+    #[test]
+    fn absorbing_a_transcript_state_binds_its_data() {
+        let mut child_a = DefaultTranscript::<FrField>::new(&[0xAA]);
+        let child_b = DefaultTranscript::<FrField>::new(&[0xBB]);
+        let _ = child_a.sample_field_element();
+
+        let mut parent_a = DefaultTranscript::<FrField>::new(&[0x01]);
+        let mut parent_a_again = DefaultTranscript::<FrField>::new(&[0x01]);
+        let mut parent_b = DefaultTranscript::<FrField>::new(&[0x01]);
+        parent_a.absorb_transcript_state(&child_a);
+        parent_a_again.absorb_transcript_state(&child_a);
+        parent_b.absorb_transcript_state(&child_b);
+
+        let challenge_a = parent_a.sample_field_element();
+        assert_eq!(challenge_a, parent_a_again.sample_field_element());
+        assert_ne!(challenge_a, parent_b.sample_field_element());
+    }
+
+    #[test]
+    fn appending_a_field_element_appends_its_canonical_bytes() {
+        let element = FieldElement::<FrField>::from(0x0102);
+
+        let mut transcript = DefaultTranscript::<FrField>::new(&[0x01]);
+        let mut manual_transcript = DefaultTranscript::<FrField>::new(&[0x01]);
+        transcript.append_field_element(&element);
+        manual_transcript.append_bytes(&element.to_bytes_be());
+        assert_eq!(transcript.state(), manual_transcript.state());
+
+        let mut transcript =
+            DefaultTranscript::<FrField>::new(&[0x01]).with_byte_order(ByteOrder::LittleEndian);
+        let mut manual_transcript = DefaultTranscript::<FrField>::new(&[0x01]);
+        transcript.append_field_element(&element);
+        manual_transcript.append_bytes(&element.to_bytes_le());
+        assert_eq!(transcript.state(), manual_transcript.state());
+    }
+
+    #[test]
+    fn byte_order_changes_the_challenges_of_appended_field_elements() {
+        let element = FieldElement::<FrField>::from(0x0102);
+        let mut big_endian_transcript = DefaultTranscript::<FrField>::new(&[0x01]);
+        let mut little_endian_transcript =
+            DefaultTranscript::<FrField>::new(&[0x01]).with_byte_order(ByteOrder::LittleEndian);
+        big_endian_transcript.append_field_element(&element);
+        little_endian_transcript.append_field_element(&element);
+
+        assert_eq!(
+            little_endian_transcript.byte_order(),
+            ByteOrder::LittleEndian
+        );
+        assert_eq!(
+            little_endian_transcript.fork().byte_order(),
+            ByteOrder::LittleEndian
+        );
+        assert_ne!(
+            big_endian_transcript.sample_field_element(),
+            little_endian_transcript.sample_field_element()
+        );
+    }
+
+    #[test]
+    fn poseidon_hasher_does_not_confuse_leading_zeros() {
+        let mut hasher = PoseidonStark252Hasher::default();
+        hasher.update(&[0x01]);
+        let digest = hasher.finalize_reset();
+
+        hasher.update(&[0x00, 0x01]);
+        assert_ne!(digest, hasher.finalize_reset());
+        hasher.update(&[0x01]);
+        assert_eq!(digest, hasher.finalize_reset());
+    }
+
+    #[test]
+    fn poseidon_hasher_digest_is_the_poseidon_hash_of_the_packed_bytes() {
+        let data: Vec<u8> = (0..40).collect();
+        let mut hasher = PoseidonStark252Hasher::default();
+        hasher.update(&data[..10]);
+        hasher.update(&data[10..]);
+
+        let mut first_chunk = [0u8; 32];
+        first_chunk[1..].copy_from_slice(&data[..31]);
+        let mut second_chunk = [0u8; 32];
+        second_chunk[23..].copy_from_slice(&data[31..]);
+        let expected = PoseidonCairoStark252::hash_many(&[
+            FieldElement::from_bytes_be(&first_chunk).unwrap(),
+            FieldElement::from_bytes_be(&second_chunk).unwrap(),
+            FieldElement::from(40),
+        ]);
+
+        assert_eq!(hasher.finalize_reset().to_vec(), expected.to_bytes_be());
+    }
+
+    #[test]
+    fn transcripts_with_different_hashers_produce_different_challenges() {
+        let mut keccak_transcript = DefaultTranscript::<FrField>::new(&[0x01, 0x02]);
+        let mut sha3_transcript = DefaultTranscript::<FrField, Sha3_256>::new(&[0x01, 0x02]);
+        let mut sha2_transcript = DefaultTranscript::<FrField, Sha256>::new(&[0x01, 0x02]);
+        let mut poseidon_transcript =
+            DefaultTranscript::<FrField, PoseidonStark252Hasher>::new(&[0x01, 0x02]);
+        for data in [[0xFF_u8, 0xAB], [0xDD, 0x8C]] {
+            keccak_transcript.append_bytes(&data);
+            sha3_transcript.append_bytes(&data);
+            sha2_transcript.append_bytes(&data);
+            poseidon_transcript.append_bytes(&data);
+        }
+
+        let keccak_challenge = keccak_transcript.sample_field_element();
+        let sha3_challenge = sha3_transcript.sample_field_element();
+        let sha2_challenge = sha2_transcript.sample_field_element();
+        let poseidon_challenge = poseidon_transcript.sample_field_element();
+        assert_ne!(keccak_challenge, sha3_challenge);
+        assert_ne!(keccak_challenge, sha2_challenge);
+        assert_ne!(sha3_challenge, sha2_challenge);
+        assert_ne!(keccak_challenge, poseidon_challenge);
+    }
 }
@@ -1,9 +1,10 @@
+use alloc::vec::Vec;
 use lambdaworks_math::{
     field::{
         element::FieldElement,
         traits::{IsField, IsSubFieldOf},
     },
-    traits::AsBytes,
+    traits::ByteOrder,
 };
 
 /// The functionality of a transcript to be used in the STARK Prove and Verify protocols.
@@ -12,21 +13,53 @@ pub trait IsTranscript<F: IsField> {
     fn append_field_element(&mut self, element: &FieldElement<F>);
     /// Appends a bytes to the transcript.
     fn append_bytes(&mut self, new_bytes: &[u8]);
+    /// Appends several byte strings to the transcript with a single call to `append_bytes`.
+    /// Each of them is prefixed with its length as a big endian `u64`, so that different
+    /// lists never give the same bytes. The transcript state is therefore not the same as
+    /// appending each of them with `append_bytes`: a prover and a verifier must agree on
+    /// which of the two methods they use.
+    fn append_many(&mut self, items: &[&[u8]]) {
+        let total_length = items.iter().map(|item| 8 + item.len()).sum();
+        let mut bytes = Vec::with_capacity(total_length);
+        for item in items {
+            bytes.extend_from_slice(&(item.len() as u64).to_be_bytes());
+            bytes.extend_from_slice(item);
+        }
+        self.append_bytes(&bytes);
+    }
+    /// Returns the order of the bytes of the field elements appended to the transcript. The
+    /// prover and the verifier must use the same one.
+    fn byte_order(&self) -> ByteOrder {
+        ByteOrder::BigEndian
+    }
     /// Returns the inner state of the transcript that fully determines its outputs.
     fn state(&self) -> [u8; 32];
+    /// Returns an independent copy of the transcript in its current state, or `None` if the
+    /// transcript can not be copied. Both copies produce the same challenges as long as they
+    /// receive the same data.
+    fn try_fork(&self) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        None
+    }
     /// Returns a random field element.
     fn sample_field_element(&mut self) -> FieldElement<F>;
     /// Returns a random index between 0 and `upper_bound`.
     fn sample_u64(&mut self, upper_bound: u64) -> u64;
     /// Returns a field element not contained in `lde_roots_of_unity_coset` or `trace_roots_of_unity`.
+    ///
+    /// Field elements are sampled from the transcript until one lies outside both domains,
+    /// so that the out of domain point never makes the denominators of the DEEP composition
+    /// polynomial vanish. Each draw is rejected with probability `(|lde| + |trace|) / |F|`,
+    /// which is negligible for the fields used in practice, so a single draw is expected.
+    /// Since rejected draws are also taken from the transcript, the prover and the verifier
+    /// perform the same number of draws and stay in sync.
     fn sample_z_ood<S: IsSubFieldOf<F>>(
         &mut self,
         lde_roots_of_unity_coset: &[FieldElement<S>],
         trace_roots_of_unity: &[FieldElement<S>],
-    ) -> FieldElement<F>
-    where
-        FieldElement<F>: AsBytes,
-    {
+    ) -> FieldElement<F> {
         loop {
             let value: FieldElement<F> = self.sample_field_element();
             if !lde_roots_of_unity_coset
@@ -41,3 +74,53 @@ pub trait IsTranscript<F: IsField> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fiat_shamir::test_transcript::TestTranscript;
+    use lambdaworks_math::field::fields::u64_prime_field::FE17;
+
+    fn trace_roots_of_unity() -> [FE17; 4] {
+        [FE17::from(1), FE17::from(4), FE17::from(16), FE17::from(13)]
+    }
+
+    fn lde_roots_of_unity_coset() -> [FE17; 8] {
+        [1u64, 2, 4, 8, 16, 15, 13, 9].map(|x| FE17::from(3) * FE17::from(x))
+    }
+
+    #[test]
+    fn sample_z_ood_returns_first_draw_outside_both_domains() {
+        let mut transcript = TestTranscript::with_field_elements(&[FE17::from(2)]);
+
+        let z = transcript.sample_z_ood(&lde_roots_of_unity_coset(), &trace_roots_of_unity());
+
+        assert_eq!(z, FE17::from(2));
+    }
+
+    #[test]
+    fn sample_z_ood_rejects_draw_in_trace_domain() {
+        let mut transcript = TestTranscript::with_field_elements(&[FE17::from(4), FE17::from(2)]);
+
+        let z = transcript.sample_z_ood(&lde_roots_of_unity_coset(), &trace_roots_of_unity());
+
+        assert_eq!(z, FE17::from(2));
+        assert!(transcript.remaining_field_elements().is_empty());
+    }
+
+    #[test]
+    fn sample_z_ood_rejects_draws_in_lde_domain() {
+        let lde_roots_of_unity_coset = lde_roots_of_unity_coset();
+        let mut transcript = TestTranscript::with_field_elements(&[
+            lde_roots_of_unity_coset[2],
+            lde_roots_of_unity_coset[5],
+            FE17::from(2),
+            FE17::from(8),
+        ]);
+
+        let z = transcript.sample_z_ood(&lde_roots_of_unity_coset, &trace_roots_of_unity());
+
+        assert_eq!(z, FE17::from(2));
+        assert_eq!(transcript.remaining_field_elements(), vec![FE17::from(8)]);
+    }
+}
@@ -1,4 +1,4 @@
 pub mod default_transcript;
 pub mod is_transcript;
-#[cfg(feature = "test_fiat_shamir")]
+#[cfg(any(test, feature = "test_fiat_shamir"))]
 pub mod test_transcript;
@@ -1,25 +1,77 @@
-use super::transcript::Transcript;
+use super::is_transcript::IsTranscript;
+use alloc::{collections::VecDeque, vec::Vec};
+use lambdaworks_math::field::{element::FieldElement, traits::IsField};
 
-/// This transcript will ALWAYS return the exact same value every time it's called.
+/// This transcript returns predetermined field elements and integers, in the order they were
+/// given, and then ALWAYS the exact same value every time it's called. Appended data is ignored.
 /// It is meant for testing only, never use this in production.
-pub struct TestTranscript;
-
-impl Transcript for TestTranscript {
-    fn append(&mut self, _new_data: &[u8]) {}
-
-    fn challenge(&mut self) -> [u8; 32] {
-        [1; 32]
-    }
+pub struct TestTranscript<F: IsField> {
+    field_elements: VecDeque<FieldElement<F>>,
+    u64_values: VecDeque<u64>,
 }
 
-impl Default for TestTranscript {
+impl<F: IsField> Default for TestTranscript<F> {
     fn default() -> Self {
         Self::new()
     }
 }
 
-impl TestTranscript {
+impl<F: IsField> TestTranscript<F> {
     pub fn new() -> Self {
-        Self {}
+        Self {
+            field_elements: VecDeque::new(),
+            u64_values: VecDeque::new(),
+        }
+    }
+
+    /// Creates a transcript whose first samples are the given `field_elements`.
+    pub fn with_field_elements(field_elements: &[FieldElement<F>]) -> Self {
+        Self {
+            field_elements: field_elements.iter().cloned().collect(),
+            u64_values: VecDeque::new(),
+        }
+    }
+
+    /// Queues `field_element` to be returned after the already queued ones.
+    pub fn push_field_element(&mut self, field_element: FieldElement<F>) {
+        self.field_elements.push_back(field_element);
+    }
+
+    /// Queues `value` to be returned, reduced modulo the upper bound, by `sample_u64` after the
+    /// already queued ones.
+    pub fn push_u64(&mut self, value: u64) {
+        self.u64_values.push_back(value);
+    }
+
+    /// Returns the field elements that have not been sampled yet.
+    pub fn remaining_field_elements(&self) -> Vec<FieldElement<F>> {
+        self.field_elements.iter().cloned().collect()
+    }
+}
+
+impl<F: IsField> IsTranscript<F> for TestTranscript<F> {
+    fn append_field_element(&mut self, _element: &FieldElement<F>) {}
+
+    fn append_bytes(&mut self, _new_bytes: &[u8]) {}
+
+    fn state(&self) -> [u8; 32] {
+        [1; 32]
+    }
+
+    fn try_fork(&self) -> Option<Self> {
+        Some(Self {
+            field_elements: self.field_elements.clone(),
+            u64_values: self.u64_values.clone(),
+        })
+    }
+
+    fn sample_field_element(&mut self) -> FieldElement<F> {
+        self.field_elements
+            .pop_front()
+            .unwrap_or_else(FieldElement::one)
+    }
+
+    fn sample_u64(&mut self, upper_bound: u64) -> u64 {
+        self.u64_values.pop_front().unwrap_or(1) % upper_bound
     }
 }
@@ -48,6 +48,14 @@ where
         hasher.update(right);
         hasher.finalize().into()
     }
+
+    fn hash_children(children: &[[u8; NUM_BYTES]]) -> [u8; NUM_BYTES] {
+        let mut hasher = D::new();
+        for child in children {
+            hasher.update(child);
+        }
+        hasher.finalize().into()
+    }
 }
 
 #[derive(Clone, Default)]
@@ -83,7 +91,13 @@ mod tests {
     };
     use sha3::{Keccak256, Keccak512, Sha3_256, Sha3_512};
 
-    use crate::merkle_tree::{backends::field_element::FieldElementBackend, merkle::MerkleTree};
+    use crate::{
+        hash::poseidon::starknet::PoseidonCairoStark252,
+        merkle_tree::{
+            backends::field_element::{FieldElementBackend, TreePoseidon},
+            merkle::MerkleTree,
+        },
+    };
 
     type F = Stark252PrimeField;
     type FE = FieldElement<F>;
@@ -135,4 +149,21 @@ mod tests {
             &values[0]
         ));
     }
+
+    #[test]
+    fn tree_poseidon_backend_works_with_stark252_poseidon() {
+        let values: Vec<FE> = (1..6).map(FE::from).collect();
+        let merkle_tree = MerkleTree::<TreePoseidon<PoseidonCairoStark252>>::build(&values);
+        let proof = merkle_tree.get_proof_by_pos(3).unwrap();
+        assert!(proof.verify::<TreePoseidon<PoseidonCairoStark252>>(
+            &merkle_tree.root,
+            3,
+            &values[3]
+        ));
+        assert!(!proof.verify::<TreePoseidon<PoseidonCairoStark252>>(
+            &merkle_tree.root,
+            3,
+            &values[2]
+        ));
+    }
 }
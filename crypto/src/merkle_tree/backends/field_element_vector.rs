@@ -56,6 +56,16 @@ where
         result_hash.copy_from_slice(&hasher.finalize());
         result_hash
     }
+
+    fn hash_children(children: &[[u8; NUM_BYTES]]) -> [u8; NUM_BYTES] {
+        let mut hasher = D::new();
+        for child in children {
+            hasher.update(child);
+        }
+        let mut result_hash = [0_u8; NUM_BYTES];
+        result_hash.copy_from_slice(&hasher.finalize());
+        result_hash
+    }
 }
 
 #[derive(Clone, Default)]
@@ -2,27 +2,61 @@ use core::fmt::Display;
 
 use alloc::vec::Vec;
 
-use super::{proof::Proof, traits::IsMerkleTreeBackend, utils::*};
+use super::{
+    proof::{Proof, RangeProof},
+    traits::{IsMerkleTreeBackend, VectorCommitment},
+    utils::*,
+};
 
-#[derive(Debug)]
-pub enum Error {
+#[derive(Debug, PartialEq, Eq)]
+pub enum MerkleError {
     OutOfBounds,
+    /// A proof was requested for the leaf at `index` of a tree with `len` leaves.
+    IndexOutOfRange {
+        index: usize,
+        len: usize,
+    },
+    /// A range proof was requested for no leaves.
+    EmptyRange,
+    /// A range proof was requested to a tree of the given arity, they are only supported for
+    /// binary trees.
+    UnsupportedArity {
+        arity: usize,
+    },
 }
 
-impl Display for Error {
+impl Display for MerkleError {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        write!(f, "Accessed node was out of bound")
+        match self {
+            MerkleError::OutOfBounds => write!(f, "Accessed node was out of bound"),
+            MerkleError::IndexOutOfRange { index, len } => {
+                write!(
+                    f,
+                    "Leaf index {} out of range for a tree with {} leaves",
+                    index, len
+                )
+            }
+            MerkleError::EmptyRange => write!(f, "Requested a proof for an empty range of leaves"),
+            MerkleError::UnsupportedArity { arity } => {
+                write!(
+                    f,
+                    "Range proofs are not supported for trees of arity {}",
+                    arity
+                )
+            }
+        }
     }
 }
 
 #[cfg(feature = "std")]
-impl std::error::Error for Error {}
+impl std::error::Error for MerkleError {}
 
 #[derive(Clone)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MerkleTree<B: IsMerkleTreeBackend> {
     pub root: B::Node,
     nodes: Vec<B::Node>,
+    arity: usize,
 }
 
 const ROOT: usize = 0;
@@ -32,8 +66,39 @@ where
     B: IsMerkleTreeBackend,
 {
     pub fn build(unhashed_leaves: &[B::Data]) -> Self {
-        let mut hashed_leaves: Vec<B::Node> = B::hash_leaves(unhashed_leaves);
+        Self::build_from_hashed_leaves(B::hash_leaves(unhashed_leaves))
+    }
+
+    /// Builds a tree in which every inner node has `arity` children, hashed together with
+    /// `B::hash_children`. The leaves are padded to the next power of `arity` by repeating the
+    /// last one. A tree of arity 2 is the tree `build` gives.
+    ///
+    /// A higher arity makes the tree shallower, so fewer hashes are needed to go from a leaf
+    /// to the root, but every level of an authentication path holds `arity - 1` siblings
+    /// instead of one. Proofs are checked with [`Proof::verify_with_arity`].
+    ///
+    /// # Panics
+    /// Panics if `arity` is smaller than 2.
+    pub fn build_with_arity(unhashed_leaves: &[B::Data], arity: usize) -> Self {
+        assert!(arity >= 2, "a Merkle tree must have an arity of at least 2");
+        Self::build_from_hashed_leaves_with_arity(B::hash_leaves(unhashed_leaves), arity)
+    }
+
+    /// Builds a tree whose leaves are the already hashed `digests`, so only the inner nodes are
+    /// hashed. A tree built from the digests `B::hash_data(value)` of some values is the tree
+    /// `build` gives for the values, with the same root and proofs. This avoids hashing twice
+    /// leaves that are digests already, such as the roots of other trees.
+    pub fn build_from_digests(digests: Vec<B::Node>) -> Self {
+        Self::build_from_hashed_leaves(digests)
+    }
 
+    /// Returns an empty tree to which leaves can be appended one by one.
+    /// See [`IncrementalMerkleTree`].
+    pub fn new_incremental() -> IncrementalMerkleTree<B> {
+        IncrementalMerkleTree::new()
+    }
+
+    fn build_from_hashed_leaves(mut hashed_leaves: Vec<B::Node>) -> Self {
         //The leaf must be a power of 2 set
         hashed_leaves = complete_until_power_of_two(&mut hashed_leaves);
 
@@ -44,51 +109,297 @@ where
         inner_nodes.extend(hashed_leaves);
 
         //Build the inner nodes of the tree
-        build::<B>(&mut inner_nodes, ROOT);
+        build_by_levels::<B>(&mut inner_nodes);
 
         MerkleTree {
             root: inner_nodes[ROOT].clone(),
             nodes: inner_nodes,
+            arity: 2,
         }
     }
 
-    pub fn get_proof_by_pos(&self, pos: usize) -> Option<Proof<B::Node>> {
-        let pos = pos + self.nodes.len() / 2;
-        let Ok(merkle_path) = self.build_merkle_path(pos) else {
-            return None;
-        };
+    fn build_from_hashed_leaves_with_arity(mut hashed_leaves: Vec<B::Node>, arity: usize) -> Self {
+        if arity == 2 {
+            return Self::build_from_hashed_leaves(hashed_leaves);
+        }
+        hashed_leaves = complete_until_power_of(&mut hashed_leaves, arity);
+
+        // A complete tree of arity k with n leaves has (n - 1) / (k - 1) inner nodes
+        let number_of_inner_nodes = (hashed_leaves.len() - 1) / (arity - 1);
+        let mut nodes = vec![hashed_leaves[0].clone(); number_of_inner_nodes];
+        nodes.extend(hashed_leaves);
+
+        build_by_levels_with_arity::<B>(&mut nodes, arity);
+
+        MerkleTree {
+            root: nodes[ROOT].clone(),
+            nodes,
+            arity,
+        }
+    }
+
+    /// Returns the number of children of every inner node of the tree.
+    pub fn arity(&self) -> usize {
+        self.arity
+    }
+
+    /// Returns the number of leaves of the tree, which is the number of leaves it was built
+    /// with padded to the next power of its arity.
+    pub fn number_of_leaves(&self) -> usize {
+        number_of_leaves(self.nodes.len(), self.arity)
+    }
+
+    /// Returns the authentication path of the leaf at position `pos`. In a tree of arity
+    /// greater than 2 it holds, for every level from the leaf up, the siblings of the node of
+    /// the path from left to right.
+    /// Fails with `MerkleError::IndexOutOfRange` if `pos` is not smaller than the number of
+    /// leaves of the tree.
+    pub fn get_proof_by_pos(&self, pos: usize) -> Result<Proof<B::Node>, MerkleError> {
+        let len = self.number_of_leaves();
+        if pos >= len {
+            return Err(MerkleError::IndexOutOfRange { index: pos, len });
+        }
 
-        self.create_proof(merkle_path)
+        let merkle_path = self.build_merkle_path(pos + self.nodes.len() - len)?;
+        Ok(Proof { merkle_path })
     }
 
-    fn create_proof(&self, merkle_path: Vec<B::Node>) -> Option<Proof<B::Node>> {
-        Some(Proof { merkle_path })
+    /// Checks that `proof` authenticates `leaf` at position `index` in a tree with root `root`,
+    /// without needing the tree itself. It is equivalent to `proof.verify::<B>(root, index, leaf)`.
+    pub fn verify(root: &B::Node, index: usize, leaf: &B::Data, proof: &Proof<B::Node>) -> bool {
+        proof.verify::<B>(root, index, leaf)
     }
 
-    fn build_merkle_path(&self, pos: usize) -> Result<Vec<B::Node>, Error> {
+    /// Returns a proof authenticating the `len` consecutive leaves starting at position `start`,
+    /// which is smaller than `len` proofs of the single leaves. See [`RangeProof`].
+    /// Fails with `MerkleError::UnsupportedArity` if the tree is not binary.
+    pub fn get_range_proof(
+        &self,
+        start: usize,
+        len: usize,
+    ) -> Result<RangeProof<B::Node>, MerkleError> {
+        if self.arity != 2 {
+            return Err(MerkleError::UnsupportedArity { arity: self.arity });
+        }
+        if len == 0 {
+            return Err(MerkleError::EmptyRange);
+        }
+        let number_of_leaves = self.number_of_leaves();
+        if start + len > number_of_leaves {
+            return Err(MerkleError::IndexOutOfRange {
+                index: start + len - 1,
+                len: number_of_leaves,
+            });
+        }
+
+        let first_leaf = self.nodes.len() / 2;
+        let mut first = first_leaf + start;
+        let mut last = first + len - 1;
+        let mut boundary_nodes = Vec::new();
+        while first != ROOT {
+            // Right children have even indexes, their left sibling is outside the range
+            let left_node = (first % 2 == 0).then(|| self.nodes[first - 1].clone());
+            // Left children have odd indexes, their right sibling is outside the range
+            let right_node = (last % 2 == 1).then(|| self.nodes[last + 1].clone());
+            boundary_nodes.push((left_node, right_node));
+
+            first = parent_index(first);
+            last = parent_index(last);
+        }
+
+        Ok(RangeProof { boundary_nodes })
+    }
+
+    /// Checks that `proof` authenticates `leaf` at position `index` in a tree of the given
+    /// `arity` with root `root`. It is equivalent to
+    /// `proof.verify_with_arity::<B>(root, index, leaf, arity)`.
+    pub fn verify_with_arity(
+        root: &B::Node,
+        index: usize,
+        leaf: &B::Data,
+        proof: &Proof<B::Node>,
+        arity: usize,
+    ) -> bool {
+        proof.verify_with_arity::<B>(root, index, leaf, arity)
+    }
+
+    /// Checks that `proof` authenticates `leaves` at the positions starting at `start` in a tree
+    /// with root `root`. It is equivalent to `proof.verify::<B>(root, start, leaves)`.
+    pub fn verify_range_proof(
+        root: &B::Node,
+        start: usize,
+        leaves: &[B::Data],
+        proof: &RangeProof<B::Node>,
+    ) -> bool {
+        proof.verify::<B>(root, start, leaves)
+    }
+
+    fn build_merkle_path(&self, pos: usize) -> Result<Vec<B::Node>, MerkleError> {
         let mut merkle_path = Vec::new();
         let mut pos = pos;
 
         while pos != ROOT {
-            let Some(node) = self.nodes.get(sibling_index(pos)) else {
-                // out of bounds, exit returning the current merkle_path
-                return Err(Error::OutOfBounds);
-            };
-            merkle_path.push(node.clone());
+            if self.arity == 2 {
+                let Some(node) = self.nodes.get(sibling_index(pos)) else {
+                    // out of bounds, exit returning the current merkle_path
+                    return Err(MerkleError::OutOfBounds);
+                };
+                merkle_path.push(node.clone());
 
-            pos = parent_index(pos);
+                pos = parent_index(pos);
+            } else {
+                let parent = (pos - 1) / self.arity;
+                let first_child = parent * self.arity + 1;
+                let Some(children) = self.nodes.get(first_child..first_child + self.arity) else {
+                    return Err(MerkleError::OutOfBounds);
+                };
+                merkle_path.extend(
+                    (first_child..)
+                        .zip(children)
+                        .filter(|(index, _)| *index != pos)
+                        .map(|(_, node)| node.clone()),
+                );
+
+                pos = parent;
+            }
         }
 
         Ok(merkle_path)
     }
 }
 
+impl<B> VectorCommitment for MerkleTree<B>
+where
+    B: IsMerkleTreeBackend,
+{
+    type Value = B::Data;
+    type Commitment = B::Node;
+    type Opening = Proof<B::Node>;
+    type Error = MerkleError;
+
+    fn commit(values: &[B::Data]) -> (Self, B::Node) {
+        let tree = Self::build(values);
+        let root = tree.root.clone();
+        (tree, root)
+    }
+
+    fn open(&self, index: usize) -> Result<Proof<B::Node>, MerkleError> {
+        self.get_proof_by_pos(index)
+    }
+
+    fn verify(root: &B::Node, index: usize, leaf: &B::Data, proof: &Proof<B::Node>) -> bool {
+        proof.verify::<B>(root, index, leaf)
+    }
+}
+
+/// A Merkle tree built by appending leaves one at a time, for provers that produce
+/// the committed data incrementally.
+///
+/// The root is updated on every `push` in logarithmic time, keeping the roots of the
+/// complete subtrees on the right edge of the tree. Like [`MerkleTree::build`], the leaves
+/// are padded to the next power of two by repeating the last one, so the root of the
+/// incremental tree is always the root `build` would give on the leaves pushed so far.
+#[derive(Clone)]
+pub struct IncrementalMerkleTree<B: IsMerkleTreeBackend> {
+    hashed_leaves: Vec<B::Node>,
+    /// The root of the complete subtree with 2^i leaves waiting for its right sibling,
+    /// if there is one, at position i.
+    frontier: Vec<Option<B::Node>>,
+}
+
+impl<B> IncrementalMerkleTree<B>
+where
+    B: IsMerkleTreeBackend,
+{
+    pub fn new() -> Self {
+        Self {
+            hashed_leaves: Vec::new(),
+            frontier: Vec::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.hashed_leaves.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.hashed_leaves.is_empty()
+    }
+
+    /// Appends a leaf to the tree.
+    pub fn push(&mut self, leaf: &B::Data) {
+        let hashed_leaf = B::hash_data(leaf);
+        self.hashed_leaves.push(hashed_leaf.clone());
+
+        let mut node = hashed_leaf;
+        let mut level = 0;
+        while let Some(Some(left)) = self.frontier.get_mut(level).map(Option::take) {
+            node = B::hash_new_parent(&left, &node);
+            level += 1;
+        }
+        if level == self.frontier.len() {
+            self.frontier.push(None);
+        }
+        self.frontier[level] = Some(node);
+    }
+
+    /// Returns the root of the tree with the leaves pushed so far, or `None` if there are none.
+    pub fn root(&self) -> Option<B::Node> {
+        let last_leaf = self.hashed_leaves.last()?;
+
+        // Root of a subtree of the padding, which repeats the last leaf
+        let mut padding = last_leaf.clone();
+        // Root of the subtree at the current level holding the rightmost leaves and padding
+        let mut partial: Option<B::Node> = None;
+
+        let depth = self.len().next_power_of_two().trailing_zeros() as usize;
+        for level in 0..depth {
+            partial = match (&self.frontier[level], partial) {
+                (Some(left), Some(right)) => Some(B::hash_new_parent(left, &right)),
+                (Some(left), None) => Some(B::hash_new_parent(left, &padding)),
+                (None, Some(left)) => Some(B::hash_new_parent(&left, &padding)),
+                (None, None) => None,
+            };
+            padding = B::hash_new_parent(&padding, &padding);
+        }
+
+        match partial {
+            Some(root) => Some(root),
+            // The number of leaves is a power of two and the tree is complete
+            None => self.frontier[depth].clone(),
+        }
+    }
+
+    /// Returns the tree with the leaves pushed so far, with the same root and proofs as
+    /// [`MerkleTree::build`] on the same leaves. Returns `None` if there are no leaves.
+    pub fn finalize(self) -> Option<MerkleTree<B>> {
+        if self.is_empty() {
+            return None;
+        }
+        Some(MerkleTree::build_from_hashed_leaves(self.hashed_leaves))
+    }
+}
+
+impl<B> Default for IncrementalMerkleTree<B>
+where
+    B: IsMerkleTreeBackend,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use lambdaworks_math::field::{element::FieldElement, fields::u64_prime_field::U64PrimeField};
 
-    use crate::merkle_tree::{merkle::MerkleTree, test_merkle::TestBackend};
+    use lambdaworks_math::field::fields::fft_friendly::stark_252_prime_field::Stark252PrimeField;
+    use sha3::Keccak256;
+
+    use crate::merkle_tree::{
+        backends::field_element::FieldElementBackend, merkle::MerkleTree, test_merkle::TestBackend,
+    };
 
     const MODULUS: u64 = 13;
     type U64PF = U64PrimeField<MODULUS>;
@@ -112,4 +423,311 @@ mod tests {
         let merkle_tree = MerkleTree::<TestBackend<U64PF>>::build(&values);
         assert_eq!(merkle_tree.root, FE::new(8));
     }
+
+    #[test]
+    fn incremental_tree_root_matches_built_tree_root_after_each_push() {
+        type F = Stark252PrimeField;
+        type Backend = FieldElementBackend<F, Keccak256, 32>;
+
+        let values: Vec<FieldElement<F>> = (1..12u64).map(FieldElement::from).collect();
+        let mut incremental_tree = MerkleTree::<Backend>::new_incremental();
+        assert_eq!(incremental_tree.root(), None);
+
+        for (i, value) in values.iter().enumerate() {
+            incremental_tree.push(value);
+            let merkle_tree = MerkleTree::<Backend>::build(&values[..=i]);
+            assert_eq!(incremental_tree.root(), Some(merkle_tree.root));
+        }
+    }
+
+    #[test]
+    fn finalized_incremental_tree_has_the_same_proofs_as_built_tree() {
+        type F = Stark252PrimeField;
+        type Backend = FieldElementBackend<F, Keccak256, 32>;
+
+        let values: Vec<FieldElement<F>> = (1..12u64).map(FieldElement::from).collect();
+        let mut incremental_tree = MerkleTree::<Backend>::new_incremental();
+        for value in values.iter() {
+            incremental_tree.push(value);
+        }
+        let root = incremental_tree.root().unwrap();
+        let finalized_tree = incremental_tree.finalize().unwrap();
+        let merkle_tree = MerkleTree::<Backend>::build(&values);
+
+        assert_eq!(finalized_tree.root, root);
+        assert_eq!(finalized_tree.root, merkle_tree.root);
+        for pos in [0, 5, 10] {
+            let proof = finalized_tree.get_proof_by_pos(pos).unwrap();
+            assert_eq!(
+                proof.merkle_path,
+                merkle_tree.get_proof_by_pos(pos).unwrap().merkle_path
+            );
+            assert!(proof.verify::<Backend>(&merkle_tree.root, pos, &values[pos]));
+        }
+    }
+
+    #[test]
+    fn verify_accepts_valid_path_and_rejects_tampered_leaf() {
+        type F = Stark252PrimeField;
+        type Backend = FieldElementBackend<F, Keccak256, 32>;
+
+        let values: Vec<FieldElement<F>> = (1..9u64).map(FieldElement::from).collect();
+        let merkle_tree = MerkleTree::<Backend>::build(&values);
+        let root = merkle_tree.root;
+        let proof = merkle_tree.get_proof_by_pos(3).unwrap();
+
+        assert!(MerkleTree::<Backend>::verify(&root, 3, &values[3], &proof));
+        assert!(!MerkleTree::<Backend>::verify(
+            &root,
+            3,
+            &(values[3] + FieldElement::one()),
+            &proof
+        ));
+        assert!(!MerkleTree::<Backend>::verify(&root, 2, &values[3], &proof));
+    }
+
+    #[test]
+    fn tree_built_from_digests_matches_tree_built_from_values() {
+        type F = Stark252PrimeField;
+        type Backend = FieldElementBackend<F, Keccak256, 32>;
+
+        let values: Vec<FieldElement<F>> = (1..12u64).map(FieldElement::from).collect();
+        let digests = values.iter().map(Backend::hash_data).collect();
+        let merkle_tree = MerkleTree::<Backend>::build(&values);
+        let digests_tree = MerkleTree::<Backend>::build_from_digests(digests);
+
+        assert_eq!(digests_tree.root, merkle_tree.root);
+        for pos in [0, 5, 10] {
+            let proof = digests_tree.get_proof_by_pos(pos).unwrap();
+            assert_eq!(
+                proof.merkle_path,
+                merkle_tree.get_proof_by_pos(pos).unwrap().merkle_path
+            );
+            assert!(proof.verify::<Backend>(&merkle_tree.root, pos, &values[pos]));
+            assert!(proof.verify_digest::<Backend>(
+                &merkle_tree.root,
+                pos,
+                &Backend::hash_data(&values[pos])
+            ));
+        }
+    }
+
+    #[test]
+    fn get_proof_by_pos_fails_for_an_index_out_of_range() {
+        type F = Stark252PrimeField;
+        type Backend = FieldElementBackend<F, Keccak256, 32>;
+
+        let values: Vec<FieldElement<F>> = (1..12u64).map(FieldElement::from).collect();
+        let merkle_tree = MerkleTree::<Backend>::build(&values);
+
+        assert_eq!(merkle_tree.number_of_leaves(), 16);
+        assert!(merkle_tree.get_proof_by_pos(15).is_ok());
+        assert_eq!(
+            merkle_tree.get_proof_by_pos(16).unwrap_err(),
+            MerkleError::IndexOutOfRange { index: 16, len: 16 }
+        );
+    }
+
+    fn number_of_nodes<T: PartialEq + Eq>(proof: &RangeProof<T>) -> usize {
+        proof
+            .boundary_nodes
+            .iter()
+            .map(|(left, right)| usize::from(left.is_some()) + usize::from(right.is_some()))
+            .sum()
+    }
+
+    #[test]
+    fn range_proof_of_all_the_leaves_holds_no_nodes() {
+        type F = Stark252PrimeField;
+        type Backend = FieldElementBackend<F, Keccak256, 32>;
+
+        let values: Vec<FieldElement<F>> = (1..9u64).map(FieldElement::from).collect();
+        let merkle_tree = MerkleTree::<Backend>::build(&values);
+        let proof = merkle_tree.get_range_proof(0, 8).unwrap();
+
+        assert_eq!(number_of_nodes(&proof), 0);
+        assert!(MerkleTree::<Backend>::verify_range_proof(
+            &merkle_tree.root,
+            0,
+            &values,
+            &proof
+        ));
+    }
+
+    #[test]
+    fn range_proof_of_some_leaves_is_smaller_than_their_single_proofs() {
+        type F = Stark252PrimeField;
+        type Backend = FieldElementBackend<F, Keccak256, 32>;
+
+        let values: Vec<FieldElement<F>> = (1..17u64).map(FieldElement::from).collect();
+        let merkle_tree = MerkleTree::<Backend>::build(&values);
+        let proof = merkle_tree.get_range_proof(3, 6).unwrap();
+
+        assert!(number_of_nodes(&proof) < 6 * 4);
+        assert!(proof.verify::<Backend>(&merkle_tree.root, 3, &values[3..9]));
+        assert!(!proof.verify::<Backend>(&merkle_tree.root, 4, &values[4..10]));
+        assert!(!proof.verify::<Backend>(&merkle_tree.root, 3, &values[3..8]));
+
+        let mut tampered_values = values[3..9].to_vec();
+        tampered_values[2] += FieldElement::<F>::one();
+        assert!(!proof.verify::<Backend>(&merkle_tree.root, 3, &tampered_values));
+    }
+
+    #[test]
+    fn range_proof_of_a_single_leaf_holds_its_path() {
+        type F = Stark252PrimeField;
+        type Backend = FieldElementBackend<F, Keccak256, 32>;
+
+        let values: Vec<FieldElement<F>> = (1..9u64).map(FieldElement::from).collect();
+        let merkle_tree = MerkleTree::<Backend>::build(&values);
+        let proof = merkle_tree.get_range_proof(5, 1).unwrap();
+
+        let path: Vec<_> = proof
+            .boundary_nodes
+            .iter()
+            .map(|(left, right)| left.clone().or(right.clone()).unwrap())
+            .collect();
+        assert_eq!(path, merkle_tree.get_proof_by_pos(5).unwrap().merkle_path);
+        assert!(proof.verify::<Backend>(&merkle_tree.root, 5, &values[5..6]));
+    }
+
+    #[test]
+    fn range_proof_of_an_empty_or_out_of_bounds_range_is_an_error() {
+        let values: Vec<FE> = (1..5).map(FE::new).collect();
+        let merkle_tree = MerkleTree::<TestBackend<U64PF>>::build(&values);
+
+        assert!(matches!(
+            merkle_tree.get_range_proof(1, 0),
+            Err(MerkleError::EmptyRange)
+        ));
+        assert!(matches!(
+            merkle_tree.get_range_proof(2, 3),
+            Err(MerkleError::IndexOutOfRange { index: 4, len: 4 })
+        ));
+    }
+
+    #[test]
+    fn tree_of_arity_two_is_the_binary_tree() {
+        type F = Stark252PrimeField;
+        type Backend = FieldElementBackend<F, Keccak256, 32>;
+
+        let values: Vec<FieldElement<F>> = (1..12u64).map(FieldElement::from).collect();
+        let merkle_tree = MerkleTree::<Backend>::build(&values);
+        let binary_tree = MerkleTree::<Backend>::build_with_arity(&values, 2);
+
+        assert_eq!(binary_tree.root, merkle_tree.root);
+        assert_eq!(binary_tree.arity(), 2);
+        let proof = binary_tree.get_proof_by_pos(6).unwrap();
+        assert_eq!(proof, merkle_tree.get_proof_by_pos(6).unwrap());
+        assert!(proof.verify_with_arity::<Backend>(&merkle_tree.root, 6, &values[6], 2));
+    }
+
+    #[test]
+    fn proofs_of_a_tree_of_arity_four_verify() {
+        type F = Stark252PrimeField;
+        type Backend = FieldElementBackend<F, Keccak256, 32>;
+
+        let values: Vec<FieldElement<F>> = (1..12u64).map(FieldElement::from).collect();
+        let merkle_tree = MerkleTree::<Backend>::build_with_arity(&values, 4);
+        let root = merkle_tree.root;
+
+        assert_eq!(merkle_tree.number_of_leaves(), 16);
+        assert_ne!(root, MerkleTree::<Backend>::build(&values).root);
+        for (pos, value) in values.iter().enumerate() {
+            let proof = merkle_tree.get_proof_by_pos(pos).unwrap();
+            assert!(MerkleTree::<Backend>::verify_with_arity(
+                &root, pos, value, &proof, 4
+            ));
+            assert!(!MerkleTree::<Backend>::verify_with_arity(
+                &root,
+                pos,
+                &(value + FieldElement::one()),
+                &proof,
+                4
+            ));
+            assert!(!MerkleTree::<Backend>::verify_with_arity(
+                &root,
+                (pos + 1) % values.len(),
+                value,
+                &proof,
+                4
+            ));
+            assert!(!MerkleTree::<Backend>::verify(&root, pos, value, &proof));
+        }
+        assert!(merkle_tree.get_proof_by_pos(16).is_err());
+    }
+
+    #[test]
+    fn tree_of_arity_four_holds_the_leaves_padded_like_the_binary_tree() {
+        // Inner nodes are the sums of their children and leaves double the values
+        let values: Vec<FE> = (1..6).map(FE::new).collect();
+        let merkle_tree = MerkleTree::<TestBackend<U64PF>>::build_with_arity(&values, 4);
+
+        // 2 * (1 + 2 + 3 + 4 + 5 + 5 * 11) = 140 = 10 mod 13
+        assert_eq!(merkle_tree.number_of_leaves(), 16);
+        assert_eq!(merkle_tree.root, FE::new(10));
+    }
+
+    #[test]
+    fn paths_of_a_tree_of_arity_four_need_half_the_hashes_of_a_binary_tree() {
+        type F = Stark252PrimeField;
+        type Backend = FieldElementBackend<F, Keccak256, 32>;
+
+        let values: Vec<FieldElement<F>> = (0..64u64).map(FieldElement::from).collect();
+        let binary_proof = MerkleTree::<Backend>::build(&values)
+            .get_proof_by_pos(37)
+            .unwrap();
+        let quaternary_proof = MerkleTree::<Backend>::build_with_arity(&values, 4)
+            .get_proof_by_pos(37)
+            .unwrap();
+
+        // One hash per level from the leaf to the root, with one or three siblings each
+        let binary_hashes = binary_proof.merkle_path.len();
+        let quaternary_hashes = quaternary_proof.merkle_path.len() / 3;
+        assert_eq!(binary_hashes, 6);
+        assert_eq!(quaternary_hashes, 3);
+        assert_eq!(quaternary_proof.merkle_path.len(), 9);
+    }
+
+    #[test]
+    fn range_proof_of_a_tree_of_arity_four_is_an_error() {
+        let values: Vec<FE> = (1..5).map(FE::new).collect();
+        let merkle_tree = MerkleTree::<TestBackend<U64PF>>::build_with_arity(&values, 4);
+
+        assert_eq!(
+            merkle_tree.get_range_proof(0, 2).unwrap_err(),
+            MerkleError::UnsupportedArity { arity: 4 }
+        );
+    }
+
+    #[test]
+    fn vector_commitment_of_a_merkle_tree_matches_its_root_and_proofs() {
+        type F = Stark252PrimeField;
+        type Backend = FieldElementBackend<F, Keccak256, 32>;
+
+        let values: Vec<FieldElement<F>> = (1..7u64).map(FieldElement::from).collect();
+        let merkle_tree = MerkleTree::<Backend>::build(&values);
+        let (committed, commitment) = <MerkleTree<Backend> as VectorCommitment>::commit(&values);
+
+        assert_eq!(commitment, merkle_tree.root);
+        for (index, value) in values.iter().enumerate() {
+            let opening = committed.open(index).unwrap();
+            assert_eq!(
+                opening.merkle_path,
+                merkle_tree.get_proof_by_pos(index).unwrap().merkle_path
+            );
+            assert!(<MerkleTree<Backend> as VectorCommitment>::verify(
+                &commitment,
+                index,
+                value,
+                &opening
+            ));
+        }
+        assert!(!<MerkleTree<Backend> as VectorCommitment>::verify(
+            &commitment,
+            0,
+            &values[1],
+            &committed.open(0).unwrap()
+        ));
+    }
 }
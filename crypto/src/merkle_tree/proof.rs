@@ -10,33 +10,151 @@ use super::traits::IsMerkleTreeBackend;
 /// `merkle_path` field, in such a way that, if the merkle tree is of height `n`, the
 /// `i`-th element of `merkle_path` is the sibling node in the `n - 1 - i`-th check
 /// when verifying.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Proof<T: PartialEq + Eq> {
     pub merkle_path: Vec<T>,
 }
 
 impl<T: PartialEq + Eq> Proof<T> {
-    pub fn verify<B>(&self, root_hash: &B::Node, mut index: usize, value: &B::Data) -> bool
+    /// Checks that the path authenticates `value` as the leaf at position `index` of a tree
+    /// with root `root_hash`. The index must be smaller than the number of leaves of the tree,
+    /// `2^merkle_path.len()`, otherwise different indexes would be accepted for the same leaf.
+    pub fn verify<B>(&self, root_hash: &B::Node, index: usize, value: &B::Data) -> bool
     where
         B: IsMerkleTreeBackend<Node = T>,
     {
-        let mut hashed_value = B::hash_data(value);
+        self.verify_digest::<B>(root_hash, index, &B::hash_data(value))
+    }
+
+    /// Checks that the path authenticates the already hashed leaf `digest` at position `index`
+    /// of a tree with root `root_hash`, as for the trees built with
+    /// [`MerkleTree::build_from_digests`](super::merkle::MerkleTree::build_from_digests).
+    pub fn verify_digest<B>(&self, root_hash: &B::Node, mut index: usize, digest: &B::Node) -> bool
+    where
+        B: IsMerkleTreeBackend<Node = T>,
+    {
+        let height = self.merkle_path.len();
+        if height < usize::BITS as usize && index >> height != 0 {
+            return false;
+        }
+
+        let mut hashed_value: Option<B::Node> = None;
 
         for sibling_node in self.merkle_path.iter() {
-            if index % 2 == 0 {
-                hashed_value = B::hash_new_parent(&hashed_value, sibling_node);
+            let current = hashed_value.as_ref().unwrap_or(digest);
+            hashed_value = Some(if index % 2 == 0 {
+                B::hash_new_parent(current, sibling_node)
             } else {
-                hashed_value = B::hash_new_parent(sibling_node, &hashed_value);
-            }
+                B::hash_new_parent(sibling_node, current)
+            });
 
             index >>= 1;
         }
 
+        root_hash == hashed_value.as_ref().unwrap_or(digest)
+    }
+
+    /// Checks that the path authenticates `value` as the leaf at position `index` of a tree of
+    /// the given `arity` with root `root_hash`, as for the trees built with
+    /// [`MerkleTree::build_with_arity`](super::merkle::MerkleTree::build_with_arity). For an
+    /// arity of 2 it is `verify`.
+    pub fn verify_with_arity<B>(
+        &self,
+        root_hash: &B::Node,
+        mut index: usize,
+        value: &B::Data,
+        arity: usize,
+    ) -> bool
+    where
+        B: IsMerkleTreeBackend<Node = T>,
+        T: Clone,
+    {
+        if arity == 2 {
+            return self.verify::<B>(root_hash, index, value);
+        }
+        if arity < 2 || self.merkle_path.len() % (arity - 1) != 0 {
+            return false;
+        }
+        let height = self.merkle_path.len() / (arity - 1);
+        if matches!(arity.checked_pow(height as u32), Some(number_of_leaves) if index >= number_of_leaves)
+        {
+            return false;
+        }
+
+        let mut hashed_value = B::hash_data(value);
+
+        for siblings in self.merkle_path.chunks(arity - 1) {
+            let position = index % arity;
+            let mut children = Vec::with_capacity(arity);
+            children.extend_from_slice(&siblings[..position]);
+            children.push(hashed_value);
+            children.extend_from_slice(&siblings[position..]);
+            hashed_value = B::hash_children(&children);
+
+            index /= arity;
+        }
+
         root_hash == &hashed_value
     }
 }
 
+/// Authenticates a range of consecutive leaves at once, sharing the nodes their paths have
+/// in common. For every level of the tree, from the leaves up, it holds the nodes next to the
+/// left and to the right of the range that can not be computed from the leaves of the range,
+/// so at most two nodes per level instead of one per level and leaf.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RangeProof<T: PartialEq + Eq> {
+    pub boundary_nodes: Vec<(Option<T>, Option<T>)>,
+}
+
+impl<T: PartialEq + Eq + Clone> RangeProof<T> {
+    /// Checks that the proof authenticates `values` as the leaves at the positions
+    /// `start..start + values.len()` of a tree with root `root_hash`.
+    pub fn verify<B>(&self, root_hash: &B::Node, start: usize, values: &[B::Data]) -> bool
+    where
+        B: IsMerkleTreeBackend<Node = T>,
+    {
+        if values.is_empty() {
+            return false;
+        }
+
+        let mut nodes = B::hash_leaves(values);
+        // Positions of the first and the last node of the range at the current level
+        let mut first = start;
+        let mut last = start + values.len() - 1;
+
+        for (left_node, right_node) in self.boundary_nodes.iter() {
+            match (first % 2 == 1, left_node) {
+                (true, Some(node)) => {
+                    nodes.insert(0, node.clone());
+                    first -= 1;
+                }
+                (false, None) => {}
+                _ => return false,
+            }
+            match (last % 2 == 0, right_node) {
+                (true, Some(node)) => {
+                    nodes.push(node.clone());
+                    last += 1;
+                }
+                (false, None) => {}
+                _ => return false,
+            }
+
+            nodes = nodes
+                .chunks(2)
+                .map(|pair| B::hash_new_parent(&pair[0], &pair[1]))
+                .collect();
+            first >>= 1;
+            last >>= 1;
+        }
+
+        first == 0 && last == 0 && &nodes[0] == root_hash
+    }
+}
+
 #[cfg(feature = "alloc")]
 impl<T> Serializable for Proof<T>
 where
@@ -147,6 +265,17 @@ mod tests {
         assert!(proof.verify::<TestBackend<Ecgfp5>>(&merkle_tree.root, 9349, &Ecgfp5FE::new(9350)));
     }
 
+    #[test]
+    fn proof_does_not_verify_for_an_index_beyond_the_number_of_leaves() {
+        let values: Vec<FE> = (1..6).map(FE::new).collect();
+        let merkle_tree = MerkleTree::<TestBackend<U64PF>>::build(&values);
+        let proof = merkle_tree.get_proof_by_pos(1).unwrap();
+
+        // The tree has 8 leaves, so index 9 has the same low bits as index 1.
+        assert!(proof.verify::<TestBackend<U64PF>>(&merkle_tree.root, 1, &FE::new(2)));
+        assert!(!proof.verify::<TestBackend<U64PF>>(&merkle_tree.root, 9, &FE::new(2)));
+    }
+
     fn assert_merkle_path(values: &[FE], expected_values: &[FE]) {
         for (node, expected_node) in values.iter().zip(expected_values) {
             assert_eq!(node, expected_node);
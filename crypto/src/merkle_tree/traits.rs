@@ -26,4 +26,42 @@ pub trait IsMerkleTreeBackend: Default {
     /// This function takes to children nodes and builds a new parent node.
     /// It will be used in the construction of the Merkle tree.
     fn hash_new_parent(child_1: &Self::Node, child_2: &Self::Node) -> Self::Node;
+
+    /// This function takes the children nodes of a tree of any arity, from left to right, and
+    /// builds their parent node. For two children it must be `hash_new_parent`, so that binary
+    /// trees have the same nodes whichever function builds them. By default the children are
+    /// folded with `hash_new_parent` from the left.
+    fn hash_children(children: &[Self::Node]) -> Self::Node {
+        children[1..]
+            .iter()
+            .fold(children[0].clone(), |parent, child| {
+                Self::hash_new_parent(&parent, child)
+            })
+    }
+}
+
+/// A scheme to commit to a vector of values and later open the commitment at any position,
+/// proving the value found there. [`MerkleTree`](super::merkle::MerkleTree) is the default
+/// implementation.
+pub trait VectorCommitment: Sized {
+    type Value;
+    type Commitment;
+    type Opening;
+    type Error;
+
+    /// Commits to `values`, returning the committed vector, which is needed to open it
+    /// later, and the commitment to it.
+    fn commit(values: &[Self::Value]) -> (Self, Self::Commitment);
+
+    /// Returns the opening proving the value at position `index` of the committed vector.
+    fn open(&self, index: usize) -> Result<Self::Opening, Self::Error>;
+
+    /// Checks that `opening` proves that `value` is at position `index` of the vector
+    /// committed to by `commitment`.
+    fn verify(
+        commitment: &Self::Commitment,
+        index: usize,
+        value: &Self::Value,
+        opening: &Self::Opening,
+    ) -> bool;
 }
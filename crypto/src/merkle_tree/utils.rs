@@ -1,4 +1,8 @@
 use alloc::vec::Vec;
+#[cfg(feature = "parallel")]
+use rayon::prelude::{
+    IndexedParallelIterator, IntoParallelIterator, ParallelIterator, ParallelSlice,
+};
 
 use super::traits::IsMerkleTreeBackend;
 
@@ -30,6 +34,19 @@ pub fn is_power_of_two(x: usize) -> bool {
     (x != 0) && ((x & (x - 1)) == 0)
 }
 
+// The list of values is completed repeating the last value to a power of `arity` length
+pub fn complete_until_power_of<T: Clone>(values: &mut Vec<T>, arity: usize) -> Vec<T> {
+    let mut len = 1;
+    while len < values.len() {
+        len *= arity;
+    }
+    values.resize(len, values[values.len() - 1].clone());
+    values.to_vec()
+}
+
+/// Builds the inner nodes of the tree stored in `nodes` recursively from `parent_index`. Trees
+/// are built with [`build_by_levels`], this is the reference it is tested against.
+#[cfg(test)]
 pub fn build<B: IsMerkleTreeBackend>(nodes: &mut Vec<B::Node>, parent_index: usize)
 where
     B::Node: Clone,
@@ -47,14 +64,61 @@ where
     nodes[parent_index] = B::hash_new_parent(&nodes[left_child_index], &nodes[right_child_index]);
 }
 
+/// Builds the inner nodes of the tree stored in `nodes`, whose second half holds the leaves,
+/// one level at a time from the leaves up. It gives the same nodes as [`build`] from the root,
+/// but the parents of each level are independent, so they are hashed in parallel with the
+/// `parallel` feature.
+pub fn build_by_levels<B: IsMerkleTreeBackend>(nodes: &mut [B::Node]) {
+    build_by_levels_with_arity::<B>(nodes, 2)
+}
+
+/// Builds the inner nodes of the tree of the given `arity` stored in `nodes`, whose last
+/// `number_of_leaves(nodes.len(), arity)` elements are the leaves. The children of the node
+/// at index `i` are at the indexes `arity * i + 1..=arity * i + arity`.
+pub fn build_by_levels_with_arity<B: IsMerkleTreeBackend>(nodes: &mut [B::Node], arity: usize) {
+    let mut level_len = number_of_leaves(nodes.len(), arity);
+    let mut level_start = nodes.len() - level_len;
+    while level_len > 1 {
+        let parents_start = (level_start - 1) / arity;
+        let (parents, children) = nodes.split_at_mut(level_start);
+        let parents = &mut parents[parents_start..parents_start + level_len / arity];
+        let children = &children[..level_len];
+
+        #[cfg(feature = "parallel")]
+        let iter = parents.into_par_iter().zip(children.par_chunks(arity));
+        #[cfg(not(feature = "parallel"))]
+        let iter = parents.iter_mut().zip(children.chunks(arity));
+
+        iter.for_each(|(parent, siblings)| {
+            *parent = if arity == 2 {
+                B::hash_new_parent(&siblings[0], &siblings[1])
+            } else {
+                B::hash_children(siblings)
+            }
+        });
+
+        level_start = parents_start;
+        level_len /= arity;
+    }
+}
+
+/// Returns the number of leaves of a complete tree of the given `arity` with
+/// `number_of_nodes` nodes, `n` such that `n + (n - 1) / (arity - 1) = number_of_nodes`.
+pub fn number_of_leaves(number_of_nodes: usize, arity: usize) -> usize {
+    (number_of_nodes * (arity - 1) + 1) / arity
+}
+
+#[cfg(test)]
 pub fn is_leaf(lenght: usize, node_index: usize) -> bool {
     (node_index >= (lenght / 2)) && node_index < lenght
 }
 
+#[cfg(test)]
 pub fn left_child_index(parent_index: usize) -> usize {
     parent_index * 2 + 1
 }
 
+#[cfg(test)]
 pub fn right_child_index(parent_index: usize) -> usize {
     parent_index * 2 + 2
 }
@@ -66,7 +130,7 @@ mod tests {
 
     use crate::merkle_tree::{test_merkle::TestBackend, traits::IsMerkleTreeBackend};
 
-    use super::{build, complete_until_power_of_two};
+    use super::{build, build_by_levels, complete_until_power_of_two};
 
     const MODULUS: u64 = 13;
     type U64PF = U64PrimeField<MODULUS>;
@@ -109,4 +173,19 @@ mod tests {
         build::<TestBackend<U64PF>>(&mut nodes, ROOT);
         assert_eq!(nodes[ROOT], FE::new(10));
     }
+
+    #[test]
+    fn building_by_levels_gives_the_nodes_of_building_from_the_root() {
+        for number_of_leaves in [1, 2, 8, 64] {
+            let leaves: Vec<FE> = (0..number_of_leaves).map(FE::new).collect();
+            let mut nodes = vec![FE::zero(); leaves.len() - 1];
+            nodes.extend(leaves);
+            let mut nodes_by_levels = nodes.clone();
+
+            build::<TestBackend<U64PF>>(&mut nodes, ROOT);
+            build_by_levels::<TestBackend<U64PF>>(&mut nodes_by_levels);
+
+            assert_eq!(nodes, nodes_by_levels);
+        }
+    }
 }
@@ -21,3 +21,40 @@ static ALLOC: wee_alloc::WeeAlloc = wee_alloc::WeeAlloc::INIT;
 use lambdaworks_crypto;
 #[allow(unused_imports)]
 use lambdaworks_math;
+
+use lambdaworks_math::field::{
+    element::FieldElement, fields::fft_friendly::stark_252_prime_field::Stark252PrimeField,
+};
+use stark_platinum_prover::{
+    examples::simple_fibonacci::{fibonacci_trace, FibonacciAIR, FibonacciPublicInputs},
+    proof::options::ProofOptions,
+    prover::{IsStarkProver, Prover},
+    transcript::StoneProverTranscript,
+    verifier::{IsStarkVerifier, Verifier},
+};
+
+/// Proves and verifies a Fibonacci trace, so that the prover and the verifier are built without `std`.
+#[no_mangle]
+pub extern "C" fn prove_and_verify_fibonacci() -> bool {
+    let trace = fibonacci_trace([FieldElement::one(), FieldElement::one()], 8);
+    let pub_inputs = FibonacciPublicInputs {
+        a0: FieldElement::<Stark252PrimeField>::one(),
+        a1: FieldElement::one(),
+    };
+    let proof_options = ProofOptions::default_test_options();
+
+    let Ok(proof) = Prover::<FibonacciAIR<Stark252PrimeField>>::prove(
+        &trace,
+        &pub_inputs,
+        &proof_options,
+        StoneProverTranscript::new(&[]),
+    ) else {
+        return false;
+    };
+    Verifier::<FibonacciAIR<Stark252PrimeField>>::verify(
+        &proof,
+        &pub_inputs,
+        &proof_options,
+        StoneProverTranscript::new(&[]),
+    )
+}
@@ -47,9 +47,9 @@ fn generate_merkle_proof(tree_path: String, pos: usize) -> Result<(), io::Error>
     let values: Vec<FE> = load_tree_values(&tree_path)?;
     let merkle_tree = MerkleTree::<TreePoseidon<PoseidonCairoStark252>>::build(&values);
 
-    let Some(proof) = merkle_tree.get_proof_by_pos(pos) else {
-        return Err(io::Error::new(io::ErrorKind::Other, "Index out of bounds"));
-    };
+    let proof = merkle_tree
+        .get_proof_by_pos(pos)
+        .map_err(|err| io::Error::new(io::ErrorKind::Other, err.to_string()))?;
 
     let proof_path = tree_path.replace(".csv", format!("_proof_{pos}.json").as_str());
     let file = File::create(proof_path)?;
@@ -1,12 +1,13 @@
 #![no_main]
 use libfuzzer_sys::fuzz_target;
-use stark_platinum_prover::proof::stark::StarkProof;
 use lambdaworks_math::field::fields::fft_friendly::stark_252_prime_field::Stark252PrimeField;
-use lambdaworks_math::traits::Deserializable;
+use stark_platinum_prover::{
+    examples::simple_fibonacci::FibonacciPublicInputs, proof::stark::SelfDescribingProof,
+};
 
+type F = Stark252PrimeField;
 
+// Reading a proof from arbitrary bytes must fail with a decoding error, never panic.
 fuzz_target!(|data: Vec<u8>| {
-    
-    let _proof = StarkProof::<Stark252PrimeField>::deserialize(&data);
-
+    let _proof = SelfDescribingProof::<F, F, FibonacciPublicInputs<F>>::from_bytes(&data);
 });
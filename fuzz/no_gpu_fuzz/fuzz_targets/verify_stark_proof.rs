@@ -0,0 +1,56 @@
+#![no_main]
+use std::sync::OnceLock;
+
+use libfuzzer_sys::fuzz_target;
+use lambdaworks_math::field::{
+    element::FieldElement, fields::fft_friendly::stark_252_prime_field::Stark252PrimeField,
+};
+use stark_platinum_prover::{
+    examples::simple_fibonacci::{fibonacci_trace, FibonacciAIR, FibonacciPublicInputs},
+    proof::{options::ProofOptions, stark::SelfDescribingProof},
+    prover::{IsStarkProver, Prover},
+    transcript::StoneProverTranscript,
+    verifier::{IsStarkVerifier, Verifier},
+};
+
+type F = Stark252PrimeField;
+type FE = FieldElement<F>;
+
+/// The bytes of a valid self describing proof of a small Fibonacci trace.
+fn valid_proof_bytes() -> &'static [u8] {
+    static PROOF_BYTES: OnceLock<Vec<u8>> = OnceLock::new();
+    PROOF_BYTES.get_or_init(|| {
+        let trace = fibonacci_trace([FE::one(), FE::one()], 8);
+        let pub_inputs = FibonacciPublicInputs {
+            a0: FE::one(),
+            a1: FE::one(),
+        };
+        let proof_options = ProofOptions::default_test_options();
+        let proof = Prover::<FibonacciAIR<F>>::prove(
+            &trace,
+            &pub_inputs,
+            &proof_options,
+            StoneProverTranscript::new(&[]),
+        )
+        .unwrap();
+        SelfDescribingProof::new::<FibonacciAIR<F>>(proof, pub_inputs, &proof_options)
+            .to_bytes()
+            .unwrap()
+    })
+}
+
+// Flips bytes of a valid proof at the given positions, so that most inputs still decode into
+// a proof but one that is wrong. Verifying it must return an error, never panic.
+fuzz_target!(|flips: Vec<(u16, u8)>| {
+    let mut proof_bytes = valid_proof_bytes().to_vec();
+    for (position, mask) in flips {
+        let position = position as usize % proof_bytes.len();
+        proof_bytes[position] ^= mask;
+    }
+
+    let _result = Verifier::<FibonacciAIR<F>>::verify_self_describing(
+        &proof_bytes,
+        &ProofOptions::default_test_options(),
+        StoneProverTranscript::new(&[]),
+    );
+});
@@ -140,6 +140,49 @@ fn poly_interpolation_benchmarks(c: &mut Criterion) {
     group.finish();
 }
 
+/// Low degree extension of the columns of a 50 column trace of length 2^16 with blowup
+/// factor 4, computing the twiddle factors for every column or once for all of them.
+fn trace_lde_benchmarks(c: &mut Criterion) {
+    const NUM_COLUMNS: usize = 50;
+    const TRACE_ORDER: u64 = 16;
+    const BLOWUP_FACTOR: usize = 4;
+
+    let mut group = c.benchmark_group("Trace LDE");
+    group.throughput(criterion::Throughput::Elements(
+        NUM_COLUMNS as u64 * (1 << TRACE_ORDER),
+    ));
+    let polys: Vec<_> = (0..NUM_COLUMNS)
+        .map(|_| stark252_utils::rand_poly(TRACE_ORDER))
+        .collect();
+
+    group.bench_with_input("Twiddles per column", &polys, |bench, polys| {
+        bench.iter_with_large_drop(|| {
+            polys
+                .iter()
+                .map(|poly| fft_functions::poly_evaluate_offset_fft(poly, BLOWUP_FACTOR))
+                .collect::<Vec<_>>()
+        });
+    });
+    group.bench_with_input("Cached twiddles", &polys, |bench, polys| {
+        bench.iter_with_large_drop(|| {
+            let lde_order = TRACE_ORDER + BLOWUP_FACTOR.trailing_zeros() as u64;
+            let twiddles = stark252_utils::twiddles(lde_order, RootsConfig::BitReverse);
+            polys
+                .iter()
+                .map(|poly| {
+                    fft_functions::poly_evaluate_offset_fft_with_twiddles(
+                        poly,
+                        BLOWUP_FACTOR,
+                        &twiddles,
+                    )
+                })
+                .collect::<Vec<_>>()
+        });
+    });
+
+    group.finish();
+}
+
 #[cfg(not(any(feature = "metal", feature = "cuda")))]
 criterion_group!(
     name = seq_fft;
@@ -150,6 +193,7 @@ criterion_group!(
         bitrev_permutation_benchmarks,
         poly_evaluation_benchmarks,
         poly_interpolation_benchmarks,
+        trace_lde_benchmarks,
 );
 
 #[cfg(any(feature = "metal", feature = "cuda"))]
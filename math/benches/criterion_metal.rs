@@ -3,7 +3,7 @@ use lambdaworks_gpu::metal::abstractions::state::MetalState;
 use lambdaworks_math::fft::gpu::metal::ops::gen_twiddles;
 use lambdaworks_math::field::traits::RootsConfig;
 
-use utils::stark252_utils::F;
+use utils::stark252_utils::{F, FE};
 
 mod utils;
 use utils::metal_functions;
@@ -83,6 +83,29 @@ fn poly_evaluation_benchmarks(c: &mut Criterion) {
     group.finish();
 }
 
+fn poly_offset_evaluation_benchmarks(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Polynomial");
+
+    // Evaluation of a trace polynomial over a 2^20 LDE domain, as done by the STARK prover.
+    let blowup_factor = 4;
+    let poly = stark252_utils::rand_poly(18);
+    let offset = FE::from(3);
+    group.throughput(criterion::Throughput::Elements(
+        (poly.coefficients().len() * blowup_factor) as u64,
+    ));
+    group.bench_with_input(
+        "evaluate_offset_fft_metal",
+        &(poly, offset),
+        |bench, (poly, offset)| {
+            bench.iter_with_large_drop(|| {
+                metal_functions::poly_evaluate_offset_fft(poly, blowup_factor, offset);
+            });
+        },
+    );
+
+    group.finish();
+}
+
 fn poly_interpolation_benchmarks(c: &mut Criterion) {
     let mut group = c.benchmark_group("Polynomial");
 
@@ -106,6 +129,7 @@ criterion_group!(
         twiddles_generation_benchmarks,
         bitrev_permutation_benchmarks,
         poly_evaluation_benchmarks,
+        poly_offset_evaluation_benchmarks,
         poly_interpolation_benchmarks,
 );
 
@@ -150,6 +150,13 @@ pub fn starkfield_ops_benchmarks(c: &mut Criterion) {
         });
     }
 
+    for i in input.clone().into_iter() {
+        group.bench_with_input(format!("batch inverse {:?}", &i.len()), &i, |bench, i| {
+            let i: Vec<F> = i.iter().map(|(x, _)| *x).collect();
+            bench.iter(|| black_box(F::batch_inverse(black_box(&i)).unwrap()));
+        });
+    }
+
     for i in input.clone().into_iter() {
         group.bench_with_input(format!("div {:?}", &i.len()), &i, |bench, i| {
             bench.iter(|| {
@@ -75,4 +75,13 @@ pub fn polynomial_benchmarks(c: &mut Criterion) {
             criterion::BatchSize::SmallInput,
         );
     });
+
+    group.bench_function(
+        "div by 'x - b' with Ruffini into a new polynomial",
+        |bench| {
+            let poly = rand_poly(order);
+            let b = rand_field_elements(1)[0];
+            bench.iter(|| black_box(&poly).ruffini_division(black_box(&b)));
+        },
+    );
 }
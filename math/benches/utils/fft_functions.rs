@@ -34,6 +34,25 @@ pub fn poly_evaluate_fft(poly: &Polynomial<FE>) -> Vec<FE> {
     Polynomial::evaluate_fft::<F>(poly, black_box(1), black_box(None)).unwrap()
 }
 
+pub fn poly_evaluate_offset_fft(poly: &Polynomial<FE>, blowup_factor: usize) -> Vec<FE> {
+    Polynomial::evaluate_offset_fft::<F>(poly, blowup_factor, None, &FE::from(3)).unwrap()
+}
+
+pub fn poly_evaluate_offset_fft_with_twiddles(
+    poly: &Polynomial<FE>,
+    blowup_factor: usize,
+    twiddles: &[FE],
+) -> Vec<FE> {
+    Polynomial::evaluate_offset_fft_with_twiddles::<F>(
+        poly,
+        blowup_factor,
+        None,
+        &FE::from(3),
+        twiddles,
+    )
+    .unwrap()
+}
+
 pub fn poly_interpolate_fft(evals: &[FE]) {
     Polynomial::interpolate_fft::<F>(evals).unwrap();
 }
@@ -23,6 +23,9 @@ pub fn bitrev_permute(input: &[FE]) {
 pub fn poly_evaluate_fft(poly: &Polynomial<FE>) {
     Polynomial::evaluate_fft::<F>(poly, 1, None).unwrap();
 }
+pub fn poly_evaluate_offset_fft(poly: &Polynomial<FE>, blowup_factor: usize, offset: &FE) {
+    Polynomial::evaluate_offset_fft::<F>(poly, blowup_factor, None, offset).unwrap();
+}
 pub fn poly_interpolate_fft(evals: &[FE]) {
     Polynomial::interpolate_fft::<F>(evals).unwrap();
 }
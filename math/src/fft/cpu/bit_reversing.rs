@@ -8,6 +8,16 @@ pub fn in_place_bit_reverse_permute<E>(input: &mut [E]) {
     }
 }
 
+/// Returns a copy of `input` in bit-reversed order, that is, with the element at position
+/// `i` moved to position `reverse_index(i, input.len())`. Requires input length to be a power
+/// of two.
+#[cfg(feature = "alloc")]
+pub fn bit_reverse_permute<E: Clone>(input: &[E]) -> alloc::vec::Vec<E> {
+    let mut output = input.to_vec();
+    in_place_bit_reverse_permute(&mut output);
+    output
+}
+
 /// Reverses the `log2(size)` first bits of `i`
 pub fn reverse_index(i: usize, size: u64) -> usize {
     if size == 1 {
@@ -41,6 +51,18 @@ mod test {
         );
     }
 
+    #[test]
+    fn bit_reverse_permute_moves_each_element_to_its_reversed_index() {
+        let input: Vec<usize> = (0..8).collect();
+        let permuted = bit_reverse_permute(&input);
+
+        assert_eq!(permuted, [0, 4, 2, 6, 1, 5, 3, 7]);
+        for (i, element) in input.iter().enumerate() {
+            assert_eq!(&permuted[reverse_index(i, 8)], element);
+        }
+        assert_eq!(bit_reverse_permute(&permuted), input);
+    }
+
     #[test]
     fn bit_reverse_permutation_edge_case() {
         let mut edge_case = [0];
@@ -43,3 +43,50 @@ where
     let scale_factor = FieldElement::from(fft_evals.len() as u64).inv().unwrap();
     Ok(Polynomial::new(&coeffs).scale_coeffs(&scale_factor))
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::field::{
+        fields::fft_friendly::stark_252_prime_field::Stark252PrimeField, traits::IsFFTField,
+    };
+    use proptest::{collection, prelude::*};
+
+    use super::*;
+
+    type F = Stark252PrimeField;
+    type FE = FieldElement<F>;
+
+    prop_compose! {
+        fn field_element()(num in any::<u64>()) -> FE {
+            FE::from(num)
+        }
+    }
+
+    prop_compose! {
+        fn poly(max_exp: u8)(exp in 0..max_exp)(coeffs in collection::vec(field_element(), 1 << exp)) -> Polynomial<FE> {
+            Polynomial::new(&coeffs)
+        }
+    }
+
+    proptest! {
+        // The Metal path of `evaluate_offset_fft` must give the evaluations at `offset * w^i`
+        // in natural order, as the CPU path does.
+        #[test]
+        fn test_metal_offset_fft_matches_naive_evaluation(
+            poly in poly(6),
+            blowup_factor in prop_oneof![Just(1usize), Just(2), Just(4)],
+            offset in field_element(),
+        ) {
+            let evaluations =
+                Polynomial::evaluate_offset_fft::<F>(&poly, blowup_factor, None, &offset).unwrap();
+
+            let order = evaluations.len().trailing_zeros();
+            let root = F::get_primitive_root_of_unity(order.into()).unwrap();
+            let expected: Vec<_> = (0..evaluations.len() as u64)
+                .map(|i| poly.evaluate(&(&offset * root.pow(i))))
+                .collect();
+
+            prop_assert_eq!(evaluations, expected);
+        }
+    }
+}
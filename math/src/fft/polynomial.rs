@@ -1,3 +1,4 @@
+use crate::fft::cpu::bit_reversing::in_place_bit_reverse_permute;
 use crate::fft::errors::FFTError;
 
 use crate::field::traits::{IsField, IsSubFieldOf};
@@ -17,6 +18,16 @@ use crate::fft::gpu::metal::polynomial::{evaluate_fft_metal, interpolate_fft_met
 
 use super::cpu::{ops, roots_of_unity};
 
+/// The order in which FFT evaluations are returned.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FftOrder {
+    /// The evaluation at `offset * w^i` is at position `i`.
+    Natural,
+    /// The evaluation at `offset * w^i` is at position `reverse_index(i, N)`, that is,
+    /// the natural order with the bits of the indices reversed.
+    BitReversed,
+}
+
 impl<E: IsField> Polynomial<FieldElement<E>> {
     /// Returns `N` evaluations of this polynomial using FFT over a domain in a subfield F of E (so the results
     /// are P(w^i), with w being a primitive root of unity).
@@ -41,7 +52,14 @@ impl<E: IsField> Polynomial<FieldElement<E>> {
         #[cfg(feature = "metal")]
         {
             if !F::field_name().is_empty() {
-                Ok(evaluate_fft_metal::<F, E>(&coeffs)?)
+                // There may be no Metal device, or no kernel for this field.
+                evaluate_fft_metal::<F, E>(&coeffs).or_else(|error| {
+                    println!(
+                        "GPU evaluation failed: {}. Program will fallback to CPU.",
+                        error
+                    );
+                    evaluate_fft_cpu::<F, E>(&coeffs)
+                })
             } else {
                 println!(
                     "GPU evaluation failed for field {}. Program will fallback to CPU.",
@@ -81,6 +99,85 @@ impl<E: IsField> Polynomial<FieldElement<E>> {
         Polynomial::evaluate_fft::<F>(&scaled, blowup_factor, domain_size)
     }
 
+    /// Same as [Self::evaluate_offset_fft()], returning the evaluations in the given `order`.
+    /// [Self::evaluate_offset_fft()] always returns them in [FftOrder::Natural] order.
+    pub fn evaluate_offset_fft_with_order<F: IsFFTField + IsSubFieldOf<E>>(
+        poly: &Polynomial<FieldElement<E>>,
+        blowup_factor: usize,
+        domain_size: Option<usize>,
+        offset: &FieldElement<F>,
+        order: FftOrder,
+    ) -> Result<Vec<FieldElement<E>>, FFTError> {
+        let mut evaluations =
+            Polynomial::evaluate_offset_fft(poly, blowup_factor, domain_size, offset)?;
+        if order == FftOrder::BitReversed {
+            in_place_bit_reverse_permute(&mut evaluations);
+        }
+        Ok(evaluations)
+    }
+
+    /// Same as [Self::evaluate_offset_fft()], returning the evaluations in
+    /// [FftOrder::BitReversed] order, the order in which the STARK prover commits to them.
+    /// In this order the evaluations at the symmetric points `x` and `-x` of the domain are
+    /// next to each other, at positions `2i` and `2i + 1`.
+    pub fn evaluate_offset_fft_bit_reversed<F: IsFFTField + IsSubFieldOf<E>>(
+        poly: &Polynomial<FieldElement<E>>,
+        blowup_factor: usize,
+        domain_size: Option<usize>,
+        offset: &FieldElement<F>,
+    ) -> Result<Vec<FieldElement<E>>, FFTError> {
+        Polynomial::evaluate_offset_fft_with_order(
+            poly,
+            blowup_factor,
+            domain_size,
+            offset,
+            FftOrder::BitReversed,
+        )
+    }
+
+    /// Same as [Self::evaluate_offset_fft()], computed on the CPU with precomputed `twiddles`:
+    /// the twiddle factors of an FFT of size at least `N`, as returned by `get_twiddles` with
+    /// `RootsConfig::BitReverse`. Only the first `N / 2` of them are used, so the twiddles of the
+    /// largest FFT can be reused by all the smaller ones. If there are fewer, they are computed
+    /// again as in [Self::evaluate_offset_fft()].
+    pub fn evaluate_offset_fft_with_twiddles<F: IsFFTField + IsSubFieldOf<E>>(
+        poly: &Polynomial<FieldElement<E>>,
+        blowup_factor: usize,
+        domain_size: Option<usize>,
+        offset: &FieldElement<F>,
+        twiddles: &[FieldElement<F>],
+    ) -> Result<Vec<FieldElement<E>>, FFTError> {
+        let domain_size = domain_size.unwrap_or(0);
+        let len = core::cmp::max(poly.coeff_len(), domain_size).next_power_of_two() * blowup_factor;
+        let mut coeffs = poly.scale(offset).coefficients().to_vec();
+        coeffs.resize(len, FieldElement::zero());
+
+        if twiddles.len() < len / 2 {
+            evaluate_fft_cpu::<F, E>(&coeffs)
+        } else {
+            ops::fft(&coeffs, twiddles)
+        }
+    }
+
+    /// Same as [Self::interpolate_offset_fft()], computed on the CPU with precomputed
+    /// `inverse_twiddles`: the twiddle factors of an FFT of size at least `N`, as returned by
+    /// `get_twiddles` with `RootsConfig::BitReverseInversed`. If there are fewer than `N / 2`,
+    /// they are computed again as in [Self::interpolate_offset_fft()].
+    pub fn interpolate_offset_fft_with_twiddles<F: IsFFTField + IsSubFieldOf<E>>(
+        fft_evals: &[FieldElement<E>],
+        offset: &FieldElement<F>,
+        inverse_twiddles: &[FieldElement<F>],
+    ) -> Result<Polynomial<FieldElement<E>>, FFTError> {
+        let scaled = if inverse_twiddles.len() < fft_evals.len() / 2 {
+            interpolate_fft_cpu::<F, E>(fft_evals)?
+        } else {
+            let coeffs = ops::fft(fft_evals, inverse_twiddles)?;
+            let scale_factor = FieldElement::from(fft_evals.len() as u64).inv().unwrap();
+            Polynomial::new(&coeffs).scale_coeffs(&scale_factor)
+        };
+        Ok(scaled.scale(&offset.inv().unwrap()))
+    }
+
     /// Returns a new polynomial that interpolates `(w^i, fft_evals[i])`, with `w` being a
     /// Nth primitive root of unity in a subfield F of E, and `i in 0..N`, with `N = fft_evals.len()`.
     /// This is considered to be the inverse operation of [Self::evaluate_fft()].
@@ -90,7 +187,13 @@ impl<E: IsField> Polynomial<FieldElement<E>> {
         #[cfg(feature = "metal")]
         {
             if !F::field_name().is_empty() {
-                Ok(interpolate_fft_metal::<F, E>(fft_evals)?)
+                interpolate_fft_metal::<F, E>(fft_evals).or_else(|error| {
+                    println!(
+                        "GPU interpolation failed: {}. Program will fallback to CPU.",
+                        error
+                    );
+                    interpolate_fft_cpu::<F, E>(fft_evals)
+                })
             } else {
                 println!(
                     "GPU interpolation failed for field {}. Program will fallback to CPU.",
@@ -247,6 +350,16 @@ mod tests {
         (fft_poly, naive_poly)
     }
 
+    fn gen_fft_coset_interpolate_and_evaluate<F: IsFFTField>(
+        poly: Polynomial<FieldElement<F>>,
+        offset: &FieldElement<F>,
+    ) -> (Polynomial<FieldElement<F>>, Polynomial<FieldElement<F>>) {
+        let eval = Polynomial::evaluate_offset_fft::<F>(&poly, 1, None, offset).unwrap();
+        let new_poly = Polynomial::interpolate_offset_fft::<F>(&eval, offset).unwrap();
+
+        (poly, new_poly)
+    }
+
     fn gen_fft_interpolate_and_evaluate<F: IsFFTField>(
         poly: Polynomial<FieldElement<F>>,
     ) -> (Polynomial<FieldElement<F>>, Polynomial<FieldElement<F>>) {
@@ -259,6 +372,7 @@ mod tests {
     #[cfg(all(not(feature = "metal"), not(feature = "cuda")))]
     mod u64_field_tests {
         use super::*;
+        use crate::fft::cpu::bit_reversing::reverse_index;
         use crate::field::test_fields::u64_test_field::U64TestField;
 
         // FFT related tests
@@ -341,6 +455,132 @@ mod tests {
 
                 prop_assert_eq!(poly, new_poly);
             }
+
+            // Property-based test that ensures coset interpolation is the inverse operation of coset evaluation.
+            #[test]
+            fn test_fft_bit_reversed_order_permutes_natural_order(poly in poly(8), offset in offset(), blowup_factor in powers_of_two(4)) {
+                let natural = Polynomial::evaluate_offset_fft_with_order(&poly, blowup_factor, None, &offset, FftOrder::Natural).unwrap();
+                let bit_reversed = Polynomial::evaluate_offset_fft_with_order(&poly, blowup_factor, None, &offset, FftOrder::BitReversed).unwrap();
+
+                let n = natural.len();
+                for (i, evaluation) in natural.iter().enumerate() {
+                    prop_assert_eq!(evaluation, &bit_reversed[reverse_index(i, n as u64)]);
+                }
+            }
+
+            #[test]
+            fn test_fft_bit_reversed_pairs_symmetric_points(poly in poly(8), offset in offset(), blowup_factor in powers_of_two(4)) {
+                let natural = Polynomial::evaluate_offset_fft(&poly, blowup_factor, None, &offset).unwrap();
+                let bit_reversed = Polynomial::evaluate_offset_fft_bit_reversed(&poly, blowup_factor, None, &offset).unwrap();
+
+                // x = offset * w^j and -x = offset * w^(j + n / 2) are at positions 2i and 2i + 1.
+                let n = natural.len();
+                for pair in 0..n / 2 {
+                    let j = reverse_index(2 * pair, n as u64);
+                    prop_assert_eq!(&bit_reversed[2 * pair], &natural[j]);
+                    prop_assert_eq!(&bit_reversed[2 * pair + 1], &natural[j + n / 2]);
+                }
+            }
+
+            #[test]
+            fn test_fft_coset_interpolate_is_inverse_of_evaluate(poly in poly(4)
+                                                           .prop_filter("Avoid polynomials of size not power of two",
+                                                                        |poly| poly.coeff_len().is_power_of_two()), offset in offset()) {
+                let (poly, new_poly) = gen_fft_coset_interpolate_and_evaluate(poly, &offset);
+
+                prop_assert_eq!(poly, new_poly);
+            }
+
+            // Property-based test that ensures interpolation rejects inputs whose size is not a power of two.
+            #[test]
+            fn test_fft_interpolate_fails_with_non_power_of_two_input(evals in non_power_of_two_sized_field_vec(4)) {
+                prop_assert!(matches!(
+                    Polynomial::interpolate_fft::<F>(&evals),
+                    Err(FFTError::InputError(len)) if len == evals.len()
+                ));
+            }
+        }
+
+        // Reconstructs the evaluation of a polynomial at the i-th point h wⁱ of a coset from the
+        // evaluations of its parts over the coset with offset hᵈ, as the verifier does with the
+        // parts of the composition polynomial.
+        #[test]
+        fn polynomial_is_reconstructed_from_its_parts_on_a_coset() {
+            let number_of_parts = 2;
+            let poly = Polynomial::new(&(1u64..=16).map(FE::from).collect::<Vec<_>>());
+            let offset = FE::from(3);
+            let coset_evaluations =
+                Polynomial::evaluate_offset_fft::<F>(&poly, 1, None, &offset).unwrap();
+            let parts_offset = offset.pow(number_of_parts as u64);
+            let parts_coset_evaluations: Vec<_> = poly
+                .break_in_parts(number_of_parts)
+                .iter()
+                .map(|part| {
+                    Polynomial::evaluate_offset_fft::<F>(part, 1, Some(16), &parts_offset).unwrap()
+                })
+                .collect();
+            let coset = get_powers_of_primitive_root_coset(4, 16, &offset).unwrap();
+
+            for (index, point) in coset.iter().enumerate() {
+                let parts_evaluations: Vec<_> = parts_coset_evaluations
+                    .iter()
+                    .map(|evaluations| evaluations[index * number_of_parts % 16].clone())
+                    .collect();
+                assert_eq!(
+                    Polynomial::evaluate_from_parts(&parts_evaluations, point),
+                    coset_evaluations[index]
+                );
+            }
+            assert_eq!(
+                Polynomial::interpolate_offset_fft::<F>(&coset_evaluations, &offset).unwrap(),
+                poly
+            );
+        }
+
+        #[test]
+        fn fft_with_twiddles_of_a_larger_fft_matches_fft() {
+            let poly = Polynomial::new(&(1u64..=16).map(FE::from).collect::<Vec<_>>());
+            let offset = FE::from(3);
+            let twiddles = roots_of_unity::get_twiddles::<F>(8, RootsConfig::BitReverse).unwrap();
+            let inverse_twiddles =
+                roots_of_unity::get_twiddles::<F>(8, RootsConfig::BitReverseInversed).unwrap();
+
+            for blowup_factor in [1, 2, 4] {
+                let evaluations =
+                    Polynomial::evaluate_offset_fft::<F>(&poly, blowup_factor, None, &offset)
+                        .unwrap();
+                let evaluations_with_twiddles = Polynomial::evaluate_offset_fft_with_twiddles(
+                    &poly,
+                    blowup_factor,
+                    None,
+                    &offset,
+                    &twiddles,
+                )
+                .unwrap();
+                assert_eq!(evaluations_with_twiddles, evaluations);
+                assert_eq!(
+                    Polynomial::interpolate_offset_fft_with_twiddles(
+                        &evaluations,
+                        &offset,
+                        &inverse_twiddles
+                    )
+                    .unwrap(),
+                    poly
+                );
+            }
+
+            // Too few twiddles are computed again.
+            assert_eq!(
+                Polynomial::evaluate_offset_fft_with_twiddles(
+                    &poly,
+                    4,
+                    None,
+                    &offset,
+                    &twiddles[..4]
+                )
+                .unwrap(),
+                Polynomial::evaluate_offset_fft::<F>(&poly, 4, None, &offset).unwrap()
+            );
         }
 
         #[test]
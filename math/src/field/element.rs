@@ -64,6 +64,14 @@ impl<F: IsField> FieldElement<F> {
         Ok(())
     }
 
+    /// Returns the inverses of `numbers`, in the same order, computing them with a single
+    /// inversion as `inplace_batch_inverse` does. Fails if any of them is zero.
+    pub fn batch_inverse(numbers: &[Self]) -> Result<alloc::vec::Vec<Self>, FieldError> {
+        let mut inverses = numbers.to_vec();
+        Self::inplace_batch_inverse(&mut inverses)?;
+        Ok(inverses)
+    }
+
     #[inline(always)]
     pub fn to_subfield_vec<S>(self) -> alloc::vec::Vec<FieldElement<S>>
     where
@@ -429,13 +437,25 @@ where
         &self.value
     }
 
-    /// Returns the multiplicative inverse of `self`
+    /// Returns the multiplicative inverse of `self`, or `FieldError::InvZeroError` if `self`
+    /// is zero.
     #[inline(always)]
     pub fn inv(&self) -> Result<Self, FieldError> {
         let value = F::inv(&self.value)?;
         Ok(Self { value })
     }
 
+    /// Returns the multiplicative inverse of `self`, for callers that know it is non-zero,
+    /// such as the evaluations of a zerofier outside of the points it vanishes on.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` is zero.
+    #[inline(always)]
+    pub fn inv_unchecked(&self) -> Self {
+        self.inv().expect("inverse of zero")
+    }
+
     /// Returns the square of `self`
     #[inline(always)]
     pub fn square(&self) -> Self {
@@ -715,6 +735,7 @@ where
 #[cfg(test)]
 mod tests {
     use crate::field::element::FieldElement;
+    use crate::field::errors::FieldError;
     use crate::field::fields::fft_friendly::stark_252_prime_field::Stark252PrimeField;
     use crate::field::fields::u64_prime_field::U64PrimeField;
     use crate::field::test_fields::u64_test_field::U64TestField;
@@ -771,6 +792,13 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn batch_inverse_of_a_zero_fails() {
+        let numbers = [1u64, 0, 3].map(FieldElement::<U64PrimeField<41>>::from);
+        assert!(FieldElement::batch_inverse(&numbers).is_err());
+    }
+
     #[test]
     fn one_of_sqrt_roots_for_4_is_2() {
         type FrField = Stark252PrimeField;
@@ -881,7 +909,28 @@ mod tests {
         }
     }
 
+    #[test]
+    fn inverse_of_zero_is_an_error() {
+        let zero = FieldElement::<Stark252PrimeField>::zero();
+        assert!(matches!(zero.inv(), Err(FieldError::InvZeroError)));
+    }
+
+    #[test]
+    #[should_panic]
+    fn unchecked_inverse_of_zero_panics() {
+        FieldElement::<Stark252PrimeField>::zero().inv_unchecked();
+    }
+
     proptest! {
+        #[cfg(feature = "alloc")]
+        #[test]
+        fn test_inv_returns_the_inverse_of_non_zero_elements(x in field_element()) {
+            use alloc::format;
+
+            prop_assert_eq!(&x * x.inv().unwrap(), FieldElement::<Stark252PrimeField>::one());
+            prop_assert_eq!(x.inv_unchecked(), x.inv().unwrap());
+        }
+
         #[cfg(feature = "alloc")]
         #[test]
         fn test_inplace_batch_inverse_returns_inverses(vec in field_vec(10)) {
@@ -895,5 +944,19 @@ mod tests {
                 prop_assert_eq!(x * input[i], FieldElement::<Stark252PrimeField>::one());
             }
         }
+
+        #[cfg(feature = "alloc")]
+        #[test]
+        fn test_batch_inverse_matches_inv(vec in field_vec(10)) {
+            use alloc::format;
+
+            let input: Vec<_> = vec.into_iter().filter(|x| x != &FieldElement::<Stark252PrimeField>::zero()).collect();
+            let inverses = FieldElement::batch_inverse(&input).unwrap();
+
+            prop_assert_eq!(inverses.len(), input.len());
+            for (inverse, x) in inverses.iter().zip(&input) {
+                prop_assert_eq!(inverse, &x.inv().unwrap());
+            }
+        }
     }
 }
@@ -26,6 +26,8 @@ pub type Babybear31PrimeField =
 //In the future we should allow this with metal and cuda feature, and just dispatch it to the CPU until the implementation is done
 #[cfg(any(not(feature = "metal"), not(feature = "cuda")))]
 impl IsFFTField for Babybear31PrimeField {
+    /// `p - 1 = 15 * 2^27`, but the root of unity is of order `2^24`, so FFT domains hold up to
+    /// `2^24` elements.
     const TWO_ADICITY: u64 = 24;
 
     const TWO_ADIC_PRIMITVE_ROOT_OF_UNITY: Self::BaseType = UnsignedInteger { limbs: [21] };
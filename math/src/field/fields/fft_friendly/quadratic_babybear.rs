@@ -1,7 +1,13 @@
-use crate::field::{
-    element::FieldElement, extensions::quadratic::*,
-    fields::fft_friendly::babybear::Babybear31PrimeField,
+use crate::{
+    errors::ByteConversionError,
+    field::{
+        element::FieldElement, extensions::quadratic::*,
+        fields::fft_friendly::babybear::Babybear31PrimeField,
+    },
+    traits::ByteConversion,
 };
+#[cfg(feature = "alloc")]
+use crate::traits::AsBytes;
 
 /// Quadratic field extension of Babybear
 pub type QuadraticBabybearField =
@@ -17,6 +23,54 @@ impl HasQuadraticNonResidue<Babybear31PrimeField> for Babybear31PrimeField {
 pub type QuadraticBabybearFieldElement =
     QuadraticExtensionFieldElement<Babybear31PrimeField, Babybear31PrimeField>;
 
+/// The bytes of the two coordinates of the element, one after the other.
+#[cfg(feature = "alloc")]
+impl AsBytes for QuadraticBabybearFieldElement {
+    fn as_bytes(&self) -> alloc::vec::Vec<u8> {
+        let [a, b] = self.value();
+        let mut bytes = a.as_bytes();
+        bytes.extend(b.as_bytes());
+        bytes
+    }
+}
+
+/// The bytes of an element are the ones of its two coordinates, one after the other. When
+/// reading an element, the bytes are split in two halves, one for each coordinate, so that
+/// it can be built from the 32 random bytes of a transcript.
+impl ByteConversion for QuadraticBabybearFieldElement {
+    #[cfg(feature = "alloc")]
+    fn to_bytes_be(&self) -> alloc::vec::Vec<u8> {
+        let [a, b] = self.value();
+        let mut bytes = a.to_bytes_be().to_vec();
+        bytes.extend(b.to_bytes_be());
+        bytes
+    }
+
+    #[cfg(feature = "alloc")]
+    fn to_bytes_le(&self) -> alloc::vec::Vec<u8> {
+        let [a, b] = self.value();
+        let mut bytes = a.to_bytes_le().to_vec();
+        bytes.extend(b.to_bytes_le());
+        bytes
+    }
+
+    fn from_bytes_be(bytes: &[u8]) -> Result<Self, ByteConversionError> {
+        let (a, b) = bytes.split_at(bytes.len() / 2);
+        Ok(Self::new([
+            FieldElement::from_bytes_be(a)?,
+            FieldElement::from_bytes_be(b)?,
+        ]))
+    }
+
+    fn from_bytes_le(bytes: &[u8]) -> Result<Self, ByteConversionError> {
+        let (a, b) = bytes.split_at(bytes.len() / 2);
+        Ok(Self::new([
+            FieldElement::from_bytes_le(a)?,
+            FieldElement::from_bytes_le(b)?,
+        ]))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -71,6 +125,15 @@ mod tests {
         assert_eq!(a / b, expected_result);
     }
 
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn bytes_of_an_element_are_read_back() {
+        let a = Fee::new([FE::from(12), FE::from(5)]);
+
+        assert_eq!(Fee::from_bytes_be(&a.to_bytes_be()).unwrap(), a);
+        assert_eq!(Fee::from_bytes_le(&a.to_bytes_le()).unwrap(), a);
+    }
+
     #[test]
     fn test_conjugate_quadratic() {
         let a = Fee::new([FE::from(12), FE::from(5)]);
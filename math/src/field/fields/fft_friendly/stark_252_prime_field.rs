@@ -17,6 +17,7 @@ impl IsModulus<U256> for MontgomeryConfigStark252PrimeField {
 pub type Stark252PrimeField = U256PrimeField<MontgomeryConfigStark252PrimeField>;
 
 impl IsFFTField for Stark252PrimeField {
+    /// `p - 1 = 2^192 * (2^59 + 17)`, so FFT domains hold up to `2^192` elements.
     const TWO_ADICITY: u64 = 192;
     // Change this line for a new function like `from_limbs`.
     const TWO_ADIC_PRIMITVE_ROOT_OF_UNITY: U256 = UnsignedInteger::from_hex_unchecked(
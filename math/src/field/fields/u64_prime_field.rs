@@ -16,6 +16,7 @@ pub type F17 = U64PrimeField<17>;
 pub type FE17 = U64FieldElement<17>;
 
 impl IsFFTField for F17 {
+    /// `17 - 1 = 2^4`, so FFT domains hold up to 16 elements.
     const TWO_ADICITY: u64 = 4;
     const TWO_ADIC_PRIMITVE_ROOT_OF_UNITY: u64 = 3;
 }
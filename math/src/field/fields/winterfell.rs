@@ -19,6 +19,7 @@ use winter_math::{ExtensionOf, FieldElement as IsWinterfellFieldElement, StarkFi
 // its quadratic extension `QuadFelt`.
 
 impl IsFFTField for Felt {
+    /// `p - 1 = 2^32 * (2^32 - 1)`, so FFT domains hold up to `2^32` elements.
     const TWO_ADICITY: u64 = <Felt as StarkField>::TWO_ADICITY as u64;
     const TWO_ADIC_PRIMITVE_ROOT_OF_UNITY: Self::BaseType = Felt::TWO_ADIC_ROOT_OF_UNITY;
 }
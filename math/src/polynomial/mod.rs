@@ -42,7 +42,8 @@ impl<F: IsField> Polynomial<FieldElement<F>> {
 
     /// Returns a polynomial that interpolates the points with x coordinates and y coordinates given by
     /// `xs` and `ys`.
-    /// `xs` and `ys` must be the same length, and `xs` values should be unique. If not, panics.
+    /// `xs` and `ys` must be the same length, and `xs` values should be unique. If not, returns an
+    /// `InterpolateError`.
     pub fn interpolate(
         xs: &[FieldElement<F>],
         ys: &[FieldElement<F>],
@@ -112,20 +113,44 @@ impl<F: IsField> Polynomial<FieldElement<F>> {
         input.iter().map(|x| self.evaluate(x)).collect()
     }
 
+    /// Returns the degree of the polynomial, ignoring the trailing zero coefficients it may
+    /// have if it was built from its `coefficients` field directly or padded. The degree of
+    /// the zero polynomial is 0.
     pub fn degree(&self) -> usize {
-        if self.coefficients.is_empty() {
-            0
-        } else {
-            self.coefficients.len() - 1
-        }
+        self.coefficients
+            .iter()
+            .rposition(|coefficient| *coefficient != FieldElement::zero())
+            .unwrap_or(0)
     }
 
+    /// Returns the coefficient of the highest power of X with a non-zero coefficient, or zero
+    /// for the zero polynomial.
     pub fn leading_coefficient(&self) -> FieldElement<F> {
-        if let Some(coefficient) = self.coefficients.last() {
-            coefficient.clone()
-        } else {
-            FieldElement::zero()
-        }
+        self.coefficients
+            .iter()
+            .rev()
+            .find(|coefficient| **coefficient != FieldElement::zero())
+            .cloned()
+            .unwrap_or_else(FieldElement::zero)
+    }
+
+    /// Returns whether all the coefficients of the polynomial are zero, including the case of
+    /// a polynomial without coefficients.
+    pub fn is_zero(&self) -> bool {
+        self.coefficients
+            .iter()
+            .all(|coefficient| *coefficient == FieldElement::zero())
+    }
+
+    /// Removes the trailing zero coefficients, so that the polynomial compares equal to the
+    /// one built with `Polynomial::new` from the same coefficients.
+    fn remove_trailing_zeros(&mut self) {
+        let len = self
+            .coefficients
+            .iter()
+            .rposition(|coefficient| *coefficient != FieldElement::zero())
+            .map_or(0, |degree| degree + 1);
+        self.coefficients.truncate(len);
     }
 
     /// Returns coefficients of the polynomial as an array
@@ -148,8 +173,12 @@ impl<F: IsField> Polynomial<FieldElement<F>> {
             core::mem::swap(coeff, &mut c);
         }
         self.coefficients.pop();
+        self.remove_trailing_zeros();
     }
 
+    /// Computes the quotient of the division by `x - b` with Ruffini's rule, which takes a
+    /// linear number of operations instead of the quadratic ones of the generic division.
+    /// The remainder, `self(b)`, is dropped.
     pub fn ruffini_division<L>(&self, b: &FieldElement<L>) -> Polynomial<FieldElement<L>>
     where
         L: IsField,
@@ -179,7 +208,7 @@ impl<F: IsField> Polynomial<FieldElement<F>> {
             let mut n = self;
             let mut q: Vec<FieldElement<F>> = vec![FieldElement::zero(); n.degree() + 1];
             let denominator = dividend.leading_coefficient().inv().unwrap();
-            while n != Polynomial::zero() && n.degree() >= dividend.degree() {
+            while !n.is_zero() && n.degree() >= dividend.degree() {
                 let new_coefficient = n.leading_coefficient() * &denominator;
                 q[n.degree() - dividend.degree()] = new_coefficient.clone();
                 let d = dividend.mul_with_ref(&Polynomial::new_monomial(
@@ -198,12 +227,12 @@ impl<F: IsField> Polynomial<FieldElement<F>> {
     }
 
     pub fn mul_with_ref(&self, factor: &Self) -> Self {
-        let degree = self.degree() + factor.degree();
-        let mut coefficients = vec![FieldElement::zero(); degree + 1];
-
-        if self.coefficients.is_empty() || factor.coefficients.is_empty() {
-            Polynomial::new(&[FieldElement::zero()])
+        if self.is_zero() || factor.is_zero() {
+            Polynomial::zero()
         } else {
+            let degree = self.degree() + factor.degree();
+            let mut coefficients = vec![FieldElement::zero(); degree + 1];
+
             for i in 0..=factor.degree() {
                 for j in 0..=self.degree() {
                     coefficients[i + j] += &factor.coefficients[i] * &self.coefficients[j];
@@ -213,6 +242,7 @@ impl<F: IsField> Polynomial<FieldElement<F>> {
         }
     }
 
+    /// Returns the polynomial `p(factor * x)`, where `p` is `self`.
     pub fn scale<S: IsSubFieldOf<F>>(&self, factor: &FieldElement<S>) -> Self {
         let scaled_coefficients = self
             .coefficients
@@ -222,20 +252,25 @@ impl<F: IsField> Polynomial<FieldElement<F>> {
             }))
             .map(|(coeff, power)| power * coeff)
             .collect();
-        Self {
+        let mut scaled = Self {
             coefficients: scaled_coefficients,
-        }
+        };
+        scaled.remove_trailing_zeros();
+        scaled
     }
 
+    /// Returns the polynomial `factor * p(x)`, where `p` is `self`.
     pub fn scale_coeffs(&self, factor: &FieldElement<F>) -> Self {
         let scaled_coefficients = self
             .coefficients
             .iter()
             .map(|coeff| factor * coeff)
             .collect();
-        Self {
+        let mut scaled = Self {
             coefficients: scaled_coefficients,
-        }
+        };
+        scaled.remove_trailing_zeros();
+        scaled
     }
 
     /// Returns a vector of polynomials [p₀, p₁, ..., p_{d-1}], where d is `number_of_parts`, such that `self` equals
@@ -258,6 +293,19 @@ impl<F: IsField> Polynomial<FieldElement<F>> {
         parts
     }
 
+    /// Returns the evaluation at `x` of a polynomial broken with `break_in_parts`, given the
+    /// evaluations of its parts p₀, p₁, ..., p_{d-1} at xᵈ, where d is the number of parts. That is,
+    /// p₀(xᵈ) + x p₁(xᵈ) + ... + x^(d-1) p_{d-1}(xᵈ).
+    pub fn evaluate_from_parts(
+        parts_evaluations: &[FieldElement<F>],
+        x: &FieldElement<F>,
+    ) -> FieldElement<F> {
+        parts_evaluations
+            .iter()
+            .rev()
+            .fold(FieldElement::zero(), |acc, evaluation| acc * x + evaluation)
+    }
+
     pub fn to_extension<L: IsField>(self) -> Polynomial<FieldElement<L>>
     where
         F: IsSubFieldOf<L>,
@@ -497,9 +545,11 @@ where
             .iter()
             .map(|value| &multiplicand * value)
             .collect();
-        Polynomial {
+        let mut product = Polynomial {
             coefficients: new_coefficients,
-        }
+        };
+        product.remove_trailing_zeros();
+        product
     }
 }
 
@@ -1070,6 +1120,40 @@ mod tests {
         assert_eq!(FE::new(0), p.evaluate(&FE::new(0)));
     }
 
+    #[test]
+    fn interpolate_goes_through_four_arbitrary_points() {
+        let xs = [FE::new(3), FE::new(8), FE::new(13), FE::new(21)];
+        let ys = [FE::new(9), FE::new(0), FE::new(17), FE::new(4)];
+        let p = Polynomial::interpolate(&xs, &ys).unwrap();
+
+        assert!(p.degree() <= 3);
+        for (x, y) in xs.iter().zip(ys.iter()) {
+            assert_eq!(p.evaluate(x), *y);
+        }
+    }
+
+    #[test]
+    fn interpolate_fails_with_repeated_xs() {
+        let xs = [FE::new(3), FE::new(8), FE::new(3)];
+        let ys = [FE::new(1), FE::new(2), FE::new(3)];
+
+        assert!(matches!(
+            Polynomial::interpolate(&xs, &ys),
+            Err(InterpolateError::NonUniqueXs)
+        ));
+    }
+
+    #[test]
+    fn interpolate_fails_with_unequal_lengths() {
+        let xs = [FE::new(3), FE::new(8)];
+        let ys = [FE::new(1)];
+
+        assert!(matches!(
+            Polynomial::interpolate(&xs, &ys),
+            Err(InterpolateError::UnequalLengths(2, 1))
+        ));
+    }
+
     #[test]
     fn composition_works() {
         let p = Polynomial::new(&[FE::new(0), FE::new(2)]);
@@ -1094,6 +1178,38 @@ mod tests {
         assert_eq!(p1, &p1_expected);
     }
 
+    #[test]
+    fn degree_and_leading_coefficient_ignore_trailing_zeros() {
+        let p = Polynomial {
+            coefficients: vec![FE::new(1), FE::new(2), FE::zero(), FE::zero()],
+        };
+        assert_eq!(p.degree(), 1);
+        assert_eq!(p.leading_coefficient(), FE::new(2));
+        assert!(!p.is_zero());
+    }
+
+    #[test]
+    fn polynomial_with_only_zero_coefficients_is_zero() {
+        let p = Polynomial {
+            coefficients: vec![FE::zero(); 3],
+        };
+        assert!(p.is_zero());
+        assert!(Polynomial::<FE>::zero().is_zero());
+        assert_eq!(p.degree(), 0);
+        assert_eq!(p.leading_coefficient(), FE::zero());
+        assert_eq!(&p * &polynomial_a(), Polynomial::zero());
+    }
+
+    #[test]
+    fn product_by_zero_scalar_is_normalized() {
+        assert_eq!(polynomial_a() * FE::zero(), Polynomial::zero());
+        assert_eq!(polynomial_a().scale_coeffs(&FE::zero()), Polynomial::zero());
+        assert_eq!(
+            polynomial_a().scale(&FE::zero()),
+            Polynomial::new(&[FE::new(1)])
+        );
+    }
+
     use alloc::format;
     use proptest::prelude::*;
     proptest! {
@@ -1111,6 +1227,30 @@ mod tests {
         }
     }
 
+    proptest! {
+        #[test]
+        fn scale_evaluates_at_the_scaled_point(p in any::<Vec<u64>>(), factor in any::<u64>(), x in any::<u64>()) {
+            let p: Vec<_> = p.into_iter().map(FE::from).collect();
+            let p = Polynomial::new(&p);
+            let factor = FE::from(factor);
+            let x = FE::from(x);
+
+            prop_assert_eq!(p.scale(&factor).evaluate(&x), p.evaluate(&(factor * x)));
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn scale_coeffs_equals_product_by_scalar(p in any::<Vec<u64>>(), factor in any::<u64>()) {
+            let p: Vec<_> = p.into_iter().map(FE::from).collect();
+            let p = Polynomial::new(&p);
+            let factor = FE::from(factor);
+
+            prop_assert_eq!(p.scale_coeffs(&factor), &p * &factor);
+            prop_assert_eq!(&p * -FE::one(), -p);
+        }
+    }
+
     proptest! {
         #[test]
         fn ruffini_inplace_equals_ruffini(p in any::<Vec<u64>>(), b in any::<u64>()) {
@@ -1122,4 +1262,26 @@ mod tests {
             prop_assert_eq!(q, p);
         }
     }
+
+    proptest! {
+        #[test]
+        fn degree_of_product_is_the_sum_of_degrees(p in any::<Vec<u64>>(), q in any::<Vec<u64>>()) {
+            let p = Polynomial::new(&p.into_iter().map(FE::from).collect::<Vec<_>>());
+            let q = Polynomial::new(&q.into_iter().map(FE::from).collect::<Vec<_>>());
+            prop_assume!(!p.is_zero() && !q.is_zero());
+
+            prop_assert_eq!((&p * &q).degree(), p.degree() + q.degree());
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn difference_with_itself_is_zero(p in any::<Vec<u64>>()) {
+            let p = Polynomial::new(&p.into_iter().map(FE::from).collect::<Vec<_>>());
+            let difference = &p - &p;
+
+            prop_assert!(difference.is_zero());
+            prop_assert_eq!(difference, Polynomial::zero());
+        }
+    }
 }
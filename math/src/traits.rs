@@ -27,6 +27,41 @@ pub trait ByteConversion {
         Self: Sized;
 }
 
+/// The order of the bytes of an element's representation, for the places where the prover and
+/// the verifier must agree on it, like the data appended to a transcript.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(
+    any(
+        feature = "lambdaworks-serde-binary",
+        feature = "lambdaworks-serde-string"
+    ),
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub enum ByteOrder {
+    #[default]
+    BigEndian,
+    LittleEndian,
+}
+
+impl ByteOrder {
+    /// Returns the byte representation of `value` in this order.
+    #[cfg(feature = "alloc")]
+    pub fn to_bytes<T: ByteConversion>(self, value: &T) -> alloc::vec::Vec<u8> {
+        match self {
+            ByteOrder::BigEndian => value.to_bytes_be(),
+            ByteOrder::LittleEndian => value.to_bytes_le(),
+        }
+    }
+
+    /// Returns the element from its byte representation in this order.
+    pub fn from_bytes<T: ByteConversion>(self, bytes: &[u8]) -> Result<T, ByteConversionError> {
+        match self {
+            ByteOrder::BigEndian => T::from_bytes_be(bytes),
+            ByteOrder::LittleEndian => T::from_bytes_le(bytes),
+        }
+    }
+}
+
 /// Serialize function without args
 /// Used for serialization when formatting options are not relevant
 #[cfg(feature = "alloc")]
@@ -59,3 +94,35 @@ pub trait Deserializable {
 pub trait IsRandomFieldElementGenerator<F: IsField> {
     fn generate(&self) -> FieldElement<F>;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::fields::fft_friendly::stark_252_prime_field::Stark252PrimeField;
+
+    type FE = FieldElement<Stark252PrimeField>;
+
+    #[test]
+    fn field_elements_round_trip_in_both_byte_orders() {
+        let element = FE::from_hex_unchecked("0x123456789abcdef0fedcba9876543210");
+        for byte_order in [ByteOrder::BigEndian, ByteOrder::LittleEndian] {
+            let bytes = byte_order.to_bytes(&element);
+            assert_eq!(byte_order.from_bytes::<FE>(&bytes).unwrap(), element);
+        }
+    }
+
+    #[test]
+    fn byte_orders_give_reversed_representations() {
+        let element = FE::from(0x0102);
+        let mut big_endian = ByteOrder::BigEndian.to_bytes(&element);
+        big_endian.reverse();
+
+        assert_eq!(big_endian, ByteOrder::LittleEndian.to_bytes(&element));
+        assert_ne!(
+            ByteOrder::LittleEndian
+                .from_bytes::<FE>(&ByteOrder::BigEndian.to_bytes(&element))
+                .unwrap(),
+            element
+        );
+    }
+}
@@ -429,7 +429,8 @@ impl<const NUM_LIMBS: usize> UnsignedInteger<NUM_LIMBS> {
     }
 
     /// Creates an `UnsignedInteger` from a hexstring. It can contain `0x` or not.
-    /// Returns an `CreationError::InvalidHexString`if the value is not a hexstring.
+    /// Returns an `CreationError::InvalidHexString`if the value is not a hexstring or does not
+    /// fit in `NUM_LIMBS` limbs.
     /// Returns a `CreationError::EmptyString` if the input string is empty.
     pub fn from_hex(value: &str) -> Result<Self, CreationError> {
         let mut string = value;
@@ -446,7 +447,12 @@ impl<const NUM_LIMBS: usize> UnsignedInteger<NUM_LIMBS> {
         if !Self::is_hex_string(string) {
             return Err(CreationError::InvalidHexString);
         }
-        Ok(Self::from_hex_unchecked(string))
+        // Each hex digit holds 4 bits, so the value fits if its significant digits do.
+        let significant_digits = string.trim_start_matches('0');
+        if significant_digits.len() > NUM_LIMBS * 16 {
+            return Err(CreationError::InvalidHexString);
+        }
+        Ok(Self::from_hex_unchecked(significant_digits))
     }
 
     /// Creates an `UnsignedInteger` from a hexstring
@@ -863,6 +869,8 @@ impl<const NUM_LIMBS: usize> UnsignedInteger<NUM_LIMBS> {
     }
 
     /// Convert from a decimal string.
+    /// Returns a `CreationError::InvalidDecString` if the value is not a decimal string or does
+    /// not fit in `NUM_LIMBS` limbs.
     pub fn from_dec_str(value: &str) -> Result<Self, CreationError> {
         if value.is_empty() {
             return Err(CreationError::InvalidDecString);
@@ -876,7 +884,11 @@ impl<const NUM_LIMBS: usize> UnsignedInteger<NUM_LIMBS> {
             if high > Self::from_u64(0) {
                 return Err(CreationError::InvalidDecString);
             }
-            res = low + Self::from(b as u64);
+            let (sum, overflow) = Self::add(&low, &Self::from_u64(b as u64));
+            if overflow {
+                return Err(CreationError::InvalidDecString);
+            }
+            res = sum;
         }
         Ok(res)
     }
@@ -2060,6 +2072,7 @@ mod tests_u384 {
 
 #[cfg(test)]
 mod tests_u256 {
+    use crate::errors::CreationError;
     use crate::unsigned_integer::element::ByteConversion;
     use crate::unsigned_integer::element::{UnsignedInteger, U256};
     #[cfg(feature = "proptest")]
@@ -2315,6 +2328,59 @@ mod tests_u256 {
         assert!(U256::from_dec_str("0xff").is_err());
     }
 
+    #[test]
+    fn construct_new_integer_from_dec_max_value() {
+        let a = U256::from_dec_str(
+            "115792089237316195423570985008687907853269984665640564039457584007913129639935",
+        )
+        .unwrap();
+        assert_eq!(a.limbs, [u64::MAX; 4]);
+    }
+
+    #[test]
+    fn construct_new_integer_from_dec_too_big() {
+        assert_eq!(
+            U256::from_dec_str(
+                "115792089237316195423570985008687907853269984665640564039457584007913129639936",
+            ),
+            Err(CreationError::InvalidDecString)
+        );
+    }
+
+    #[test]
+    fn construct_new_integer_from_hex_max_value() {
+        let a =
+            U256::from_hex("0xffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff")
+                .unwrap();
+        assert_eq!(a.limbs, [u64::MAX; 4]);
+    }
+
+    #[test]
+    fn construct_new_integer_from_hex_with_leading_zeros() {
+        let a = U256::from_hex(
+            "00000000000000000000000000000000000000000000000000000000000000000000001",
+        )
+        .unwrap();
+        assert_eq!(a, U256::from_u64(1));
+    }
+
+    #[test]
+    fn construct_new_integer_from_hex_too_big() {
+        // 2^256 has 257 bits.
+        assert_eq!(
+            U256::from_hex("0x10000000000000000000000000000000000000000000000000000000000000000"),
+            Err(CreationError::InvalidHexString)
+        );
+    }
+
+    #[test]
+    fn construct_new_integer_from_hex_malformed() {
+        assert_eq!(
+            U256::from_hex("0x12g4"),
+            Err(CreationError::InvalidHexString)
+        );
+    }
+
     #[test]
     fn equality_works_1() {
         let a = U256::from_hex_unchecked("1");
@@ -759,6 +759,7 @@ impl AIR for CairoAIR {
             num_main_columns,
             num_aux_columns,
             step_size: Self::STEP_SIZE,
+            column_names: None,
         }
     }
 
@@ -2,8 +2,8 @@ use super::air::CairoAIR;
 use lambdaworks_math::field::element::FieldElement;
 use lambdaworks_math::field::fields::fft_friendly::stark_252_prime_field::Stark252PrimeField;
 use serde::{Deserialize, Serialize};
-use stark_platinum_prover::proof::options::ProofOptions;
 use stark_platinum_prover::proof::options::SecurityLevel;
+use stark_platinum_prover::proof::options::{CombinationMode, ProofOptions};
 use stark_platinum_prover::proof::stark::StarkProof;
 use stark_platinum_prover::transcript::StoneProverTranscript;
 use stark_platinum_prover::verifier::{IsStarkVerifier, Verifier};
@@ -73,5 +73,11 @@ pub fn new_proof_options(
         fri_number_of_queries,
         coset_offset: coset_offset as u64,
         grinding_factor,
+        zero_knowledge: false,
+        composition_blowup_factor: None,
+        fri_final_poly_degree: 0,
+        deep_combination: CombinationMode::PowersOfGamma,
+        constraint_combination: CombinationMode::PowersOfGamma,
+        distinct_queries: false,
     }
 }
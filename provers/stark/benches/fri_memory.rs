@@ -0,0 +1,106 @@
+//! Compares the peak memory of the FRI commit and query phases when every layer is kept,
+//! `fri::commit_phase` and `fri::query_phase`, with the one when only the roots and the
+//! polynomials of the layers are kept, `fri::commit_phase_compact` and
+//! `fri::query_phase_compact`, on a domain of size 2^20.
+//!
+//! Run it with `cargo bench --bench fri_memory`.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+use lambdaworks_crypto::fiat_shamir::is_transcript::IsTranscript;
+use lambdaworks_math::field::fields::fft_friendly::stark_252_prime_field::Stark252PrimeField;
+use stark_platinum_prover::config::MerkleVectorCommitment;
+use stark_platinum_prover::fri::{self, FieldElement, Polynomial};
+use stark_platinum_prover::transcript::StoneProverTranscript;
+
+type FE = FieldElement<Stark252PrimeField>;
+
+const LOG_DOMAIN_SIZE: usize = 20;
+const LOG_BLOWUP_FACTOR: usize = 2;
+const NUMBER_OF_QUERIES: usize = 30;
+
+/// The global allocator, which records the number of bytes allocated and its peak.
+struct PeakAllocator;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for PeakAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            let allocated = ALLOCATED.fetch_add(layout.size(), Ordering::SeqCst) + layout.size();
+            PEAK.fetch_max(allocated, Ordering::SeqCst);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        ALLOCATED.fetch_sub(layout.size(), Ordering::SeqCst);
+    }
+}
+
+#[global_allocator]
+static GLOBAL: PeakAllocator = PeakAllocator;
+
+/// Runs `f` and prints the time it took and the peak of the memory it allocated on top of the
+/// memory already allocated.
+fn measure(name: &str, f: impl FnOnce()) {
+    let baseline = ALLOCATED.load(Ordering::SeqCst);
+    PEAK.store(baseline, Ordering::SeqCst);
+    let start = Instant::now();
+    f();
+    let elapsed = start.elapsed();
+    let peak = PEAK.load(Ordering::SeqCst) - baseline;
+    println!(
+        "{name}: peak memory {:.1} MiB, time {elapsed:?}",
+        peak as f64 / (1 << 20) as f64
+    );
+}
+
+fn main() {
+    let domain_size = 1 << LOG_DOMAIN_SIZE;
+    let number_layers = LOG_DOMAIN_SIZE - LOG_BLOWUP_FACTOR;
+    let coefficients: Vec<_> = (0..domain_size >> LOG_BLOWUP_FACTOR)
+        .map(|_| FE::from(rand::random::<u64>()))
+        .collect();
+    let p_0 = Polynomial::new(&coefficients);
+    let coset_offset = FE::from(3);
+    let sample_iotas = |transcript: &mut StoneProverTranscript| -> Vec<usize> {
+        (0..NUMBER_OF_QUERIES)
+            .map(|_| transcript.sample_u64((domain_size >> 1) as u64) as usize)
+            .collect()
+    };
+
+    measure("FRI layers", || {
+        let mut transcript = StoneProverTranscript::new(&[]);
+        let (_, fri_layers) = fri::commit_phase::<_, _, MerkleVectorCommitment>(
+            number_layers,
+            1,
+            p_0.clone(),
+            &mut transcript,
+            &coset_offset,
+            domain_size,
+        );
+        let iotas = sample_iotas(&mut transcript);
+        fri::query_phase(&fri_layers, &iotas);
+    });
+
+    measure("compact FRI layers", || {
+        let mut transcript = StoneProverTranscript::new(&[]);
+        let (_, fri_layers) = fri::commit_phase_compact::<_, _, MerkleVectorCommitment>(
+            number_layers,
+            1,
+            p_0.clone(),
+            &mut transcript,
+            &coset_offset,
+            domain_size,
+            |_| {},
+        );
+        let iotas = sample_iotas(&mut transcript);
+        fri::query_phase_compact(&fri_layers, &iotas);
+    });
+}
@@ -0,0 +1,55 @@
+//! Proves a Fibonacci trace, serializes the proof, reads it back and verifies it.
+//!
+//! Run with `cargo run --release --example prove_verify_fibonacci`.
+
+use lambdaworks_math::field::{
+    element::FieldElement, fields::fft_friendly::stark_252_prime_field::Stark252PrimeField,
+};
+use stark_platinum_prover::{
+    examples::simple_fibonacci::{fibonacci_trace, FibonacciAIR, FibonacciPublicInputs},
+    proof::{options::ProofOptions, stark::StarkProof},
+    prover::{IsStarkProver, Prover},
+    transcript::StoneProverTranscript,
+    verifier::{IsStarkVerifier, Verifier},
+};
+
+type F = Stark252PrimeField;
+type FE = FieldElement<F>;
+
+fn main() {
+    let trace_length = 1024;
+    let trace = fibonacci_trace([FE::one(), FE::one()], trace_length);
+    let pub_inputs = FibonacciPublicInputs {
+        a0: FE::one(),
+        a1: FE::one(),
+    };
+    let proof_options = ProofOptions::default_test_options();
+
+    let proof = Prover::<FibonacciAIR<F>>::prove(
+        &trace,
+        &pub_inputs,
+        &proof_options,
+        StoneProverTranscript::new(&[]),
+    )
+    .expect("the Fibonacci trace should be provable");
+
+    let proof_bytes = bincode::serde::encode_to_vec(&proof, bincode::config::standard())
+        .expect("the proof should be serializable");
+    println!(
+        "Proved {} Fibonacci steps, the proof is {} bytes long",
+        trace_length,
+        proof_bytes.len()
+    );
+
+    let (read_proof, _): (StarkProof<F, F>, usize) =
+        bincode::serde::decode_from_slice(&proof_bytes, bincode::config::standard())
+            .expect("the proof should be deserializable");
+
+    assert!(Verifier::<FibonacciAIR<F>>::verify(
+        &read_proof,
+        &pub_inputs,
+        &proof_options,
+        StoneProverTranscript::new(&[]),
+    ));
+    println!("The proof read back verifies");
+}
@@ -1,8 +1,19 @@
+use alloc::vec::Vec;
+use core::{fmt::Debug, hash::Hash};
+
 use lambdaworks_crypto::merkle_tree::{
     backends::types::{BatchKeccak256Backend, Keccak256Backend},
-    merkle::MerkleTree,
+    merkle::{MerkleError, MerkleTree},
+    proof::Proof,
+    traits::VectorCommitment,
+};
+use lambdaworks_math::{
+    field::{element::FieldElement, traits::IsField},
+    traits::AsBytes,
 };
 
+use crate::prover::ProvingError;
+
 // Merkle Trees configuration
 
 // Security of both hashes should match
@@ -18,3 +29,74 @@ pub type Commitment = [u8; COMMITMENT_SIZE];
 
 pub type BatchedMerkleTreeBackend<F> = BatchKeccak256Backend<F>;
 pub type BatchedMerkleTree<F> = MerkleTree<BatchedMerkleTreeBackend<F>>;
+
+/// The vector commitment scheme a STARK proof commits with to the rows of the trace, of the
+/// composition polynomial and of the FRI layers, over any of the fields of the proof. The
+/// prover, the verifier and the proof are generic over it, with
+/// [`MerkleVectorCommitment`] as the default.
+pub trait IsStarkVectorCommitment {
+    /// The commitment to a vector, sent to the verifier and appended to the transcript.
+    type Commitment: Clone
+        + Debug
+        + PartialEq
+        + Eq
+        + Hash
+        + AsRef<[u8]>
+        + Send
+        + Sync
+        + serde::Serialize
+        + serde::de::DeserializeOwned;
+    /// The proof that a value is at some position of a committed vector.
+    type Opening: Clone
+        + Debug
+        + PartialEq
+        + Eq
+        + Hash
+        + Send
+        + Sync
+        + serde::Serialize
+        + serde::de::DeserializeOwned;
+    /// The error of a failed opening.
+    type Error: Debug + Into<ProvingError>;
+    /// The committed vector of rows of elements of `F`.
+    type Scheme<F: IsField>: VectorCommitment<
+            Value = Vec<FieldElement<F>>,
+            Commitment = Self::Commitment,
+            Opening = Self::Opening,
+            Error = Self::Error,
+        > + Clone
+        + Send
+        + Sync
+    where
+        FieldElement<F>: AsBytes + Sync + Send;
+
+    /// Returns the size of `opening`, which the structural check of a proof compares with
+    /// `expected_opening_size` before any hashing.
+    fn opening_size(opening: &Self::Opening) -> usize;
+
+    /// Returns the size of the openings of a committed vector of `len` values.
+    fn expected_opening_size(len: usize) -> usize;
+}
+
+/// The Merkle trees of [`BatchedMerkleTreeBackend`], opened with authentication paths. The
+/// size of an opening is the length of its path, the depth of the tree.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct MerkleVectorCommitment;
+
+impl IsStarkVectorCommitment for MerkleVectorCommitment {
+    type Commitment = Commitment;
+    type Opening = Proof<Commitment>;
+    type Error = MerkleError;
+    type Scheme<F: IsField>
+        = BatchedMerkleTree<F>
+    where
+        FieldElement<F>: AsBytes + Sync + Send;
+
+    fn opening_size(opening: &Proof<Commitment>) -> usize {
+        opening.merkle_path.len()
+    }
+
+    fn expected_opening_size(len: usize) -> usize {
+        len.max(1).trailing_zeros() as usize
+    }
+}
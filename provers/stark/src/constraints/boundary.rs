@@ -1,4 +1,7 @@
-use itertools::Itertools;
+use crate::zerofier::linear_zerofier;
+use alloc::collections::BTreeSet;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
 use lambdaworks_math::{
     field::{element::FieldElement, traits::IsField},
     polynomial::Polynomial,
@@ -87,18 +90,20 @@ impl<F: IsField> BoundaryConstraints<F> {
     }
 
     pub fn steps_for_boundary(&self) -> Vec<usize> {
+        let mut seen = BTreeSet::new();
         self.constraints
             .iter()
-            .unique_by(|elem| elem.step)
             .map(|v| v.step)
+            .filter(|step| seen.insert(*step))
             .collect()
     }
 
     pub fn cols_for_boundary(&self) -> Vec<usize> {
+        let mut seen = BTreeSet::new();
         self.constraints
             .iter()
-            .unique_by(|elem| elem.col)
             .map(|v| v.col)
+            .filter(|col| seen.insert(*col))
             .collect()
     }
 
@@ -150,10 +155,8 @@ impl<F: IsField> BoundaryConstraints<F> {
         self.steps(col).into_iter().fold(
             Polynomial::new_monomial(FieldElement::<F>::one(), 0),
             |zerofier, step| {
-                let binomial =
-                    Polynomial::new(&[-primitive_root.pow(step), FieldElement::<F>::one()]);
                 // TODO: Implement the MulAssign trait for Polynomials?
-                zerofier * binomial
+                zerofier * linear_zerofier(primitive_root.pow(step)).to_polynomial()
             },
         )
     }
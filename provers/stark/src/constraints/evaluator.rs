@@ -1,14 +1,21 @@
 use super::boundary::BoundaryConstraints;
 #[cfg(all(debug_assertions, not(feature = "parallel")))]
-use crate::debug::check_boundary_polys_divisibility;
+use crate::debug::{check_boundary_polys_divisibility, overestimated_transition_degrees};
 use crate::domain::Domain;
 use crate::trace::LDETraceTable;
 use crate::traits::AIR;
+use crate::zerofier::linear_zerofier;
 use crate::{frame::Frame, prover::evaluate_polynomial_on_lde_domain};
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
 use itertools::Itertools;
 #[cfg(all(debug_assertions, not(feature = "parallel")))]
 use lambdaworks_math::polynomial::Polynomial;
-use lambdaworks_math::{fft::errors::FFTError, field::element::FieldElement, traits::AsBytes};
+use lambdaworks_math::{
+    fft::errors::FFTError,
+    field::{element::FieldElement, errors::FieldError},
+    traits::AsBytes,
+};
 #[cfg(feature = "parallel")]
 use rayon::{
     iter::IndexedParallelIterator,
@@ -37,7 +44,7 @@ impl<A: AIR> ConstraintEvaluator<A> {
         transition_coefficients: &[FieldElement<A::FieldExtension>],
         boundary_coefficients: &[FieldElement<A::FieldExtension>],
         rap_challenges: &[FieldElement<A::FieldExtension>],
-    ) -> Vec<FieldElement<A::FieldExtension>>
+    ) -> Result<Vec<FieldElement<A::FieldExtension>>, FieldError>
     where
         FieldElement<A::Field>: AsBytes + Send + Sync,
         FieldElement<A::FieldExtension>: AsBytes + Send + Sync,
@@ -50,16 +57,10 @@ impl<A: AIR> ConstraintEvaluator<A> {
                 .constraints
                 .iter()
                 .map(|bc| {
-                    let point = &domain.trace_primitive_root.pow(bc.step as u64);
-                    let mut evals = domain
-                        .lde_roots_of_unity_coset
-                        .iter()
-                        .map(|v| v.clone() - point)
-                        .collect::<Vec<FieldElement<A::Field>>>();
-                    FieldElement::inplace_batch_inverse(&mut evals).unwrap();
-                    evals
+                    linear_zerofier(domain.trace_primitive_root.pow(bc.step as u64))
+                        .inverse_evaluations(&domain.lde_roots_of_unity_coset)
                 })
-                .collect::<Vec<Vec<FieldElement<A::Field>>>>();
+                .collect::<Result<Vec<Vec<FieldElement<A::Field>>>, FieldError>>()?;
 
         #[cfg(all(debug_assertions, not(feature = "parallel")))]
         let boundary_polys: Vec<Polynomial<FieldElement<A::Field>>> = Vec::new();
@@ -76,6 +77,7 @@ impl<A: AIR> ConstraintEvaluator<A> {
                     domain.blowup_factor,
                     domain.interpolation_domain_size,
                     &domain.coset_offset,
+                    domain.twiddle_cache.as_ref(),
                 )
             })
             .collect::<Result<Vec<Vec<FieldElement<A::Field>>>, FFTError>>()
@@ -210,12 +212,27 @@ impl<A: AIR> ConstraintEvaluator<A> {
             })
             .collect();
 
+        // Warn about the transition constraints declared with a higher degree than needed.
+        #[cfg(all(debug_assertions, not(feature = "parallel")))]
+        {
+            let mut declared_degrees = vec![0; air.context().num_transition_constraints()];
+            for constraint in air.transition_constraints() {
+                declared_degrees[constraint.constraint_idx()] = constraint.degree();
+            }
+            overestimated_transition_degrees(
+                &declared_degrees,
+                &transition_evaluations,
+                air.trace_length(),
+                &domain.coset_offset,
+            );
+        }
+
         #[cfg(feature = "instruments")]
         println!(
             "     Evaluated transitions and accumulated results: {:#?}",
             timer.elapsed()
         );
 
-        evaluations_t
+        Ok(evaluations_t)
     }
 }
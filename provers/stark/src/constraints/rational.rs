@@ -0,0 +1,180 @@
+use crate::constraints::transition::TransitionConstraint;
+use crate::frame::Frame;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use lambdaworks_math::field::element::FieldElement;
+use lambdaworks_math::field::traits::{IsFFTField, IsField, IsSubFieldOf};
+
+/// RationalConstraint represents a transition constraint given as a sum of fractions
+/// `n_0 / d_0 + n_1 / d_1 + ... + n_k / d_k` that has to vanish over the trace, where every
+/// numerator and denominator is a polynomial in the elements of the frame. This is the shape
+/// of the constraints of permutation and lookup arguments.
+///
+/// A rational constraint is turned into a regular transition constraint by wrapping it in a
+/// [`RationalTransitionConstraint`], which clears the denominators. Since the cleared
+/// constraint also vanishes wherever a denominator does, the denominators should not vanish
+/// over the trace, which is normally ensured by shifting them with a random challenge.
+pub trait RationalConstraint<F, E>: Send + Sync
+where
+    F: IsSubFieldOf<E> + IsFFTField + Send + Sync,
+    E: IsField + Send + Sync,
+{
+    /// The degrees of the numerator and the denominator of each fraction, interpreting them as
+    /// multivariate polynomials, in the same order as returned by `evaluate_fractions()`.
+    fn fraction_degrees(&self) -> Vec<(usize, usize)>;
+
+    /// The index of the constraint. See [`TransitionConstraint::constraint_idx`].
+    fn constraint_idx(&self) -> usize;
+
+    /// The number of exemptions at the end of the trace. See
+    /// [`TransitionConstraint::end_exemptions`].
+    fn end_exemptions(&self) -> usize;
+
+    /// Evaluates the numerator and the denominator of each fraction of the constraint over
+    /// the elements of the trace table found in `frame`.
+    fn evaluate_fractions(
+        &self,
+        frame: &Frame<F, E>,
+        periodic_values: &[FieldElement<F>],
+        rap_challenges: &[FieldElement<E>],
+    ) -> Vec<(FieldElement<E>, FieldElement<E>)>;
+}
+
+/// Wraps a [`RationalConstraint`] so it can be used as a [`TransitionConstraint`].
+///
+/// The sum of fractions `n_0 / d_0 + ... + n_k / d_k` is evaluated with its denominators
+/// cleared, as `sum_i n_i * prod_{j != i} d_j`, so the composition polynomial gets divided
+/// by the zerofier only. The degree of the constraint is the one of the cleared polynomial,
+/// `max_i (deg(n_i) + sum_{j != i} deg(d_j))`.
+pub struct RationalTransitionConstraint<C> {
+    constraint: C,
+}
+
+impl<C> RationalTransitionConstraint<C> {
+    pub fn new(constraint: C) -> Self {
+        Self { constraint }
+    }
+}
+
+impl<F, E, C> TransitionConstraint<F, E> for RationalTransitionConstraint<C>
+where
+    F: IsSubFieldOf<E> + IsFFTField + Send + Sync,
+    E: IsField + Send + Sync,
+    C: RationalConstraint<F, E>,
+{
+    fn degree(&self) -> usize {
+        let degrees = self.constraint.fraction_degrees();
+        let denominators_degree: usize = degrees.iter().map(|(_, d)| d).sum();
+
+        degrees
+            .iter()
+            .map(|(n, d)| n + denominators_degree - d)
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn constraint_idx(&self) -> usize {
+        self.constraint.constraint_idx()
+    }
+
+    fn end_exemptions(&self) -> usize {
+        self.constraint.end_exemptions()
+    }
+
+    fn evaluate(
+        &self,
+        frame: &Frame<F, E>,
+        transition_evaluations: &mut [FieldElement<E>],
+        periodic_values: &[FieldElement<F>],
+        rap_challenges: &[FieldElement<E>],
+    ) {
+        let fractions = self
+            .constraint
+            .evaluate_fractions(frame, periodic_values, rap_challenges);
+
+        // Adds up the fractions keeping the numerator and the denominator apart:
+        // a / b + n / d = (a * d + n * b) / (b * d)
+        let (numerator, _) = fractions.into_iter().fold(
+            (FieldElement::<E>::zero(), FieldElement::<E>::one()),
+            |(acc_numerator, acc_denominator), (numerator, denominator)| {
+                (
+                    acc_numerator * &denominator + numerator * &acc_denominator,
+                    acc_denominator * denominator,
+                )
+            },
+        );
+
+        transition_evaluations[self.constraint_idx()] = numerator;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::table::TableView;
+    use lambdaworks_math::field::fields::u64_prime_field::{F17, FE17};
+
+    /// `a / b - c / d`, with a degree 2 numerator in the second fraction.
+    struct FractionDifference;
+
+    impl RationalConstraint<F17, F17> for FractionDifference {
+        fn fraction_degrees(&self) -> Vec<(usize, usize)> {
+            vec![(1, 1), (2, 1)]
+        }
+
+        fn constraint_idx(&self) -> usize {
+            0
+        }
+
+        fn end_exemptions(&self) -> usize {
+            0
+        }
+
+        fn evaluate_fractions(
+            &self,
+            frame: &Frame<F17, F17>,
+            _periodic_values: &[FE17],
+            _rap_challenges: &[FE17],
+        ) -> Vec<(FE17, FE17)> {
+            let step = frame.get_evaluation_step(0);
+            let a = step.get_main_evaluation_element(0, 0);
+            let b = step.get_main_evaluation_element(0, 1);
+            let c = step.get_main_evaluation_element(0, 2);
+            let d = step.get_main_evaluation_element(0, 3);
+
+            vec![(*a, *b), (-c * c, *d)]
+        }
+    }
+
+    fn evaluate(row: &[FE17]) -> FE17 {
+        let frame = Frame::new(vec![TableView::new(vec![row], vec![&[][..]])]);
+        let mut evaluations = vec![FE17::zero()];
+        RationalTransitionConstraint::new(FractionDifference).evaluate(
+            &frame,
+            &mut evaluations,
+            &[],
+            &[],
+        );
+        evaluations.remove(0)
+    }
+
+    #[test]
+    fn degree_accounts_for_the_denominators() {
+        let constraint = RationalTransitionConstraint::new(FractionDifference);
+        assert_eq!(TransitionConstraint::<F17, F17>::degree(&constraint), 3);
+    }
+
+    #[test]
+    fn evaluation_clears_the_denominators() {
+        // 3 / 2 - 4^2 / 5 = (3 * 5 - 16 * 2) / 10
+        let row = [3u64, 2, 4, 5].map(FE17::from);
+        assert_eq!(evaluate(&row), FE17::from(15) - FE17::from(32));
+    }
+
+    #[test]
+    fn equal_fractions_evaluate_to_zero() {
+        // 2 / 3 = 4 / 6 with 4 = 2^2
+        let row = [2u64, 3, 2, 6].map(FE17::from);
+        assert_eq!(evaluate(&row), FE17::zero());
+    }
+}
@@ -1,12 +1,14 @@
 use crate::domain::Domain;
 use crate::frame::Frame;
 use crate::prover::evaluate_polynomial_on_lde_domain;
+use crate::zerofier::coset_vanishing_polynomial;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use core::ops::Div;
 use itertools::Itertools;
 use lambdaworks_math::field::element::FieldElement;
 use lambdaworks_math::field::traits::{IsFFTField, IsField, IsSubFieldOf};
 use lambdaworks_math::polynomial::Polynomial;
-use num_integer::Integer;
-use std::ops::Div;
 /// TransitionConstraint represents the behaviour that a transition constraint
 /// over the computation that wants to be proven must comply with.
 pub trait TransitionConstraint<F, E>: Send + Sync
@@ -80,8 +82,23 @@ where
     /// the constraint evaluation at the end of the trace. For example, for a fibonacci
     /// computation that has to use the result 2 following steps, this method is defined
     /// to return the value 2.
+    ///
+    /// The exemptions count from the end of the trace, in steps of `period()` rows: see
+    /// `end_exempted_rows()`. So `end_exemptions() * period()` must not exceed the trace
+    /// length, which the prover checks.
     fn end_exemptions(&self) -> usize;
 
+    /// Returns the rows of a trace of length `trace_length` where the constraint is not
+    /// enforced because of its end exemptions: `trace_length - period()`, ...,
+    /// `trace_length - end_exemptions() * period()`. They are the roots of
+    /// `end_exemptions_poly()`, as powers of the primitive root of the trace.
+    fn end_exempted_rows(&self, trace_length: usize) -> Vec<usize> {
+        let period = self.period();
+        (1..=self.end_exemptions())
+            .map(|exemption| trace_length - exemption * period)
+            .collect()
+    }
+
     /// Method for calculating the end exemptions polynomial.
     ///
     /// This polynomial is used to compute zerofiers of the constraint, and the default
@@ -95,10 +112,10 @@ where
         if self.end_exemptions() == 0 {
             return one_poly;
         }
-        let period = self.period();
         // FIXME: CHECK IF WE NEED TO CHANGE THE NEW MONOMIAL'S ARGUMENTS TO trace_root^(offset * trace_length / period) INSTEAD OF ONE!!!!
-        (1..=self.end_exemptions())
-            .map(|exemption| trace_primitive_root.pow(trace_length - exemption * period))
+        self.end_exempted_rows(trace_length)
+            .into_iter()
+            .map(|row| trace_primitive_root.pow(row))
             .fold(one_poly, |acc, offset| {
                 acc * (Polynomial::new_monomial(FieldElement::<F>::one(), 1) - offset)
             })
@@ -120,7 +137,7 @@ where
         if let Some(exemptions_period) = self.exemptions_period() {
             // FIXME: Rather than making this assertions here, it would be better to handle these
             // errors or make these checks when the AIR is initialized.
-            debug_assert!(exemptions_period.is_multiple_of(&self.period()));
+            debug_assert!(exemptions_period % self.period() == 0);
             debug_assert!(self.periodic_exemptions_offset().is_some());
 
             // The elements of the domain have order `trace_length * blowup_factor`, so the zerofier evaluations
@@ -128,19 +145,22 @@ where
             // so we only need to compute those.
             let last_exponent = blowup_factor * exemptions_period;
 
+            let numerator = coset_vanishing_polynomial(
+                trace_length / exemptions_period,
+                &trace_primitive_root.pow(self.periodic_exemptions_offset().unwrap()),
+            );
+            let denominator = coset_vanishing_polynomial(
+                trace_length / self.period(),
+                &trace_primitive_root.pow(self.offset()),
+            );
+
             let evaluations: Vec<_> = (0..last_exponent)
                 .map(|exponent| {
-                    let x = lde_root.pow(exponent);
-                    let offset_times_x = coset_offset * &x;
-                    let offset_exponent = trace_length * self.periodic_exemptions_offset().unwrap()
-                        / exemptions_period;
+                    let offset_times_x = coset_offset * lde_root.pow(exponent);
 
-                    let numerator = offset_times_x.pow(trace_length / exemptions_period)
-                        - trace_primitive_root.pow(offset_exponent);
-                    let denominator = offset_times_x.pow(trace_length / self.period())
-                        - trace_primitive_root.pow(self.offset() * trace_length / self.period());
-
-                    numerator.div(denominator)
+                    numerator
+                        .evaluate::<F>(&offset_times_x)
+                        .div(denominator.evaluate::<F>(&offset_times_x))
                 })
                 .collect();
 
@@ -152,6 +172,7 @@ where
                 blowup_factor,
                 domain.interpolation_domain_size,
                 coset_offset,
+                domain.twiddle_cache.as_ref(),
             )
             .unwrap();
 
@@ -160,7 +181,7 @@ where
                 .cycle()
                 .take(end_exemption_evaluations.len());
 
-            std::iter::zip(cycled_evaluations, end_exemption_evaluations)
+            core::iter::zip(cycled_evaluations, end_exemption_evaluations)
                 .map(|(eval, exemption_eval)| eval * exemption_eval)
                 .collect()
 
@@ -170,15 +191,15 @@ where
         } else {
             let last_exponent = blowup_factor * self.period();
 
-            let mut evaluations = (0..last_exponent)
-                .map(|exponent| {
-                    let x = lde_root.pow(exponent);
-                    (coset_offset * &x).pow(trace_length / self.period())
-                        - trace_primitive_root.pow(self.offset() * trace_length / self.period())
-                })
+            let points = (0..last_exponent)
+                .map(|exponent| coset_offset * lde_root.pow(exponent))
                 .collect_vec();
-
-            FieldElement::inplace_batch_inverse(&mut evaluations).unwrap();
+            let evaluations = coset_vanishing_polynomial(
+                trace_length / self.period(),
+                &trace_primitive_root.pow(self.offset()),
+            )
+            .inverse_evaluations(&points)
+            .unwrap();
 
             // FIXME: Instead of computing this evaluations for each constraint, they can be computed
             // once for every constraint with the same end exemptions (combination of end_exemptions()
@@ -188,6 +209,7 @@ where
                 blowup_factor,
                 domain.interpolation_domain_size,
                 coset_offset,
+                domain.twiddle_cache.as_ref(),
             )
             .unwrap();
 
@@ -196,7 +218,7 @@ where
                 .cycle()
                 .take(end_exemption_evaluations.len());
 
-            std::iter::zip(cycled_evaluations, end_exemption_evaluations)
+            core::iter::zip(cycled_evaluations, end_exemption_evaluations)
                 .map(|(eval, exemption_eval)| eval * exemption_eval)
                 .collect()
         }
@@ -211,27 +233,24 @@ where
         trace_length: usize,
     ) -> FieldElement<E> {
         let end_exemptions_poly = self.end_exemptions_poly(trace_primitive_root, trace_length);
+        let denominator = coset_vanishing_polynomial(
+            trace_length / self.period(),
+            &trace_primitive_root.pow(self.offset()),
+        );
 
         if let Some(exemptions_period) = self.exemptions_period() {
-            debug_assert!(exemptions_period.is_multiple_of(&self.period()));
+            debug_assert!(exemptions_period % self.period() == 0);
             debug_assert!(self.periodic_exemptions_offset().is_some());
 
-            let periodic_exemptions_offset = self.periodic_exemptions_offset().unwrap();
-            let offset_exponent = trace_length * periodic_exemptions_offset / exemptions_period;
-
-            let numerator = -trace_primitive_root.pow(offset_exponent)
-                + z.pow(trace_length / exemptions_period);
-            let denominator = -trace_primitive_root
-                .pow(self.offset() * trace_length / self.period())
-                + z.pow(trace_length / self.period());
+            let numerator = coset_vanishing_polynomial(
+                trace_length / exemptions_period,
+                &trace_primitive_root.pow(self.periodic_exemptions_offset().unwrap()),
+            )
+            .evaluate(z);
 
-            return numerator.div(denominator) * end_exemptions_poly.evaluate(z);
+            return numerator.div(denominator.evaluate(z)) * end_exemptions_poly.evaluate(z);
         }
 
-        (-trace_primitive_root.pow(self.offset() * trace_length / self.period())
-            + z.pow(trace_length / self.period()))
-        .inv()
-        .unwrap()
-            * end_exemptions_poly.evaluate(z)
+        denominator.evaluate(z).inv_unchecked() * end_exemptions_poly.evaluate(z)
     }
 }
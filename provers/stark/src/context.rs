@@ -1,8 +1,10 @@
-use std::collections::HashSet;
+use alloc::collections::BTreeSet;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
 
 use super::proof::options::ProofOptions;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct AirContext {
     pub proof_options: ProofOptions,
     pub trace_columns: usize,
@@ -13,6 +15,9 @@ pub struct AirContext {
     /// offsets that are needed to compute EVERY transition constraint, even if some
     /// constraints don't use all of the indexes in said offsets.
     pub transition_offsets: Vec<usize>,
+    /// The end exemptions of each transition constraint, by constraint index. The zerofier
+    /// of each constraint, and so the rows where it has to hold, is given by the constraint
+    /// itself: see `TransitionConstraint::end_exemptions` and `exemptions_period`.
     pub transition_exemptions: Vec<usize>,
     pub num_transition_constraints: usize,
 }
@@ -28,7 +33,7 @@ impl AirContext {
         self.transition_exemptions
             .iter()
             .filter(|&x| *x != 0)
-            .collect::<HashSet<_>>()
+            .collect::<BTreeSet<_>>()
             .len()
     }
 }
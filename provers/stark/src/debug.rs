@@ -1,14 +1,77 @@
 use super::domain::Domain;
 use super::traits::AIR;
-use crate::{frame::Frame, trace::LDETraceTable};
+use crate::{
+    frame::Frame,
+    prover::{
+        check_blowup_factor, check_composition_blowup_factor, check_trace_length,
+        check_trace_shape, check_transition_exemptions, check_two_adicity,
+        evaluate_polynomial_on_lde_domain, ProvingError,
+    },
+    trace::{LDETraceTable, TraceTable},
+};
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use core::{fmt, ops::ControlFlow};
 use lambdaworks_math::{
     field::{
         element::FieldElement,
-        traits::{IsFFTField, IsField},
+        traits::{IsFFTField, IsField, IsSubFieldOf},
     },
     polynomial::Polynomial,
 };
-use log::{error, info};
+
+/// A constraint of the AIR that does not hold over an execution trace.
+///
+/// `row` is the row of the trace where the violation was found, `constraint_idx` the
+/// index of the offending constraint and `value` the non-zero value it evaluated to. For
+/// boundary constraints, `value` is the difference between the value found in the trace
+/// and the expected one.
+#[derive(Clone, Debug)]
+pub enum ConstraintViolation<E: IsField> {
+    Boundary {
+        row: usize,
+        constraint_idx: usize,
+        value: FieldElement<E>,
+    },
+    Transition {
+        row: usize,
+        constraint_idx: usize,
+        value: FieldElement<E>,
+    },
+}
+
+// Not derived, as the derive would require `E: PartialEq`, which the fields do not implement.
+impl<E: IsField> PartialEq for ConstraintViolation<E> {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (
+                Self::Boundary {
+                    row,
+                    constraint_idx,
+                    value,
+                },
+                Self::Boundary {
+                    row: other_row,
+                    constraint_idx: other_constraint_idx,
+                    value: other_value,
+                },
+            )
+            | (
+                Self::Transition {
+                    row,
+                    constraint_idx,
+                    value,
+                },
+                Self::Transition {
+                    row: other_row,
+                    constraint_idx: other_constraint_idx,
+                    value: other_value,
+                },
+            ) => row == other_row && constraint_idx == other_constraint_idx && value == other_value,
+            _ => false,
+        }
+    }
+}
 
 /// Validates that the trace is valid with respect to the supplied AIR constraints
 pub fn validate_trace<A: AIR>(
@@ -19,15 +82,19 @@ pub fn validate_trace<A: AIR>(
     rap_challenges: &[FieldElement<A::FieldExtension>],
 ) -> bool {
     info!("Starting constraints validation over trace...");
-    let mut ret = true;
 
+    // The trace polynomials may have degree at least the trace length when they are masked
+    // in zero-knowledge mode, so they are evaluated folding the coefficients over the domain.
+    let one = FieldElement::<A::Field>::one();
     let main_trace_columns: Vec<_> = main_trace_polys
         .iter()
         .map(|poly| {
-            Polynomial::<FieldElement<A::Field>>::evaluate_fft::<A::Field>(
+            evaluate_polynomial_on_lde_domain(
                 poly,
                 1,
-                Some(domain.interpolation_domain_size),
+                domain.interpolation_domain_size,
+                &one,
+                domain.twiddle_cache.as_ref(),
             )
             .unwrap()
         })
@@ -36,8 +103,14 @@ pub fn validate_trace<A: AIR>(
     let aux_trace_columns: Vec<_> = aux_trace_polys
         .iter()
         .map(|poly| {
-            Polynomial::evaluate_fft::<A::Field>(poly, 1, Some(domain.interpolation_domain_size))
-                .unwrap()
+            evaluate_polynomial_on_lde_domain(
+                poly,
+                1,
+                domain.interpolation_domain_size,
+                &one,
+                domain.twiddle_cache.as_ref(),
+            )
+            .unwrap()
         })
         .collect();
 
@@ -57,40 +130,109 @@ pub fn validate_trace<A: AIR>(
         })
         .collect();
 
+    let ret = match validate_lde_trace(air, &lde_trace, &periodic_columns, rap_challenges) {
+        Ok(()) => true,
+        Err(ConstraintViolation::Boundary {
+            row,
+            constraint_idx,
+            value,
+        }) => {
+            error!(
+                "Boundary constraint {} inconsistency in row {} - found a difference of {:?} with the expected value",
+                constraint_idx, row, value
+            );
+            false
+        }
+        Err(ConstraintViolation::Transition {
+            row,
+            constraint_idx,
+            value,
+        }) => {
+            error!(
+                "Inconsistent evaluation of transition {} in row {} - expected 0, got {:?}",
+                constraint_idx, row, value
+            );
+            false
+        }
+    };
+    info!("Constraints validation check ended");
+    ret
+}
+
+/// Checks the boundary and transition constraints of the AIR over a trace with blowup
+/// factor 1, stopping at the first constraint that does not hold.
+/// `periodic_columns` holds the values of each periodic column at every step of the trace.
+pub(crate) fn validate_lde_trace<A: AIR>(
+    air: &A,
+    lde_trace: &LDETraceTable<A::Field, A::FieldExtension>,
+    periodic_columns: &[Vec<FieldElement<A::Field>>],
+    rap_challenges: &[FieldElement<A::FieldExtension>],
+) -> Result<(), ConstraintViolation<A::FieldExtension>> {
+    let mut first_violation = None;
+    for_each_lde_trace_violation(
+        air,
+        lde_trace,
+        periodic_columns,
+        rap_challenges,
+        |violation| {
+            first_violation = Some(violation);
+            ControlFlow::Break(())
+        },
+    );
+
+    first_violation.map_or(Ok(()), Err)
+}
+
+/// Checks the boundary and transition constraints of the AIR over a trace with blowup
+/// factor 1, calling `on_violation` with each constraint that does not hold, boundary
+/// constraints first and then transition constraints row by row, until it breaks.
+pub(crate) fn for_each_lde_trace_violation<A: AIR>(
+    air: &A,
+    lde_trace: &LDETraceTable<A::Field, A::FieldExtension>,
+    periodic_columns: &[Vec<FieldElement<A::Field>>],
+    rap_challenges: &[FieldElement<A::FieldExtension>],
+    mut on_violation: impl FnMut(ConstraintViolation<A::FieldExtension>) -> ControlFlow<()>,
+) {
     // --------- VALIDATE BOUNDARY CONSTRAINTS ------------
-    air.boundary_constraints(rap_challenges)
+    for (constraint_idx, constraint) in air
+        .boundary_constraints(rap_challenges)
         .constraints
         .iter()
-        .for_each(|constraint| {
-            let col = constraint.col;
-            let step = constraint.step;
-            let boundary_value = constraint.value.clone();
-
-            let trace_value = if !constraint.is_aux {
-                lde_trace.get_main(step, col).clone().to_extension()
-            } else {
-                lde_trace.get_aux(step,  col).clone()
-            };
+        .enumerate()
+    {
+        let col = constraint.col;
+        let step = constraint.step;
+
+        let trace_value = if !constraint.is_aux {
+            lde_trace.get_main(step, col).clone().to_extension()
+        } else {
+            lde_trace.get_aux(step, col).clone()
+        };
 
-            if boundary_value.clone().to_extension() != trace_value {
-                ret = false;
-                error!("Boundary constraint inconsistency - Expected value {:?} in step {} and column {}, found: {:?}", boundary_value, step, col, trace_value);
+        if constraint.value != trace_value {
+            let violation = ConstraintViolation::Boundary {
+                row: step,
+                constraint_idx,
+                value: trace_value - &constraint.value,
+            };
+            if on_violation(violation).is_break() {
+                return;
             }
-        });
+        }
+    }
 
     // --------- VALIDATE TRANSITION CONSTRAINTS -----------
     let n_transition_constraints = air.context().num_transition_constraints();
-    let transition_exemptions = &air.context().transition_exemptions;
 
-    let exemption_steps: Vec<usize> = vec![lde_trace.num_rows(); n_transition_constraints]
-        .iter()
-        .zip(transition_exemptions)
-        .map(|(trace_steps, exemptions)| trace_steps - exemptions)
-        .collect();
+    // Each constraint has its own end exemptions, which are the ones its zerofier excludes.
+    let mut exemption_steps = vec![lde_trace.num_rows(); n_transition_constraints];
+    for constraint in air.transition_constraints() {
+        exemption_steps[constraint.constraint_idx()] -= constraint.end_exemptions();
+    }
 
     // Iterate over trace and compute transitions
     for step in 0..lde_trace.num_steps() {
-        let frame = Frame::read_step_from_lde(&lde_trace, step, &air.context().transition_offsets);
+        let frame = Frame::read_step_from_lde(lde_trace, step, &air.context().transition_offsets);
         let periodic_values: Vec<_> = periodic_columns
             .iter()
             .map(|col| col[step].clone())
@@ -99,21 +241,204 @@ pub fn validate_trace<A: AIR>(
 
         // Iterate over each transition evaluation. When the evaluated step is not from
         // the exemption steps corresponding to the transition, it should have zero as a
-        // result
-        evaluations.iter().enumerate().for_each(|(i, eval)| {
-            // Check that all the transition constraint evaluations of the trace are zero.
-            // We don't take into account the transition exemptions.
-            if step < exemption_steps[i] && eval != &FieldElement::zero() {
-                ret = false;
-                error!(
-                    "Inconsistent evaluation of transition {} in step {} - expected 0, got {:?}",
-                    i, step, eval
-                );
+        // result. We don't take into account the transition exemptions.
+        for (constraint_idx, eval) in evaluations
+            .into_iter()
+            .enumerate()
+            .filter(|(i, eval)| step < exemption_steps[*i] && eval != &FieldElement::zero())
+        {
+            let violation = ConstraintViolation::Transition {
+                row: lde_trace.step_to_row(step),
+                constraint_idx,
+                value: eval,
+            };
+            if on_violation(violation).is_break() {
+                return;
             }
-        })
+        }
     }
-    info!("Constraints validation check ended");
-    ret
+}
+
+/// A problem found by [`lint_trace`] when checking a trace against an AIR.
+#[derive(Debug)]
+pub enum LintFinding<E: IsField> {
+    /// The shape of the trace or the parameters of the AIR are rejected by the prover before
+    /// proving, with this error.
+    Parameters(ProvingError),
+    /// A constraint of the AIR does not hold over the trace.
+    Constraint(ConstraintViolation<E>),
+}
+
+impl<E: IsField> LintFinding<E> {
+    /// Returns the row of the trace where the finding is located, if it is about a row.
+    pub fn row(&self) -> Option<usize> {
+        match self {
+            LintFinding::Parameters(_) => None,
+            LintFinding::Constraint(ConstraintViolation::Boundary { row, .. })
+            | LintFinding::Constraint(ConstraintViolation::Transition { row, .. }) => Some(*row),
+        }
+    }
+}
+
+impl<E: IsField> fmt::Display for LintFinding<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LintFinding::Parameters(ProvingError::TraceShapeMismatch { expected, got }) => write!(
+                f,
+                "the trace has {} rows and {} columns, but the AIR expects {} rows and {} main columns",
+                got.0, got.1, expected.0, expected.1
+            ),
+            LintFinding::Parameters(ProvingError::TraceTooShort { minimum }) => write!(
+                f,
+                "the trace is shorter than the minimum trace length {} of the AIR",
+                minimum
+            ),
+            LintFinding::Parameters(ProvingError::BlowupTooSmall { needed, got }) => write!(
+                f,
+                "the blowup factor {} is smaller than {}, the number of parts of the composition polynomial",
+                got, needed
+            ),
+            LintFinding::Parameters(ProvingError::InsufficientTwoAdicity { needed, available }) => {
+                write!(
+                    f,
+                    "the LDE domain needs roots of unity of order 2^{}, but the field only has them up to 2^{}",
+                    needed, available
+                )
+            }
+            LintFinding::Parameters(ProvingError::ExemptionsOutOfTrace {
+                constraint_idx,
+                exempted_rows,
+                trace_length,
+            }) => write!(
+                f,
+                "transition constraint {} exempts {} rows at the end of a trace of {} rows",
+                constraint_idx, exempted_rows, trace_length
+            ),
+            LintFinding::Parameters(ProvingError::WrongParameter(message)) => {
+                write!(f, "{}", message)
+            }
+            LintFinding::Parameters(error) => write!(f, "{:?}", error),
+            LintFinding::Constraint(ConstraintViolation::Boundary {
+                row,
+                constraint_idx,
+                value,
+            }) => write!(
+                f,
+                "boundary constraint {} does not hold in row {}: the trace differs from the expected value by {:?}",
+                constraint_idx, row, value
+            ),
+            LintFinding::Constraint(ConstraintViolation::Transition {
+                row,
+                constraint_idx,
+                value,
+            }) => write!(
+                f,
+                "transition constraint {} does not hold in row {}: it evaluates to {:?}",
+                constraint_idx, row, value
+            ),
+        }
+    }
+}
+
+/// Checks everything the prover needs from a trace and an AIR before proving, and returns all
+/// the problems found instead of stopping at the first one, as [`TraceTable::validate`] does:
+/// the shape of the trace, the end exemptions of the transition constraints, the blowup
+/// factors given the degrees of the constraints, the two-adicity of the field and finally every
+/// boundary and transition constraint over the trace.
+///
+/// The auxiliary trace is built from the main trace and the given `rap_challenges`. The
+/// constraints are only checked if the trace has the shape of the AIR and the exemptions fit in
+/// it, since otherwise they cannot be evaluated.
+pub fn lint_trace<A: AIR>(
+    trace: &TraceTable<A::Field>,
+    air: &A,
+    rap_challenges: &[FieldElement<A::FieldExtension>],
+) -> Result<(), Vec<LintFinding<A::FieldExtension>>> {
+    let shape = [
+        check_trace_shape(air, trace),
+        check_transition_exemptions(air),
+    ];
+    let can_evaluate_constraints = shape.iter().all(Result::is_ok);
+    let parameters = [
+        check_trace_length(air),
+        check_blowup_factor(air),
+        check_composition_blowup_factor(air),
+        check_two_adicity(air),
+    ];
+
+    let mut findings: Vec<_> = shape
+        .into_iter()
+        .chain(parameters)
+        .filter_map(Result::err)
+        .map(LintFinding::Parameters)
+        .collect();
+    if can_evaluate_constraints {
+        findings.extend(
+            trace
+                .constraint_violations(air, rap_challenges)
+                .into_iter()
+                .map(LintFinding::Constraint),
+        );
+    }
+
+    if findings.is_empty() {
+        Ok(())
+    } else {
+        Err(findings)
+    }
+}
+
+/// A transition constraint whose declared degree exceeds by more than one the degree it
+/// realizes over a trace. See `overestimated_transition_degrees`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DegreeOverestimate {
+    pub constraint_idx: usize,
+    pub declared: usize,
+    pub realized: usize,
+}
+
+/// Interpolates each transition constraint from its evaluations over an LDE coset with the given
+/// offset and warns about the constraints whose declared degree exceeds by more than one their
+/// realized degree, that is, the degree of their polynomial over the degree of the trace
+/// polynomials. Overestimated degrees make the composition polynomial, and so the proof, larger
+/// than needed.
+/// `transition_evaluations` holds the evaluations of all the transition constraints at each
+/// point of the coset and `declared_degrees` their degrees, by constraint index.
+#[cfg(any(test, all(debug_assertions, not(feature = "parallel"))))]
+pub(crate) fn overestimated_transition_degrees<F, E>(
+    declared_degrees: &[usize],
+    transition_evaluations: &[Vec<FieldElement<E>>],
+    trace_length: usize,
+    coset_offset: &FieldElement<F>,
+) -> Vec<DegreeOverestimate>
+where
+    F: IsFFTField + IsSubFieldOf<E>,
+    E: IsField,
+{
+    declared_degrees
+        .iter()
+        .enumerate()
+        .filter_map(|(constraint_idx, &declared)| {
+            let evaluations: Vec<_> = transition_evaluations
+                .iter()
+                .map(|evaluations| evaluations[constraint_idx].clone())
+                .collect();
+            let poly = Polynomial::interpolate_offset_fft(&evaluations, coset_offset).ok()?;
+            let realized = (poly.degree() + trace_length - 2) / (trace_length - 1);
+
+            (declared > realized + 1).then(|| {
+                warn!(
+                    "Transition constraint {} is declared with degree {} but has degree {} over the trace, declaring a lower degree would make the proof smaller",
+                    constraint_idx, declared, realized
+                );
+                DegreeOverestimate {
+                    constraint_idx,
+                    declared,
+                    realized,
+                }
+            })
+        })
+        .collect()
 }
 
 pub fn check_boundary_polys_divisibility<F: IsFFTField>(
@@ -141,3 +466,115 @@ where
     let rows: Vec<Vec<FieldElement<F>>> = data.chunks(width).map(|c| c.to_vec()).collect();
     rows.iter().all(|r| r.len() == rows[0].len())
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        examples::simple_fibonacci::{fibonacci_trace, FibonacciAIR, FibonacciPublicInputs},
+        proof::options::ProofOptions,
+    };
+    use lambdaworks_math::field::fields::fft_friendly::stark_252_prime_field::Stark252PrimeField;
+
+    type FE = FieldElement<Stark252PrimeField>;
+
+    #[test]
+    fn lint_reports_every_constraint_that_does_not_hold() {
+        let mut trace = fibonacci_trace([FE::one(), FE::one()], 8);
+        let pub_inputs = FibonacciPublicInputs {
+            a0: FE::one(),
+            a1: FE::one(),
+        };
+        let air = FibonacciAIR::new(
+            trace.n_rows(),
+            &pub_inputs,
+            &ProofOptions::default_test_options(),
+        );
+        assert!(lint_trace(&trace, &air, &[]).is_ok());
+
+        // The first value breaks both the boundary constraint on it and the transition from it.
+        trace.get_row_mut(0)[0] = FE::from(2);
+        let findings = lint_trace(&trace, &air, &[]).unwrap_err();
+
+        assert_eq!(findings.len(), 2);
+        assert!(matches!(
+            &findings[0],
+            LintFinding::Constraint(ConstraintViolation::Boundary {
+                row: 0,
+                constraint_idx: 0,
+                ..
+            })
+        ));
+        assert!(matches!(
+            &findings[1],
+            LintFinding::Constraint(ConstraintViolation::Transition {
+                row: 0,
+                constraint_idx: 0,
+                ..
+            })
+        ));
+        assert_eq!(
+            findings.iter().map(LintFinding::row).collect::<Vec<_>>(),
+            vec![Some(0), Some(0)]
+        );
+    }
+
+    #[test]
+    fn lint_reports_a_trace_with_the_wrong_shape_without_evaluating_the_constraints() {
+        let trace = fibonacci_trace([FE::one(), FE::one()], 8);
+        let pub_inputs = FibonacciPublicInputs {
+            a0: FE::one(),
+            a1: FE::one(),
+        };
+        let air = FibonacciAIR::new(16, &pub_inputs, &ProofOptions::default_test_options());
+
+        let findings = lint_trace(&trace, &air, &[]).unwrap_err();
+
+        assert_eq!(findings.len(), 1);
+        assert!(matches!(
+            &findings[0],
+            LintFinding::Parameters(ProvingError::TraceShapeMismatch {
+                expected: (16, 1),
+                got: (8, 1),
+            })
+        ));
+        assert_eq!(
+            findings[0].to_string(),
+            "the trace has 8 rows and 1 columns, but the AIR expects 16 rows and 1 main columns"
+        );
+    }
+
+    #[test]
+    fn constraint_declared_with_a_too_high_degree_is_reported() {
+        let trace_length = 8;
+        let blowup_factor = 4;
+        let coset_offset = FE::from(3);
+        // Both constraints have degree 1 over the trace, the degree of the trace polynomials.
+        let constraint_poly = Polynomial::new_monomial(FE::from(5), trace_length - 1);
+        let evaluations = Polynomial::evaluate_offset_fft(
+            &constraint_poly,
+            blowup_factor,
+            Some(trace_length),
+            &coset_offset,
+        )
+        .unwrap();
+        let transition_evaluations: Vec<_> = evaluations
+            .into_iter()
+            .map(|evaluation| vec![evaluation, evaluation])
+            .collect();
+
+        assert_eq!(
+            overestimated_transition_degrees(
+                &[3, 2],
+                &transition_evaluations,
+                trace_length,
+                &coset_offset
+            ),
+            vec![DegreeOverestimate {
+                constraint_idx: 0,
+                declared: 3,
+                realized: 1,
+            }]
+        );
+    }
+}
@@ -1,10 +1,44 @@
+use alloc::sync::Arc;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
 use lambdaworks_math::{
-    fft::cpu::roots_of_unity::get_powers_of_primitive_root_coset,
-    field::{element::FieldElement, traits::IsFFTField},
+    fft::cpu::roots_of_unity::{get_powers_of_primitive_root_coset, get_twiddles},
+    field::{
+        element::FieldElement,
+        traits::{IsFFTField, IsField, IsSubFieldOf, RootsConfig},
+    },
 };
 
 use super::traits::AIR;
+use crate::{fri, proof::stark::SelfDescribingProof, verifier::VerificationError};
+
+/// The twiddle factors of the FFTs over the LDE domain, computed once so that they are not
+/// computed again for every polynomial the prover evaluates or interpolates. Since an FFT only
+/// uses a prefix of the twiddles of a larger one, they also serve the FFTs over the trace and
+/// composition domains. Cloning it does not copy them.
+#[derive(Clone)]
+pub struct TwiddleCache<F: IsFFTField> {
+    pub(crate) twiddles: Arc<Vec<FieldElement<F>>>,
+    pub(crate) inverse_twiddles: Arc<Vec<FieldElement<F>>>,
+}
 
+impl<F: IsFFTField> TwiddleCache<F> {
+    /// Computes the twiddle factors of the FFTs of size up to `2^order`.
+    pub fn new(order: u64) -> Self {
+        Self {
+            twiddles: Arc::new(get_twiddles(order, RootsConfig::BitReverse).unwrap()),
+            inverse_twiddles: Arc::new(
+                get_twiddles(order, RootsConfig::BitReverseInversed).unwrap(),
+            ),
+        }
+    }
+}
+
+/// The domains the prover and the verifier work over. It only depends on the trace length,
+/// the blowup factors and the coset offset, so it can be built once with
+/// [`Domain::for_params`] and shared by every proof of that size.
+#[derive(Clone)]
 pub struct Domain<F: IsFFTField> {
     pub(crate) root_order: u32,
     pub(crate) lde_roots_of_unity_coset: Vec<FieldElement<F>>,
@@ -13,6 +47,12 @@ pub struct Domain<F: IsFFTField> {
     pub(crate) coset_offset: FieldElement<F>,
     pub(crate) blowup_factor: usize,
     pub(crate) interpolation_domain_size: usize,
+    /// The blowup factor of the domain over which the constraints are evaluated to interpolate
+    /// the composition polynomial. See [`Domain::composition_domain`].
+    pub(crate) composition_blowup_factor: usize,
+    /// The twiddle factors of the FFTs over the LDE domain, if they were computed with
+    /// [`Domain::with_twiddle_cache`].
+    pub(crate) twiddle_cache: Option<TwiddleCache<F>>,
 }
 
 impl<F: IsFFTField> Domain<F> {
@@ -20,11 +60,73 @@ impl<F: IsFFTField> Domain<F> {
     where
         A: AIR<Field = F>,
     {
+        Self::for_params(
+            air.trace_length(),
+            air.options().blowup_factor as usize,
+            air.options().coset_offset,
+        )
+        .with_composition_blowup_factor(air.composition_blowup_factor() as usize)
+    }
+
+    /// Rebuilds the domain a self describing proof was produced over from the trace length of
+    /// the proof and the proof options of its context, without building its AIR. The
+    /// parameters come from the proof, so they are checked before use: the trace length and
+    /// the blowup factors must be powers of two, the LDE domain must fit in the two-adic
+    /// subgroup of the field, and the proof must have as many FRI layers as FRI folds the
+    /// polynomials of this domain.
+    pub fn from_proof_metadata<E, PI>(
+        proof: &SelfDescribingProof<F, E, PI>,
+    ) -> Result<Self, VerificationError>
+    where
+        F: IsSubFieldOf<E>,
+        E: IsField,
+    {
+        let options = &proof.context.proof_options;
+        let trace_length = proof.proof.trace_length;
+        let blowup_factor = options.blowup_factor as usize;
+        let composition_blowup_factor = options
+            .composition_blowup_factor
+            .unwrap_or(options.blowup_factor) as usize;
+
+        if !trace_length.is_power_of_two()
+            || !blowup_factor.is_power_of_two()
+            || !composition_blowup_factor.is_power_of_two()
+            || composition_blowup_factor > blowup_factor
+        {
+            return Err(VerificationError::InvalidProof);
+        }
+        let lde_order = trace_length.trailing_zeros() + blowup_factor.trailing_zeros();
+        if lde_order as u64 > F::TWO_ADICITY {
+            return Err(VerificationError::InvalidProof);
+        }
+
+        let (number_of_folds, _) = fri::fold_schedule(
+            trace_length << usize::from(options.zero_knowledge),
+            options.fri_final_poly_degree,
+        );
+        let expected = number_of_folds
+            .checked_sub(1)
+            .ok_or(VerificationError::InvalidProof)?;
+        let got = proof.proof.fri_layers_merkle_roots.len();
+        if got != expected {
+            return Err(VerificationError::WrongFriLayerCount { expected, got });
+        }
+
+        Ok(
+            Self::for_params(trace_length, blowup_factor, options.coset_offset)
+                .with_composition_blowup_factor(composition_blowup_factor),
+        )
+    }
+
+    /// Builds the domain for traces of length `trace_length`, which must be a power of two,
+    /// with the given blowup factor and coset offset. Its composition blowup factor is the
+    /// blowup factor, see [`Domain::with_composition_blowup_factor`] to change it.
+    pub fn for_params(trace_length: usize, blowup_factor: usize, coset_offset: u64) -> Self {
         // Initial definitions
-        let blowup_factor = air.options().blowup_factor as usize;
-        let coset_offset = FieldElement::from(air.options().coset_offset);
-        let interpolation_domain_size = air.trace_length();
-        let root_order = air.trace_length().trailing_zeros();
+        let composition_blowup_factor = blowup_factor;
+        let coset_offset = FieldElement::from(coset_offset);
+        let interpolation_domain_size = trace_length;
+        let root_order = trace_length.trailing_zeros();
         // * Generate Coset
         let trace_primitive_root = F::get_primitive_root_of_unity(root_order as u64).unwrap();
         let trace_roots_of_unity = get_powers_of_primitive_root_coset(
@@ -34,10 +136,10 @@ impl<F: IsFFTField> Domain<F> {
         )
         .unwrap();
 
-        let lde_root_order = (air.trace_length() * blowup_factor).trailing_zeros();
+        let lde_root_order = (trace_length * blowup_factor).trailing_zeros();
         let lde_roots_of_unity_coset = get_powers_of_primitive_root_coset(
             lde_root_order as u64,
-            air.trace_length() * blowup_factor,
+            trace_length * blowup_factor,
             &coset_offset,
         )
         .unwrap();
@@ -50,6 +152,136 @@ impl<F: IsFFTField> Domain<F> {
             blowup_factor,
             coset_offset,
             interpolation_domain_size,
+            composition_blowup_factor,
+            twiddle_cache: None,
         }
     }
+
+    /// Computes the twiddle factors of the FFTs over the LDE domain, which the prover then
+    /// reuses for every polynomial. The verifier does not need them.
+    pub fn with_twiddle_cache(mut self) -> Self {
+        let order = self.lde_roots_of_unity_coset.len().trailing_zeros();
+        self.twiddle_cache = Some(TwiddleCache::new(order as u64));
+        self
+    }
+
+    /// Sets the blowup factor of the domain over which the constraints are evaluated, see
+    /// `ProofOptions::composition_blowup_factor`.
+    pub fn with_composition_blowup_factor(mut self, composition_blowup_factor: usize) -> Self {
+        self.composition_blowup_factor = composition_blowup_factor;
+        self
+    }
+
+    /// Returns the domain over which the constraints are evaluated to interpolate the
+    /// composition polynomial. Its LDE coset has the same offset and a blowup factor of
+    /// `composition_blowup_factor`, so it is made of one of every
+    /// `blowup_factor / composition_blowup_factor` elements of the LDE coset of `self`.
+    pub(crate) fn composition_domain(&self) -> Self {
+        let step = self.blowup_factor / self.composition_blowup_factor;
+        let lde_roots_of_unity_coset = self
+            .lde_roots_of_unity_coset
+            .iter()
+            .step_by(step)
+            .cloned()
+            .collect();
+
+        Self {
+            root_order: self.root_order,
+            lde_roots_of_unity_coset,
+            trace_primitive_root: self.trace_primitive_root.clone(),
+            trace_roots_of_unity: self.trace_roots_of_unity.clone(),
+            coset_offset: self.coset_offset.clone(),
+            blowup_factor: self.composition_blowup_factor,
+            interpolation_domain_size: self.interpolation_domain_size,
+            composition_blowup_factor: self.composition_blowup_factor,
+            twiddle_cache: self.twiddle_cache.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        examples::simple_fibonacci::{self, FibonacciAIR, FibonacciPublicInputs},
+        proof::options::ProofOptions,
+        prover::{IsStarkProver, Prover},
+        transcript::StoneProverTranscript,
+        Felt252,
+    };
+    use lambdaworks_math::field::fields::fft_friendly::stark_252_prime_field::Stark252PrimeField;
+
+    type F = Stark252PrimeField;
+
+    fn fibonacci_self_describing_proof(
+        proof_options: &ProofOptions,
+    ) -> SelfDescribingProof<F, F, FibonacciPublicInputs<F>> {
+        let trace = simple_fibonacci::fibonacci_trace([Felt252::from(1), Felt252::from(1)], 16);
+        let pub_inputs = FibonacciPublicInputs {
+            a0: Felt252::one(),
+            a1: Felt252::one(),
+        };
+        let proof = Prover::<FibonacciAIR<F>>::prove(
+            &trace,
+            &pub_inputs,
+            proof_options,
+            StoneProverTranscript::new(&[]),
+        )
+        .unwrap();
+        SelfDescribingProof::new::<FibonacciAIR<F>>(proof, pub_inputs, proof_options)
+    }
+
+    #[test]
+    fn domain_from_proof_metadata_is_the_domain_of_the_air() {
+        let proof_options = ProofOptions::default_test_options().with_blowup_factor(8);
+        let proof = fibonacci_self_describing_proof(&proof_options);
+
+        let domain = Domain::from_proof_metadata(&proof).unwrap();
+        let air = FibonacciAIR::new(
+            proof.proof.trace_length,
+            &proof.public_inputs,
+            &proof_options,
+        );
+        let expected = Domain::new(&air);
+
+        assert_eq!(domain.root_order, expected.root_order);
+        assert_eq!(
+            domain.lde_roots_of_unity_coset,
+            expected.lde_roots_of_unity_coset
+        );
+        assert_eq!(domain.trace_primitive_root, expected.trace_primitive_root);
+        assert_eq!(domain.trace_roots_of_unity, expected.trace_roots_of_unity);
+        assert_eq!(domain.coset_offset, expected.coset_offset);
+        assert_eq!(domain.blowup_factor, expected.blowup_factor);
+        assert_eq!(
+            domain.interpolation_domain_size,
+            expected.interpolation_domain_size
+        );
+        assert_eq!(
+            domain.composition_blowup_factor,
+            expected.composition_blowup_factor
+        );
+    }
+
+    #[test]
+    fn domain_from_proof_metadata_rejects_a_wrong_fri_layer_count() {
+        let mut proof = fibonacci_self_describing_proof(&ProofOptions::default_test_options());
+        proof.proof.fri_layers_merkle_roots.pop();
+
+        assert!(matches!(
+            Domain::from_proof_metadata(&proof),
+            Err(VerificationError::WrongFriLayerCount { .. })
+        ));
+    }
+
+    #[test]
+    fn domain_from_proof_metadata_rejects_a_trace_length_that_is_not_a_power_of_two() {
+        let mut proof = fibonacci_self_describing_proof(&ProofOptions::default_test_options());
+        proof.proof.trace_length = 12;
+
+        assert!(matches!(
+            Domain::from_proof_metadata(&proof),
+            Err(VerificationError::InvalidProof)
+        ));
+    }
 }
@@ -0,0 +1,182 @@
+#[cfg(not(feature = "std"))]
+use alloc::{boxed::Box, vec::Vec};
+use core::marker::PhantomData;
+
+use crate::{
+    constraints::{boundary::BoundaryConstraints, transition::TransitionConstraint},
+    context::AirContext,
+    frame::Frame,
+    proof::options::ProofOptions,
+    trace::TraceTable,
+    traits::AIR,
+};
+use lambdaworks_math::field::{element::FieldElement, traits::IsFFTField};
+
+#[derive(Clone)]
+struct BitConstraint<F: IsFFTField> {
+    phantom: PhantomData<F>,
+}
+
+impl<F: IsFFTField> BitConstraint<F> {
+    pub fn new() -> Self {
+        Self {
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<F> TransitionConstraint<F, F> for BitConstraint<F>
+where
+    F: IsFFTField + Send + Sync,
+{
+    fn degree(&self) -> usize {
+        2
+    }
+
+    fn constraint_idx(&self) -> usize {
+        0
+    }
+
+    fn end_exemptions(&self) -> usize {
+        0
+    }
+
+    fn evaluate(
+        &self,
+        frame: &Frame<F, F>,
+        transition_evaluations: &mut [FieldElement<F>],
+        _periodic_values: &[FieldElement<F>],
+        _rap_challenges: &[FieldElement<F>],
+    ) {
+        let step = frame.get_evaluation_step(0);
+
+        let bit = step.get_main_evaluation_element(0, 0);
+
+        transition_evaluations[self.constraint_idx()] = bit * (bit - FieldElement::<F>::one());
+    }
+}
+
+/// Proves that all the values of a single column trace are bits. It has no boundary
+/// constraints, so it only exercises the transition part of the composition polynomial.
+pub struct BitColumnAIR<F>
+where
+    F: IsFFTField,
+{
+    context: AirContext,
+    trace_length: usize,
+    transition_constraints: Vec<Box<dyn TransitionConstraint<F, F>>>,
+}
+
+impl<F> AIR for BitColumnAIR<F>
+where
+    F: IsFFTField + Send + Sync + 'static,
+{
+    type Field = F;
+    type FieldExtension = F;
+    type PublicInputs = ();
+
+    const STEP_SIZE: usize = 1;
+
+    fn new(
+        trace_length: usize,
+        _pub_inputs: &Self::PublicInputs,
+        proof_options: &ProofOptions,
+    ) -> Self {
+        let transition_constraints: Vec<
+            Box<dyn TransitionConstraint<Self::Field, Self::FieldExtension>>,
+        > = vec![Box::new(BitConstraint::new())];
+
+        let context = AirContext {
+            proof_options: proof_options.clone(),
+            trace_columns: 1,
+            transition_offsets: vec![0],
+            transition_exemptions: vec![0],
+            num_transition_constraints: transition_constraints.len(),
+        };
+
+        Self {
+            context,
+            trace_length,
+            transition_constraints,
+        }
+    }
+
+    fn boundary_constraints(
+        &self,
+        _rap_challenges: &[FieldElement<Self::FieldExtension>],
+    ) -> BoundaryConstraints<Self::FieldExtension> {
+        BoundaryConstraints::new()
+    }
+
+    fn transition_constraints(
+        &self,
+    ) -> &Vec<Box<dyn TransitionConstraint<Self::Field, Self::FieldExtension>>> {
+        &self.transition_constraints
+    }
+
+    fn context(&self) -> &AirContext {
+        &self.context
+    }
+
+    fn composition_poly_degree_bound(&self) -> usize {
+        2 * self.trace_length()
+    }
+
+    fn trace_layout(&self) -> (usize, usize) {
+        (1, 0)
+    }
+
+    fn trace_length(&self) -> usize {
+        self.trace_length
+    }
+
+    fn pub_inputs(&self) -> &Self::PublicInputs {
+        &()
+    }
+
+    fn compute_transition_verifier(
+        &self,
+        frame: &Frame<Self::FieldExtension, Self::FieldExtension>,
+        periodic_values: &[FieldElement<Self::FieldExtension>],
+        rap_challenges: &[FieldElement<Self::FieldExtension>],
+    ) -> Vec<FieldElement<Self::Field>> {
+        self.compute_transition_prover(frame, periodic_values, rap_challenges)
+    }
+}
+
+/// Builds a trace with a single column holding the `bits`. Its length is the length of the
+/// trace, so it should be a power of two.
+pub fn bit_column_trace<F: IsFFTField>(bits: &[bool]) -> TraceTable<F> {
+    let column = bits
+        .iter()
+        .map(|&bit| FieldElement::from(bit as u64))
+        .collect();
+
+    TraceTable::from_columns_main(vec![column], 1)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use lambdaworks_math::field::fields::u64_prime_field::{F17, FE17};
+
+    #[test]
+    fn bit_column_air_has_no_boundary_constraints() {
+        let trace = bit_column_trace::<F17>(&[true, false, false, true]);
+        let air =
+            BitColumnAIR::<F17>::new(trace.n_rows(), &(), &ProofOptions::default_test_options());
+
+        assert_eq!(air.num_boundary_constraints(&[]), 0);
+        assert_eq!(trace.validate(&air, &[]), Ok(()));
+    }
+
+    #[test]
+    fn non_bit_value_breaks_the_transition_constraint() {
+        let column = [1u64, 0, 2, 1].map(FE17::from).to_vec();
+        let trace = TraceTable::<F17>::from_columns_main(vec![column], 1);
+        let air =
+            BitColumnAIR::<F17>::new(trace.n_rows(), &(), &ProofOptions::default_test_options());
+
+        assert!(trace.validate(&air, &[]).is_err());
+    }
+}
@@ -7,10 +7,12 @@ use crate::{
     traits::AIR,
     Felt252,
 };
+#[cfg(not(feature = "std"))]
+use alloc::{boxed::Box, vec::Vec};
+use core::iter;
 use lambdaworks_math::field::{
     element::FieldElement, fields::fft_friendly::stark_252_prime_field::Stark252PrimeField,
 };
-use std::iter;
 
 type StarkField = Stark252PrimeField;
 
@@ -1,4 +1,6 @@
-use std::marker::PhantomData;
+#[cfg(not(feature = "std"))]
+use alloc::{boxed::Box, vec::Vec};
+use core::marker::PhantomData;
 
 use crate::{
     constraints::{
@@ -140,7 +142,7 @@ impl AIR for DummyAIR {
         let context = AirContext {
             proof_options: proof_options.clone(),
             trace_columns: 2,
-            transition_exemptions: vec![0, 2],
+            transition_exemptions: vec![2, 0],
             transition_offsets: vec![0, 1, 2],
             num_transition_constraints: 2,
         };
@@ -217,3 +219,32 @@ pub fn dummy_trace<F: IsFFTField>(trace_length: usize) -> TraceTable<F> {
         1,
     )
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::debug::ConstraintViolation;
+
+    #[test]
+    fn each_constraint_is_exempted_on_its_own_rows() {
+        let trace_length = 16;
+        let air = DummyAIR::new(trace_length, &(), &ProofOptions::default_test_options());
+
+        // The Fibonacci constraint does not hold on the last two rows, which wrap around.
+        let trace = dummy_trace::<StarkField>(trace_length);
+        assert_eq!(trace.validate(&air, &[]), Ok(()));
+
+        // The bit constraint has no exemptions, so it has to hold on the last row.
+        let mut columns = trace.columns();
+        columns[0][trace_length - 1] = FieldElement::from(2);
+        let trace = TraceTable::from_columns(columns, 2, 1);
+        assert_eq!(
+            trace.validate(&air, &[]),
+            Err(ConstraintViolation::Transition {
+                row: trace_length - 1,
+                constraint_idx: 1,
+                value: FieldElement::from(2),
+            })
+        );
+    }
+}
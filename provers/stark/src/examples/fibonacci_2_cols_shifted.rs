@@ -9,11 +9,13 @@ use crate::{
     trace::TraceTable,
     traits::AIR,
 };
+#[cfg(not(feature = "std"))]
+use alloc::{boxed::Box, vec::Vec};
+use core::marker::PhantomData;
 use lambdaworks_math::{
     field::{element::FieldElement, traits::IsFFTField},
     traits::AsBytes,
 };
-use std::marker::PhantomData;
 
 #[derive(Clone)]
 struct ShiftedFibTransition1<F: IsFFTField> {
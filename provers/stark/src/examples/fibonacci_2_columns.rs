@@ -1,4 +1,6 @@
-use std::marker::PhantomData;
+#[cfg(not(feature = "std"))]
+use alloc::{boxed::Box, vec::Vec};
+use core::marker::PhantomData;
 
 use super::simple_fibonacci::FibonacciPublicInputs;
 use crate::{
@@ -0,0 +1,208 @@
+#[cfg(not(feature = "std"))]
+use alloc::{boxed::Box, vec::Vec};
+use core::marker::PhantomData;
+
+use crate::{
+    constraints::{
+        boundary::{BoundaryConstraint, BoundaryConstraints},
+        transition::TransitionConstraint,
+    },
+    context::AirContext,
+    frame::Frame,
+    proof::options::ProofOptions,
+    traits::AIR,
+};
+use lambdaworks_math::field::{
+    element::FieldElement,
+    traits::{IsFFTField, IsField, IsSubFieldOf},
+};
+
+use super::simple_fibonacci::FibonacciPublicInputs;
+
+/// The Fibonacci constraint `a_{i+2} - a_{i+1} - a_i = 0`, evaluated over the base field and
+/// embedded in the extension.
+struct FibConstraint<F, E> {
+    phantom: PhantomData<(F, E)>,
+}
+
+impl<F, E> FibConstraint<F, E> {
+    pub fn new() -> Self {
+        Self {
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<F, E> TransitionConstraint<F, E> for FibConstraint<F, E>
+where
+    F: IsFFTField + IsSubFieldOf<E> + Send + Sync,
+    E: IsField + Send + Sync,
+{
+    fn degree(&self) -> usize {
+        1
+    }
+
+    fn constraint_idx(&self) -> usize {
+        0
+    }
+
+    fn end_exemptions(&self) -> usize {
+        2
+    }
+
+    fn evaluate(
+        &self,
+        frame: &Frame<F, E>,
+        transition_evaluations: &mut [FieldElement<E>],
+        _periodic_values: &[FieldElement<F>],
+        _rap_challenges: &[FieldElement<E>],
+    ) {
+        let a0 = frame
+            .get_evaluation_step(0)
+            .get_main_evaluation_element(0, 0);
+        let a1 = frame
+            .get_evaluation_step(1)
+            .get_main_evaluation_element(0, 0);
+        let a2 = frame
+            .get_evaluation_step(2)
+            .get_main_evaluation_element(0, 0);
+
+        transition_evaluations[self.constraint_idx()] = (a2 - a1 - a0).to_extension();
+    }
+}
+
+/// A Fibonacci AIR whose trace is over the field `F` and whose challenges, out of domain
+/// point and FRI layers are over its extension `E`. This is how AIRs over small fields, such
+/// as Babybear, reach the soundness of a large field: the trace is committed and
+/// interpolated over `F`, and only the values that depend on the verifier randomness are
+/// in `E`.
+pub struct FibonacciExtensionAIR<F, E>
+where
+    F: IsFFTField,
+{
+    context: AirContext,
+    trace_length: usize,
+    pub_inputs: FibonacciPublicInputs<F>,
+    constraints: Vec<Box<dyn TransitionConstraint<F, E>>>,
+}
+
+impl<F, E> AIR for FibonacciExtensionAIR<F, E>
+where
+    F: IsFFTField + IsSubFieldOf<E> + Send + Sync + 'static,
+    E: IsField + Send + Sync + 'static,
+{
+    type Field = F;
+    type FieldExtension = E;
+    type PublicInputs = FibonacciPublicInputs<F>;
+
+    const STEP_SIZE: usize = 1;
+
+    fn new(
+        trace_length: usize,
+        pub_inputs: &Self::PublicInputs,
+        proof_options: &ProofOptions,
+    ) -> Self {
+        let constraints: Vec<Box<dyn TransitionConstraint<F, E>>> =
+            vec![Box::new(FibConstraint::new())];
+
+        let context = AirContext {
+            proof_options: proof_options.clone(),
+            trace_columns: 1,
+            transition_exemptions: vec![2],
+            transition_offsets: vec![0, 1, 2],
+            num_transition_constraints: constraints.len(),
+        };
+
+        Self {
+            pub_inputs: pub_inputs.clone(),
+            context,
+            trace_length,
+            constraints,
+        }
+    }
+
+    fn composition_poly_degree_bound(&self) -> usize {
+        self.trace_length()
+    }
+
+    fn transition_constraints(&self) -> &Vec<Box<dyn TransitionConstraint<F, E>>> {
+        &self.constraints
+    }
+
+    fn boundary_constraints(
+        &self,
+        _rap_challenges: &[FieldElement<Self::FieldExtension>],
+    ) -> BoundaryConstraints<Self::FieldExtension> {
+        let a0 =
+            BoundaryConstraint::new_simple_main(0, self.pub_inputs.a0.clone().to_extension::<E>());
+        let a1 =
+            BoundaryConstraint::new_simple_main(1, self.pub_inputs.a1.clone().to_extension::<E>());
+
+        BoundaryConstraints::from_constraints(vec![a0, a1])
+    }
+
+    fn context(&self) -> &AirContext {
+        &self.context
+    }
+
+    fn trace_length(&self) -> usize {
+        self.trace_length
+    }
+
+    fn trace_layout(&self) -> (usize, usize) {
+        (1, 0)
+    }
+
+    fn pub_inputs(&self) -> &Self::PublicInputs {
+        &self.pub_inputs
+    }
+
+    /// The out of domain frame of the verifier is over the extension, so the constraint is
+    /// evaluated directly over it.
+    fn compute_transition_verifier(
+        &self,
+        frame: &Frame<Self::FieldExtension, Self::FieldExtension>,
+        _periodic_values: &[FieldElement<Self::FieldExtension>],
+        _rap_challenges: &[FieldElement<Self::FieldExtension>],
+    ) -> Vec<FieldElement<Self::FieldExtension>> {
+        let a0 = frame
+            .get_evaluation_step(0)
+            .get_main_evaluation_element(0, 0);
+        let a1 = frame
+            .get_evaluation_step(1)
+            .get_main_evaluation_element(0, 0);
+        let a2 = frame
+            .get_evaluation_step(2)
+            .get_main_evaluation_element(0, 0);
+
+        vec![a2 - a1 - a0]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::examples::simple_fibonacci::fibonacci_trace;
+    use lambdaworks_math::field::fields::fft_friendly::{
+        babybear::Babybear31PrimeField, quadratic_babybear::QuadraticBabybearField,
+    };
+
+    type F = Babybear31PrimeField;
+    type E = QuadraticBabybearField;
+
+    #[test]
+    fn fibonacci_trace_is_valid_over_the_extension() {
+        let trace = fibonacci_trace([FieldElement::<F>::one(), FieldElement::one()], 16);
+        let pub_inputs = FibonacciPublicInputs {
+            a0: FieldElement::one(),
+            a1: FieldElement::one(),
+        };
+        let air = FibonacciExtensionAIR::<F, E>::new(
+            trace.n_rows(),
+            &pub_inputs,
+            &ProofOptions::default_test_options(),
+        );
+
+        assert!(trace.validate(&air, &[]).is_ok());
+    }
+}
@@ -1,4 +1,6 @@
-use std::{marker::PhantomData, ops::Div};
+#[cfg(not(feature = "std"))]
+use alloc::{boxed::Box, vec::Vec};
+use core::{marker::PhantomData, ops::Div};
 
 use crate::{
     constraints::{
@@ -232,11 +234,16 @@ where
         let a1 =
             BoundaryConstraint::new_simple_main(1, FieldElement::<Self::FieldExtension>::one());
 
-        // Auxiliary boundary constraints
+        // Auxiliary boundary constraints. The accumulated product starts at one, and it ends
+        // at one only if the second column is a permutation of the first one.
         let a0_aux = BoundaryConstraint::new_aux(0, 0, FieldElement::<Self::FieldExtension>::one());
+        let a_last_aux = BoundaryConstraint::new_aux(
+            0,
+            self.trace_length() - 1,
+            FieldElement::<Self::FieldExtension>::one(),
+        );
 
-        BoundaryConstraints::from_constraints(vec![a0, a1, a0_aux])
-        // BoundaryConstraints::from_constraints(vec![a0, a1])
+        BoundaryConstraints::from_constraints(vec![a0, a1, a0_aux, a_last_aux])
     }
 
     fn transition_constraints(
@@ -300,7 +307,10 @@ pub fn fibonacci_rap_trace<F: IsFFTField>(
 #[cfg(test)]
 mod test {
     use super::*;
-    use lambdaworks_math::field::fields::u64_prime_field::FE17;
+    use crate::debug::ConstraintViolation;
+    use lambdaworks_math::field::fields::{
+        fft_friendly::stark_252_prime_field::Stark252PrimeField, u64_prime_field::FE17,
+    };
 
     #[test]
     fn test_build_fibonacci_rap_trace() {
@@ -367,4 +377,48 @@ mod test {
 
         assert_eq!(aux_col.last().unwrap(), &FE17::one());
     }
+
+    fn rap_air(steps: usize, trace_length: usize) -> FibonacciRAP<Stark252PrimeField> {
+        let pub_inputs = FibonacciRAPPublicInputs {
+            steps,
+            a0: FieldElement::one(),
+            a1: FieldElement::one(),
+        };
+        FibonacciRAP::new(
+            trace_length,
+            &pub_inputs,
+            &ProofOptions::default_test_options(),
+        )
+    }
+
+    #[test]
+    fn permuted_trace_satisfies_constraints() {
+        let trace = fibonacci_rap_trace::<Stark252PrimeField>(
+            [FieldElement::one(), FieldElement::one()],
+            16,
+        );
+        let air = rap_air(16, trace.n_rows());
+        let gamma = FieldElement::from(10);
+
+        assert_eq!(trace.validate(&air, &[gamma]), Ok(()));
+    }
+
+    #[test]
+    fn non_permuted_trace_breaks_the_last_aux_boundary_constraint() {
+        let mut trace = fibonacci_rap_trace::<Stark252PrimeField>(
+            [FieldElement::one(), FieldElement::one()],
+            16,
+        );
+        // Replace a value of the permuted column, so that it is no longer a permutation
+        // of the Fibonacci column.
+        trace.get_row_mut(3)[1] = FieldElement::from(4);
+        let air = rap_air(16, trace.n_rows());
+        let gamma = FieldElement::from(10);
+
+        let last_row = trace.n_rows() - 1;
+        assert!(matches!(
+            trace.validate(&air, &[gamma]),
+            Err(ConstraintViolation::Boundary { row, constraint_idx: 3, .. }) if row == last_row
+        ));
+    }
 }
@@ -0,0 +1,232 @@
+#[cfg(not(feature = "std"))]
+use alloc::{boxed::Box, vec::Vec};
+use core::marker::PhantomData;
+
+use crate::{
+    constraints::{
+        boundary::{BoundaryConstraint, BoundaryConstraints},
+        transition::TransitionConstraint,
+    },
+    context::AirContext,
+    frame::Frame,
+    logup::{self, LogUpConstraint},
+    proof::options::ProofOptions,
+    trace::TraceTable,
+    traits::AIR,
+};
+use lambdaworks_crypto::fiat_shamir::is_transcript::IsTranscript;
+use lambdaworks_math::field::{element::FieldElement, traits::IsFFTField};
+
+/// Constrains the table column to hold the values 0, 1, 2, ... by checking that each
+/// value is the previous one plus one.
+#[derive(Clone)]
+struct TableIncrementConstraint<F: IsFFTField> {
+    phantom: PhantomData<F>,
+}
+
+impl<F: IsFFTField> TableIncrementConstraint<F> {
+    pub fn new() -> Self {
+        Self {
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<F> TransitionConstraint<F, F> for TableIncrementConstraint<F>
+where
+    F: IsFFTField + Send + Sync,
+{
+    fn degree(&self) -> usize {
+        1
+    }
+
+    fn constraint_idx(&self) -> usize {
+        0
+    }
+
+    fn end_exemptions(&self) -> usize {
+        1
+    }
+
+    fn evaluate(
+        &self,
+        frame: &Frame<F, F>,
+        transition_evaluations: &mut [FieldElement<F>],
+        _periodic_values: &[FieldElement<F>],
+        _rap_challenges: &[FieldElement<F>],
+    ) {
+        let first_step = frame.get_evaluation_step(0);
+        let second_step = frame.get_evaluation_step(1);
+
+        let t0 = first_step.get_main_evaluation_element(0, 1);
+        let t1 = second_step.get_main_evaluation_element(0, 1);
+
+        transition_evaluations[self.constraint_idx()] = t1 - t0 - FieldElement::<F>::one();
+    }
+}
+
+/// Proves that all the values of a witness column lie in the range [0, N), where N is the
+/// trace length, with a LogUp argument against a table column holding 0, 1, ..., N - 1.
+///
+///   Main trace                        Auxiliary trace
+///   witness | table | multiplicities  running sum
+///
+/// The table column is fixed by a boundary constraint on its first row and a transition
+/// constraint incrementing it by one at each row.
+pub struct LogUpRangeCheckAIR<F>
+where
+    F: IsFFTField,
+{
+    context: AirContext,
+    trace_length: usize,
+    pub_inputs: LogUpRangeCheckPublicInputs,
+    transition_constraints: Vec<Box<dyn TransitionConstraint<F, F>>>,
+    phantom: PhantomData<F>,
+}
+
+#[derive(Clone, Debug)]
+pub struct LogUpRangeCheckPublicInputs;
+
+impl<F> AIR for LogUpRangeCheckAIR<F>
+where
+    F: IsFFTField + Send + Sync + 'static,
+{
+    type Field = F;
+    type FieldExtension = F;
+    type PublicInputs = LogUpRangeCheckPublicInputs;
+
+    const STEP_SIZE: usize = 1;
+
+    fn new(
+        trace_length: usize,
+        pub_inputs: &Self::PublicInputs,
+        proof_options: &ProofOptions,
+    ) -> Self {
+        let transition_constraints: Vec<
+            Box<dyn TransitionConstraint<Self::Field, Self::FieldExtension>>,
+        > = vec![
+            Box::new(TableIncrementConstraint::new()),
+            Box::new(LogUpConstraint::new(1, 0, 1, 2, 0, 0)),
+        ];
+
+        let context = AirContext {
+            proof_options: proof_options.clone(),
+            trace_columns: 4,
+            transition_offsets: vec![0, 1],
+            transition_exemptions: vec![1, 0],
+            num_transition_constraints: transition_constraints.len(),
+        };
+
+        Self {
+            context,
+            trace_length,
+            pub_inputs: pub_inputs.clone(),
+            transition_constraints,
+            phantom: PhantomData,
+        }
+    }
+
+    fn build_auxiliary_trace(
+        &self,
+        main_trace: &TraceTable<Self::Field>,
+        challenges: &[FieldElement<Self::FieldExtension>],
+    ) -> TraceTable<Self::FieldExtension> {
+        let columns = main_trace.columns();
+        let running_sum =
+            logup::build_auxiliary_column(&columns[0], &columns[1], &columns[2], &challenges[0]);
+
+        TraceTable::from_columns(vec![running_sum], 0, 1)
+    }
+
+    fn build_rap_challenges(
+        &self,
+        transcript: &mut impl IsTranscript<Self::FieldExtension>,
+    ) -> Vec<FieldElement<Self::FieldExtension>> {
+        vec![transcript.sample_field_element()]
+    }
+
+    fn trace_layout(&self) -> (usize, usize) {
+        (3, 1)
+    }
+
+    fn boundary_constraints(
+        &self,
+        _rap_challenges: &[FieldElement<Self::FieldExtension>],
+    ) -> BoundaryConstraints<Self::FieldExtension> {
+        let table_start = BoundaryConstraint::new_main(1, 0, FieldElement::zero());
+
+        BoundaryConstraints::from_constraints(vec![table_start])
+    }
+
+    fn transition_constraints(
+        &self,
+    ) -> &Vec<Box<dyn TransitionConstraint<Self::Field, Self::FieldExtension>>> {
+        &self.transition_constraints
+    }
+
+    fn context(&self) -> &AirContext {
+        &self.context
+    }
+
+    fn composition_poly_degree_bound(&self) -> usize {
+        2 * self.trace_length()
+    }
+
+    fn trace_length(&self) -> usize {
+        self.trace_length
+    }
+
+    fn pub_inputs(&self) -> &Self::PublicInputs {
+        &self.pub_inputs
+    }
+
+    fn compute_transition_verifier(
+        &self,
+        frame: &Frame<Self::FieldExtension, Self::FieldExtension>,
+        periodic_values: &[FieldElement<Self::FieldExtension>],
+        rap_challenges: &[FieldElement<Self::FieldExtension>],
+    ) -> Vec<FieldElement<Self::Field>> {
+        self.compute_transition_prover(frame, periodic_values, rap_challenges)
+    }
+}
+
+/// Builds the main trace of the range check of the `witness` values. The length of
+/// `witness` is the length of the trace, so it should be a power of two.
+pub fn logup_range_check_trace<F: IsFFTField>(witness: Vec<FieldElement<F>>) -> TraceTable<F> {
+    let table: Vec<_> = (0..witness.len() as u64).map(FieldElement::from).collect();
+    let multiplicities = logup::compute_multiplicities(&witness, &table);
+
+    TraceTable::from_columns_main(vec![witness, table, multiplicities], 1)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use lambdaworks_math::field::fields::u64_prime_field::{F17, FE17};
+
+    #[test]
+    fn range_check_trace_satisfies_constraints() {
+        let witness = [3u64, 0, 3, 1, 7, 7, 7, 2].map(FE17::from).to_vec();
+        let trace = logup_range_check_trace::<F17>(witness);
+        let air = LogUpRangeCheckAIR::new(
+            trace.n_rows(),
+            &LogUpRangeCheckPublicInputs,
+            &ProofOptions::default_test_options(),
+        );
+
+        assert_eq!(trace.validate(&air, &[FE17::from(10)]), Ok(()));
+    }
+
+    #[test]
+    fn out_of_range_witness_breaks_the_lookup() {
+        let witness = [3u64, 0, 3, 1, 8, 7, 7, 2].map(FE17::from).to_vec();
+        let trace = logup_range_check_trace::<F17>(witness);
+        let air = LogUpRangeCheckAIR::new(
+            trace.n_rows(),
+            &LogUpRangeCheckPublicInputs,
+            &ProofOptions::default_test_options(),
+        );
+
+        assert!(trace.validate(&air, &[FE17::from(10)]).is_err());
+    }
+}
@@ -1,8 +1,14 @@
+pub mod bit_column;
 pub mod bit_flags;
 pub mod dummy_air;
 pub mod fibonacci_2_cols_shifted;
 pub mod fibonacci_2_columns;
+pub mod fibonacci_extension;
 pub mod fibonacci_rap;
+pub mod logup_range_check;
+pub mod offset_counter;
 pub mod quadratic_air;
+pub mod range_check;
 pub mod simple_fibonacci;
 pub mod simple_periodic_cols;
+pub mod unit_fractions;
@@ -0,0 +1,199 @@
+use crate::{
+    constraints::{
+        boundary::{BoundaryConstraint, BoundaryConstraints},
+        transition::TransitionConstraint,
+    },
+    context::AirContext,
+    frame::Frame,
+    proof::options::ProofOptions,
+    trace::TraceTable,
+    traits::AIR,
+};
+#[cfg(not(feature = "std"))]
+use alloc::{boxed::Box, vec::Vec};
+use core::marker::PhantomData;
+use lambdaworks_math::field::{element::FieldElement, traits::IsFFTField};
+
+/// The distance between the two rows linked by the transition constraint.
+pub const OFFSET: usize = 7;
+
+#[derive(Clone)]
+struct OffsetCounterConstraint<F: IsFFTField> {
+    phantom: PhantomData<F>,
+}
+
+impl<F: IsFFTField> OffsetCounterConstraint<F> {
+    pub fn new() -> Self {
+        Self {
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<F> TransitionConstraint<F, F> for OffsetCounterConstraint<F>
+where
+    F: IsFFTField + Send + Sync,
+{
+    fn degree(&self) -> usize {
+        1
+    }
+
+    fn constraint_idx(&self) -> usize {
+        0
+    }
+
+    fn end_exemptions(&self) -> usize {
+        OFFSET
+    }
+
+    fn evaluate(
+        &self,
+        frame: &Frame<F, F>,
+        transition_evaluations: &mut [FieldElement<F>],
+        _periodic_values: &[FieldElement<F>],
+        _rap_challenges: &[FieldElement<F>],
+    ) {
+        let first_step = frame.get_evaluation_step(0);
+        let second_step = frame.get_evaluation_step(1);
+
+        let a_i = first_step.get_main_evaluation_element(0, 0);
+        let a_i_plus_offset = second_step.get_main_evaluation_element(0, 0);
+
+        transition_evaluations[self.constraint_idx()] =
+            a_i_plus_offset - a_i - FieldElement::<F>::from(OFFSET as u64);
+    }
+}
+
+/// Proves that a single column counts up from a public start value, with a transition
+/// constraint linking the rows `i` and `i + OFFSET` only. Its frame is made of two rows
+/// that are not adjacent in the trace.
+pub struct OffsetCounterAIR<F>
+where
+    F: IsFFTField,
+{
+    context: AirContext,
+    trace_length: usize,
+    start: FieldElement<F>,
+    constraints: Vec<Box<dyn TransitionConstraint<F, F>>>,
+}
+
+impl<F> AIR for OffsetCounterAIR<F>
+where
+    F: IsFFTField + Send + Sync + 'static,
+{
+    type Field = F;
+    type FieldExtension = F;
+    type PublicInputs = FieldElement<F>;
+
+    const STEP_SIZE: usize = 1;
+
+    fn new(
+        trace_length: usize,
+        pub_inputs: &Self::PublicInputs,
+        proof_options: &ProofOptions,
+    ) -> Self {
+        let constraints: Vec<Box<dyn TransitionConstraint<F, F>>> =
+            vec![Box::new(OffsetCounterConstraint::new())];
+
+        let context = AirContext {
+            proof_options: proof_options.clone(),
+            trace_columns: 1,
+            transition_exemptions: vec![OFFSET],
+            transition_offsets: vec![0, OFFSET],
+            num_transition_constraints: constraints.len(),
+        };
+
+        Self {
+            context,
+            trace_length,
+            start: pub_inputs.clone(),
+            constraints,
+        }
+    }
+
+    fn composition_poly_degree_bound(&self) -> usize {
+        self.trace_length()
+    }
+
+    fn transition_constraints(&self) -> &Vec<Box<dyn TransitionConstraint<F, F>>> {
+        &self.constraints
+    }
+
+    fn boundary_constraints(
+        &self,
+        _rap_challenges: &[FieldElement<Self::Field>],
+    ) -> BoundaryConstraints<Self::Field> {
+        BoundaryConstraints::from_constraints(vec![BoundaryConstraint::new_simple_main(
+            0,
+            self.start.clone(),
+        )])
+    }
+
+    fn context(&self) -> &AirContext {
+        &self.context
+    }
+
+    fn trace_length(&self) -> usize {
+        self.trace_length
+    }
+
+    fn trace_layout(&self) -> (usize, usize) {
+        (1, 0)
+    }
+
+    fn pub_inputs(&self) -> &Self::PublicInputs {
+        &self.start
+    }
+
+    fn compute_transition_verifier(
+        &self,
+        frame: &Frame<Self::FieldExtension, Self::FieldExtension>,
+        periodic_values: &[FieldElement<Self::FieldExtension>],
+        rap_challenges: &[FieldElement<Self::FieldExtension>],
+    ) -> Vec<FieldElement<Self::Field>> {
+        self.compute_transition_prover(frame, periodic_values, rap_challenges)
+    }
+}
+
+/// Builds the single column trace `start, start + 1, start + 2, ...` of length `trace_length`.
+pub fn offset_counter_trace<F: IsFFTField>(
+    start: FieldElement<F>,
+    trace_length: usize,
+) -> TraceTable<F> {
+    let column = (0..trace_length as u64)
+        .map(|i| &start + FieldElement::<F>::from(i))
+        .collect();
+
+    TraceTable::from_columns_main(vec![column], 1)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use lambdaworks_math::field::fields::u64_prime_field::{F17, FE17};
+
+    #[test]
+    fn offset_counter_trace_satisfies_the_air() {
+        let trace = offset_counter_trace(FE17::from(3), 16);
+        let air = OffsetCounterAIR::<F17>::new(
+            trace.n_rows(),
+            &FE17::from(3),
+            &ProofOptions::default_test_options(),
+        );
+
+        assert_eq!(trace.validate(&air, &[]), Ok(()));
+    }
+
+    #[test]
+    fn changing_a_row_breaks_the_constraint_seven_rows_before() {
+        let mut trace = offset_counter_trace(FE17::from(3), 16);
+        trace.get_row_mut(9)[0] = FE17::zero();
+        let air = OffsetCounterAIR::<F17>::new(
+            trace.n_rows(),
+            &FE17::from(3),
+            &ProofOptions::default_test_options(),
+        );
+
+        assert!(trace.validate(&air, &[]).is_err());
+    }
+}
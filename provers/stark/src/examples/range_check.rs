@@ -0,0 +1,326 @@
+#[cfg(not(feature = "std"))]
+use alloc::{boxed::Box, vec::Vec};
+use core::marker::PhantomData;
+
+use crate::{
+    constraints::{
+        boundary::{BoundaryConstraint, BoundaryConstraints},
+        transition::TransitionConstraint,
+    },
+    context::AirContext,
+    frame::Frame,
+    proof::options::ProofOptions,
+    trace::TraceTable,
+    traits::AIR,
+};
+use lambdaworks_math::field::{element::FieldElement, traits::IsFFTField};
+
+/// The constraint `b * (b - 1) = 0` over the bit column, on every row.
+#[derive(Clone)]
+struct BitConstraint<F: IsFFTField> {
+    phantom: PhantomData<F>,
+}
+
+impl<F: IsFFTField> BitConstraint<F> {
+    pub fn new() -> Self {
+        Self {
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<F> TransitionConstraint<F, F> for BitConstraint<F>
+where
+    F: IsFFTField + Send + Sync,
+{
+    fn degree(&self) -> usize {
+        2
+    }
+
+    fn constraint_idx(&self) -> usize {
+        0
+    }
+
+    fn end_exemptions(&self) -> usize {
+        0
+    }
+
+    fn evaluate(
+        &self,
+        frame: &Frame<F, F>,
+        transition_evaluations: &mut [FieldElement<F>],
+        _periodic_values: &[FieldElement<F>],
+        _rap_challenges: &[FieldElement<F>],
+    ) {
+        let step = frame.get_evaluation_step(0);
+
+        let bit = step.get_main_evaluation_element(0, 0);
+
+        transition_evaluations[self.constraint_idx()] = bit * (bit - FieldElement::<F>::one());
+    }
+}
+
+/// The constraint `acc_{i+1} = 2 * acc_i + b_{i+1}`, which accumulates the bits from the most
+/// significant one.
+#[derive(Clone)]
+struct AccumulationConstraint<F: IsFFTField> {
+    phantom: PhantomData<F>,
+}
+
+impl<F: IsFFTField> AccumulationConstraint<F> {
+    pub fn new() -> Self {
+        Self {
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<F> TransitionConstraint<F, F> for AccumulationConstraint<F>
+where
+    F: IsFFTField + Send + Sync,
+{
+    fn degree(&self) -> usize {
+        1
+    }
+
+    fn constraint_idx(&self) -> usize {
+        1
+    }
+
+    fn end_exemptions(&self) -> usize {
+        1
+    }
+
+    fn evaluate(
+        &self,
+        frame: &Frame<F, F>,
+        transition_evaluations: &mut [FieldElement<F>],
+        _periodic_values: &[FieldElement<F>],
+        _rap_challenges: &[FieldElement<F>],
+    ) {
+        let first_step = frame.get_evaluation_step(0);
+        let second_step = frame.get_evaluation_step(1);
+
+        let acc = first_step.get_main_evaluation_element(0, 1);
+        let next_bit = second_step.get_main_evaluation_element(0, 0);
+        let next_acc = second_step.get_main_evaluation_element(0, 1);
+
+        transition_evaluations[self.constraint_idx()] = next_acc - (acc + acc + next_bit);
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct RangeCheckPublicInputs<F>
+where
+    F: IsFFTField,
+{
+    /// The value that is checked to be in the range `[0, 2^num_bits)`.
+    pub value: FieldElement<F>,
+    pub num_bits: usize,
+}
+
+/// Proves that `value` fits in `num_bits` bits by decomposing it into them.
+///
+/// The trace has a bit column and an accumulator column. The bits are laid out from the
+/// most significant one, below a first row of zeros, and the accumulator holds the value of
+/// the bits seen so far, so it starts at zero and ends at `value`. The rows above the
+/// `num_bits` lowest bits are padding, and their bits are constrained to be zero at the
+/// boundary, so the trace length should be a power of two greater than `num_bits`.
+pub struct RangeCheckAIR<F>
+where
+    F: IsFFTField,
+{
+    context: AirContext,
+    trace_length: usize,
+    pub_inputs: RangeCheckPublicInputs<F>,
+    transition_constraints: Vec<Box<dyn TransitionConstraint<F, F>>>,
+}
+
+impl<F> AIR for RangeCheckAIR<F>
+where
+    F: IsFFTField + Send + Sync + 'static,
+{
+    type Field = F;
+    type FieldExtension = F;
+    type PublicInputs = RangeCheckPublicInputs<Self::Field>;
+
+    const STEP_SIZE: usize = 1;
+
+    fn new(
+        trace_length: usize,
+        pub_inputs: &Self::PublicInputs,
+        proof_options: &ProofOptions,
+    ) -> Self {
+        let transition_constraints: Vec<
+            Box<dyn TransitionConstraint<Self::Field, Self::FieldExtension>>,
+        > = vec![
+            Box::new(BitConstraint::new()),
+            Box::new(AccumulationConstraint::new()),
+        ];
+
+        let context = AirContext {
+            proof_options: proof_options.clone(),
+            trace_columns: 2,
+            transition_offsets: vec![0, 1],
+            transition_exemptions: vec![0, 1],
+            num_transition_constraints: transition_constraints.len(),
+        };
+
+        Self {
+            context,
+            trace_length,
+            pub_inputs: pub_inputs.clone(),
+            transition_constraints,
+        }
+    }
+
+    fn boundary_constraints(
+        &self,
+        _rap_challenges: &[FieldElement<Self::FieldExtension>],
+    ) -> BoundaryConstraints<Self::FieldExtension> {
+        let last_step = self.trace_length - 1;
+        let padding_bits = last_step.saturating_sub(self.pub_inputs.num_bits);
+
+        let mut constraints = vec![BoundaryConstraint::new_main(1, 0, FieldElement::zero())];
+        constraints.extend(
+            (1..=padding_bits)
+                .map(|step| BoundaryConstraint::new_main(0, step, FieldElement::zero())),
+        );
+        constraints.push(BoundaryConstraint::new_main(
+            1,
+            last_step,
+            self.pub_inputs.value.clone(),
+        ));
+
+        BoundaryConstraints::from_constraints(constraints)
+    }
+
+    fn transition_constraints(
+        &self,
+    ) -> &Vec<Box<dyn TransitionConstraint<Self::Field, Self::FieldExtension>>> {
+        &self.transition_constraints
+    }
+
+    fn context(&self) -> &AirContext {
+        &self.context
+    }
+
+    fn trace_layout(&self) -> (usize, usize) {
+        (2, 0)
+    }
+
+    fn trace_length(&self) -> usize {
+        self.trace_length
+    }
+
+    fn pub_inputs(&self) -> &Self::PublicInputs {
+        &self.pub_inputs
+    }
+
+    fn compute_transition_verifier(
+        &self,
+        frame: &Frame<Self::FieldExtension, Self::FieldExtension>,
+        periodic_values: &[FieldElement<Self::FieldExtension>],
+        rap_challenges: &[FieldElement<Self::FieldExtension>],
+    ) -> Vec<FieldElement<Self::Field>> {
+        self.compute_transition_prover(frame, periodic_values, rap_challenges)
+    }
+}
+
+/// Builds the trace decomposing `value` into `num_bits` bits, padded with leading zeros up to
+/// the smallest power of two length greater than `num_bits`. The higher bits of `value` land
+/// on the padding rows, so the trace is valid only if `value` fits in `num_bits` bits.
+pub fn range_check_trace<F: IsFFTField>(value: u64, num_bits: usize) -> TraceTable<F> {
+    let trace_length = (num_bits + 1).next_power_of_two();
+
+    let mut bits = vec![FieldElement::<F>::zero(); trace_length];
+    let mut accumulator = vec![FieldElement::<F>::zero(); trace_length];
+    for step in 1..trace_length {
+        let shift = (trace_length - 1 - step) as u32;
+        let bit = value.checked_shr(shift).unwrap_or(0) & 1;
+        bits[step] = FieldElement::from(bit);
+        accumulator[step] = accumulator[step - 1].clone() + &accumulator[step - 1] + &bits[step];
+    }
+
+    TraceTable::from_columns_main(vec![bits, accumulator], 1)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use lambdaworks_math::field::fields::u64_prime_field::{F17, FE17};
+
+    #[test]
+    fn range_check_trace_is_valid() {
+        let trace = range_check_trace::<F17>(13, 4);
+        let pub_inputs = RangeCheckPublicInputs {
+            value: FE17::from(13),
+            num_bits: 4,
+        };
+        let air = RangeCheckAIR::<F17>::new(
+            trace.n_rows(),
+            &pub_inputs,
+            &ProofOptions::default_test_options(),
+        );
+
+        assert_eq!(trace.n_rows(), 8);
+        assert_eq!(trace.validate(&air, &[]), Ok(()));
+    }
+
+    #[test]
+    fn accumulation_constraint_is_exempted_on_the_last_row() {
+        let air = RangeCheckAIR::<F17>::new(
+            8,
+            &RangeCheckPublicInputs {
+                value: FE17::from(13),
+                num_bits: 4,
+            },
+            &ProofOptions::default_test_options(),
+        );
+
+        assert_eq!(
+            air.transition_constraints()[0].end_exempted_rows(8),
+            Vec::<usize>::new()
+        );
+        assert_eq!(
+            air.transition_constraints()[1].end_exempted_rows(8),
+            vec![7]
+        );
+    }
+
+    #[test]
+    fn value_out_of_range_breaks_the_padding_bits() {
+        // 13 does not fit in 3 bits, so its highest bit lands on a padding row.
+        let trace = range_check_trace::<F17>(13, 4);
+        let pub_inputs = RangeCheckPublicInputs {
+            value: FE17::from(13),
+            num_bits: 3,
+        };
+        let air = RangeCheckAIR::<F17>::new(
+            trace.n_rows(),
+            &pub_inputs,
+            &ProofOptions::default_test_options(),
+        );
+
+        assert!(trace.validate(&air, &[]).is_err());
+    }
+
+    #[test]
+    fn non_bit_value_breaks_the_transition_constraint() {
+        // 2 * 1 + 2 = 4 is accumulated with a "bit" equal to 2.
+        let bits = [0u64, 0, 0, 0, 0, 0, 1, 2].map(FE17::from).to_vec();
+        let accumulator = [0u64, 0, 0, 0, 0, 0, 1, 4].map(FE17::from).to_vec();
+        let trace = TraceTable::<F17>::from_columns_main(vec![bits, accumulator], 1);
+        let pub_inputs = RangeCheckPublicInputs {
+            value: FE17::from(4),
+            num_bits: 2,
+        };
+        let air = RangeCheckAIR::<F17>::new(
+            trace.n_rows(),
+            &pub_inputs,
+            &ProofOptions::default_test_options(),
+        );
+
+        assert!(trace.validate(&air, &[]).is_err());
+    }
+}
@@ -9,8 +9,10 @@ use crate::{
     trace::TraceTable,
     traits::AIR,
 };
+#[cfg(not(feature = "std"))]
+use alloc::{boxed::Box, vec::Vec};
+use core::marker::PhantomData;
 use lambdaworks_math::field::{element::FieldElement, traits::IsFFTField};
-use std::marker::PhantomData;
 
 #[derive(Clone)]
 struct FibConstraint<F: IsFFTField> {
@@ -72,7 +74,7 @@ where
     constraints: Vec<Box<dyn TransitionConstraint<F, F>>>,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct FibonacciPublicInputs<F>
 where
     F: IsFFTField,
@@ -1,4 +1,6 @@
-use std::marker::PhantomData;
+#[cfg(not(feature = "std"))]
+use alloc::{boxed::Box, vec::Vec};
+use core::marker::PhantomData;
 
 use crate::{
     constraints::{
@@ -204,3 +206,36 @@ pub fn simple_periodic_trace<F: IsFFTField>(trace_length: usize) -> TraceTable<F
 
     TraceTable::from_columns_main(vec![ret], 1)
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use lambdaworks_math::field::fields::u64_prime_field::{F17, FE17};
+
+    #[test]
+    fn periodic_column_is_interpolated_over_the_trace_domain() {
+        let trace = simple_periodic_trace::<F17>(8);
+        let pub_inputs = SimplePeriodicPublicInputs {
+            a0: FE17::one(),
+            a1: FE17::from(8),
+        };
+        let air = SimplePeriodicAIR::new(
+            trace.n_rows(),
+            &pub_inputs,
+            &ProofOptions::default_test_options(),
+        );
+
+        let periodic_poly = &air.get_periodic_column_polynomials()[0];
+        let g = air.trace_primitive_root();
+        for i in 0..8 {
+            let expected = if i % 2 == 0 {
+                FE17::zero()
+            } else {
+                FE17::one()
+            };
+            assert_eq!(periodic_poly.evaluate(&g.pow(i as u64)), expected);
+        }
+
+        assert_eq!(trace.validate(&air, &[]), Ok(()));
+    }
+}
@@ -0,0 +1,208 @@
+#[cfg(not(feature = "std"))]
+use alloc::{boxed::Box, vec::Vec};
+use core::marker::PhantomData;
+
+use crate::{
+    constraints::{
+        boundary::{BoundaryConstraint, BoundaryConstraints},
+        rational::{RationalConstraint, RationalTransitionConstraint},
+        transition::TransitionConstraint,
+    },
+    context::AirContext,
+    frame::Frame,
+    proof::options::ProofOptions,
+    trace::TraceTable,
+    traits::AIR,
+};
+use lambdaworks_math::field::{element::FieldElement, traits::IsFFTField};
+
+/// The rational constraint `x_{i+1} - x_i / (x_i + 1) = 0`.
+struct UnitFractionConstraint<F: IsFFTField> {
+    phantom: PhantomData<F>,
+}
+
+impl<F: IsFFTField> UnitFractionConstraint<F> {
+    pub fn new() -> Self {
+        Self {
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<F> RationalConstraint<F, F> for UnitFractionConstraint<F>
+where
+    F: IsFFTField + Send + Sync,
+{
+    fn fraction_degrees(&self) -> Vec<(usize, usize)> {
+        vec![(1, 0), (1, 1)]
+    }
+
+    fn constraint_idx(&self) -> usize {
+        0
+    }
+
+    fn end_exemptions(&self) -> usize {
+        1
+    }
+
+    fn evaluate_fractions(
+        &self,
+        frame: &Frame<F, F>,
+        _periodic_values: &[FieldElement<F>],
+        _rap_challenges: &[FieldElement<F>],
+    ) -> Vec<(FieldElement<F>, FieldElement<F>)> {
+        let first_step = frame.get_evaluation_step(0);
+        let second_step = frame.get_evaluation_step(1);
+
+        let x = first_step.get_main_evaluation_element(0, 0);
+        let x_next = second_step.get_main_evaluation_element(0, 0);
+
+        vec![
+            (x_next.clone(), FieldElement::one()),
+            (-x, x + FieldElement::<F>::one()),
+        ]
+    }
+}
+
+/// Proves the sequence of unit fractions `1, 1/2, 1/3, ...`, given by the recurrence
+/// `x_{i+1} = x_i / (x_i + 1)` starting at `x_0 = 1`. Its only transition constraint is
+/// rational, so it exercises the clearing of the denominators.
+pub struct UnitFractionsAIR<F>
+where
+    F: IsFFTField,
+{
+    context: AirContext,
+    trace_length: usize,
+    transition_constraints: Vec<Box<dyn TransitionConstraint<F, F>>>,
+}
+
+impl<F> AIR for UnitFractionsAIR<F>
+where
+    F: IsFFTField + Send + Sync + 'static,
+{
+    type Field = F;
+    type FieldExtension = F;
+    type PublicInputs = ();
+
+    const STEP_SIZE: usize = 1;
+
+    fn new(
+        trace_length: usize,
+        _pub_inputs: &Self::PublicInputs,
+        proof_options: &ProofOptions,
+    ) -> Self {
+        let transition_constraints: Vec<
+            Box<dyn TransitionConstraint<Self::Field, Self::FieldExtension>>,
+        > = vec![Box::new(RationalTransitionConstraint::new(
+            UnitFractionConstraint::new(),
+        ))];
+
+        let context = AirContext {
+            proof_options: proof_options.clone(),
+            trace_columns: 1,
+            transition_offsets: vec![0, 1],
+            transition_exemptions: vec![1],
+            num_transition_constraints: transition_constraints.len(),
+        };
+
+        Self {
+            context,
+            trace_length,
+            transition_constraints,
+        }
+    }
+
+    fn boundary_constraints(
+        &self,
+        _rap_challenges: &[FieldElement<Self::FieldExtension>],
+    ) -> BoundaryConstraints<Self::FieldExtension> {
+        let x0 = BoundaryConstraint::new_simple_main(0, FieldElement::one());
+
+        BoundaryConstraints::from_constraints(vec![x0])
+    }
+
+    fn transition_constraints(
+        &self,
+    ) -> &Vec<Box<dyn TransitionConstraint<Self::Field, Self::FieldExtension>>> {
+        &self.transition_constraints
+    }
+
+    fn context(&self) -> &AirContext {
+        &self.context
+    }
+
+    fn trace_layout(&self) -> (usize, usize) {
+        (1, 0)
+    }
+
+    fn trace_length(&self) -> usize {
+        self.trace_length
+    }
+
+    fn pub_inputs(&self) -> &Self::PublicInputs {
+        &()
+    }
+
+    fn compute_transition_verifier(
+        &self,
+        frame: &Frame<Self::FieldExtension, Self::FieldExtension>,
+        periodic_values: &[FieldElement<Self::FieldExtension>],
+        rap_challenges: &[FieldElement<Self::FieldExtension>],
+    ) -> Vec<FieldElement<Self::Field>> {
+        self.compute_transition_prover(frame, periodic_values, rap_challenges)
+    }
+}
+
+/// Builds the trace `1, 1/2, ..., 1/trace_length`. The trace length should be a power of
+/// two smaller than the characteristic of the field.
+pub fn unit_fractions_trace<F: IsFFTField>(trace_length: usize) -> TraceTable<F> {
+    let column = (1..=trace_length as u64)
+        .map(|i| FieldElement::<F>::from(i).inv().unwrap())
+        .collect();
+
+    TraceTable::from_columns_main(vec![column], 1)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use lambdaworks_math::field::fields::u64_prime_field::{F17, FE17};
+
+    #[test]
+    fn unit_fractions_trace_is_valid() {
+        let trace = unit_fractions_trace::<F17>(8);
+        let air = UnitFractionsAIR::<F17>::new(
+            trace.n_rows(),
+            &(),
+            &ProofOptions::default_test_options(),
+        );
+
+        assert_eq!(trace.validate(&air, &[]), Ok(()));
+    }
+
+    #[test]
+    fn cleared_constraint_has_degree_two() {
+        let air = UnitFractionsAIR::<F17>::new(8, &(), &ProofOptions::default_test_options());
+
+        assert_eq!(air.transition_constraints()[0].degree(), 2);
+        assert_eq!(air.composition_poly_degree_bound(), 16);
+    }
+
+    #[test]
+    fn wrong_fraction_breaks_the_transition_constraint() {
+        let column = [1u64, 9, 6, 13].map(FE17::from).to_vec();
+        let mut wrong = column.clone();
+        wrong[2] = FE17::from(5);
+
+        let trace = TraceTable::<F17>::from_columns_main(vec![column], 1);
+        let air = UnitFractionsAIR::<F17>::new(
+            trace.n_rows(),
+            &(),
+            &ProofOptions::default_test_options(),
+        );
+        assert_eq!(trace.validate(&air, &[]), Ok(()));
+
+        let trace = TraceTable::<F17>::from_columns_main(vec![wrong], 1);
+        assert!(trace.validate(&air, &[]).is_err());
+    }
+}
@@ -1,11 +1,18 @@
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use core::hash::{Hash, Hasher};
+
 use crate::{table::TableView, trace::LDETraceTable};
 use itertools::Itertools;
-use lambdaworks_math::field::traits::{IsField, IsSubFieldOf};
+use lambdaworks_math::field::{
+    element::FieldElement,
+    traits::{IsField, IsSubFieldOf},
+};
 
 /// A frame represents a collection of trace steps.
 /// The collected steps are all the necessary steps for
 /// all transition costraints over a trace to be evaluated.
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug)]
 pub struct Frame<'t, F: IsSubFieldOf<E>, E: IsField>
 where
     E: IsField,
@@ -14,15 +21,93 @@ where
     steps: Vec<TableView<'t, F, E>>,
 }
 
+impl<F: IsSubFieldOf<E>, E: IsField> PartialEq for Frame<'_, F, E> {
+    fn eq(&self, other: &Self) -> bool {
+        self.steps == other.steps
+    }
+}
+
+impl<F: IsSubFieldOf<E>, E: IsField> Eq for Frame<'_, F, E> {}
+
+impl<F: IsSubFieldOf<E>, E: IsField> Hash for Frame<'_, F, E>
+where
+    FieldElement<F>: Hash,
+    FieldElement<E>: Hash,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.steps.hash(state);
+    }
+}
+
 impl<'t, F: IsSubFieldOf<E>, E: IsField> Frame<'t, F, E> {
     pub fn new(steps: Vec<TableView<'t, F, E>>) -> Self {
         Self { steps }
     }
 
+    /// Returns the `step`-th step of the frame, that is, the rows found at the `step`-th
+    /// transition offset of the AIR. With offsets `[0, 1]`, step `0` holds the current row
+    /// and step `1` the next one, so a two-step constraint reads:
+    ///
+    /// ```
+    /// use lambdaworks_math::field::fields::u64_prime_field::{F17, FE17};
+    /// use stark_platinum_prover::{frame::Frame, table::TableView};
+    ///
+    /// // A column that doubles on every row: x_{i+1} = 2 * x_i.
+    /// let current = [FE17::from(3)];
+    /// let next = [FE17::from(6)];
+    /// let frame = Frame::<F17, F17>::new(vec![
+    ///     TableView::new(vec![&current[..]], vec![&[][..]]),
+    ///     TableView::new(vec![&next[..]], vec![&[][..]]),
+    /// ]);
+    ///
+    /// assert_eq!(frame.num_steps(), 2);
+    /// let x = frame.get_evaluation_step(0).get_main_evaluation_element(0, 0);
+    /// let x_next = frame.get_evaluation_step(1).get_main_evaluation_element(0, 0);
+    /// assert_eq!(x_next - FE17::from(2) * x, FE17::zero());
+    /// ```
+    ///
+    /// Panics if the frame has less than `step + 1` steps.
     pub fn get_evaluation_step(&self, step: usize) -> &TableView<'t, F, E> {
         &self.steps[step]
     }
 
+    /// Returns the number of steps of the frame, one per transition offset of the AIR.
+    pub fn num_steps(&self) -> usize {
+        self.steps.len()
+    }
+
+    /// Returns the rows of all the steps of the frame in order, as pairs of main and
+    /// auxiliary rows.
+    fn rows(&self) -> impl Iterator<Item = (&'t [FieldElement<F>], &'t [FieldElement<E>])> + '_ {
+        self.steps
+            .iter()
+            .flat_map(|step| step.data.iter().copied().zip(step.aux_data.iter().copied()))
+    }
+
+    /// Returns the `i`-th row of the frame, counting the rows of all its steps, with the
+    /// main columns followed by the auxiliary ones.
+    /// Panics if the frame has less than `i + 1` rows.
+    pub fn row(&self, i: usize) -> Vec<FieldElement<E>> {
+        let (main_row, aux_row) = self.rows().nth(i).expect("row index out of range");
+        main_row
+            .iter()
+            .map(|element| element.clone().to_extension())
+            .chain(aux_row.iter().cloned())
+            .collect()
+    }
+
+    /// Returns the values of the `j`-th column of the frame at every row, where the
+    /// auxiliary columns are numbered after the main ones.
+    /// Panics if the frame has less than `j + 1` columns.
+    pub fn column(&self, j: usize) -> Vec<FieldElement<E>> {
+        self.rows()
+            .map(|(main_row, aux_row)| match main_row.get(j) {
+                Some(element) => element.clone().to_extension(),
+                None => aux_row[j - main_row.len()].clone(),
+            })
+            .collect()
+    }
+
     pub fn read_from_lde(
         lde_trace: &'t LDETraceTable<F, E>,
         row: usize,
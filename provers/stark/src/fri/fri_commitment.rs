@@ -1,37 +1,86 @@
-use lambdaworks_crypto::merkle_tree::{merkle::MerkleTree, traits::IsMerkleTreeBackend};
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
 use lambdaworks_math::{
-    field::{element::FieldElement, traits::IsField},
+    field::{
+        element::FieldElement,
+        traits::{IsField, IsSubFieldOf},
+    },
+    polynomial::Polynomial,
     traits::AsBytes,
 };
 
+use crate::config::IsStarkVectorCommitment;
+
 #[derive(Clone)]
-pub struct FriLayer<F, B>
+pub struct FriLayer<F, VC>
 where
     F: IsField,
-    FieldElement<F>: AsBytes,
-    B: IsMerkleTreeBackend,
+    FieldElement<F>: AsBytes + Sync + Send,
+    VC: IsStarkVectorCommitment,
 {
     pub evaluation: Vec<FieldElement<F>>,
-    pub merkle_tree: MerkleTree<B>,
+    pub merkle_tree: VC::Scheme<F>,
+    pub root: VC::Commitment,
     pub coset_offset: FieldElement<F>,
     pub domain_size: usize,
 }
 
-impl<F, B> FriLayer<F, B>
+impl<F, VC> FriLayer<F, VC>
 where
     F: IsField,
-    FieldElement<F>: AsBytes,
-    B: IsMerkleTreeBackend,
+    FieldElement<F>: AsBytes + Sync + Send,
+    VC: IsStarkVectorCommitment,
 {
     pub fn new(
         evaluation: &[FieldElement<F>],
-        merkle_tree: MerkleTree<B>,
+        merkle_tree: VC::Scheme<F>,
+        root: VC::Commitment,
         coset_offset: FieldElement<F>,
         domain_size: usize,
     ) -> Self {
         Self {
             evaluation: evaluation.to_vec(),
             merkle_tree,
+            root,
+            coset_offset,
+            domain_size,
+        }
+    }
+}
+
+/// A FRI layer that keeps only its commitment and what is needed to rebuild the committed
+/// vector: the folded polynomial, which has `blowup_factor` times fewer coefficients than
+/// the layer has evaluations, and the coset it is evaluated over. The evaluations and the tree
+/// are dropped once the layer is committed, and `fri::query_phase_compact` rebuilds them one
+/// layer at a time to open it, so that at most one of them is held in memory.
+#[derive(Clone)]
+pub struct CompactFriLayer<F, E, VC>
+where
+    F: IsSubFieldOf<E>,
+    E: IsField,
+    VC: IsStarkVectorCommitment,
+{
+    pub poly: Polynomial<FieldElement<E>>,
+    pub root: VC::Commitment,
+    pub coset_offset: FieldElement<F>,
+    pub domain_size: usize,
+}
+
+impl<F, E, VC> CompactFriLayer<F, E, VC>
+where
+    F: IsSubFieldOf<E>,
+    E: IsField,
+    VC: IsStarkVectorCommitment,
+{
+    pub fn new(
+        poly: Polynomial<FieldElement<E>>,
+        root: VC::Commitment,
+        coset_offset: FieldElement<F>,
+        domain_size: usize,
+    ) -> Self {
+        Self {
+            poly,
+            root,
             coset_offset,
             domain_size,
         }
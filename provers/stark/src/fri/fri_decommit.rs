@@ -1,11 +1,33 @@
-use lambdaworks_crypto::merkle_tree::proof::Proof;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use core::hash::{Hash, Hasher};
+
 use lambdaworks_math::field::element::FieldElement;
 use lambdaworks_math::field::traits::IsField;
 
-use crate::config::Commitment;
+use crate::config::{IsStarkVectorCommitment, MerkleVectorCommitment};
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
-pub struct FriDecommitment<F: IsField> {
-    pub layers_auth_paths: Vec<Proof<Commitment>>,
+pub struct FriDecommitment<F: IsField, VC: IsStarkVectorCommitment = MerkleVectorCommitment> {
+    pub layers_auth_paths: Vec<VC::Opening>,
     pub layers_evaluations_sym: Vec<FieldElement<F>>,
 }
+
+impl<F: IsField, VC: IsStarkVectorCommitment> PartialEq for FriDecommitment<F, VC> {
+    fn eq(&self, other: &Self) -> bool {
+        self.layers_auth_paths == other.layers_auth_paths
+            && self.layers_evaluations_sym == other.layers_evaluations_sym
+    }
+}
+
+impl<F: IsField, VC: IsStarkVectorCommitment> Eq for FriDecommitment<F, VC> {}
+
+impl<F: IsField, VC: IsStarkVectorCommitment> Hash for FriDecommitment<F, VC>
+where
+    FieldElement<F>: Hash,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.layers_auth_paths.hash(state);
+        self.layers_evaluations_sym.hash(state);
+    }
+}
@@ -1,4 +1,6 @@
 use super::Polynomial;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
 use lambdaworks_math::{
     field::{element::FieldElement, traits::IsField},
     polynomial,
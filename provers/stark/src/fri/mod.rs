@@ -1,47 +1,179 @@
+//! The FRI protocol, which proves that the DEEP composition polynomial is close to a
+//! polynomial of low degree.
+//!
+//! # Ordering of the evaluations
+//!
+//! Every committed layer of FRI holds its evaluations in bit-reversed order: the evaluation
+//! at `offset * w^j` of a domain of size `N` is at position `reverse_index(j, N)`. The points
+//! `x` and `-x`, which are folded together, are then at the positions `2i` and `2i + 1`, so each
+//! pair goes in a single leaf `i` of the Merkle tree, and a query index `iota` of a layer is
+//! the index `iota >> 1` of the next one. The trace and composition polynomial commitments use
+//! the same order, and `Polynomial::evaluate_offset_fft_bit_reversed` evaluates directly in it.
+
 pub mod fri_commitment;
 pub mod fri_decommit;
 mod fri_functions;
 
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
 use lambdaworks_crypto::fiat_shamir::is_transcript::IsTranscript;
+use lambdaworks_crypto::merkle_tree::traits::VectorCommitment;
 use lambdaworks_math::field::traits::{IsFFTField, IsField};
 use lambdaworks_math::traits::AsBytes;
-use lambdaworks_math::{
-    fft::cpu::bit_reversing::in_place_bit_reverse_permute, field::traits::IsSubFieldOf,
-};
+use lambdaworks_math::{fft::cpu::bit_reversing::reverse_index, field::traits::IsSubFieldOf};
 pub use lambdaworks_math::{
     field::{element::FieldElement, fields::u64_prime_field::U64PrimeField},
     polynomial::Polynomial,
 };
 
-use crate::config::{BatchedMerkleTree, BatchedMerkleTreeBackend};
+use crate::config::IsStarkVectorCommitment;
+use crate::domain::Domain;
 
-use self::fri_commitment::FriLayer;
+use self::fri_commitment::{CompactFriLayer, FriLayer};
 use self::fri_decommit::FriDecommitment;
 use self::fri_functions::fold_polynomial;
 
-pub fn commit_phase<F: IsFFTField + IsSubFieldOf<E>, E: IsField>(
+/// The reasons the FRI part of a proof can be rejected, see [`verify_fri`].
+#[derive(Debug, PartialEq, Eq)]
+pub enum FriError {
+    /// The number of decommitments differs from the number of query indexes or of
+    /// evaluations of the first layer.
+    WrongNumberOfQueries { expected: usize, got: usize },
+    /// The decommitment of a query does not hold one opening per committed layer.
+    WrongNumberOfLayers { query_idx: usize },
+    /// The opening of a layer does not match the root committed for it.
+    InvalidLayerOpening { query_idx: usize, layer_idx: usize },
+    /// Folding the evaluations of a query does not give the evaluation of the final
+    /// polynomial of FRI. Since every query has to fold to the evaluation of the same
+    /// polynomial, this also rejects a last layer of higher degree than the final polynomial,
+    /// which is what a polynomial above the degree bound leaves after folding.
+    LastValueMismatch { query_idx: usize },
+}
+
+/// Returns the number of times FRI folds a polynomial of degree less than `degree_bound`, a
+/// power of two, so that the last fold has degree at most `final_poly_degree`, along with the
+/// number of coefficients of that final polynomial. The number of coefficients is a power of
+/// two, and FRI folds at least once.
+pub fn fold_schedule(degree_bound: usize, final_poly_degree: usize) -> (usize, usize) {
+    let max_final_poly_len = 1 << (usize::BITS - 1 - (final_poly_degree + 1).leading_zeros());
+    let final_poly_len = max_final_poly_len.min(degree_bound / 2).max(1);
+    let number_of_folds = (degree_bound / final_poly_len).trailing_zeros() as usize;
+    (number_of_folds, final_poly_len)
+}
+
+/// The coefficients of the final polynomial of FRI and the committed layers, as returned by
+/// the commit phase.
+pub type CommitPhaseResult<E, L> = (Vec<FieldElement<E>>, Vec<L>);
+
+pub fn commit_phase<F: IsFFTField + IsSubFieldOf<E>, E: IsField, VC: IsStarkVectorCommitment>(
+    number_layers: usize,
+    final_poly_len: usize,
+    p_0: Polynomial<FieldElement<E>>,
+    transcript: &mut impl IsTranscript<E>,
+    coset_offset: &FieldElement<F>,
+    domain_size: usize,
+) -> CommitPhaseResult<E, FriLayer<E, VC>>
+where
+    FieldElement<F>: AsBytes + Sync + Send,
+    FieldElement<E>: AsBytes + Sync + Send,
+{
+    commit_phase_with_progress(
+        number_layers,
+        final_poly_len,
+        p_0,
+        transcript,
+        coset_offset,
+        domain_size,
+        |_| {},
+    )
+}
+
+/// Same as `commit_phase`, calling `on_layer` with the number of layers folded so far each
+/// time a layer is folded and committed, the last one being the final polynomial.
+pub fn commit_phase_with_progress<
+    F: IsFFTField + IsSubFieldOf<E>,
+    E: IsField,
+    VC: IsStarkVectorCommitment,
+>(
     number_layers: usize,
+    final_poly_len: usize,
     p_0: Polynomial<FieldElement<E>>,
     transcript: &mut impl IsTranscript<E>,
     coset_offset: &FieldElement<F>,
     domain_size: usize,
-) -> (
-    FieldElement<E>,
-    Vec<FriLayer<E, BatchedMerkleTreeBackend<E>>>,
-)
+    on_layer: impl FnMut(usize),
+) -> CommitPhaseResult<E, FriLayer<E, VC>>
 where
     FieldElement<F>: AsBytes + Sync + Send,
     FieldElement<E>: AsBytes + Sync + Send,
 {
+    fold_and_commit(
+        number_layers,
+        final_poly_len,
+        p_0,
+        transcript,
+        coset_offset,
+        domain_size,
+        new_fri_layer::<F, E, VC>,
+        |layer| layer.root.clone(),
+        on_layer,
+    )
+}
+
+/// Same as `commit_phase_with_progress`, but the layers keep only their roots and their
+/// folded polynomials, see [`CompactFriLayer`]. The evaluations and the committed vector of
+/// each layer are dropped once it is committed. The commitments and the transcript interaction are
+/// the same as in `commit_phase`. The layers should be opened with `query_phase_compact`.
+pub fn commit_phase_compact<
+    F: IsFFTField + IsSubFieldOf<E>,
+    E: IsField,
+    VC: IsStarkVectorCommitment,
+>(
+    number_layers: usize,
+    final_poly_len: usize,
+    p_0: Polynomial<FieldElement<E>>,
+    transcript: &mut impl IsTranscript<E>,
+    coset_offset: &FieldElement<F>,
+    domain_size: usize,
+    on_layer: impl FnMut(usize),
+) -> CommitPhaseResult<E, CompactFriLayer<F, E, VC>>
+where
+    FieldElement<F>: AsBytes + Sync + Send,
+    FieldElement<E>: AsBytes + Sync + Send,
+{
+    fold_and_commit(
+        number_layers,
+        final_poly_len,
+        p_0,
+        transcript,
+        coset_offset,
+        domain_size,
+        new_compact_fri_layer::<F, E, VC>,
+        |layer| layer.root.clone(),
+        on_layer,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn fold_and_commit<F: IsFFTField + IsSubFieldOf<E>, E: IsField, L, C: AsRef<[u8]>>(
+    number_layers: usize,
+    final_poly_len: usize,
+    p_0: Polynomial<FieldElement<E>>,
+    transcript: &mut impl IsTranscript<E>,
+    coset_offset: &FieldElement<F>,
+    domain_size: usize,
+    new_layer: impl Fn(&Polynomial<FieldElement<E>>, &FieldElement<F>, usize) -> L,
+    layer_root: impl Fn(&L) -> C,
+    mut on_layer: impl FnMut(usize),
+) -> CommitPhaseResult<E, L> {
     let mut domain_size = domain_size;
 
     let mut fri_layer_list = Vec::with_capacity(number_layers);
-    let mut current_layer: FriLayer<E, BatchedMerkleTreeBackend<E>>;
     let mut current_poly = p_0;
 
     let mut coset_offset = coset_offset.clone();
 
-    for _ in 1..number_layers {
+    for folded_layers in 1..number_layers {
         // <<<< Receive challenge 𝜁ₖ₋₁
         let zeta = transcript.sample_field_element();
         coset_offset = coset_offset.square();
@@ -49,12 +181,13 @@ where
 
         // Compute layer polynomial and domain
         current_poly = FieldElement::<F>::from(2) * fold_polynomial(&current_poly, &zeta);
-        current_layer = new_fri_layer(&current_poly, &coset_offset, domain_size);
-        let new_data = &current_layer.merkle_tree.root;
-        fri_layer_list.push(current_layer.clone()); // TODO: remove this clone
+        let current_layer = new_layer(&current_poly, &coset_offset, domain_size);
+        let new_data = layer_root(&current_layer);
+        fri_layer_list.push(current_layer);
 
         // >>>> Send commitment: [pₖ]
-        transcript.append_bytes(new_data);
+        transcript.append_bytes(new_data.as_ref());
+        on_layer(folded_layers);
     }
 
     // <<<< Receive challenge: 𝜁ₙ₋₁
@@ -62,22 +195,294 @@ where
 
     let last_poly = FieldElement::<F>::from(2) * fold_polynomial(&current_poly, &zeta);
 
-    let last_value = last_poly
-        .coefficients()
-        .first()
-        .unwrap_or(&FieldElement::zero())
-        .clone();
+    // With `number_layers` folds of a polynomial whose degree bound is `final_poly_len` times
+    // 2^number_layers, the last fold has at most `final_poly_len` coefficients. If `p_0`
+    // exceeds its degree bound, the higher coefficients are dropped and the folds of the
+    // queries differ from the evaluations of the final polynomial, so the verifier rejects
+    // the proof.
+    let mut final_poly = last_poly.coefficients().to_vec();
+    final_poly.resize(final_poly_len, FieldElement::zero());
 
-    // >>>> Send value: pₙ
-    transcript.append_field_element(&last_value);
+    // >>>> Send final polynomial: pₙ
+    for coefficient in &final_poly {
+        transcript.append_field_element(coefficient);
+    }
+    on_layer(number_layers);
 
-    (last_value, fri_layer_list)
+    (final_poly, fri_layer_list)
 }
 
-pub fn query_phase<F: IsField>(
-    fri_layers: &Vec<FriLayer<F, BatchedMerkleTreeBackend<F>>>,
+/// Replays the FRI commit phase on the verifier side: samples the folding challenges 𝜁ₖ
+/// and appends the commitments of the layers and the coefficients of the final polynomial,
+/// as `commit_phase` does. Returns the folding challenges.
+pub fn replay_commit_phase<E: IsField>(
+    layer_roots: &[impl AsRef<[u8]>],
+    final_poly: &[FieldElement<E>],
+    transcript: &mut impl IsTranscript<E>,
+) -> Vec<FieldElement<E>> {
+    let mut zetas = layer_roots
+        .iter()
+        .map(|root| {
+            // >>>> Send challenge 𝜁ₖ
+            let element = transcript.sample_field_element();
+            // <<<< Receive commitment: [pₖ₊₁]
+            transcript.append_bytes(root.as_ref());
+            element
+        })
+        .collect::<Vec<FieldElement<E>>>();
+
+    // >>>> Send challenge 𝜁ₙ₋₁
+    zetas.push(transcript.sample_field_element());
+
+    // <<<< Receive final polynomial: pₙ
+    for coefficient in final_poly {
+        transcript.append_field_element(coefficient);
+    }
+
+    zetas
+}
+
+/// Samples the `number_of_queries` FRI query indexes 𝜄ₛ. Each of them points to a pair of
+/// symmetric elements of the LDE domain `domain`.
+pub fn sample_query_indexes<F: IsFFTField, E: IsField>(
+    number_of_queries: usize,
+    domain: &Domain<F>,
+    transcript: &mut impl IsTranscript<E>,
+) -> Vec<usize> {
+    let domain_size = domain.lde_roots_of_unity_coset.len() as u64;
+    (0..number_of_queries)
+        .map(|_| (transcript.sample_u64(domain_size >> 1)) as usize)
+        .collect::<Vec<usize>>()
+}
+
+/// Samples `number_of_queries` distinct FRI query indexes 𝜄ₛ, drawing indexes from the
+/// transcript as `sample_query_indexes` does and skipping the ones already drawn. The number of
+/// queries must not exceed the number of pairs of symmetric elements of the LDE domain, half
+/// of its size, or this never returns.
+pub fn sample_distinct_query_indexes<F: IsFFTField, E: IsField>(
+    number_of_queries: usize,
+    domain: &Domain<F>,
+    transcript: &mut impl IsTranscript<E>,
+) -> Vec<usize> {
+    let domain_size = domain.lde_roots_of_unity_coset.len() as u64;
+    let mut iotas = Vec::with_capacity(number_of_queries);
+    while iotas.len() < number_of_queries {
+        let iota = transcript.sample_u64(domain_size >> 1) as usize;
+        if !iotas.contains(&iota) {
+            iotas.push(iota);
+        }
+    }
+    iotas
+}
+
+/// Checks the FRI part of a proof in isolation from the rest of the STARK protocol. The
+/// transcript should be in the state it was in when the prover started the FRI commit phase.
+/// The commit phase and the proof of work `nonce`, if any, are replayed to sample the folding
+/// challenges and the query indexes; the nonce itself is not checked. Then every query is
+/// checked with [`verify_query_phase`], given the evaluations of the first layer p₀ at the
+/// pair of points it points to.
+pub fn verify_fri<F: IsFFTField + IsSubFieldOf<E>, E: IsField, VC: IsStarkVectorCommitment>(
+    layer_roots: &[VC::Commitment],
+    final_poly: &[FieldElement<E>],
+    nonce: Option<u64>,
+    query_list: &[FriDecommitment<E, VC>],
+    first_layer_evaluations: &[(FieldElement<E>, FieldElement<E>)],
+    domain: &Domain<F>,
+    transcript: &mut impl IsTranscript<E>,
+) -> Result<(), FriError>
+where
+    FieldElement<F>: AsBytes + Sync + Send,
+    FieldElement<E>: AsBytes + Sync + Send,
+{
+    let zetas = replay_commit_phase(layer_roots, final_poly, transcript);
+    if let Some(nonce) = nonce {
+        transcript.append_bytes(&nonce.to_be_bytes());
+    }
+    let iotas = sample_query_indexes(query_list.len(), domain, transcript);
+
+    verify_query_phase(
+        layer_roots,
+        final_poly,
+        query_list,
+        &zetas,
+        &iotas,
+        first_layer_evaluations,
+        domain,
+    )
+}
+
+/// Checks the FRI decommitment of every query index 𝜄ₛ in `iotas`: that the openings of each
+/// layer match its root, and that folding the evaluations with the challenges `zetas` gives
+/// the evaluation of the final polynomial `final_poly`, given by its coefficients.
+/// `first_layer_evaluations` holds p₀(𝜐) and p₀(-𝜐) for every query, where 𝜐 and
+/// -𝜐 are the elements of the LDE domain the query points to.
+pub fn verify_query_phase<
+    F: IsFFTField + IsSubFieldOf<E>,
+    E: IsField,
+    VC: IsStarkVectorCommitment,
+>(
+    layer_roots: &[VC::Commitment],
+    final_poly: &[FieldElement<E>],
+    query_list: &[FriDecommitment<E, VC>],
+    zetas: &[FieldElement<E>],
     iotas: &[usize],
-) -> Vec<FriDecommitment<F>>
+    first_layer_evaluations: &[(FieldElement<E>, FieldElement<E>)],
+    domain: &Domain<F>,
+) -> Result<(), FriError>
+where
+    FieldElement<F>: AsBytes + Sync + Send,
+    FieldElement<E>: AsBytes + Sync + Send,
+{
+    for got in [query_list.len(), first_layer_evaluations.len()] {
+        if got != iotas.len() {
+            return Err(FriError::WrongNumberOfQueries {
+                expected: iotas.len(),
+                got,
+            });
+        }
+    }
+
+    let lde_domain_size = domain.lde_roots_of_unity_coset.len() as u64;
+    let evaluation_points = iotas
+        .iter()
+        .map(|iota| {
+            domain.lde_roots_of_unity_coset[reverse_index(iota * 2, lde_domain_size)].clone()
+        })
+        .collect::<Vec<FieldElement<F>>>();
+    let mut evaluation_points_inverse = evaluation_points.clone();
+    FieldElement::inplace_batch_inverse(&mut evaluation_points_inverse).unwrap();
+
+    query_list
+        .iter()
+        .zip(iotas)
+        .zip(evaluation_points.iter().zip(evaluation_points_inverse))
+        .zip(first_layer_evaluations)
+        .enumerate()
+        .try_for_each(
+            |(
+                query_idx,
+                (((decommitment, iota), (evaluation_point, evaluation_point_inv)), p0_evaluations),
+            )| {
+                verify_query(
+                    layer_roots,
+                    final_poly,
+                    zetas,
+                    query_idx,
+                    *iota,
+                    decommitment,
+                    evaluation_point,
+                    evaluation_point_inv,
+                    p0_evaluations,
+                )
+            },
+        )
+}
+
+/// Verifies a single FRI query.
+/// `zetas`: the vector of all challenges sent by the verifier to the prover at the commit
+/// phase to fold polynomials.
+/// `iota`: the index challenge of this FRI query. This index uniquely determines two elements 𝜐 and -𝜐
+/// of the evaluation domain of FRI layer 0.
+/// `evaluation_point`, `evaluation_point_inv`: the element 𝜐 and its precomputed inverse 𝜐⁻¹.
+/// `p0_evaluations`: the values p₀(𝜐) and p₀(-𝜐), where p₀ is the deep composition polynomial.
+#[allow(clippy::too_many_arguments)]
+fn verify_query<F: IsFFTField + IsSubFieldOf<E>, E: IsField, VC: IsStarkVectorCommitment>(
+    layer_roots: &[VC::Commitment],
+    final_poly: &[FieldElement<E>],
+    zetas: &[FieldElement<E>],
+    query_idx: usize,
+    iota: usize,
+    decommitment: &FriDecommitment<E, VC>,
+    evaluation_point: &FieldElement<F>,
+    evaluation_point_inv: FieldElement<F>,
+    p0_evaluations: &(FieldElement<E>, FieldElement<E>),
+) -> Result<(), FriError>
+where
+    FieldElement<F>: AsBytes + Sync + Send,
+    FieldElement<E>: AsBytes + Sync + Send,
+{
+    if decommitment.layers_auth_paths.len() != layer_roots.len()
+        || decommitment.layers_evaluations_sym.len() != layer_roots.len()
+    {
+        return Err(FriError::WrongNumberOfLayers { query_idx });
+    }
+
+    let (p0_eval, p0_eval_sym) = p0_evaluations;
+
+    // Reconstruct p₁(𝜐²)
+    let mut v =
+        (p0_eval + p0_eval_sym) + &evaluation_point_inv * &zetas[0] * (p0_eval - p0_eval_sym);
+    let mut evaluation_point_inv = evaluation_point_inv.square();
+    let mut index = iota;
+
+    // For each FRI layer, starting from the layer 1: use the proof to verify the validity of values pᵢ(−𝜐^(2ⁱ)) (given by the prover) and
+    // pᵢ(𝜐^(2ⁱ)) (computed on the previous iteration by the verifier). Then use them to obtain pᵢ₊₁(𝜐^(2ⁱ⁺¹)).
+    for (layer_idx, ((merkle_root, auth_path_sym), evaluation_sym)) in layer_roots
+        .iter()
+        .zip(&decommitment.layers_auth_paths)
+        .zip(&decommitment.layers_evaluations_sym)
+        .enumerate()
+    {
+        // Verify opening Open(pᵢ(Dₖ), −𝜐^(2ⁱ)) and Open(pᵢ(Dₖ), 𝜐^(2ⁱ)).
+        // `v` is pᵢ(𝜐^(2ⁱ)).
+        // `evaluation_sym` is pᵢ(−𝜐^(2ⁱ)).
+        if !verify_layer_openings::<E, VC>(merkle_root, auth_path_sym, &v, evaluation_sym, index) {
+            return Err(FriError::InvalidLayerOpening {
+                query_idx,
+                layer_idx,
+            });
+        }
+
+        // Update `v` with next value pᵢ₊₁(𝜐^(2ⁱ⁺¹)).
+        v = (&v + evaluation_sym)
+            + &evaluation_point_inv * &zetas[layer_idx + 1] * (&v - evaluation_sym);
+        evaluation_point_inv = evaluation_point_inv.square();
+
+        // Update index for next iteration. The index of the squares in the next layer
+        // is obtained by halving the current index. This is due to the bit-reverse
+        // ordering of the elements in the Merkle tree.
+        index >>= 1;
+    }
+
+    // Check that the final value is the evaluation of the final polynomial given by the
+    // prover at 𝜐^(2ⁿ), n being the number of folds.
+    let final_point = (0..zetas.len()).fold(evaluation_point.clone(), |point, _| point.square());
+    let final_value = final_poly
+        .iter()
+        .rev()
+        .fold(FieldElement::<E>::zero(), |acc, coefficient| {
+            &final_point * &acc + coefficient
+        });
+    if v != final_value {
+        return Err(FriError::LastValueMismatch { query_idx });
+    }
+    Ok(())
+}
+
+/// Verifies the openings of a fold polynomial of an inner layer of FRI.
+fn verify_layer_openings<E: IsField, VC: IsStarkVectorCommitment>(
+    merkle_root: &VC::Commitment,
+    auth_path_sym: &VC::Opening,
+    evaluation: &FieldElement<E>,
+    evaluation_sym: &FieldElement<E>,
+    iota: usize,
+) -> bool
+where
+    FieldElement<E>: AsBytes + Sync + Send,
+{
+    let evaluations = if iota % 2 == 1 {
+        vec![evaluation_sym.clone(), evaluation.clone()]
+    } else {
+        vec![evaluation.clone(), evaluation_sym.clone()]
+    };
+
+    VC::Scheme::<E>::verify(merkle_root, iota >> 1, &evaluations, auth_path_sym)
+}
+
+pub fn query_phase<F: IsField, VC: IsStarkVectorCommitment>(
+    fri_layers: &Vec<FriLayer<F, VC>>,
+    iotas: &[usize],
+) -> Vec<FriDecommitment<F, VC>>
 where
     FieldElement<F>: AsBytes + Sync + Send,
 {
@@ -92,7 +497,7 @@ where
                 for layer in fri_layers {
                     // symmetric element
                     let evaluation_sym = layer.evaluation[index ^ 1].clone();
-                    let auth_path_sym = layer.merkle_tree.get_proof_by_pos(index >> 1).unwrap();
+                    let auth_path_sym = layer.merkle_tree.open(index >> 1).unwrap();
                     layers_evaluations_sym.push(evaluation_sym);
                     layers_auth_paths_sym.push(auth_path_sym);
 
@@ -112,31 +517,395 @@ where
     }
 }
 
-pub fn new_fri_layer<F: IsFFTField + IsSubFieldOf<E>, E: IsField>(
-    poly: &Polynomial<FieldElement<E>>,
-    coset_offset: &FieldElement<F>,
-    domain_size: usize,
-) -> crate::fri::fri_commitment::FriLayer<E, BatchedMerkleTreeBackend<E>>
+/// Same as `query_phase`, for layers committed with `commit_phase_compact`. The evaluations
+/// and the committed vector of each layer are rebuilt from its polynomial, opened for every query
+/// and dropped before the next layer is rebuilt, so the decommitments are the same as the ones
+/// `query_phase` returns for the layers of `commit_phase`.
+pub fn query_phase_compact<
+    F: IsFFTField + IsSubFieldOf<E>,
+    E: IsField,
+    VC: IsStarkVectorCommitment,
+>(
+    fri_layers: &[CompactFriLayer<F, E, VC>],
+    iotas: &[usize],
+) -> Vec<FriDecommitment<E, VC>>
 where
     FieldElement<F>: AsBytes + Sync + Send,
     FieldElement<E>: AsBytes + Sync + Send,
 {
-    let mut evaluation =
-        Polynomial::evaluate_offset_fft(poly, 1, Some(domain_size), coset_offset).unwrap(); // TODO: return error
+    let mut query_list: Vec<_> = iotas
+        .iter()
+        .map(|_| FriDecommitment {
+            layers_auth_paths: Vec::with_capacity(fri_layers.len()),
+            layers_evaluations_sym: Vec::with_capacity(fri_layers.len()),
+        })
+        .collect();
 
-    in_place_bit_reverse_permute(&mut evaluation);
+    for (layer_idx, layer) in fri_layers.iter().enumerate() {
+        let (evaluation, merkle_tree, root) =
+            evaluate_and_commit::<F, E, VC>(&layer.poly, &layer.coset_offset, layer.domain_size);
+        debug_assert_eq!(root, layer.root);
 
-    let mut to_commit = Vec::new();
-    for chunk in evaluation.chunks(2) {
-        to_commit.push(vec![chunk[0].clone(), chunk[1].clone()]);
+        for (decommitment, iota_s) in query_list.iter_mut().zip(iotas) {
+            // The index of the query in the layer `layer_idx`, halved once per fold.
+            let index = iota_s >> layer_idx;
+            // symmetric element
+            let evaluation_sym = evaluation[index ^ 1].clone();
+            let auth_path_sym = merkle_tree.open(index >> 1).unwrap();
+            decommitment.layers_evaluations_sym.push(evaluation_sym);
+            decommitment.layers_auth_paths.push(auth_path_sym);
+        }
     }
 
-    let merkle_tree = BatchedMerkleTree::build(&to_commit);
+    query_list
+}
+
+pub fn new_fri_layer<F: IsFFTField + IsSubFieldOf<E>, E: IsField, VC: IsStarkVectorCommitment>(
+    poly: &Polynomial<FieldElement<E>>,
+    coset_offset: &FieldElement<F>,
+    domain_size: usize,
+) -> FriLayer<E, VC>
+where
+    FieldElement<F>: AsBytes + Sync + Send,
+    FieldElement<E>: AsBytes + Sync + Send,
+{
+    let (evaluation, merkle_tree, root) =
+        evaluate_and_commit::<F, E, VC>(poly, coset_offset, domain_size);
 
     FriLayer::new(
         &evaluation,
         merkle_tree,
+        root,
         coset_offset.clone().to_extension(),
         domain_size,
     )
 }
+
+pub fn new_compact_fri_layer<
+    F: IsFFTField + IsSubFieldOf<E>,
+    E: IsField,
+    VC: IsStarkVectorCommitment,
+>(
+    poly: &Polynomial<FieldElement<E>>,
+    coset_offset: &FieldElement<F>,
+    domain_size: usize,
+) -> CompactFriLayer<F, E, VC>
+where
+    FieldElement<F>: AsBytes + Sync + Send,
+    FieldElement<E>: AsBytes + Sync + Send,
+{
+    let (_, _, root) = evaluate_and_commit::<F, E, VC>(poly, coset_offset, domain_size);
+
+    CompactFriLayer::new(poly.clone(), root, coset_offset.clone(), domain_size)
+}
+
+/// Evaluates `poly` over the coset of size `domain_size` and offset `coset_offset`, and
+/// commits to the bit-reversed evaluations, putting each symmetric pair in the same row.
+#[allow(clippy::type_complexity)]
+fn evaluate_and_commit<F: IsFFTField + IsSubFieldOf<E>, E: IsField, VC: IsStarkVectorCommitment>(
+    poly: &Polynomial<FieldElement<E>>,
+    coset_offset: &FieldElement<F>,
+    domain_size: usize,
+) -> (Vec<FieldElement<E>>, VC::Scheme<E>, VC::Commitment)
+where
+    FieldElement<F>: AsBytes + Sync + Send,
+    FieldElement<E>: AsBytes + Sync + Send,
+{
+    let evaluation =
+        Polynomial::evaluate_offset_fft_bit_reversed(poly, 1, Some(domain_size), coset_offset)
+            .unwrap(); // TODO: return error
+
+    let mut to_commit = Vec::new();
+    for chunk in evaluation.chunks(2) {
+        to_commit.push(vec![chunk[0].clone(), chunk[1].clone()]);
+    }
+
+    let (merkle_tree, root) = VC::Scheme::<E>::commit(&to_commit);
+
+    (evaluation, merkle_tree, root)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{Commitment, MerkleVectorCommitment};
+    use crate::transcript::StoneProverTranscript;
+    use lambdaworks_crypto::fiat_shamir::test_transcript::TestTranscript;
+    use lambdaworks_math::field::fields::fft_friendly::stark_252_prime_field::Stark252PrimeField;
+
+    type FE = FieldElement<Stark252PrimeField>;
+
+    /// Runs the FRI commit and query phases on `p_0` over `domain`, folding it until the
+    /// final polynomial has `final_poly_len` coefficients. Returns the roots of the layers,
+    /// the final polynomial, the decommitments and the evaluations of `p_0` at the queried
+    /// points and their symmetric ones.
+    #[allow(clippy::type_complexity)]
+    fn commit_and_query(
+        p_0: &Polynomial<FE>,
+        domain: &Domain<Stark252PrimeField>,
+        final_poly_len: usize,
+        number_of_queries: usize,
+    ) -> (
+        Vec<Commitment>,
+        Vec<FE>,
+        Vec<FriDecommitment<Stark252PrimeField>>,
+        Vec<(FE, FE)>,
+    ) {
+        let domain_size = domain.lde_roots_of_unity_coset.len();
+        let number_layers = domain.root_order as usize - final_poly_len.trailing_zeros() as usize;
+
+        let mut transcript = StoneProverTranscript::new(&[]);
+        let (final_poly, fri_layers) = commit_phase::<_, _, MerkleVectorCommitment>(
+            number_layers,
+            final_poly_len,
+            p_0.clone(),
+            &mut transcript,
+            &domain.coset_offset,
+            domain_size,
+        );
+        let iotas = sample_query_indexes(number_of_queries, domain, &mut transcript);
+        let query_list = query_phase(&fri_layers, &iotas);
+        let layer_roots: Vec<_> = fri_layers.iter().map(|layer| layer.root).collect();
+        let first_layer_evaluations: Vec<_> = iotas
+            .iter()
+            .map(|iota| {
+                let point =
+                    &domain.lde_roots_of_unity_coset[reverse_index(iota * 2, domain_size as u64)];
+                let point_sym = &domain.lde_roots_of_unity_coset
+                    [reverse_index(iota * 2 + 1, domain_size as u64)];
+                (p_0.evaluate(point), p_0.evaluate(point_sym))
+            })
+            .collect();
+
+        (layer_roots, final_poly, query_list, first_layer_evaluations)
+    }
+
+    #[test]
+    fn verify_fri_rejects_a_corrupted_symmetric_evaluation() {
+        let coefficients: Vec<_> = (0..16u64).map(|i| FE::from(i * i + 3)).collect();
+        let p_0 = Polynomial::new(&coefficients);
+        let domain = Domain::<Stark252PrimeField>::for_params(16, 4, 3);
+        let (layer_roots, final_poly, mut query_list, first_layer_evaluations) =
+            commit_and_query(&p_0, &domain, 1, 3);
+
+        let verify = |query_list: &[FriDecommitment<Stark252PrimeField>]| {
+            verify_fri(
+                &layer_roots,
+                &final_poly,
+                None,
+                query_list,
+                &first_layer_evaluations,
+                &domain,
+                &mut StoneProverTranscript::new(&[]),
+            )
+        };
+        assert_eq!(verify(&query_list), Ok(()));
+
+        query_list[1].layers_evaluations_sym[2] += FE::one();
+        assert_eq!(
+            verify(&query_list),
+            Err(FriError::InvalidLayerOpening {
+                query_idx: 1,
+                layer_idx: 2
+            })
+        );
+    }
+
+    #[test]
+    fn last_value_is_the_constant_the_polynomial_folds_to() {
+        let coefficients: Vec<_> = (0..16u64).map(|i| FE::from(i * i + 3)).collect();
+        let p_0 = Polynomial::new(&coefficients);
+        let domain = Domain::<Stark252PrimeField>::for_params(16, 4, 3);
+        let (layer_roots, final_poly, query_list, first_layer_evaluations) =
+            commit_and_query(&p_0, &domain, 1, 3);
+        assert_eq!(final_poly.len(), 1);
+
+        let mut transcript = StoneProverTranscript::new(&[]);
+        let zetas = replay_commit_phase(&layer_roots, &final_poly, &mut transcript);
+        let iotas = sample_query_indexes(3, &domain, &mut transcript);
+        let verify = |final_poly: &[FE]| {
+            verify_query_phase(
+                &layer_roots,
+                final_poly,
+                &query_list,
+                &zetas,
+                &iotas,
+                &first_layer_evaluations,
+                &domain,
+            )
+        };
+
+        assert_eq!(verify(&final_poly), Ok(()));
+        assert_eq!(
+            verify(&[final_poly[0] + FE::one()]),
+            Err(FriError::LastValueMismatch { query_idx: 0 })
+        );
+    }
+
+    #[test]
+    fn verify_fri_rejects_a_polynomial_whose_last_layer_is_not_constant() {
+        // Twice the degree bound of the domain, so folding it `root_order` times leaves a
+        // polynomial of degree one instead of a constant.
+        let coefficients: Vec<_> = (0..32u64).map(|i| FE::from(i * i + 3)).collect();
+        let p_0 = Polynomial::new(&coefficients);
+        let domain = Domain::<Stark252PrimeField>::for_params(16, 4, 3);
+        let (layer_roots, final_poly, query_list, first_layer_evaluations) =
+            commit_and_query(&p_0, &domain, 1, 8);
+
+        assert!(matches!(
+            verify_fri(
+                &layer_roots,
+                &final_poly,
+                None,
+                &query_list,
+                &first_layer_evaluations,
+                &domain,
+                &mut StoneProverTranscript::new(&[]),
+            ),
+            Err(FriError::LastValueMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn fri_stopping_at_a_final_polynomial_verifies_with_fewer_layers() {
+        let coefficients: Vec<_> = (0..16u64).map(|i| FE::from(i * i + 3)).collect();
+        let p_0 = Polynomial::new(&coefficients);
+        let domain = Domain::<Stark252PrimeField>::for_params(16, 4, 3);
+        let (layer_roots, mut final_poly, query_list, first_layer_evaluations) =
+            commit_and_query(&p_0, &domain, 4, 3);
+        assert_eq!(layer_roots.len(), 1);
+        assert_eq!(final_poly.len(), 4);
+
+        let mut transcript = StoneProverTranscript::new(&[]);
+        let zetas = replay_commit_phase(&layer_roots, &final_poly, &mut transcript);
+        let iotas = sample_query_indexes(3, &domain, &mut transcript);
+        let verify = |final_poly: &[FE]| {
+            verify_query_phase(
+                &layer_roots,
+                final_poly,
+                &query_list,
+                &zetas,
+                &iotas,
+                &first_layer_evaluations,
+                &domain,
+            )
+        };
+        assert_eq!(verify(&final_poly), Ok(()));
+
+        final_poly[3] += FE::one();
+        assert_eq!(
+            verify(&final_poly),
+            Err(FriError::LastValueMismatch { query_idx: 0 })
+        );
+    }
+
+    #[test]
+    fn fold_schedule_stops_at_the_largest_power_of_two_within_the_final_degree() {
+        assert_eq!(fold_schedule(1024, 0), (10, 1));
+        assert_eq!(fold_schedule(1024, 15), (6, 16));
+        assert_eq!(fold_schedule(1024, 16), (6, 16));
+        assert_eq!(fold_schedule(16, 1000), (1, 8));
+    }
+
+    #[test]
+    fn layer_opening_pairs_the_evaluations_at_symmetric_points() {
+        let coefficients: Vec<_> = (0..8u64).map(|i| FE::from(i + 1)).collect();
+        let p = Polynomial::new(&coefficients);
+        let coset_offset = FE::from(3);
+        let domain_size = 32;
+        let layer = new_fri_layer::<_, _, MerkleVectorCommitment>(&p, &coset_offset, domain_size);
+        let primitive_root = Stark252PrimeField::get_primitive_root_of_unity(5).unwrap();
+
+        for iota in [0, 7, 10, 31] {
+            let x = coset_offset * primitive_root.pow(reverse_index(iota, domain_size as u64));
+            let evaluation = &layer.evaluation[iota];
+            let evaluation_sym = &layer.evaluation[iota ^ 1];
+            assert_eq!(evaluation, &p.evaluate(&x));
+            assert_eq!(evaluation_sym, &p.evaluate(&-x));
+
+            let auth_path = layer.merkle_tree.open(iota >> 1).unwrap();
+            assert!(verify_layer_openings::<_, MerkleVectorCommitment>(
+                &layer.root,
+                &auth_path,
+                evaluation,
+                evaluation_sym,
+                iota
+            ));
+        }
+    }
+
+    #[test]
+    fn compact_fri_layers_produce_the_same_commitments_and_decommitments() {
+        let coefficients: Vec<_> = (0..16u64).map(|i| FE::from(i * i + 3)).collect();
+        let p_0 = Polynomial::new(&coefficients);
+        let coset_offset = FE::from(3);
+        let domain_size = 64;
+        let number_layers = 4;
+
+        let mut transcript = StoneProverTranscript::new(&[]);
+        let (final_poly, fri_layers) = commit_phase::<_, _, MerkleVectorCommitment>(
+            number_layers,
+            1,
+            p_0.clone(),
+            &mut transcript,
+            &coset_offset,
+            domain_size,
+        );
+
+        let mut transcript_compact = StoneProverTranscript::new(&[]);
+        let (final_poly_compact, fri_layers_compact) =
+            commit_phase_compact::<_, _, MerkleVectorCommitment>(
+                number_layers,
+                1,
+                p_0,
+                &mut transcript_compact,
+                &coset_offset,
+                domain_size,
+                |_| {},
+            );
+
+        assert_eq!(final_poly, final_poly_compact);
+        assert_eq!(transcript.state(), transcript_compact.state());
+        for (layer, layer_compact) in fri_layers.iter().zip(&fri_layers_compact) {
+            assert_eq!(layer.root, layer_compact.root);
+        }
+
+        let iotas = [0, 5, 17, 31];
+        let decommitments = query_phase(&fri_layers, &iotas);
+        let decommitments_compact = query_phase_compact(&fri_layers_compact, &iotas);
+        for (decommitment, decommitment_compact) in decommitments.iter().zip(&decommitments_compact)
+        {
+            assert_eq!(
+                decommitment.layers_evaluations_sym,
+                decommitment_compact.layers_evaluations_sym
+            );
+            for (path, path_compact) in decommitment
+                .layers_auth_paths
+                .iter()
+                .zip(&decommitment_compact.layers_auth_paths)
+            {
+                assert_eq!(path.merkle_path, path_compact.merkle_path);
+            }
+        }
+    }
+
+    fn transcript_sampling(values: &[u64]) -> TestTranscript<Stark252PrimeField> {
+        let mut transcript = TestTranscript::new();
+        for value in values {
+            transcript.push_u64(*value);
+        }
+        transcript
+    }
+
+    #[test]
+    fn distinct_query_indexes_skip_repetitions() {
+        let domain = Domain::<Stark252PrimeField>::for_params(16, 4, 3);
+        let values = [3, 3, 5, 3, 7];
+
+        let iotas = sample_query_indexes(3, &domain, &mut transcript_sampling(&values));
+        assert_eq!(iotas, vec![3, 3, 5]);
+
+        let iotas = sample_distinct_query_indexes(3, &domain, &mut transcript_sampling(&values));
+        assert_eq!(iotas, vec![3, 5, 7]);
+    }
+}
@@ -1,7 +1,15 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg_attr(not(feature = "std"), macro_use)]
+extern crate alloc;
+
 use lambdaworks_math::field::{
     element::FieldElement, fields::fft_friendly::stark_252_prime_field::Stark252PrimeField,
 };
 
+#[macro_use]
+mod logging;
+
 pub mod constraints;
 pub mod context;
 pub mod debug;
@@ -10,14 +18,20 @@ pub mod examples;
 pub mod frame;
 pub mod fri;
 pub mod grinding;
+pub mod logup;
 pub mod proof;
 pub mod prover;
+pub mod session;
 pub mod table;
 pub mod trace;
 pub mod traits;
 pub mod transcript;
 pub mod utils;
 pub mod verifier;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+pub mod zerofier;
+pub mod zk;
 
 #[cfg(test)]
 pub mod tests;
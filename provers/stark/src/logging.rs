@@ -0,0 +1,57 @@
+//! The logging macros of the crate. With the `log` feature they are the ones of the `log`
+//! crate, otherwise they discard their arguments, so that the prover can be built without it.
+
+#[cfg(feature = "log")]
+macro_rules! info {
+    ($($arg:tt)+) => { ::log::info!($($arg)+) };
+}
+
+#[cfg(feature = "log")]
+macro_rules! debug {
+    ($($arg:tt)+) => { ::log::debug!($($arg)+) };
+}
+
+#[cfg(feature = "log")]
+macro_rules! warn {
+    ($($arg:tt)+) => { ::log::warn!($($arg)+) };
+}
+
+#[cfg(feature = "log")]
+macro_rules! error {
+    ($($arg:tt)+) => { ::log::error!($($arg)+) };
+}
+
+/// Returns whether messages of the debug level are logged, to skip computing them otherwise.
+#[cfg(feature = "log")]
+macro_rules! debug_enabled {
+    () => {
+        ::log::log_enabled!(::log::Level::Debug)
+    };
+}
+
+#[cfg(not(feature = "log"))]
+macro_rules! info {
+    ($($arg:tt)+) => {{ let _ = format_args!($($arg)+); }};
+}
+
+#[cfg(not(feature = "log"))]
+macro_rules! debug {
+    ($($arg:tt)+) => {{ let _ = format_args!($($arg)+); }};
+}
+
+#[cfg(not(feature = "log"))]
+macro_rules! warn {
+    ($($arg:tt)+) => {{ let _ = format_args!($($arg)+); }};
+}
+
+#[cfg(not(feature = "log"))]
+macro_rules! error {
+    ($($arg:tt)+) => {{ let _ = format_args!($($arg)+); }};
+}
+
+#[cfg(not(feature = "log"))]
+macro_rules! debug_enabled {
+    () => {
+        false
+    };
+}
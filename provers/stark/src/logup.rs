@@ -0,0 +1,198 @@
+//! Building blocks for lookup arguments based on logarithmic derivatives (LogUp).
+//!
+//! A LogUp argument proves that every value of a witness column is contained in a table
+//! column. Given a challenge `α` sampled after committing the main trace, and a column
+//! `m` with the number of times each table value is looked up, the witness values are
+//! contained in the table if and only if (with high probability)
+//!
+//!   Σᵢ 1 / (wᵢ - α) = Σᵢ mᵢ / (tᵢ - α)
+//!
+//! The equality is checked with an auxiliary column `s` accumulating the running sum of
+//! the differences, `sᵢ₊₁ = sᵢ + 1 / (wᵢ - α) - mᵢ / (tᵢ - α)`, starting at `s₀ = 0`.
+//! The transition constraint given by [`LogUpConstraint`] is applied to every row of the
+//! trace, including the last one, whose next row is the first one. Since the running sum
+//! has to come back to its initial value, this forces the total sum to be zero.
+
+use crate::{constraints::transition::TransitionConstraint, frame::Frame};
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use lambdaworks_math::field::{
+    element::FieldElement,
+    traits::{IsFFTField, IsField, IsSubFieldOf},
+};
+
+/// Builds the running sum auxiliary column of a LogUp argument, given the `witness`,
+/// `table` and `multiplicities` columns of the main trace and the sampled `challenge`.
+/// All the columns must have the same length.
+///
+/// Panics if the challenge coincides with one of the witness or table values, which
+/// happens with negligible probability.
+pub fn build_auxiliary_column<F, E>(
+    witness: &[FieldElement<F>],
+    table: &[FieldElement<F>],
+    multiplicities: &[FieldElement<F>],
+    challenge: &FieldElement<E>,
+) -> Vec<FieldElement<E>>
+where
+    F: IsSubFieldOf<E>,
+    E: IsField,
+{
+    debug_assert_eq!(witness.len(), table.len());
+    debug_assert_eq!(witness.len(), multiplicities.len());
+
+    let mut witness_inverses: Vec<_> = witness.iter().map(|w| w - challenge).collect();
+    let mut table_inverses: Vec<_> = table.iter().map(|t| t - challenge).collect();
+    FieldElement::inplace_batch_inverse(&mut witness_inverses)
+        .expect("challenge should not be a witness value");
+    FieldElement::inplace_batch_inverse(&mut table_inverses)
+        .expect("challenge should not be a table value");
+
+    let mut column = Vec::with_capacity(witness.len());
+    let mut accumulated = FieldElement::<E>::zero();
+    for ((witness_inverse, table_inverse), multiplicity) in witness_inverses
+        .iter()
+        .zip(&table_inverses)
+        .zip(multiplicities)
+    {
+        column.push(accumulated.clone());
+        accumulated = accumulated + witness_inverse - multiplicity * table_inverse;
+    }
+    column
+}
+
+/// Returns, for each value of `table`, the number of times it appears in `witness`.
+/// Table values are expected to be distinct. Values are looked up linearly in the table,
+/// so this is only suitable for small tables.
+pub fn compute_multiplicities<F: IsField>(
+    witness: &[FieldElement<F>],
+    table: &[FieldElement<F>],
+) -> Vec<FieldElement<F>> {
+    let mut multiplicities = vec![FieldElement::<F>::zero(); table.len()];
+    for value in witness {
+        if let Some(idx) = table.iter().position(|t| t == value) {
+            multiplicities[idx] += FieldElement::<F>::one();
+        }
+    }
+    multiplicities
+}
+
+/// The transition constraint of a LogUp argument,
+///
+///   (sᵢ₊₁ - sᵢ) · (wᵢ - α) · (tᵢ - α) - (tᵢ - α) + mᵢ · (wᵢ - α) = 0
+///
+/// where `w`, `t` and `m` are the witness, table and multiplicities main columns, `s` is
+/// the running sum auxiliary column and `α` is the RAP challenge at `challenge_idx`.
+/// It has degree 3 and needs the frame offsets `[0, 1]`.
+pub struct LogUpConstraint {
+    constraint_idx: usize,
+    witness_col: usize,
+    table_col: usize,
+    multiplicities_col: usize,
+    aux_col: usize,
+    challenge_idx: usize,
+}
+
+impl LogUpConstraint {
+    pub fn new(
+        constraint_idx: usize,
+        witness_col: usize,
+        table_col: usize,
+        multiplicities_col: usize,
+        aux_col: usize,
+        challenge_idx: usize,
+    ) -> Self {
+        Self {
+            constraint_idx,
+            witness_col,
+            table_col,
+            multiplicities_col,
+            aux_col,
+            challenge_idx,
+        }
+    }
+}
+
+impl<F, E> TransitionConstraint<F, E> for LogUpConstraint
+where
+    F: IsSubFieldOf<E> + IsFFTField + Send + Sync,
+    E: IsField + Send + Sync,
+{
+    fn degree(&self) -> usize {
+        3
+    }
+
+    fn constraint_idx(&self) -> usize {
+        self.constraint_idx
+    }
+
+    fn end_exemptions(&self) -> usize {
+        0
+    }
+
+    fn evaluate(
+        &self,
+        frame: &Frame<F, E>,
+        transition_evaluations: &mut [FieldElement<E>],
+        _periodic_values: &[FieldElement<F>],
+        rap_challenges: &[FieldElement<E>],
+    ) {
+        let first_step = frame.get_evaluation_step(0);
+        let second_step = frame.get_evaluation_step(1);
+
+        let challenge = &rap_challenges[self.challenge_idx];
+
+        let w = first_step.get_main_evaluation_element(0, self.witness_col);
+        let t = first_step.get_main_evaluation_element(0, self.table_col);
+        let m = first_step.get_main_evaluation_element(0, self.multiplicities_col);
+        let s = first_step.get_aux_evaluation_element(0, self.aux_col);
+        let s_next = second_step.get_aux_evaluation_element(0, self.aux_col);
+
+        let w_term = w - challenge;
+        let t_term = t - challenge;
+
+        let res = (s_next - s) * &w_term * &t_term - &t_term + m * w_term;
+
+        transition_evaluations[self.constraint_idx] = res;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use lambdaworks_math::field::fields::u64_prime_field::FE17;
+
+    #[test]
+    fn running_sum_wraps_around_when_witness_is_in_table() {
+        let table: Vec<_> = (0..4u64).map(FE17::from).collect();
+        let witness = vec![FE17::from(3), FE17::from(0), FE17::from(3), FE17::from(1)];
+        let multiplicities = compute_multiplicities(&witness, &table);
+        assert_eq!(
+            multiplicities,
+            vec![FE17::from(1), FE17::from(1), FE17::from(0), FE17::from(2)]
+        );
+
+        let challenge = FE17::from(10);
+        let column = build_auxiliary_column(&witness, &table, &multiplicities, &challenge);
+
+        let last = column.len() - 1;
+        let last_term = (witness[last] - challenge).inv().unwrap()
+            - multiplicities[last] * (table[last] - challenge).inv().unwrap();
+        assert_eq!(column[0], FE17::zero());
+        assert_eq!(column[last] + last_term, column[0]);
+    }
+
+    #[test]
+    fn running_sum_does_not_wrap_around_when_witness_is_not_in_table() {
+        let table: Vec<_> = (0..4u64).map(FE17::from).collect();
+        let witness = vec![FE17::from(3), FE17::from(0), FE17::from(5), FE17::from(1)];
+        let multiplicities = compute_multiplicities(&witness, &table);
+
+        let challenge = FE17::from(10);
+        let column = build_auxiliary_column(&witness, &table, &multiplicities, &challenge);
+
+        let last = column.len() - 1;
+        let last_term = (witness[last] - challenge).inv().unwrap()
+            - multiplicities[last] * (table[last] - challenge).inv().unwrap();
+        assert_ne!(column[last] + last_term, column[0]);
+    }
+}
@@ -0,0 +1,388 @@
+//! A human readable JSON representation of [`StarkProof`], for auditing proofs and for testing
+//! against other implementations. See [`StarkProof::to_json`] for its schema.
+
+use lambdaworks_crypto::merkle_tree::proof::Proof;
+use lambdaworks_math::{
+    field::{
+        element::FieldElement,
+        traits::{IsField, IsSubFieldOf},
+    },
+    traits::ByteConversion,
+};
+
+use crate::{config::Commitment, fri::fri_decommit::FriDecommitment, table::Table};
+
+use super::stark::{DeepPolynomialOpening, PolynomialOpenings, StarkProof};
+
+/// Errors that can happen when reading a proof from its JSON representation.
+#[derive(Debug)]
+pub enum ProofJsonError {
+    /// The string is not JSON, or it does not follow the schema of a proof.
+    Json(serde_json::Error),
+    /// The string is not a `0x` prefixed hexadecimal string of an even number of digits.
+    InvalidHex(String),
+    /// The hexadecimal string is not the encoding of a field element.
+    InvalidFieldElement(String),
+    /// The hexadecimal string does not hold as many bytes as a commitment.
+    InvalidCommitment(String),
+    /// The rows of the out of domain evaluations do not have the same number of elements.
+    RaggedTable,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct JsonPolynomialOpenings {
+    proof: Vec<String>,
+    proof_sym: Vec<String>,
+    evaluations: Vec<String>,
+    evaluations_sym: Vec<String>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct JsonDeepPolynomialOpening {
+    composition_poly: JsonPolynomialOpenings,
+    main_trace_polys: JsonPolynomialOpenings,
+    aux_trace_polys: Option<JsonPolynomialOpenings>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct JsonFriDecommitment {
+    layers_auth_paths: Vec<Vec<String>>,
+    layers_evaluations_sym: Vec<String>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct JsonStarkProof {
+    proof_version: u16,
+    parameter_hash: String,
+    trace_length: usize,
+    lde_trace_main_merkle_root: String,
+    lde_trace_aux_merkle_root: Option<String>,
+    trace_ood_evaluations: Vec<Vec<String>>,
+    composition_poly_root: String,
+    composition_poly_parts_ood_evaluation: Vec<String>,
+    fri_layers_merkle_roots: Vec<String>,
+    fri_final_poly: Vec<String>,
+    query_list: Vec<JsonFriDecommitment>,
+    deep_poly_openings: Vec<JsonDeepPolynomialOpening>,
+    nonce: Option<u64>,
+}
+
+fn bytes_to_hex(bytes: &[u8]) -> String {
+    let digits: String = bytes.iter().map(|byte| format!("{:02x}", byte)).collect();
+    format!("0x{}", digits)
+}
+
+fn hex_to_bytes(hex: &str) -> Result<Vec<u8>, ProofJsonError> {
+    let invalid = || ProofJsonError::InvalidHex(hex.to_string());
+    let digits = hex.strip_prefix("0x").ok_or_else(invalid)?;
+    if digits.len() % 2 != 0 || !digits.is_ascii() {
+        return Err(invalid());
+    }
+    (0..digits.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&digits[i..i + 2], 16).map_err(|_| invalid()))
+        .collect()
+}
+
+fn element_to_hex<F: IsField>(element: &FieldElement<F>) -> String
+where
+    FieldElement<F>: ByteConversion,
+{
+    bytes_to_hex(&element.to_bytes_be())
+}
+
+fn elements_to_hex<F: IsField>(elements: &[FieldElement<F>]) -> Vec<String>
+where
+    FieldElement<F>: ByteConversion,
+{
+    elements.iter().map(element_to_hex).collect()
+}
+
+fn hex_to_element<F: IsField>(hex: &str) -> Result<FieldElement<F>, ProofJsonError>
+where
+    FieldElement<F>: ByteConversion,
+{
+    FieldElement::<F>::from_bytes_be(&hex_to_bytes(hex)?)
+        .map_err(|_| ProofJsonError::InvalidFieldElement(hex.to_string()))
+}
+
+fn hex_to_elements<F: IsField>(hexes: &[String]) -> Result<Vec<FieldElement<F>>, ProofJsonError>
+where
+    FieldElement<F>: ByteConversion,
+{
+    hexes.iter().map(|hex| hex_to_element(hex)).collect()
+}
+
+fn hex_to_commitment(hex: &str) -> Result<Commitment, ProofJsonError> {
+    hex_to_bytes(hex)?
+        .try_into()
+        .map_err(|_| ProofJsonError::InvalidCommitment(hex.to_string()))
+}
+
+fn commitments_to_hex(commitments: &[Commitment]) -> Vec<String> {
+    commitments.iter().map(|c| bytes_to_hex(c)).collect()
+}
+
+fn hex_to_commitments(hexes: &[String]) -> Result<Vec<Commitment>, ProofJsonError> {
+    hexes.iter().map(|hex| hex_to_commitment(hex)).collect()
+}
+
+impl JsonPolynomialOpenings {
+    fn new<F: IsField>(openings: &PolynomialOpenings<F>) -> Self
+    where
+        FieldElement<F>: ByteConversion,
+    {
+        Self {
+            proof: commitments_to_hex(&openings.proof.merkle_path),
+            proof_sym: commitments_to_hex(&openings.proof_sym.merkle_path),
+            evaluations: elements_to_hex(&openings.evaluations),
+            evaluations_sym: elements_to_hex(&openings.evaluations_sym),
+        }
+    }
+
+    fn parse<F: IsField>(&self) -> Result<PolynomialOpenings<F>, ProofJsonError>
+    where
+        FieldElement<F>: ByteConversion,
+    {
+        Ok(PolynomialOpenings {
+            proof: Proof {
+                merkle_path: hex_to_commitments(&self.proof)?,
+            },
+            proof_sym: Proof {
+                merkle_path: hex_to_commitments(&self.proof_sym)?,
+            },
+            evaluations: hex_to_elements(&self.evaluations)?,
+            evaluations_sym: hex_to_elements(&self.evaluations_sym)?,
+        })
+    }
+}
+
+impl<F: IsSubFieldOf<E>, E: IsField> StarkProof<F, E>
+where
+    FieldElement<F>: ByteConversion,
+    FieldElement<E>: ByteConversion,
+{
+    /// Returns the JSON representation of the proof. Its keys are the names of the fields of
+    /// `StarkProof` and of the structures it holds, and:
+    /// - field elements are `0x` prefixed big endian hexadecimal strings of their canonical
+    ///   representative, as given by `ByteConversion::to_bytes_be`,
+    /// - commitments and `parameter_hash` are `0x` prefixed hexadecimal strings of their bytes,
+    /// - Merkle authentication paths are lists of commitments, from the leaf to the root,
+    /// - `trace_ood_evaluations` is the list of the rows of the table,
+    /// - the other integers are JSON numbers, and missing optional values are `null`.
+    ///
+    /// [`StarkProof::from_json`] reads it back into an identical proof.
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        let deep_poly_openings = self
+            .deep_poly_openings
+            .iter()
+            .map(|opening| JsonDeepPolynomialOpening {
+                composition_poly: JsonPolynomialOpenings::new(&opening.composition_poly),
+                main_trace_polys: JsonPolynomialOpenings::new(&opening.main_trace_polys),
+                aux_trace_polys: opening
+                    .aux_trace_polys
+                    .as_ref()
+                    .map(JsonPolynomialOpenings::new),
+            })
+            .collect();
+        let query_list = self
+            .query_list
+            .iter()
+            .map(|decommitment| JsonFriDecommitment {
+                layers_auth_paths: decommitment
+                    .layers_auth_paths
+                    .iter()
+                    .map(|proof| commitments_to_hex(&proof.merkle_path))
+                    .collect(),
+                layers_evaluations_sym: elements_to_hex(&decommitment.layers_evaluations_sym),
+            })
+            .collect();
+
+        serde_json::to_string_pretty(&JsonStarkProof {
+            proof_version: self.proof_version,
+            parameter_hash: bytes_to_hex(&self.parameter_hash),
+            trace_length: self.trace_length,
+            lde_trace_main_merkle_root: bytes_to_hex(&self.lde_trace_main_merkle_root),
+            lde_trace_aux_merkle_root: self
+                .lde_trace_aux_merkle_root
+                .as_ref()
+                .map(|root| bytes_to_hex(root)),
+            trace_ood_evaluations: (0..self.trace_ood_evaluations.height)
+                .map(|row| elements_to_hex(self.trace_ood_evaluations.get_row(row)))
+                .collect(),
+            composition_poly_root: bytes_to_hex(&self.composition_poly_root),
+            composition_poly_parts_ood_evaluation: elements_to_hex(
+                &self.composition_poly_parts_ood_evaluation,
+            ),
+            fri_layers_merkle_roots: commitments_to_hex(&self.fri_layers_merkle_roots),
+            fri_final_poly: elements_to_hex(&self.fri_final_poly),
+            query_list,
+            deep_poly_openings,
+            nonce: self.nonce,
+        })
+    }
+
+    /// Reads a proof from its JSON representation, as returned by [`StarkProof::to_json`].
+    pub fn from_json(json: &str) -> Result<Self, ProofJsonError> {
+        let proof: JsonStarkProof = serde_json::from_str(json).map_err(ProofJsonError::Json)?;
+
+        let width = proof.trace_ood_evaluations.first().map_or(0, Vec::len);
+        if proof
+            .trace_ood_evaluations
+            .iter()
+            .any(|row| row.len() != width)
+        {
+            return Err(ProofJsonError::RaggedTable);
+        }
+        let trace_ood_evaluations = proof
+            .trace_ood_evaluations
+            .iter()
+            .map(|row| hex_to_elements(row))
+            .collect::<Result<Vec<_>, _>>()?
+            .concat();
+
+        let query_list = proof
+            .query_list
+            .iter()
+            .map(|decommitment| {
+                Ok(FriDecommitment {
+                    layers_auth_paths: decommitment
+                        .layers_auth_paths
+                        .iter()
+                        .map(|path| {
+                            Ok(Proof {
+                                merkle_path: hex_to_commitments(path)?,
+                            })
+                        })
+                        .collect::<Result<_, ProofJsonError>>()?,
+                    layers_evaluations_sym: hex_to_elements(&decommitment.layers_evaluations_sym)?,
+                })
+            })
+            .collect::<Result<_, ProofJsonError>>()?;
+        let deep_poly_openings = proof
+            .deep_poly_openings
+            .iter()
+            .map(|opening| {
+                Ok(DeepPolynomialOpening {
+                    composition_poly: opening.composition_poly.parse()?,
+                    main_trace_polys: opening.main_trace_polys.parse()?,
+                    aux_trace_polys: opening
+                        .aux_trace_polys
+                        .as_ref()
+                        .map(JsonPolynomialOpenings::parse)
+                        .transpose()?,
+                })
+            })
+            .collect::<Result<_, ProofJsonError>>()?;
+
+        Ok(StarkProof {
+            proof_version: proof.proof_version,
+            parameter_hash: hex_to_commitment(&proof.parameter_hash)?,
+            trace_length: proof.trace_length,
+            lde_trace_main_merkle_root: hex_to_commitment(&proof.lde_trace_main_merkle_root)?,
+            lde_trace_aux_merkle_root: proof
+                .lde_trace_aux_merkle_root
+                .as_deref()
+                .map(hex_to_commitment)
+                .transpose()?,
+            trace_ood_evaluations: Table::new(trace_ood_evaluations, width),
+            composition_poly_root: hex_to_commitment(&proof.composition_poly_root)?,
+            composition_poly_parts_ood_evaluation: hex_to_elements(
+                &proof.composition_poly_parts_ood_evaluation,
+            )?,
+            fri_layers_merkle_roots: hex_to_commitments(&proof.fri_layers_merkle_roots)?,
+            fri_final_poly: hex_to_elements(&proof.fri_final_poly)?,
+            query_list,
+            deep_poly_openings,
+            nonce: proof.nonce,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        examples::simple_fibonacci::{self, FibonacciAIR, FibonacciPublicInputs},
+        proof::options::ProofOptions,
+        prover::{IsStarkProver, Prover},
+        transcript::StoneProverTranscript,
+        verifier::{IsStarkVerifier, Verifier},
+        Felt252,
+    };
+    use lambdaworks_math::field::fields::fft_friendly::stark_252_prime_field::Stark252PrimeField;
+
+    type FibAIR = FibonacciAIR<Stark252PrimeField>;
+
+    fn fibonacci_proof() -> (
+        StarkProof<Stark252PrimeField, Stark252PrimeField>,
+        FibonacciPublicInputs<Stark252PrimeField>,
+    ) {
+        let trace = simple_fibonacci::fibonacci_trace([Felt252::one(), Felt252::one()], 8);
+        let pub_inputs = FibonacciPublicInputs {
+            a0: Felt252::one(),
+            a1: Felt252::one(),
+        };
+        let proof = Prover::<FibAIR>::prove(
+            &trace,
+            &pub_inputs,
+            &ProofOptions::default_test_options(),
+            StoneProverTranscript::new(&[]),
+        )
+        .unwrap();
+        (proof, pub_inputs)
+    }
+
+    #[test]
+    fn json_proof_round_trips_to_an_identical_proof_that_verifies() {
+        let (proof, pub_inputs) = fibonacci_proof();
+
+        let json = proof.to_json().unwrap();
+        let parsed =
+            StarkProof::<Stark252PrimeField, Stark252PrimeField>::from_json(&json).unwrap();
+
+        assert_eq!(parsed, proof);
+        assert!(Verifier::<FibAIR>::verify(
+            &parsed,
+            &pub_inputs,
+            &ProofOptions::default_test_options(),
+            StoneProverTranscript::new(&[]),
+        ));
+    }
+
+    #[test]
+    fn json_proof_holds_field_elements_as_hex_strings() {
+        let (proof, _) = fibonacci_proof();
+
+        let json: serde_json::Value = serde_json::from_str(&proof.to_json().unwrap()).unwrap();
+
+        assert_eq!(json["trace_length"], 8);
+        // The representative is written with all its bytes, leading zeros included.
+        let first_evaluation = json["trace_ood_evaluations"][0][0].as_str().unwrap();
+        assert!(first_evaluation.starts_with("0x"));
+        assert_eq!(first_evaluation.len(), 2 + 2 * 32);
+        assert_eq!(
+            hex_to_element::<Stark252PrimeField>(first_evaluation).unwrap(),
+            proof.trace_ood_evaluations.get_row(0)[0]
+        );
+    }
+
+    #[test]
+    fn malformed_hex_is_rejected() {
+        let (proof, _) = fibonacci_proof();
+        let json = proof.to_json().unwrap();
+        let root = bytes_to_hex(&proof.composition_poly_root);
+
+        let without_prefix = json.replace(&root, &root[2..]);
+        assert!(matches!(
+            StarkProof::<Stark252PrimeField, Stark252PrimeField>::from_json(&without_prefix),
+            Err(ProofJsonError::InvalidHex(_))
+        ));
+
+        let too_short = json.replace(&root, &root[..root.len() - 2]);
+        assert!(matches!(
+            StarkProof::<Stark252PrimeField, Stark252PrimeField>::from_json(&too_short),
+            Err(ProofJsonError::InvalidCommitment(_))
+        ));
+    }
+}
@@ -1,3 +1,5 @@
 pub mod errors;
+#[cfg(feature = "std")]
+pub mod json;
 pub mod options;
 pub mod stark;
@@ -1,9 +1,72 @@
 use super::errors::InsecureOptionError;
-use lambdaworks_math::field::traits::IsPrimeField;
+use crate::config::Commitment;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use lambdaworks_crypto::fiat_shamir::is_transcript::IsTranscript;
+use lambdaworks_math::field::{
+    element::FieldElement,
+    traits::{IsField, IsPrimeField},
+};
 
 #[cfg(feature = "wasm")]
 use wasm_bindgen::prelude::wasm_bindgen;
 
+/// A rough estimate of the memory used to prove a trace, in bytes, as returned by
+/// `ProofOptions::estimate_memory`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MemoryEstimate {
+    /// The LDE of the trace, the trace polynomials and the LDE domain.
+    pub lde_trace: usize,
+    /// The nodes of the Merkle trees of the trace, the composition polynomial and the FRI
+    /// layers.
+    pub merkle_trees: usize,
+    /// The evaluations of the constraints and of the composition polynomial over the LDE
+    /// domain, its coefficients, and the leaves of its Merkle tree.
+    pub composition: usize,
+    /// The evaluations of the FRI layers.
+    pub fri_layers: usize,
+}
+
+impl MemoryEstimate {
+    pub fn total(&self) -> usize {
+        self.lde_trace + self.merkle_trees + self.composition + self.fri_layers
+    }
+}
+
+/// How the coefficients of a random linear combination are drawn from the transcript: the
+/// ones of the constraints in the composition polynomial and the ones of the terms of the
+/// DEEP composition polynomial, see `ProofOptions`.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum CombinationMode {
+    /// A single challenge 𝛾 is sampled, and its powers 1, 𝛾, 𝛾², ... are the coefficients.
+    /// This is what the Stone prover does.
+    PowersOfGamma,
+    /// Every coefficient is sampled from the transcript independently.
+    PerTerm,
+}
+
+impl CombinationMode {
+    /// Samples the `number_of_terms` coefficients of a linear combination from `transcript`.
+    pub fn sample_coefficients<E: IsField>(
+        &self,
+        number_of_terms: usize,
+        transcript: &mut impl IsTranscript<E>,
+    ) -> Vec<FieldElement<E>> {
+        match self {
+            CombinationMode::PowersOfGamma => {
+                let gamma = transcript.sample_field_element();
+                core::iter::successors(Some(FieldElement::one()), |x| Some(x * &gamma))
+                    .take(number_of_terms)
+                    .collect()
+            }
+            CombinationMode::PerTerm => (0..number_of_terms)
+                .map(|_| transcript.sample_field_element())
+                .collect(),
+        }
+    }
+}
+
 pub enum SecurityLevel {
     Conjecturable80Bits,
     Conjecturable100Bits,
@@ -15,17 +78,47 @@ pub enum SecurityLevel {
 
 /// The options for the proof
 ///
-/// - `blowup_factor`: the blowup factor for the trace
+/// - `blowup_factor`: the blowup factor for the trace. It must be at least the number of parts
+///   of the composition polynomial, the maximum degree of the transition constraints rounded up
+///   to a power of two unless the AIR gives a tighter degree bound, or its evaluations over the
+///   LDE domain alias and the prover fails with `ProvingError::BlowupTooSmall`
 /// - `fri_number_of_queries`: the number of queries for the FRI layer
 /// - `coset_offset`: the offset for the coset
 /// - `grinding_factor`: the number of leading zeros that we want for the Hash(hash || nonce)
+/// - `zero_knowledge`: whether the prover masks the trace so that the proof reveals nothing
+///   about it, see the `zk` module
+/// - `composition_blowup_factor`: the blowup factor of the domain over which the prover evaluates
+///   the constraints to interpolate the composition polynomial, `blowup_factor` if not set. It
+///   must be a power of two, at most `blowup_factor` and at least the number of parts of the
+///   composition polynomial. It does not change the proof, only the cost of computing it
+/// - `fri_final_poly_degree`: the maximum degree of the last polynomial of FRI. FRI stops
+///   folding once the polynomial has at most the largest power of two not exceeding
+///   `fri_final_poly_degree + 1` coefficients, and sends them instead of a single value. A
+///   higher degree gives fewer layers, so smaller proofs, at the cost of more coefficients
+/// - `deep_combination`: how the coefficients of the DEEP composition polynomial are sampled,
+///   see `CombinationMode`
+/// - `constraint_combination`: how the coefficients of the transition and boundary constraints
+///   in the composition polynomial are sampled, see `CombinationMode`. With
+///   `CombinationMode::PowersOfGamma` a single challenge is drawn for all of them, whatever
+///   the number of constraints
+/// - `distinct_queries`: whether the FRI query indexes are sampled without repetition. With
+///   independent indexes, a repeated one checks nothing more, so the soundness error of
+///   `fri_number_of_queries` queries is the one of their distinct indexes, which can be fewer.
+///   With distinct indexes every query counts, and the verifier rejects proofs with repeated
+///   ones. The number of queries must then be at most half the size of the LDE domain
 #[cfg_attr(feature = "wasm", wasm_bindgen)]
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct ProofOptions {
     pub blowup_factor: u8,
     pub fri_number_of_queries: usize,
     pub coset_offset: u64,
     pub grinding_factor: u8,
+    pub zero_knowledge: bool,
+    pub composition_blowup_factor: Option<u8>,
+    pub fri_final_poly_degree: usize,
+    pub deep_combination: CombinationMode,
+    pub constraint_combination: CombinationMode,
+    pub distinct_queries: bool,
 }
 
 impl ProofOptions {
@@ -42,40 +135,109 @@ impl ProofOptions {
                 fri_number_of_queries: 31,
                 coset_offset,
                 grinding_factor: 20,
+                zero_knowledge: false,
+                composition_blowup_factor: None,
+                fri_final_poly_degree: 0,
+                deep_combination: CombinationMode::PowersOfGamma,
+                constraint_combination: CombinationMode::PowersOfGamma,
+                distinct_queries: false,
             },
             SecurityLevel::Conjecturable100Bits => ProofOptions {
                 blowup_factor: 4,
                 fri_number_of_queries: 41,
                 coset_offset,
                 grinding_factor: 20,
+                zero_knowledge: false,
+                composition_blowup_factor: None,
+                fri_final_poly_degree: 0,
+                deep_combination: CombinationMode::PowersOfGamma,
+                constraint_combination: CombinationMode::PowersOfGamma,
+                distinct_queries: false,
             },
             SecurityLevel::Conjecturable128Bits => ProofOptions {
                 blowup_factor: 4,
                 fri_number_of_queries: 55,
                 coset_offset,
                 grinding_factor: 20,
+                zero_knowledge: false,
+                composition_blowup_factor: None,
+                fri_final_poly_degree: 0,
+                deep_combination: CombinationMode::PowersOfGamma,
+                constraint_combination: CombinationMode::PowersOfGamma,
+                distinct_queries: false,
             },
             SecurityLevel::Provable80Bits => ProofOptions {
                 blowup_factor: 4,
                 fri_number_of_queries: 80,
                 coset_offset,
                 grinding_factor: 20,
+                zero_knowledge: false,
+                composition_blowup_factor: None,
+                fri_final_poly_degree: 0,
+                deep_combination: CombinationMode::PowersOfGamma,
+                constraint_combination: CombinationMode::PowersOfGamma,
+                distinct_queries: false,
             },
             SecurityLevel::Provable100Bits => ProofOptions {
                 blowup_factor: 4,
                 fri_number_of_queries: 104,
                 coset_offset,
                 grinding_factor: 20,
+                zero_knowledge: false,
+                composition_blowup_factor: None,
+                fri_final_poly_degree: 0,
+                deep_combination: CombinationMode::PowersOfGamma,
+                constraint_combination: CombinationMode::PowersOfGamma,
+                distinct_queries: false,
             },
             SecurityLevel::Provable128Bits => ProofOptions {
                 blowup_factor: 4,
                 fri_number_of_queries: 140,
                 coset_offset,
                 grinding_factor: 20,
+                zero_knowledge: false,
+                composition_blowup_factor: None,
+                fri_final_poly_degree: 0,
+                deep_combination: CombinationMode::PowersOfGamma,
+                constraint_combination: CombinationMode::PowersOfGamma,
+                distinct_queries: false,
             },
         }
     }
 
+    /// Returns proof options reaching `security_bits` bits of conjectured security for a trace
+    /// of length `trace_length`, with the minimum number of FRI queries.
+    ///
+    /// The options satisfy the bound checked by `new_with_checked_security`, based on the
+    /// conjectured soundness of FRI,
+    ///
+    ///   security_bits < grinding_factor + log2(blowup_factor) * fri_number_of_queries - 1
+    ///
+    /// A blowup factor of 8 is used for traces of up to 2^16 rows, and of 4 for larger traces,
+    /// to limit the size of the LDE. The grinding factor is 20 bits, or `security_bits` if lower.
+    /// The size of the field is not checked, see `new_with_checked_security` for that.
+    pub fn for_security_level(security_bits: u32, trace_length: usize) -> Self {
+        let blowup_factor: u8 = if trace_length <= 1 << 16 { 8 } else { 4 };
+        let grinding_factor = security_bits.min(20);
+
+        let num_bits_blowup_factor = blowup_factor.trailing_zeros();
+        let fri_number_of_queries =
+            ((security_bits + 1 - grinding_factor) / num_bits_blowup_factor + 1) as usize;
+
+        ProofOptions {
+            blowup_factor,
+            fri_number_of_queries,
+            coset_offset: 3,
+            grinding_factor: grinding_factor as u8,
+            zero_knowledge: false,
+            composition_blowup_factor: None,
+            fri_final_poly_degree: 0,
+            deep_combination: CombinationMode::PowersOfGamma,
+            constraint_combination: CombinationMode::PowersOfGamma,
+            distinct_queries: false,
+        }
+    }
+
     /// Checks security of proof options given 128 bits of security
     pub fn new_with_checked_security<F: IsPrimeField>(
         blowup_factor: u8,
@@ -99,6 +261,12 @@ impl ProofOptions {
             fri_number_of_queries,
             coset_offset,
             grinding_factor,
+            zero_knowledge: false,
+            composition_blowup_factor: None,
+            fri_final_poly_degree: 0,
+            deep_combination: CombinationMode::PowersOfGamma,
+            constraint_combination: CombinationMode::PowersOfGamma,
+            distinct_queries: false,
         })
     }
 
@@ -127,6 +295,12 @@ impl ProofOptions {
             fri_number_of_queries,
             coset_offset,
             grinding_factor,
+            zero_knowledge: false,
+            composition_blowup_factor: None,
+            fri_final_poly_degree: 0,
+            deep_combination: CombinationMode::PowersOfGamma,
+            constraint_combination: CombinationMode::PowersOfGamma,
+            distinct_queries: false,
         })
     }
 
@@ -150,19 +324,115 @@ impl ProofOptions {
             fri_number_of_queries: 3,
             coset_offset: 3,
             grinding_factor: 1,
+            zero_knowledge: false,
+            composition_blowup_factor: None,
+            fri_final_poly_degree: 0,
+            deep_combination: CombinationMode::PowersOfGamma,
+            constraint_combination: CombinationMode::PowersOfGamma,
+            distinct_queries: false,
         }
     }
+
+    /// Sets the blowup factor of the trace, keeping the other options.
+    pub fn with_blowup_factor(mut self, blowup_factor: u8) -> Self {
+        self.blowup_factor = blowup_factor;
+        self
+    }
+
+    /// Sets the number of FRI queries, keeping the other options.
+    pub fn with_fri_number_of_queries(mut self, fri_number_of_queries: usize) -> Self {
+        self.fri_number_of_queries = fri_number_of_queries;
+        self
+    }
+
+    /// Estimates the memory needed to prove a trace of `trace_length` rows and `trace_columns`
+    /// main columns over the field `F` with these options, before running the prover.
+    ///
+    /// The model only counts the vectors whose size grows with the LDE domain, of
+    /// `trace_length * blowup_factor` elements, and assumes that:
+    /// - the trace has no auxiliary columns,
+    /// - the composition polynomial has a single part, as for AIRs whose composition
+    ///   polynomial degree bound is the trace length. Every additional part needs about one
+    ///   more LDE domain worth of field elements,
+    /// - every Merkle tree node is a `Commitment`, and a tree over `n` leaves has `2 * n` nodes,
+    /// - the FRI layers keep their evaluations, as `fri::commit_phase` does.
+    ///
+    /// The leaves of the composition polynomial tree, which pair its evaluations at symmetric
+    /// points, are counted as they are alive when the prover reaches its peak. The other
+    /// temporary copies made while committing, the trace itself and the checks of debug
+    /// builds, which validate the trace and the degrees of the constraints, are not counted.
+    pub fn estimate_memory<F: IsField>(
+        &self,
+        trace_length: usize,
+        trace_columns: usize,
+    ) -> MemoryEstimate {
+        let element_size = core::mem::size_of::<FieldElement<F>>();
+        let node_size = core::mem::size_of::<Commitment>();
+        let lde_length = trace_length * self.blowup_factor as usize;
+
+        // The LDE of the columns and the domain, and the trace polynomials and trace roots of
+        // unity.
+        let lde_trace = (trace_columns + 1) * (lde_length + trace_length) * element_size;
+
+        // The trace tree has a leaf per row of the LDE, while the composition polynomial tree
+        // and the FRI layer trees have a leaf per pair of symmetric evaluations. The FRI layers
+        // halve their domain, so their trees add up to the one of the first layer.
+        let trace_tree = 2 * lde_length;
+        let composition_tree = lde_length;
+        let fri_trees = lde_length;
+        let merkle_trees = (trace_tree + composition_tree + fri_trees) * node_size;
+
+        // The constraint evaluations, the evaluations of the composition polynomial and its
+        // coefficients, and the leaves of its tree, a vector of two evaluations per pair of
+        // symmetric points.
+        let leaf_size = core::mem::size_of::<Vec<FieldElement<F>>>() + 2 * element_size;
+        let composition =
+            (2 * lde_length + trace_length) * element_size + lde_length / 2 * leaf_size;
+
+        // The first layer is evaluated over half of the LDE domain, and every next layer over
+        // half of the domain of the previous one.
+        let fri_layers = lde_length * element_size;
+
+        MemoryEstimate {
+            lde_trace,
+            merkle_trees,
+            composition,
+            fri_layers,
+        }
+    }
+
+    /// Returns a copy of these options for every combination of a blowup factor in
+    /// `blowup_factors` and a number of FRI queries in `query_counts`, in that order, to
+    /// compare the cost of proving with each of them. The security of the combinations is
+    /// not checked.
+    pub fn sweep<'a>(
+        &'a self,
+        blowup_factors: &'a [u8],
+        query_counts: &'a [usize],
+    ) -> impl Iterator<Item = Self> + 'a {
+        blowup_factors.iter().flat_map(move |&blowup_factor| {
+            query_counts.iter().map(move |&fri_number_of_queries| {
+                self.clone()
+                    .with_blowup_factor(blowup_factor)
+                    .with_fri_number_of_queries(fri_number_of_queries)
+            })
+        })
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use lambdaworks_math::field::fields::{
-        fft_friendly::stark_252_prime_field::Stark252PrimeField, u64_prime_field::F17,
+    use lambdaworks_crypto::fiat_shamir::{
+        default_transcript::DefaultTranscript, is_transcript::IsTranscript,
+    };
+    use lambdaworks_math::field::{
+        element::FieldElement,
+        fields::{fft_friendly::stark_252_prime_field::Stark252PrimeField, u64_prime_field::F17},
     };
 
     use crate::proof::{errors::InsecureOptionError, options::SecurityLevel};
 
-    use super::ProofOptions;
+    use super::{CombinationMode, ProofOptions};
 
     #[test]
     fn u64_prime_field_is_not_large_enough_to_be_secure() {
@@ -171,6 +441,7 @@ mod tests {
             fri_number_of_queries,
             coset_offset,
             grinding_factor,
+            ..
         } = ProofOptions::new_secure(SecurityLevel::Conjecturable128Bits, 1);
 
         let u64_options = ProofOptions::new_with_checked_security::<F17>(
@@ -191,6 +462,7 @@ mod tests {
             fri_number_of_queries,
             coset_offset,
             grinding_factor,
+            ..
         } = ProofOptions::new_secure(SecurityLevel::Conjecturable128Bits, 1);
 
         let secure_options = ProofOptions::new_with_checked_security::<Stark252PrimeField>(
@@ -211,6 +483,7 @@ mod tests {
             fri_number_of_queries,
             coset_offset,
             grinding_factor,
+            ..
         } = ProofOptions::new_secure(SecurityLevel::Conjecturable128Bits, 1);
 
         let insecure_options = ProofOptions::new_with_checked_security::<Stark252PrimeField>(
@@ -234,6 +507,7 @@ mod tests {
             fri_number_of_queries,
             coset_offset,
             grinding_factor,
+            ..
         } = ProofOptions::new_secure(SecurityLevel::Conjecturable100Bits, 1);
 
         let secure_options = ProofOptions::new_with_checked_security::<Stark252PrimeField>(
@@ -254,6 +528,7 @@ mod tests {
             fri_number_of_queries,
             coset_offset,
             grinding_factor,
+            ..
         } = ProofOptions::new_secure(SecurityLevel::Conjecturable80Bits, 1);
 
         let secure_options = ProofOptions::new_with_checked_security::<Stark252PrimeField>(
@@ -266,4 +541,161 @@ mod tests {
 
         assert!(secure_options.is_ok());
     }
+
+    #[test]
+    fn options_for_security_level_satisfy_the_soundness_bound() {
+        for security_bits in [80, 128] {
+            for trace_length in [1 << 4, 1 << 16, 1 << 20] {
+                let ProofOptions {
+                    blowup_factor,
+                    fri_number_of_queries,
+                    coset_offset,
+                    grinding_factor,
+                    ..
+                } = ProofOptions::for_security_level(security_bits, trace_length);
+
+                let secure_options = ProofOptions::new_with_checked_security::<Stark252PrimeField>(
+                    blowup_factor,
+                    fri_number_of_queries,
+                    coset_offset,
+                    grinding_factor,
+                    security_bits as u8,
+                );
+                assert!(secure_options.is_ok());
+
+                // The number of queries is the minimum one reaching the security level
+                let insecure_options = ProofOptions::new_with_checked_security::<Stark252PrimeField>(
+                    blowup_factor,
+                    fri_number_of_queries - 1,
+                    coset_offset,
+                    grinding_factor,
+                    security_bits as u8,
+                );
+                assert!(matches!(
+                    insecure_options,
+                    Err(InsecureOptionError::LowSecurityBits)
+                ));
+            }
+        }
+    }
+
+    #[test]
+    fn options_for_128_bits_match_secure_options_for_large_traces() {
+        let options = ProofOptions::for_security_level(128, 1 << 20);
+        let secure_options = ProofOptions::new_secure(SecurityLevel::Conjecturable128Bits, 3);
+
+        assert_eq!(options.blowup_factor, secure_options.blowup_factor);
+        assert_eq!(
+            options.fri_number_of_queries,
+            secure_options.fri_number_of_queries
+        );
+        assert_eq!(options.grinding_factor, secure_options.grinding_factor);
+    }
+
+    #[test]
+    fn swept_options_match_the_options_built_one_by_one() {
+        let options = ProofOptions::default_test_options();
+        let swept: Vec<_> = options.sweep(&[2, 8], &[10, 20, 30]).collect();
+
+        let mut expected = Vec::new();
+        for blowup_factor in [2, 8] {
+            for fri_number_of_queries in [10, 20, 30] {
+                expected.push(ProofOptions {
+                    blowup_factor,
+                    fri_number_of_queries,
+                    ..ProofOptions::default_test_options()
+                });
+            }
+        }
+        assert_eq!(swept, expected);
+        assert_eq!(options, ProofOptions::default_test_options());
+    }
+
+    #[test]
+    fn builder_methods_leave_the_original_options_unchanged() {
+        let options = ProofOptions::default_test_options();
+        let modified = options
+            .clone()
+            .with_blowup_factor(16)
+            .with_fri_number_of_queries(7);
+
+        assert_eq!(modified.blowup_factor, 16);
+        assert_eq!(modified.fri_number_of_queries, 7);
+        assert_eq!(modified.coset_offset, options.coset_offset);
+        assert_eq!(options, ProofOptions::default_test_options());
+    }
+
+    #[test]
+    fn memory_estimate_grows_linearly_with_the_trace_length() {
+        let options = ProofOptions::default_test_options();
+        let estimate = options.estimate_memory::<Stark252PrimeField>(1 << 10, 3);
+        let double_estimate = options.estimate_memory::<Stark252PrimeField>(1 << 11, 3);
+
+        assert_eq!(double_estimate.total(), 2 * estimate.total());
+        assert!(
+            options
+                .clone()
+                .with_blowup_factor(8)
+                .estimate_memory::<Stark252PrimeField>(1 << 10, 3)
+                .total()
+                > estimate.total()
+        );
+    }
+
+    /// Counts the field elements sampled from the transcript it wraps.
+    struct CountingTranscript {
+        inner: DefaultTranscript<Stark252PrimeField>,
+        draws: usize,
+    }
+
+    impl IsTranscript<Stark252PrimeField> for CountingTranscript {
+        fn append_field_element(&mut self, element: &FieldElement<Stark252PrimeField>) {
+            self.inner.append_field_element(element);
+        }
+
+        fn append_bytes(&mut self, new_bytes: &[u8]) {
+            self.inner.append_bytes(new_bytes);
+        }
+
+        fn state(&self) -> [u8; 32] {
+            self.inner.state()
+        }
+
+        fn sample_field_element(&mut self) -> FieldElement<Stark252PrimeField> {
+            self.draws += 1;
+            self.inner.sample_field_element()
+        }
+
+        fn sample_u64(&mut self, upper_bound: u64) -> u64 {
+            self.inner.sample_u64(upper_bound)
+        }
+    }
+
+    #[test]
+    fn powers_of_gamma_draw_a_single_challenge() {
+        let mut transcript = CountingTranscript {
+            inner: DefaultTranscript::new(&[]),
+            draws: 0,
+        };
+        let coefficients = CombinationMode::PowersOfGamma.sample_coefficients(10, &mut transcript);
+
+        assert_eq!(transcript.draws, 1);
+        assert_eq!(coefficients.len(), 10);
+        assert_eq!(coefficients[0], FieldElement::one());
+        for pair in coefficients.windows(2) {
+            assert_eq!(pair[1], pair[0] * coefficients[1]);
+        }
+    }
+
+    #[test]
+    fn per_term_coefficients_draw_a_challenge_each() {
+        let mut transcript = CountingTranscript {
+            inner: DefaultTranscript::new(&[]),
+            draws: 0,
+        };
+        let coefficients = CombinationMode::PerTerm.sample_coefficients(10, &mut transcript);
+
+        assert_eq!(transcript.draws, 10);
+        assert_eq!(coefficients.len(), 10);
+    }
 }
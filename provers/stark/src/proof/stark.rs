@@ -1,73 +1,555 @@
-use std::collections::{BTreeSet, HashMap, HashSet};
+use alloc::collections::{BTreeMap, BTreeSet};
+#[cfg(not(feature = "std"))]
+use alloc::{borrow::ToOwned, vec::Vec};
+use core::hash::{Hash, Hasher};
 
 use lambdaworks_crypto::merkle_tree::proof::Proof;
 use lambdaworks_math::{
     field::{
         element::FieldElement,
         fields::fft_friendly::stark_252_prime_field::Stark252PrimeField,
-        traits::{IsField, IsSubFieldOf},
+        traits::{IsFFTField, IsField, IsSubFieldOf},
     },
-    traits::AsBytes,
+    traits::{AsBytes, ByteOrder},
 };
 
 use crate::{
-    config::Commitment,
-    domain::Domain,
-    fri::fri_decommit::FriDecommitment,
+    config::{Commitment, IsStarkVectorCommitment, MerkleVectorCommitment},
+    context::AirContext,
+    frame::Frame,
+    fri::{self, fri_decommit::FriDecommitment},
     table::Table,
     traits::AIR,
     transcript::StoneProverTranscript,
-    verifier::{IsStarkVerifier, Verifier},
+    verifier::{IsStarkVerifier, VerificationError, Verifier},
+    zk,
 };
 
 use super::options::ProofOptions;
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
-pub struct PolynomialOpenings<F: IsField> {
-    pub proof: Proof<Commitment>,
-    pub proof_sym: Proof<Commitment>,
+pub struct PolynomialOpenings<F: IsField, VC: IsStarkVectorCommitment = MerkleVectorCommitment> {
+    pub proof: VC::Opening,
+    pub proof_sym: VC::Opening,
     pub evaluations: Vec<FieldElement<F>>,
     pub evaluations_sym: Vec<FieldElement<F>>,
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
-pub struct DeepPolynomialOpening<F: IsSubFieldOf<E>, E: IsField> {
-    pub composition_poly: PolynomialOpenings<E>,
-    pub main_trace_polys: PolynomialOpenings<F>,
-    pub aux_trace_polys: Option<PolynomialOpenings<E>>,
+pub struct DeepPolynomialOpening<
+    F: IsSubFieldOf<E>,
+    E: IsField,
+    VC: IsStarkVectorCommitment = MerkleVectorCommitment,
+> {
+    pub composition_poly: PolynomialOpenings<E, VC>,
+    pub main_trace_polys: PolynomialOpenings<F, VC>,
+    pub aux_trace_polys: Option<PolynomialOpenings<E, VC>>,
 }
 
-pub type DeepPolynomialOpenings<F, E> = Vec<DeepPolynomialOpening<F, E>>;
+pub type DeepPolynomialOpenings<F, E, VC = MerkleVectorCommitment> =
+    Vec<DeepPolynomialOpening<F, E, VC>>;
+
+// The comparison and hashing of the proof and its parts are implemented by hand, since deriving
+// them would require the field types themselves to implement them, which most fields do not.
+impl<F: IsField, VC: IsStarkVectorCommitment> PartialEq for PolynomialOpenings<F, VC> {
+    fn eq(&self, other: &Self) -> bool {
+        self.proof == other.proof
+            && self.proof_sym == other.proof_sym
+            && self.evaluations == other.evaluations
+            && self.evaluations_sym == other.evaluations_sym
+    }
+}
+
+impl<F: IsField, VC: IsStarkVectorCommitment> Eq for PolynomialOpenings<F, VC> {}
+
+impl<F: IsField, VC: IsStarkVectorCommitment> Hash for PolynomialOpenings<F, VC>
+where
+    FieldElement<F>: Hash,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.proof.hash(state);
+        self.proof_sym.hash(state);
+        self.evaluations.hash(state);
+        self.evaluations_sym.hash(state);
+    }
+}
+
+impl<F: IsSubFieldOf<E>, E: IsField, VC: IsStarkVectorCommitment> PartialEq
+    for DeepPolynomialOpening<F, E, VC>
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.composition_poly == other.composition_poly
+            && self.main_trace_polys == other.main_trace_polys
+            && self.aux_trace_polys == other.aux_trace_polys
+    }
+}
+
+impl<F: IsSubFieldOf<E>, E: IsField, VC: IsStarkVectorCommitment> Eq
+    for DeepPolynomialOpening<F, E, VC>
+{
+}
 
+impl<F: IsSubFieldOf<E>, E: IsField, VC: IsStarkVectorCommitment> Hash
+    for DeepPolynomialOpening<F, E, VC>
+where
+    FieldElement<F>: Hash,
+    FieldElement<E>: Hash,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.composition_poly.hash(state);
+        self.main_trace_polys.hash(state);
+        self.aux_trace_polys.hash(state);
+    }
+}
+
+/// The version of the proof format. It is embedded in every proof and it is bumped whenever the
+/// proof format or the protocol change, so that the verifier rejects proofs of other versions
+/// with [`crate::verifier::VerificationError::VersionMismatch`].
+pub const PROOF_VERSION: u16 = 1;
+
+/// A STARK proof. Besides its compact binary serialization, it has a human readable JSON
+/// representation, whose schema is described in [`StarkProof::to_json`].
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
-pub struct StarkProof<F: IsSubFieldOf<E>, E: IsField> {
+pub struct StarkProof<
+    F: IsSubFieldOf<E>,
+    E: IsField,
+    VC: IsStarkVectorCommitment = MerkleVectorCommitment,
+> {
+    // Version of the proof format, see `PROOF_VERSION`
+    pub proof_version: u16,
+    // Hash of the AIR parameters and the proof options, see `AIR::parameter_hash`
+    pub parameter_hash: [u8; 32],
     // Length of the execution trace
     pub trace_length: usize,
     // Commitments of the trace columns
     // [tⱼ]
-    pub lde_trace_main_merkle_root: Commitment,
+    pub lde_trace_main_merkle_root: VC::Commitment,
     // Commitments of auxiliary trace columns
     // [tⱼ]
-    pub lde_trace_aux_merkle_root: Option<Commitment>,
+    pub lde_trace_aux_merkle_root: Option<VC::Commitment>,
     // tⱼ(zgᵏ)
     pub trace_ood_evaluations: Table<E>,
     // Commitments to Hᵢ
-    pub composition_poly_root: Commitment,
+    pub composition_poly_root: VC::Commitment,
     // Hᵢ(z^N)
     pub composition_poly_parts_ood_evaluation: Vec<FieldElement<E>>,
     // [pₖ]
-    pub fri_layers_merkle_roots: Vec<Commitment>,
-    // pₙ
-    pub fri_last_value: FieldElement<E>,
+    pub fri_layers_merkle_roots: Vec<VC::Commitment>,
+    // pₙ, by its coefficients
+    pub fri_final_poly: Vec<FieldElement<E>>,
     // Open(pₖ(Dₖ), −𝜐ₛ^(2ᵏ))
-    pub query_list: Vec<FriDecommitment<E>>,
+    pub query_list: Vec<FriDecommitment<E, VC>>,
     // Open(H₁(D_LDE, 𝜐ᵢ), Open(H₂(D_LDE, 𝜐ᵢ), Open(tⱼ(D_LDE), 𝜐ᵢ)
     // Open(H₁(D_LDE, -𝜐ᵢ), Open(H₂(D_LDE, -𝜐ᵢ), Open(tⱼ(D_LDE), -𝜐ᵢ)
-    pub deep_poly_openings: DeepPolynomialOpenings<F, E>,
+    pub deep_poly_openings: DeepPolynomialOpenings<F, E, VC>,
     // nonce obtained from grinding
     pub nonce: Option<u64>,
 }
 
+impl<F: IsSubFieldOf<E>, E: IsField, VC: IsStarkVectorCommitment> PartialEq
+    for StarkProof<F, E, VC>
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.proof_version == other.proof_version
+            && self.parameter_hash == other.parameter_hash
+            && self.trace_length == other.trace_length
+            && self.lde_trace_main_merkle_root == other.lde_trace_main_merkle_root
+            && self.lde_trace_aux_merkle_root == other.lde_trace_aux_merkle_root
+            && self.trace_ood_evaluations == other.trace_ood_evaluations
+            && self.composition_poly_root == other.composition_poly_root
+            && self.composition_poly_parts_ood_evaluation
+                == other.composition_poly_parts_ood_evaluation
+            && self.fri_layers_merkle_roots == other.fri_layers_merkle_roots
+            && self.fri_final_poly == other.fri_final_poly
+            && self.query_list == other.query_list
+            && self.deep_poly_openings == other.deep_poly_openings
+            && self.nonce == other.nonce
+    }
+}
+
+impl<F: IsSubFieldOf<E>, E: IsField, VC: IsStarkVectorCommitment> Eq for StarkProof<F, E, VC> {}
+
+impl<F: IsSubFieldOf<E>, E: IsField, VC: IsStarkVectorCommitment> Hash for StarkProof<F, E, VC>
+where
+    FieldElement<F>: Hash,
+    FieldElement<E>: Hash,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.proof_version.hash(state);
+        self.parameter_hash.hash(state);
+        self.trace_length.hash(state);
+        self.lde_trace_main_merkle_root.hash(state);
+        self.lde_trace_aux_merkle_root.hash(state);
+        self.trace_ood_evaluations.hash(state);
+        self.composition_poly_root.hash(state);
+        self.composition_poly_parts_ood_evaluation.hash(state);
+        self.fri_layers_merkle_roots.hash(state);
+        self.fri_final_poly.hash(state);
+        self.query_list.hash(state);
+        self.deep_poly_openings.hash(state);
+        self.nonce.hash(state);
+    }
+}
+
+impl<F: IsSubFieldOf<E>, E: IsField, VC: IsStarkVectorCommitment> StarkProof<F, E, VC> {
+    /// Returns the out of domain evaluations of the trace polynomials as the frame the
+    /// verifier evaluates the transition constraints on: its `k`-th row holds the evaluations
+    /// tⱼ(zgᵏ) of the main trace polynomials followed by the auxiliary ones, and it can be
+    /// read by logical column with [`Frame::column`].
+    pub fn ood_frame<A>(&self, air: &A) -> Frame<'_, E, E>
+    where
+        A: AIR<Field = F, FieldExtension = E>,
+    {
+        let (num_main_columns, _) = air.trace_layout();
+        self.trace_ood_evaluations
+            .into_frame(num_main_columns, A::STEP_SIZE)
+    }
+
+    /// Returns the values the trace committed to by the proof takes at the cells fixed by the
+    /// boundary constraints of `air`, in the order of the constraints. The verifier checks
+    /// every boundary constraint through the boundary quotients of the composition polynomial,
+    /// so once the proof verifies for `air` these are the proven public outputs of the
+    /// computation, read from the AIR rather than from anything the prover sends alongside.
+    /// The auxiliary boundary constraints depend on `rap_challenges`, which have to be the
+    /// ones of the proof, see `IsStarkVerifier::replay_rap_challenges`; AIRs without an
+    /// auxiliary trace take none.
+    ///
+    /// Fails as `structural_check` if the proof does not have the shape `air` fixes.
+    pub fn public_outputs<A>(
+        &self,
+        air: &A,
+        rap_challenges: &[FieldElement<E>],
+    ) -> Result<Vec<FieldElement<E>>, VerificationError>
+    where
+        F: IsFFTField,
+        A: AIR<Field = F, FieldExtension = E>,
+    {
+        self.structural_check(air)?;
+        Ok(air
+            .boundary_constraints(rap_challenges)
+            .constraints
+            .into_iter()
+            .map(|constraint| constraint.value)
+            .collect())
+    }
+
+    /// Checks the lengths and shapes `air` and its proof options fix for every part of the
+    /// proof, without any field operation or hashing, so that malformed proofs are rejected
+    /// before the verifier does any expensive work on them:
+    /// - the trace length, which must be the one of `air`, and the size of the LDE domain,
+    ///   which must be a power of two that fits in a `usize` and in the two-adicity of the field
+    /// - the number of FRI layers, see `IsStarkVerifier::check_fri_layer_count`, and the length
+    ///   of the final FRI polynomial
+    /// - the out of domain frame, with a column per trace column and a row per frame row
+    /// - the commitment to the auxiliary trace, present if and only if the AIR has
+    ///   auxiliary columns
+    /// - the out of domain evaluations of the composition polynomial parts, one more in
+    ///   zero-knowledge mode for the blinding polynomial
+    /// - the query indexes, FRI decommitments and DEEP openings, one per query
+    /// - in every FRI decommitment, an evaluation and an opening per layer, each of the size
+    ///   `IsStarkVectorCommitment::expected_opening_size` gives for its layer, the length of
+    ///   the authentication path for Merkle trees
+    /// - in every DEEP opening, an evaluation per column and openings of the size of the ones
+    ///   of the trace and of the composition polynomial
+    ///
+    /// Fails with `VerificationError::InvalidProof` for an LDE domain that does not fit, and
+    /// otherwise with
+    /// `VerificationError::WrongFriLayerCount` or `VerificationError::MalformedProof`, naming
+    /// the first part found with a wrong length.
+    pub fn structural_check<A>(&self, air: &A) -> Result<(), VerificationError>
+    where
+        F: IsFFTField,
+        A: AIR<Field = F, FieldExtension = E>,
+    {
+        let options = air.options();
+        check_length("trace_length", air.trace_length(), self.trace_length)?;
+        let lde_domain_size = air
+            .trace_length()
+            .checked_mul(options.blowup_factor as usize)
+            .filter(|size| {
+                size.is_power_of_two() && u64::from(size.trailing_zeros()) <= F::TWO_ADICITY
+            })
+            .ok_or(VerificationError::InvalidProof)?;
+
+        Verifier::<A, VC>::check_fri_layer_count(air, self)?;
+        let number_of_layers = self.fri_layers_merkle_roots.len();
+
+        let (num_main_columns, num_aux_columns) = air.trace_layout();
+        let num_composition_columns =
+            zk::number_of_composition_parts(air) + usize::from(options.zero_knowledge);
+        let (_, final_poly_len) = fri::fold_schedule(
+            air.trace_length() << usize::from(options.zero_knowledge),
+            options.fri_final_poly_degree,
+        );
+        // The trace is committed with a row per point of the LDE domain, the composition
+        // polynomial with a row per pair of symmetric points, and the k-th FRI layer, over a
+        // domain 2^(k + 1) times smaller, with a row per pair too.
+        let trace_opening_size = VC::expected_opening_size(lde_domain_size);
+        let composition_opening_size = VC::expected_opening_size(lde_domain_size / 2);
+        let number_of_queries = options.fri_number_of_queries;
+
+        check_length("fri_final_poly", final_poly_len, self.fri_final_poly.len())?;
+        check_length(
+            "trace_ood_evaluations.width",
+            num_main_columns + num_aux_columns,
+            self.trace_ood_evaluations.width,
+        )?;
+        check_length(
+            "trace_ood_evaluations.height",
+            air.context().transition_offsets.len() * A::STEP_SIZE,
+            self.trace_ood_evaluations.height,
+        )?;
+        check_length(
+            "trace_ood_evaluations.data",
+            self.trace_ood_evaluations.width * self.trace_ood_evaluations.height,
+            self.trace_ood_evaluations.data.len(),
+        )?;
+        check_length(
+            "lde_trace_aux_merkle_root",
+            usize::from(num_aux_columns > 0),
+            usize::from(self.lde_trace_aux_merkle_root.is_some()),
+        )?;
+        check_length(
+            "composition_poly_parts_ood_evaluation",
+            num_composition_columns,
+            self.composition_poly_parts_ood_evaluation.len(),
+        )?;
+        check_length("query_list", number_of_queries, self.query_list.len())?;
+        check_length(
+            "deep_poly_openings",
+            number_of_queries,
+            self.deep_poly_openings.len(),
+        )?;
+
+        for decommitment in &self.query_list {
+            check_length(
+                "query_list.layers_auth_paths",
+                number_of_layers,
+                decommitment.layers_auth_paths.len(),
+            )?;
+            check_length(
+                "query_list.layers_evaluations_sym",
+                number_of_layers,
+                decommitment.layers_evaluations_sym.len(),
+            )?;
+            for (layer, auth_path) in decommitment.layers_auth_paths.iter().enumerate() {
+                check_length(
+                    "query_list.layers_auth_paths.merkle_path",
+                    VC::expected_opening_size(lde_domain_size >> (layer + 2)),
+                    VC::opening_size(auth_path),
+                )?;
+            }
+        }
+
+        for opening in &self.deep_poly_openings {
+            check_openings(
+                "deep_poly_openings.composition_poly",
+                &opening.composition_poly,
+                num_composition_columns,
+                composition_opening_size,
+            )?;
+            check_openings(
+                "deep_poly_openings.main_trace_polys",
+                &opening.main_trace_polys,
+                num_main_columns,
+                trace_opening_size,
+            )?;
+            match &opening.aux_trace_polys {
+                Some(aux_trace_polys) => check_openings(
+                    "deep_poly_openings.aux_trace_polys",
+                    aux_trace_polys,
+                    num_aux_columns,
+                    trace_opening_size,
+                )?,
+                None => check_length("deep_poly_openings.aux_trace_polys", num_aux_columns, 0)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Checks that a part of a proof has the `expected` length.
+fn check_length(field: &'static str, expected: usize, got: usize) -> Result<(), VerificationError> {
+    if expected != got {
+        return Err(VerificationError::MalformedProof {
+            field,
+            expected,
+            got,
+        });
+    }
+    Ok(())
+}
+
+/// Checks that `openings` hold `num_columns` evaluations at each of the two symmetric points,
+/// and that their openings have the `opening_size` of the vector they open.
+fn check_openings<F: IsField, VC: IsStarkVectorCommitment>(
+    field: &'static str,
+    openings: &PolynomialOpenings<F, VC>,
+    num_columns: usize,
+    opening_size: usize,
+) -> Result<(), VerificationError> {
+    check_length(field, num_columns, openings.evaluations.len())?;
+    check_length(field, num_columns, openings.evaluations_sym.len())?;
+    check_length(field, opening_size, VC::opening_size(&openings.proof))?;
+    check_length(field, opening_size, VC::opening_size(&openings.proof_sym))
+}
+
+impl<F: IsSubFieldOf<E>, E: IsField> StarkProof<F, E> {
+    /// Returns the authentication paths of every opening of the proof: the ones of the DEEP
+    /// composition polynomial openings, in the order of the queries, and then the ones of the
+    /// FRI decommitments.
+    fn merkle_proofs_mut(&mut self) -> Vec<&mut Proof<Commitment>> {
+        let mut proofs = Vec::new();
+        for opening in self.deep_poly_openings.iter_mut() {
+            proofs.push(&mut opening.composition_poly.proof);
+            proofs.push(&mut opening.composition_poly.proof_sym);
+            proofs.push(&mut opening.main_trace_polys.proof);
+            proofs.push(&mut opening.main_trace_polys.proof_sym);
+            if let Some(aux_trace_polys) = opening.aux_trace_polys.as_mut() {
+                proofs.push(&mut aux_trace_polys.proof);
+                proofs.push(&mut aux_trace_polys.proof_sym);
+            }
+        }
+        for decommitment in self.query_list.iter_mut() {
+            proofs.extend(decommitment.layers_auth_paths.iter_mut());
+        }
+        proofs
+    }
+
+    /// Compresses the proof by storing each distinct Merkle node of its authentication paths
+    /// once. The paths of queries that are close to each other share their upper nodes, so
+    /// the compressed proof is smaller once serialized. See [`CompressedStarkProof`].
+    pub fn compress(mut self) -> CompressedStarkProof<F, E> {
+        let mut node_pool = Vec::new();
+        let mut pool_indexes = BTreeMap::new();
+        let mut paths = Vec::new();
+        for proof in self.merkle_proofs_mut() {
+            let path = core::mem::take(&mut proof.merkle_path)
+                .into_iter()
+                .map(|node| {
+                    *pool_indexes.entry(node).or_insert_with(|| {
+                        node_pool.push(node);
+                        node_pool.len() - 1
+                    })
+                })
+                .collect();
+            paths.push(path);
+        }
+
+        CompressedStarkProof {
+            proof: self,
+            node_pool,
+            paths,
+        }
+    }
+}
+
+/// A [`StarkProof`] whose authentication paths are stored as indexes into a pool of the
+/// distinct Merkle nodes of the proof, built with [`StarkProof::compress`]. It has to be
+/// decompressed back with [`CompressedStarkProof::decompress`] to be verified.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct CompressedStarkProof<F: IsSubFieldOf<E>, E: IsField> {
+    // The proof, with empty authentication paths
+    pub proof: StarkProof<F, E>,
+    // The distinct nodes of the authentication paths
+    pub node_pool: Vec<Commitment>,
+    // The authentication paths, as indexes into `node_pool`, in the order of
+    // `StarkProof::merkle_proofs_mut`
+    pub paths: Vec<Vec<usize>>,
+}
+
+impl<F: IsSubFieldOf<E>, E: IsField> CompressedStarkProof<F, E> {
+    /// Restores the authentication paths of the proof. Returns `None` if the paths do not
+    /// match the openings of the proof or point outside the node pool, which happens only if
+    /// the compressed proof was tampered with.
+    pub fn decompress(self) -> Option<StarkProof<F, E>> {
+        let CompressedStarkProof {
+            mut proof,
+            node_pool,
+            paths,
+        } = self;
+
+        let mut merkle_proofs = proof.merkle_proofs_mut();
+        if merkle_proofs.len() != paths.len() {
+            return None;
+        }
+        for (merkle_proof, path) in merkle_proofs.iter_mut().zip(paths) {
+            merkle_proof.merkle_path = path
+                .into_iter()
+                .map(|index| node_pool.get(index).copied())
+                .collect::<Option<Vec<_>>>()?;
+        }
+
+        Some(proof)
+    }
+}
+
+/// A proof bundled with the public inputs and the context of the AIR that produced it, so that
+/// it can be verified from its bytes alone, given the type of the AIR. See
+/// `IsStarkVerifier::verify_self_describing`.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct SelfDescribingProof<
+    F: IsSubFieldOf<E>,
+    E: IsField,
+    PI,
+    VC: IsStarkVectorCommitment = MerkleVectorCommitment,
+> {
+    pub context: AirContext,
+    pub public_inputs: PI,
+    pub proof: StarkProof<F, E, VC>,
+    /// The order of the bytes of the field elements the prover appended to its transcript.
+    /// The verifier rejects the proof if its transcript uses another one.
+    pub byte_order: ByteOrder,
+}
+
+impl<F: IsSubFieldOf<E>, E: IsField, PI, VC: IsStarkVectorCommitment>
+    SelfDescribingProof<F, E, PI, VC>
+{
+    /// Bundles `proof` with the context of the AIR `A` built for it from `public_inputs` and
+    /// `proof_options`.
+    pub fn new<A>(
+        proof: StarkProof<F, E, VC>,
+        public_inputs: PI,
+        proof_options: &ProofOptions,
+    ) -> Self
+    where
+        A: AIR<Field = F, FieldExtension = E, PublicInputs = PI>,
+    {
+        let air = A::new(proof.trace_length, &public_inputs, proof_options);
+        Self {
+            context: air.context().clone(),
+            public_inputs,
+            proof,
+            byte_order: ByteOrder::default(),
+        }
+    }
+
+    /// Records that the prover transcript appended field elements in `byte_order`, instead of
+    /// the default big-endian order.
+    pub fn with_byte_order(mut self, byte_order: ByteOrder) -> Self {
+        self.byte_order = byte_order;
+        self
+    }
+
+    #[cfg(feature = "std")]
+    pub fn to_bytes(&self) -> Result<Vec<u8>, bincode::error::EncodeError>
+    where
+        Self: serde::Serialize,
+    {
+        bincode::serde::encode_to_vec(self, bincode::config::standard())
+    }
+
+    #[cfg(feature = "std")]
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, bincode::error::DecodeError>
+    where
+        Self: serde::de::DeserializeOwned,
+    {
+        bincode::serde::decode_from_slice(bytes, bincode::config::standard())
+            .map(|(proof, _)| proof)
+    }
+}
+
 /// Serializer compatible with Stone prover
 /// (https://github.com/starkware-libs/stone-prover/)
 pub struct StoneCompatibleSerializer;
@@ -142,7 +624,8 @@ impl StoneCompatibleSerializer {
         }
     }
 
-    /// Appends the commitments to the inner layers of FRI followed by the element of the last layer.
+    /// Appends the commitments to the inner layers of FRI followed by the coefficients of the
+    /// polynomial of the last layer.
     fn append_fri_commit_phase_commitments(
         proof: &StarkProof<Stark252PrimeField, Stark252PrimeField>,
         output: &mut Vec<u8>,
@@ -156,7 +639,9 @@ impl StoneCompatibleSerializer {
                 .collect::<Vec<_>>(),
         );
 
-        output.extend_from_slice(&proof.fri_last_value.as_bytes());
+        for coefficient in proof.fri_final_poly.iter() {
+            output.extend_from_slice(&coefficient.as_bytes());
+        }
     }
 
     /// Appends the proof of work nonce in case there is one. There could be none if the `grinding_factor`
@@ -206,7 +691,7 @@ impl StoneCompatibleSerializer {
             .zip(fri_query_indexes.iter())
             .collect();
         // Remove repeated values
-        let mut seen = HashSet::new();
+        let mut seen = BTreeSet::new();
         fri_first_layer_openings.retain(|&(_, index)| seen.insert(index));
         // Sort by increasing value of query
         fri_first_layer_openings.sort_by(|a, b| a.1.cmp(b.1));
@@ -321,8 +806,8 @@ impl StoneCompatibleSerializer {
         fri_query_indexes: &[usize],
         output: &mut Vec<u8>,
     ) {
-        let mut fri_layers_evaluations: HashMap<(u64, usize, usize), FieldElement<_>> =
-            HashMap::new();
+        let mut fri_layers_evaluations: BTreeMap<(u64, usize, usize), FieldElement<_>> =
+            BTreeMap::new();
         for (decommitment, query_index) in proof.query_list.iter().zip(fri_query_indexes.iter()) {
             let mut query_layer_index = *query_index;
             for (i, element) in decommitment.layers_evaluations_sym.iter().enumerate() {
@@ -418,7 +903,7 @@ impl StoneCompatibleSerializer {
         leaf_indexes: &[usize],
     ) -> Vec<Commitment> {
         debug_assert_eq!(leaf_indexes.len(), authentication_paths.len());
-        let mut merkle_tree: HashMap<(usize, usize), Commitment> = HashMap::new();
+        let mut merkle_tree: BTreeMap<(usize, usize), Commitment> = BTreeMap::new();
         for (index_previous_layer, path) in leaf_indexes.iter().zip(authentication_paths.iter()) {
             let mut node_index = *index_previous_layer;
             for (tree_level, node) in path.merkle_path.iter().enumerate() {
@@ -451,30 +936,233 @@ impl StoneCompatibleSerializer {
         A: AIR<Field = Stark252PrimeField, FieldExtension = Stark252PrimeField>,
         A::PublicInputs: AsBytes,
     {
-        let mut transcript = StoneProverTranscript::new(&public_inputs.as_bytes());
-        let air = A::new(proof.trace_length, public_inputs, proof_options);
-        let domain = Domain::<Stark252PrimeField>::new(&air);
-        let challenges = Verifier::step_1_replay_rounds_and_recover_challenges(
-            &air,
+        Verifier::<A>::replay_query_indexes(
             proof,
-            &domain,
-            &mut transcript,
-        );
-        challenges.iotas
+            public_inputs,
+            proof_options,
+            StoneProverTranscript::new(&public_inputs.as_bytes()),
+        )
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use lambdaworks_math::{field::element::FieldElement, traits::AsBytes};
+    use lambdaworks_math::{
+        field::{
+            element::FieldElement, fields::fft_friendly::stark_252_prime_field::Stark252PrimeField,
+        },
+        traits::AsBytes,
+    };
 
     use crate::{
-        examples::fibonacci_2_cols_shifted::{self, Fibonacci2ColsShifted},
-        proof::{options::ProofOptions, stark::StoneCompatibleSerializer},
+        domain::Domain,
+        examples::{
+            fibonacci_2_cols_shifted::{self, Fibonacci2ColsShifted},
+            fibonacci_rap::{fibonacci_rap_trace, FibonacciRAP, FibonacciRAPPublicInputs},
+            simple_fibonacci::{self, FibonacciAIR, FibonacciPublicInputs},
+        },
+        proof::{
+            options::{CombinationMode, ProofOptions},
+            stark::StoneCompatibleSerializer,
+        },
         prover::{IsStarkProver, Prover},
+        traits::AIR,
         transcript::StoneProverTranscript,
+        verifier::{IsStarkVerifier, Verifier},
+        Felt252,
     };
 
+    #[test]
+    fn compressed_proof_decompresses_to_the_same_proof_and_is_smaller() {
+        let trace = simple_fibonacci::fibonacci_trace([Felt252::from(1), Felt252::from(1)], 32);
+        let mut proof_options = ProofOptions::default_test_options();
+        proof_options.fri_number_of_queries = 30;
+        let pub_inputs = FibonacciPublicInputs {
+            a0: Felt252::one(),
+            a1: Felt252::one(),
+        };
+        let proof = Prover::<FibonacciAIR<Stark252PrimeField>>::prove(
+            &trace,
+            &pub_inputs,
+            &proof_options,
+            StoneProverTranscript::new(&[]),
+        )
+        .unwrap();
+        let proof_bytes =
+            bincode::serde::encode_to_vec(&proof, bincode::config::standard()).unwrap();
+
+        let compressed_proof = proof.compress();
+        let compressed_bytes =
+            bincode::serde::encode_to_vec(&compressed_proof, bincode::config::standard()).unwrap();
+        assert!(
+            compressed_bytes.len() < proof_bytes.len(),
+            "compressed proof is {} bytes long, the proof is {}",
+            compressed_bytes.len(),
+            proof_bytes.len()
+        );
+
+        let proof = compressed_proof.decompress().unwrap();
+        assert_eq!(
+            bincode::serde::encode_to_vec(&proof, bincode::config::standard()).unwrap(),
+            proof_bytes
+        );
+        assert!(Verifier::<FibonacciAIR<Stark252PrimeField>>::verify(
+            &proof,
+            &pub_inputs,
+            &proof_options,
+            StoneProverTranscript::new(&[]),
+        ));
+    }
+
+    #[test]
+    fn compressed_proof_with_a_missing_path_does_not_decompress() {
+        let trace = simple_fibonacci::fibonacci_trace([Felt252::from(1), Felt252::from(1)], 16);
+        let proof_options = ProofOptions::default_test_options();
+        let pub_inputs = FibonacciPublicInputs {
+            a0: Felt252::one(),
+            a1: Felt252::one(),
+        };
+        let proof = Prover::<FibonacciAIR<Stark252PrimeField>>::prove(
+            &trace,
+            &pub_inputs,
+            &proof_options,
+            StoneProverTranscript::new(&[]),
+        )
+        .unwrap();
+
+        let mut compressed_proof = proof.compress();
+        compressed_proof.paths.pop();
+        assert!(compressed_proof.decompress().is_none());
+    }
+
+    #[test]
+    fn public_outputs_of_a_fibonacci_proof_are_its_asserted_first_values() {
+        let trace = simple_fibonacci::fibonacci_trace([Felt252::from(3), Felt252::from(5)], 16);
+        let proof_options = ProofOptions::default_test_options();
+        let pub_inputs = FibonacciPublicInputs {
+            a0: Felt252::from(3),
+            a1: Felt252::from(5),
+        };
+        let proof = Prover::<FibonacciAIR<Stark252PrimeField>>::prove(
+            &trace,
+            &pub_inputs,
+            &proof_options,
+            StoneProverTranscript::new(&[]),
+        )
+        .unwrap();
+        assert!(Verifier::<FibonacciAIR<Stark252PrimeField>>::verify(
+            &proof,
+            &pub_inputs,
+            &proof_options,
+            StoneProverTranscript::new(&[]),
+        ));
+
+        let air = FibonacciAIR::new(proof.trace_length, &pub_inputs, &proof_options);
+        assert_eq!(
+            proof.public_outputs(&air, &[]).unwrap(),
+            vec![trace.get_row(0)[0], trace.get_row(1)[0]]
+        );
+    }
+
+    #[test]
+    fn public_outputs_of_a_rap_proof_include_the_auxiliary_boundary_values() {
+        let steps = 16;
+        let trace = fibonacci_rap_trace([Felt252::from(1), Felt252::from(1)], steps);
+        let proof_options = ProofOptions::default_test_options();
+        let pub_inputs = FibonacciRAPPublicInputs {
+            steps,
+            a0: Felt252::one(),
+            a1: Felt252::one(),
+        };
+        let proof = Prover::<FibonacciRAP<Stark252PrimeField>>::prove(
+            &trace,
+            &pub_inputs,
+            &proof_options,
+            StoneProverTranscript::new(&[]),
+        )
+        .unwrap();
+        assert!(Verifier::<FibonacciRAP<Stark252PrimeField>>::verify(
+            &proof,
+            &pub_inputs,
+            &proof_options,
+            StoneProverTranscript::new(&[]),
+        ));
+
+        let rap_challenges = Verifier::<FibonacciRAP<Stark252PrimeField>>::replay_rap_challenges(
+            &proof,
+            &pub_inputs,
+            &proof_options,
+            StoneProverTranscript::new(&[]),
+        );
+        assert_eq!(rap_challenges.len(), 1);
+        let air = FibonacciRAP::new(proof.trace_length, &pub_inputs, &proof_options);
+        // The first two values of the main trace, and the accumulated product of the
+        // permutation argument at the first and last rows.
+        assert_eq!(
+            proof.public_outputs(&air, &rap_challenges).unwrap(),
+            vec![Felt252::one(); 4]
+        );
+
+        // The proof commits to an auxiliary trace that an AIR without one does not expect.
+        let fibonacci_air = FibonacciAIR::new(
+            proof.trace_length,
+            &FibonacciPublicInputs {
+                a0: Felt252::one(),
+                a1: Felt252::one(),
+            },
+            &proof_options,
+        );
+        assert!(proof.public_outputs(&fibonacci_air, &[]).is_err());
+    }
+
+    #[test]
+    fn ood_frame_holds_the_trace_polynomial_at_the_shifted_ood_point() {
+        let trace = simple_fibonacci::fibonacci_trace([Felt252::from(1), Felt252::from(1)], 16);
+        let proof_options = ProofOptions::default_test_options();
+        let pub_inputs = FibonacciPublicInputs {
+            a0: Felt252::one(),
+            a1: Felt252::one(),
+        };
+        let proof = Prover::<FibonacciAIR<Stark252PrimeField>>::prove(
+            &trace,
+            &pub_inputs,
+            &proof_options,
+            StoneProverTranscript::new(&[]),
+        )
+        .unwrap();
+
+        let air = FibonacciAIR::new(proof.trace_length, &pub_inputs, &proof_options);
+        let domain = Domain::new(&air);
+        let challenges = Verifier::step_1_replay_rounds_and_recover_challenges(
+            &air,
+            &proof,
+            &domain,
+            &mut StoneProverTranscript::new(&[]),
+        );
+        let trace_poly = &trace.compute_trace_polys::<Stark252PrimeField>()[0];
+
+        // The frame holds t(z), t(gz) and t(g²z).
+        let frame = proof.ood_frame(&air);
+        let column = frame.column(0);
+        assert_eq!(column.len(), 3);
+        for (k, value) in column.iter().enumerate() {
+            let point = domain.trace_primitive_root.pow(k as u64) * challenges.z;
+            assert_eq!(value, &trace_poly.evaluate(&point));
+            assert_eq!(frame.row(k), vec![*value]);
+        }
+
+        // The recurrence t(g²x) = t(gx) + t(x) holds on the trace, and the verifier evaluates
+        // its transition constraint at z on the same values.
+        let g = &domain.trace_primitive_root;
+        let x = g.pow(5u64);
+        assert_eq!(
+            trace_poly.evaluate(&(g.square() * x)),
+            trace_poly.evaluate(&(g * x)) + trace_poly.evaluate(&x)
+        );
+        let transition_evaluations = air.compute_transition_verifier(&frame, &[], &[]);
+        assert_eq!(transition_evaluations[0], column[2] - column[1] - column[0]);
+    }
+
     #[test]
     fn test_serialization_compatible_with_stone_1() {
         let trace = fibonacci_2_cols_shifted::compute_trace(FieldElement::one(), 4);
@@ -486,6 +1174,12 @@ mod tests {
             coset_offset: 3,
             grinding_factor: 0,
             fri_number_of_queries: 1,
+            zero_knowledge: false,
+            composition_blowup_factor: None,
+            fri_final_poly_degree: 0,
+            deep_combination: CombinationMode::PowersOfGamma,
+            constraint_combination: CombinationMode::PowersOfGamma,
+            distinct_queries: false,
         };
 
         let pub_inputs = fibonacci_2_cols_shifted::PublicInputs {
@@ -564,6 +1258,12 @@ mod tests {
             coset_offset: 3,
             grinding_factor: 0,
             fri_number_of_queries: 10,
+            zero_knowledge: false,
+            composition_blowup_factor: None,
+            fri_final_poly_degree: 0,
+            deep_combination: CombinationMode::PowersOfGamma,
+            constraint_combination: CombinationMode::PowersOfGamma,
+            distinct_queries: false,
         };
 
         let pub_inputs = fibonacci_2_cols_shifted::PublicInputs {
@@ -656,6 +1356,12 @@ mod tests {
             coset_offset: 3,
             grinding_factor: 0,
             fri_number_of_queries: 1,
+            zero_knowledge: false,
+            composition_blowup_factor: None,
+            fri_final_poly_degree: 0,
+            deep_combination: CombinationMode::PowersOfGamma,
+            constraint_combination: CombinationMode::PowersOfGamma,
+            distinct_queries: false,
         };
 
         let pub_inputs = fibonacci_2_cols_shifted::PublicInputs {
@@ -920,6 +1626,12 @@ mod tests {
             coset_offset: 3,
             grinding_factor: 0,
             fri_number_of_queries: 2,
+            zero_knowledge: false,
+            composition_blowup_factor: None,
+            fri_final_poly_degree: 0,
+            deep_combination: CombinationMode::PowersOfGamma,
+            constraint_combination: CombinationMode::PowersOfGamma,
+            distinct_queries: false,
         };
 
         let pub_inputs = fibonacci_2_cols_shifted::PublicInputs {
@@ -998,6 +1710,12 @@ mod tests {
             coset_offset: 3,
             grinding_factor: 0,
             fri_number_of_queries: 3,
+            zero_knowledge: false,
+            composition_blowup_factor: None,
+            fri_final_poly_degree: 0,
+            deep_combination: CombinationMode::PowersOfGamma,
+            constraint_combination: CombinationMode::PowersOfGamma,
+            distinct_queries: false,
         };
 
         let pub_inputs = fibonacci_2_cols_shifted::PublicInputs {
@@ -1,18 +1,25 @@
-use std::marker::PhantomData;
-#[cfg(feature = "instruments")]
+use alloc::collections::BTreeMap;
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec::Vec};
+use core::marker::PhantomData;
+use core::time::Duration;
+#[cfg(feature = "std")]
 use std::time::Instant;
 
 use lambdaworks_crypto::fiat_shamir::is_transcript::IsTranscript;
-use lambdaworks_math::fft::cpu::bit_reversing::{in_place_bit_reverse_permute, reverse_index};
+use lambdaworks_crypto::merkle_tree::merkle::MerkleError;
+use lambdaworks_crypto::merkle_tree::traits::VectorCommitment;
+use lambdaworks_math::fft::cpu::bit_reversing::{bit_reverse_permute, reverse_index};
 use lambdaworks_math::fft::errors::FFTError;
+use lambdaworks_math::fft::polynomial::FftOrder;
 
+use lambdaworks_math::field::errors::FieldError;
 use lambdaworks_math::field::traits::{IsField, IsSubFieldOf};
 use lambdaworks_math::traits::AsBytes;
 use lambdaworks_math::{
     field::{element::FieldElement, traits::IsFFTField},
     polynomial::Polynomial,
 };
-use log::info;
 
 #[cfg(feature = "parallel")]
 use rayon::prelude::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
@@ -22,33 +29,575 @@ use crate::debug::validate_trace;
 use crate::fri;
 use crate::proof::stark::{DeepPolynomialOpenings, PolynomialOpenings};
 use crate::table::Table;
-use crate::trace::{columns2rows, LDETraceTable};
+use crate::trace::{columns2rows, frame_row_exponents, LDETraceTable, TraceError};
 
-use super::config::{BatchedMerkleTree, Commitment};
+use super::config::{IsStarkVectorCommitment, MerkleVectorCommitment};
 use super::constraints::evaluator::ConstraintEvaluator;
-use super::domain::Domain;
+use super::domain::{Domain, TwiddleCache};
+use super::fri::fri_commitment::{CompactFriLayer, FriLayer};
 use super::fri::fri_decommit::FriDecommitment;
 use super::grinding;
 use super::proof::options::ProofOptions;
-use super::proof::stark::{DeepPolynomialOpening, StarkProof};
+use super::proof::stark::{DeepPolynomialOpening, StarkProof, PROOF_VERSION};
 use super::trace::TraceTable;
 use super::traits::AIR;
+#[cfg(any(debug_assertions, feature = "self_check"))]
+use super::verifier::{IsStarkVerifier, Verifier};
+use super::zk::{self, ZkMasking};
+
+/// A default STARK prover implementing `IsStarkProver`, committing with the vector
+/// commitment scheme `VC`.
+pub struct Prover<A: AIR, VC: IsStarkVectorCommitment = MerkleVectorCommitment> {
+    phantom: PhantomData<(A, VC)>,
+}
+
+impl<A: AIR, VC: IsStarkVectorCommitment> IsStarkProver<A, VC> for Prover<A, VC> {}
+
+impl<A: AIR, VC: IsStarkVectorCommitment> Prover<A, VC> {
+    /// Returns a builder to prove traces of the AIR with the given public inputs and proof
+    /// options, using `transcript`. The other knobs of the prover are optional and can be set
+    /// on the builder before calling `ProverBuilder::prove`.
+    /// Warning: the transcript must be safely initializated before passing it to this method.
+    pub fn builder<'a, T: IsTranscript<A::FieldExtension>>(
+        pub_inputs: &'a A::PublicInputs,
+        proof_options: &'a ProofOptions,
+        transcript: T,
+    ) -> ProverBuilder<'a, A, T, VC> {
+        ProverBuilder {
+            pub_inputs,
+            proof_options,
+            transcript,
+            zk_seed: None,
+            domain: None,
+            timings: None,
+            progress: None,
+            compact_fri: false,
+            phantom: PhantomData,
+        }
+    }
+}
 
-/// A default STARK prover implementing `IsStarkProver`.
-pub struct Prover<A: AIR> {
-    phantom: PhantomData<A>,
+/// The configuration of a proof, built with `Prover::builder`. With no knob set, `prove`
+/// gives the same proof as `IsStarkProver::prove`.
+pub struct ProverBuilder<'a, A: AIR, T, VC: IsStarkVectorCommitment = MerkleVectorCommitment> {
+    pub_inputs: &'a A::PublicInputs,
+    proof_options: &'a ProofOptions,
+    transcript: T,
+    zk_seed: Option<[u8; 32]>,
+    domain: Option<&'a Domain<A::Field>>,
+    timings: Option<&'a mut ProvingTimings>,
+    progress: Option<&'a mut dyn FnMut(ProveStage)>,
+    compact_fri: bool,
+    phantom: PhantomData<VC>,
 }
 
-impl<A: AIR> IsStarkProver<A> for Prover<A> {}
+impl<'a, A: AIR, T: IsTranscript<A::FieldExtension>, VC: IsStarkVectorCommitment>
+    ProverBuilder<'a, A, T, VC>
+{
+    /// Uses `transcript` instead of the one given to `Prover::builder`, for instance one
+    /// built on another hasher.
+    pub fn with_transcript<U: IsTranscript<A::FieldExtension>>(
+        self,
+        transcript: U,
+    ) -> ProverBuilder<'a, A, U, VC> {
+        ProverBuilder {
+            pub_inputs: self.pub_inputs,
+            proof_options: self.proof_options,
+            transcript,
+            zk_seed: self.zk_seed,
+            domain: self.domain,
+            timings: self.timings,
+            progress: self.progress,
+            compact_fri: self.compact_fri,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Derives the masking randomness of the zero-knowledge mode from `zk_seed`, as
+    /// `IsStarkProver::prove_with_zk_seed` does. By default it is random.
+    pub fn with_zk_seed(mut self, zk_seed: [u8; 32]) -> Self {
+        self.zk_seed = Some(zk_seed);
+        self
+    }
+
+    /// Proves over `domain`, as `IsStarkProver::prove_with_domain` does. By default the
+    /// domain is built for the AIR.
+    pub fn with_domain(mut self, domain: &'a Domain<A::Field>) -> Self {
+        self.domain = Some(domain);
+        self
+    }
+
+    /// Records the time spent in each round in `timings`, as
+    /// `IsStarkProver::prove_with_timings` does.
+    pub fn with_timings(mut self, timings: &'a mut ProvingTimings) -> Self {
+        self.timings = Some(timings);
+        self
+    }
+
+    /// Calls `progress` with each stage of the protocol, as
+    /// `IsStarkProver::prove_with_progress` does.
+    pub fn with_progress(mut self, progress: &'a mut dyn FnMut(ProveStage)) -> Self {
+        self.progress = Some(progress);
+        self
+    }
+
+    /// Keeps only the roots and the folded polynomials of the FRI layers once they are
+    /// committed, and rebuilds the evaluations and the Merkle tree of one layer at a time to
+    /// open the queries, see `fri::commit_phase_compact`. This lowers the peak memory of the
+    /// prover at the cost of committing every layer twice. The proof is the same. Off by default.
+    pub fn with_compact_fri(mut self, compact_fri: bool) -> Self {
+        self.compact_fri = compact_fri;
+        self
+    }
+
+    /// Proves `main_trace` with the configuration of the builder.
+    pub fn prove(
+        self,
+        main_trace: &TraceTable<A::Field>,
+    ) -> Result<StarkProof<A::Field, A::FieldExtension, VC>, ProvingError>
+    where
+        A: Send + Sync,
+        FieldElement<A::Field>: AsBytes + Send + Sync,
+        FieldElement<A::FieldExtension>: AsBytes + Send + Sync,
+    {
+        Prover::<A, VC>::prove_and_measure(
+            main_trace,
+            self.pub_inputs,
+            self.proof_options,
+            self.transcript,
+            self.zk_seed,
+            self.domain,
+            self.timings,
+            self.progress,
+            self.compact_fri,
+        )
+    }
+}
 
 #[derive(Debug)]
 pub enum ProvingError {
     WrongParameter(String),
+    /// The main trace does not have the shape the AIR expects. Shapes are given as
+    /// `(rows, columns)`.
+    TraceShapeMismatch {
+        expected: (usize, usize),
+        got: (usize, usize),
+    },
+    /// A Merkle authentication path could not be built for an opening.
+    MerkleError(MerkleError),
+    /// The trace is shorter than the minimum trace length of the AIR.
+    TraceTooShort {
+        minimum: usize,
+    },
+    /// The LDE domain is too small to hold the composition polynomial: the blowup factor
+    /// must be at least its number of parts.
+    BlowupTooSmall {
+        needed: usize,
+        got: usize,
+    },
+    /// The LDE domain has `2^needed` elements but the field only has roots of unity of order up
+    /// to `2^available`, its two-adicity. For instance, the two-adicity is 192 for
+    /// `Stark252PrimeField`, 32 for the Goldilocks field and 24 for Babybear.
+    InsufficientTwoAdicity {
+        needed: u64,
+        available: u64,
+    },
+    /// The end exemptions of the transition constraint with index `constraint_idx` exempt
+    /// rows before the start of the trace.
+    ExemptionsOutOfTrace {
+        constraint_idx: usize,
+        exempted_rows: usize,
+        trace_length: usize,
+    },
+    /// An opening was requested for a row or a column out of the LDE of the trace.
+    TraceIndexOutOfRange(TraceError),
+    /// A zerofier vanished on a point of the LDE domain, so the constraints could not be
+    /// divided by it. This happens when the coset offset lies in the trace domain.
+    FieldError(FieldError),
+}
+
+impl From<MerkleError> for ProvingError {
+    fn from(error: MerkleError) -> Self {
+        Self::MerkleError(error)
+    }
+}
+
+/// The wall-clock time spent in each round of the STARK Prove protocol, as measured by
+/// `IsStarkProver::prove_with_timings`. Without the `std` feature there is no clock to read,
+/// and the durations are zero.
+#[derive(Clone, Debug, Default)]
+pub struct ProvingTimings {
+    /// Round 0: initialization of the AIR, the domain and the transcript.
+    pub initialization: Duration,
+    /// Round 1: interpolation and commitment of the main and auxiliary traces.
+    pub round_1: Duration,
+    /// Round 2: computation and commitment of the composition polynomial.
+    pub round_2: Duration,
+    /// Round 3: out of domain evaluations.
+    pub round_3: Duration,
+    /// Round 4: DEEP composition polynomial, FRI and query openings.
+    pub round_4: Duration,
+    pub total: Duration,
+}
+
+/// A stage of the STARK Prove protocol, reported to the callback of
+/// `IsStarkProver::prove_with_progress` as the proof is built.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProveStage {
+    /// Round 0 starts: initialization of the AIR, the domain and the transcript.
+    Initialization,
+    /// Round 1 starts: interpolation and commitment of the main and auxiliary traces.
+    Round1,
+    /// Round 2 starts: computation and commitment of the composition polynomial.
+    Round2,
+    /// Round 3 starts: out of domain evaluations.
+    Round3,
+    /// Round 4 starts: DEEP composition polynomial, FRI and query openings.
+    Round4,
+    /// In round 4, `layer` of the `layers` FRI layers have been folded and committed.
+    FriFolding { layer: usize, layers: usize },
+    /// The proof is built.
+    Done,
+}
+
+impl ProveStage {
+    /// Returns a rough estimate of the percentage of the proving time elapsed when the stage
+    /// is reported. The actual split depends on the AIR and the proof options.
+    pub fn percentage(&self) -> u8 {
+        match self {
+            ProveStage::Initialization => 0,
+            ProveStage::Round1 => 5,
+            ProveStage::Round2 => 30,
+            ProveStage::Round3 => 60,
+            ProveStage::Round4 => 65,
+            ProveStage::FriFolding { layer, layers } => (65 + 30 * layer / (*layers).max(1)) as u8,
+            ProveStage::Done => 100,
+        }
+    }
+}
+
+/// Calls the progress callback of the prover, if there is one, with `stage`.
+fn report_progress(progress: &mut Option<&mut dyn FnMut(ProveStage)>, stage: ProveStage) {
+    if let Some(progress) = progress {
+        progress(stage);
+    }
+}
+
+/// A point in time, read from the clock of the operating system. Without the `std` feature
+/// there is no clock, and every duration measured is zero.
+#[derive(Clone, Copy)]
+struct Timestamp {
+    #[cfg(feature = "std")]
+    instant: Instant,
+}
+
+impl Timestamp {
+    fn now() -> Self {
+        Self {
+            #[cfg(feature = "std")]
+            instant: Instant::now(),
+        }
+    }
+
+    /// Returns the time elapsed from `earlier` to `self`.
+    #[cfg(feature = "std")]
+    fn duration_since(&self, earlier: &Self) -> Duration {
+        self.instant - earlier.instant
+    }
+
+    /// Returns the time elapsed from `earlier` to `self`.
+    #[cfg(not(feature = "std"))]
+    fn duration_since(&self, _earlier: &Self) -> Duration {
+        Duration::ZERO
+    }
+}
+
+/// Measures the duration of consecutive rounds of the prover.
+struct RoundTimer {
+    start: Timestamp,
+    round_start: Timestamp,
+}
+
+impl RoundTimer {
+    fn start() -> Self {
+        let now = Timestamp::now();
+        Self {
+            start: now,
+            round_start: now,
+        }
+    }
+
+    /// Returns the time elapsed since the current round started, and starts the next one.
+    fn lap(&mut self) -> Duration {
+        let now = Timestamp::now();
+        let elapsed = now.duration_since(&self.round_start);
+        self.round_start = now;
+        elapsed
+    }
+}
+
+/// The dimensions of a proof that its rounds are logged with.
+#[derive(Clone, Copy, Debug)]
+struct RoundFields {
+    trace_length: usize,
+    trace_columns: usize,
+    blowup_factor: u8,
+    lde_domain_size: usize,
+}
+
+impl RoundFields {
+    fn new<A: AIR>(air: &A, domain: &Domain<A::Field>) -> Self {
+        let (main_columns, aux_columns) = air.trace_layout();
+        Self {
+            trace_length: air.trace_length(),
+            trace_columns: main_columns + aux_columns,
+            blowup_factor: air.options().blowup_factor,
+            lde_domain_size: domain.lde_roots_of_unity_coset.len(),
+        }
+    }
+}
+
+/// A round of the prover, logged with the dimensions of the proof and its duration. With the
+/// `tracing` feature the round is a `prover_round` span, entered until `finish` is called,
+/// with the fields `round`, `trace_length`, `trace_columns`, `blowup_factor`,
+/// `lde_domain_size` and `duration_us`. Otherwise the same fields are logged with `log` when
+/// the round finishes.
+struct RoundSpan {
+    #[cfg(not(feature = "tracing"))]
+    round: &'static str,
+    #[cfg(not(feature = "tracing"))]
+    fields: RoundFields,
+    start: Timestamp,
+    #[cfg(feature = "tracing")]
+    span: tracing::span::EnteredSpan,
+}
+
+impl RoundSpan {
+    fn enter(round: &'static str, fields: RoundFields) -> Self {
+        Self {
+            #[cfg(not(feature = "tracing"))]
+            round,
+            #[cfg(not(feature = "tracing"))]
+            fields,
+            start: Timestamp::now(),
+            #[cfg(feature = "tracing")]
+            span: tracing::info_span!(
+                "prover_round",
+                round,
+                trace_length = fields.trace_length,
+                trace_columns = fields.trace_columns,
+                blowup_factor = fields.blowup_factor,
+                lde_domain_size = fields.lde_domain_size,
+                duration_us = tracing::field::Empty,
+            )
+            .entered(),
+        }
+    }
+
+    fn finish(self) {
+        let duration = Timestamp::now().duration_since(&self.start);
+        #[cfg(feature = "tracing")]
+        self.span.record("duration_us", duration.as_micros() as u64);
+        #[cfg(not(feature = "tracing"))]
+        info!(
+            "Prover {} took {:?}: trace of {} rows and {} columns, blowup factor {}, LDE domain of size {}",
+            self.round,
+            duration,
+            self.fields.trace_length,
+            self.fields.trace_columns,
+            self.fields.blowup_factor,
+            self.fields.lde_domain_size
+        );
+    }
+}
+
+/// Checks that the main trace has as many columns as the main segment of the AIR, and that
+/// its length is the trace length of the AIR, which must be a power of two.
+pub(crate) fn check_trace_shape<A: AIR>(
+    air: &A,
+    main_trace: &TraceTable<A::Field>,
+) -> Result<(), ProvingError> {
+    let expected = (air.trace_length().next_power_of_two(), air.trace_layout().0);
+    let got = (main_trace.n_rows(), main_trace.n_cols());
+    if expected != got {
+        return Err(ProvingError::TraceShapeMismatch { expected, got });
+    }
+    Ok(())
+}
+
+/// Checks that the trace is at least as long as the minimum trace length of the AIR.
+pub(crate) fn check_trace_length<A: AIR>(air: &A) -> Result<(), ProvingError> {
+    let minimum = air.minimum_trace_length();
+    if air.trace_length() < minimum {
+        return Err(ProvingError::TraceTooShort { minimum });
+    }
+    Ok(())
+}
+
+/// Checks that the field has roots of unity of the order of the LDE domain, so that the
+/// domain can be built.
+pub(crate) fn check_two_adicity<A: AIR>(air: &A) -> Result<(), ProvingError> {
+    let lde_size = air.trace_length() * air.blowup_factor() as usize;
+    let needed = lde_size.trailing_zeros() as u64;
+    let available = <A::Field as IsFFTField>::TWO_ADICITY;
+    if needed > available {
+        return Err(ProvingError::InsufficientTwoAdicity { needed, available });
+    }
+    Ok(())
+}
+
+/// Checks that the rows exempted at the end of the trace by each transition constraint are
+/// rows of the trace, that is, that `end_exemptions() * period()` does not exceed its length.
+pub(crate) fn check_transition_exemptions<A: AIR>(air: &A) -> Result<(), ProvingError> {
+    let trace_length = air.trace_length();
+    for constraint in air.transition_constraints() {
+        let exempted_rows = constraint.end_exemptions() * constraint.period();
+        if exempted_rows > trace_length {
+            return Err(ProvingError::ExemptionsOutOfTrace {
+                constraint_idx: constraint.constraint_idx(),
+                exempted_rows,
+                trace_length,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Checks that the blowup factor is at least the number of parts of the composition
+/// polynomial, so that its evaluations over the LDE domain do not alias.
+pub(crate) fn check_blowup_factor<A: AIR>(air: &A) -> Result<(), ProvingError> {
+    let needed = zk::number_of_composition_parts(air).next_power_of_two();
+    let got = air.blowup_factor() as usize;
+    if got < needed {
+        return Err(ProvingError::BlowupTooSmall { needed, got });
+    }
+    Ok(())
+}
+
+/// Checks that the composition blowup factor of the AIR is a power of two not larger than its
+/// blowup factor, and large enough to interpolate the parts of the composition polynomial.
+pub(crate) fn check_composition_blowup_factor<A: AIR>(air: &A) -> Result<(), ProvingError> {
+    let composition_blowup_factor = air.composition_blowup_factor() as usize;
+    let number_of_parts = zk::number_of_composition_parts(air);
+    if !composition_blowup_factor.is_power_of_two()
+        || composition_blowup_factor > air.blowup_factor() as usize
+        || composition_blowup_factor < number_of_parts
+    {
+        return Err(ProvingError::WrongParameter(format!(
+            "composition blowup factor {} should be a power of two between {} and the blowup factor {}",
+            composition_blowup_factor,
+            number_of_parts,
+            air.blowup_factor()
+        )));
+    }
+    Ok(())
+}
+
+/// Checks that there are enough pairs of symmetric elements in the LDE domain to sample the
+/// FRI query indexes without repetition, if the options ask for it.
+fn check_distinct_queries<A: AIR>(air: &A, domain: &Domain<A::Field>) -> Result<(), ProvingError> {
+    let number_of_queries = air.options().fri_number_of_queries;
+    let number_of_pairs = domain.lde_roots_of_unity_coset.len() / 2;
+    if air.options().distinct_queries && number_of_queries > number_of_pairs {
+        return Err(ProvingError::WrongParameter(format!(
+            "{} distinct FRI queries do not fit in {} pairs of points of the LDE domain",
+            number_of_queries, number_of_pairs
+        )));
+    }
+    Ok(())
+}
+
+/// Checks that a domain supplied by the caller is the one `Domain::new` builds for the AIR.
+fn check_domain<A: AIR>(air: &A, domain: &Domain<A::Field>) -> Result<(), ProvingError> {
+    let expected = (
+        air.trace_length(),
+        air.blowup_factor() as usize,
+        air.composition_blowup_factor() as usize,
+        FieldElement::<A::Field>::from(air.options().coset_offset),
+    );
+    let got = (
+        domain.interpolation_domain_size,
+        domain.blowup_factor,
+        domain.composition_blowup_factor,
+        domain.coset_offset.clone(),
+    );
+    if expected != got {
+        return Err(ProvingError::WrongParameter(format!(
+            "the domain does not match the AIR with trace length {}, blowup factor {}, composition blowup factor {} and coset offset {}",
+            expected.0, expected.1, expected.2, air.options().coset_offset
+        )));
+    }
+    Ok(())
+}
+
+/// Returns a seed for the masking randomness of the zero-knowledge mode, drawn from the
+/// operating system.
+#[cfg(feature = "std")]
+fn random_zk_seed() -> Result<[u8; 32], ProvingError> {
+    Ok(rand::random())
+}
+
+/// Without the `std` feature there is no source of randomness, so the seed of the
+/// zero-knowledge mode has to be given, see `IsStarkProver::prove_with_zk_seed`.
+#[cfg(not(feature = "std"))]
+fn random_zk_seed() -> Result<[u8; 32], ProvingError> {
+    Err(ProvingError::WrongParameter(
+        "zero knowledge without the std feature needs a seed, see prove_with_zk_seed".into(),
+    ))
+}
+
+/// Replays `transcript`, a copy of the transcript the prover started with, as the verifier does
+/// and panics if the FRI query indexes it derives from `proof` are not `query_indexes`, the ones
+/// the prover opened. A proof for which they differ never verifies, so this catches
+/// desynchronizations of the prover and verifier transcripts when proving instead of when
+/// verifying.
+#[cfg(any(debug_assertions, feature = "self_check"))]
+fn check_query_indexes<A: AIR, VC: IsStarkVectorCommitment>(
+    air: &A,
+    proof: &StarkProof<A::Field, A::FieldExtension, VC>,
+    query_indexes: &[usize],
+    domain: &Domain<A::Field>,
+    mut transcript: impl IsTranscript<A::FieldExtension>,
+) where
+    FieldElement<A::Field>: AsBytes,
+    FieldElement<A::FieldExtension>: AsBytes,
+{
+    let challenges = Verifier::<A, VC>::step_1_replay_rounds_and_recover_challenges(
+        air,
+        proof,
+        domain,
+        &mut transcript,
+    );
+    assert_eq!(
+        challenges.iotas, query_indexes,
+        "the FRI query indexes of the prover differ from the ones the verifier derives"
+    );
+}
+
+/// Returns the term `gamma * (poly - eval) / (X - point)` of the DEEP composition polynomial,
+/// where `eval` is the evaluation of `poly` at `point`, or `None` if the term is zero. This
+/// happens when `poly` is constant or `gamma` is zero, and skipping it avoids the division
+/// and the product.
+fn deep_quotient_term<E: IsField>(
+    poly: &Polynomial<FieldElement<E>>,
+    eval: &FieldElement<E>,
+    point: &FieldElement<E>,
+    gamma: &FieldElement<E>,
+) -> Option<Polynomial<FieldElement<E>>> {
+    if *gamma == FieldElement::zero() {
+        return None;
+    }
+    let mut quotient = poly - eval;
+    if quotient.coefficients().is_empty() {
+        return None;
+    }
+    quotient.ruffini_division_inplace(point);
+    Some(quotient * gamma)
 }
 
 /// A container for the intermediate results of the commitments to a trace table, main or auxiliary in case of RAP,
 /// in the first round of the STARK Prove protocol.
-pub struct Round1CommitmentData<F>
+pub struct Round1CommitmentData<F, VC: IsStarkVectorCommitment = MerkleVectorCommitment>
 where
     F: IsField,
     FieldElement<F>: AsBytes + Send + Sync,
@@ -56,13 +605,13 @@ where
     /// The result of the interpolation of the columns of the trace table.
     pub(crate) trace_polys: Vec<Polynomial<FieldElement<F>>>,
     /// The Merkle trees constructed to obtain the commitment of the entire trace table.
-    pub(crate) lde_trace_merkle_tree: BatchedMerkleTree<F>,
+    pub(crate) lde_trace_merkle_tree: VC::Scheme<F>,
     /// The root of the Merkle tree in `lde_trace_merkle_tree`.
-    pub(crate) lde_trace_merkle_root: Commitment,
+    pub(crate) lde_trace_merkle_root: VC::Commitment,
 }
 
 /// A container for the results of the first round of the STARK Prove protocol.
-pub struct Round1<A>
+pub struct Round1<A, VC: IsStarkVectorCommitment = MerkleVectorCommitment>
 where
     A: AIR,
     FieldElement<A::FieldExtension>: AsBytes + Sync + Send,
@@ -71,14 +620,14 @@ where
     /// The table of evaluations over the LDE of the main and auxiliary trace tables.
     pub(crate) lde_trace: LDETraceTable<A::Field, A::FieldExtension>,
     /// The intermediate results of the commitment to the main trace table.
-    pub(crate) main: Round1CommitmentData<A::Field>,
+    pub(crate) main: Round1CommitmentData<A::Field, VC>,
     /// The intermediate results of the commitment to the auxiliary trace table in case of RAP.
-    pub(crate) aux: Option<Round1CommitmentData<A::FieldExtension>>,
+    pub(crate) aux: Option<Round1CommitmentData<A::FieldExtension, VC>>,
     /// The challenges of the RAP round.
     pub(crate) rap_challenges: Vec<FieldElement<A::FieldExtension>>,
 }
 
-impl<A> Round1<A>
+impl<A, VC: IsStarkVectorCommitment> Round1<A, VC>
 where
     A: AIR,
     FieldElement<A::FieldExtension>: AsBytes + Sync + Send,
@@ -97,26 +646,29 @@ where
             .collect();
 
         if let Some(aux) = &self.aux {
-            trace_polys.extend_from_slice(&aux.trace_polys.to_owned())
+            trace_polys.extend_from_slice(&aux.trace_polys)
         }
         trace_polys
     }
 }
 
 /// A container for the results of the second round of the STARK Prove protocol.
-pub struct Round2<F>
+pub struct Round2<F, VC: IsStarkVectorCommitment = MerkleVectorCommitment>
 where
     F: IsField,
     FieldElement<F>: AsBytes + Sync + Send,
 {
     /// The list of polynomials `H₀, ..., Hₙ` such that `H = ∑ᵢXⁱH(Xⁿ)`, where H is the composition polynomial.
     pub(crate) composition_poly_parts: Vec<Polynomial<FieldElement<F>>>,
-    /// Evaluations of the composition polynomial parts over the LDE domain.
+    /// The random polynomial committed with the composition polynomial parts in zero-knowledge mode.
+    pub(crate) composition_poly_blinding: Option<Polynomial<FieldElement<F>>>,
+    /// Evaluations of the composition polynomial parts over the LDE domain, followed by the
+    /// evaluations of the blinding polynomial if there is one.
     pub(crate) lde_composition_poly_evaluations: Vec<Vec<FieldElement<F>>>,
     /// The Merkle tree built to compute the commitment to the composition polynomial parts.
-    pub(crate) composition_poly_merkle_tree: BatchedMerkleTree<F>,
+    pub(crate) composition_poly_merkle_tree: VC::Scheme<F>,
     /// The commitment to the composition polynomial parts.
-    pub(crate) composition_poly_root: Commitment,
+    pub(crate) composition_poly_root: VC::Commitment,
 }
 
 /// A container for the results of the third round of the STARK Prove protocol.
@@ -128,35 +680,75 @@ pub struct Round3<F: IsField> {
 }
 
 /// A container for the results of the fourth round of the STARK Prove protocol.
-pub struct Round4<F: IsSubFieldOf<E>, E: IsField> {
-    /// The final value resulting from folding the Deep composition polynomial all the way down to a constant value.
-    fri_last_value: FieldElement<E>,
+pub struct Round4<
+    F: IsSubFieldOf<E>,
+    E: IsField,
+    VC: IsStarkVectorCommitment = MerkleVectorCommitment,
+> {
+    /// The coefficients of the final polynomial resulting from folding the Deep composition
+    /// polynomial down to degree at most `fri_final_poly_degree`.
+    fri_final_poly: Vec<FieldElement<E>>,
     /// The commitments to the fold polynomials of the inner layers of FRI.
-    fri_layers_merkle_roots: Vec<Commitment>,
+    fri_layers_merkle_roots: Vec<VC::Commitment>,
     /// The values and proofs of validity of the evaluations of the trace polynomials and the composition polynomials
     /// parts at the domain values corresponding to the FRI query challenges and their symmetric counterparts.
-    deep_poly_openings: DeepPolynomialOpenings<F, E>,
+    deep_poly_openings: DeepPolynomialOpenings<F, E, VC>,
     /// The values and proofs of validity of the evaluations of the fold polynomials of the inner
     /// layers of FRI at the values corresponding to the symmetrics of the FRI query challenges.
-    query_list: Vec<FriDecommitment<E>>,
+    query_list: Vec<FriDecommitment<E, VC>>,
     /// The proof of work nonce.
     nonce: Option<u64>,
+    /// The FRI query challenges 𝜄ₛ, checked against the ones the verifier derives.
+    #[cfg(any(debug_assertions, feature = "self_check"))]
+    query_indexes: Vec<usize>,
+}
+
+/// The FRI layers committed in the fourth round, see `ProverBuilder::with_compact_fri`.
+enum FriLayers<F: IsSubFieldOf<E>, E: IsField, VC: IsStarkVectorCommitment>
+where
+    FieldElement<E>: AsBytes + Sync + Send,
+{
+    Full(Vec<FriLayer<E, VC>>),
+    Compact(Vec<CompactFriLayer<F, E, VC>>),
 }
 
 /// Returns the evaluations of the polynomial `p` over the lde domain defined by the given
 /// `blowup_factor`, `domain_size` and `offset`. The number of evaluations returned is `domain_size
 /// * blowup_factor`. The domain generator used is the one given by the implementation of `F` as `IsFFTField`.
+///
+/// The evaluations are returned in [`FftOrder::Natural`] order: the evaluation at position `i` is
+/// the one at `offset * w^i`, which is `domain.lde_roots_of_unity_coset[i]`. The Merkle trees
+/// committing to the LDE permute them to bit-reversed order, so the openings for a query index
+/// `iota` are the natural positions `reverse_index(2 * iota, N)` and `reverse_index(2 * iota + 1, N)`.
+///
+/// If a `twiddle_cache` is given, its twiddle factors are used instead of computing them again.
 pub fn evaluate_polynomial_on_lde_domain<F, E>(
     p: &Polynomial<FieldElement<E>>,
     blowup_factor: usize,
     domain_size: usize,
     offset: &FieldElement<F>,
+    twiddle_cache: Option<&TwiddleCache<F>>,
 ) -> Result<Vec<FieldElement<E>>, FFTError>
 where
     F: IsFFTField + IsSubFieldOf<E>,
     E: IsField,
 {
-    let evaluations = Polynomial::evaluate_offset_fft(p, blowup_factor, Some(domain_size), offset)?;
+    let evaluations = match twiddle_cache {
+        Some(twiddle_cache) => Polynomial::evaluate_offset_fft_with_twiddles(
+            p,
+            blowup_factor,
+            Some(domain_size),
+            offset,
+            &twiddle_cache.twiddles,
+        )?,
+        None => Polynomial::evaluate_offset_fft_with_order(
+            p,
+            blowup_factor,
+            Some(domain_size),
+            offset,
+            FftOrder::Natural,
+        )?,
+    };
     let step = evaluations.len() / (domain_size * blowup_factor);
     match step {
         1 => Ok(evaluations),
@@ -164,13 +756,64 @@ where
     }
 }
 
+/// Returns the 64-bit FNV-1a hash of the bytes of the elements of `column`. It only buckets
+/// candidate duplicates, which are then compared element by element, so it needs to be fast
+/// rather than collision resistant.
+fn column_hash<F>(column: &[FieldElement<F>]) -> u64
+where
+    F: IsField,
+    FieldElement<F>: AsBytes,
+{
+    const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const FNV_PRIME: u64 = 0x100000001b3;
+    column
+        .iter()
+        .flat_map(|element| element.as_bytes())
+        .fold(FNV_OFFSET_BASIS, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(FNV_PRIME)
+        })
+}
+
+/// Groups the identical columns of `columns`, so that their interpolation and low degree
+/// extension can be computed only once. Columns are bucketed by a hash of their contents and
+/// compared element by element within a bucket.
+/// Output: a touple with the following:
+/// • The indexes of the distinct columns, which are the first occurrence of each of them.
+/// • For each column, the position in the above vector of the column it is equal to.
+fn group_duplicate_columns<F>(columns: &[Vec<FieldElement<F>>]) -> (Vec<usize>, Vec<usize>)
+where
+    F: IsField,
+    FieldElement<F>: AsBytes,
+{
+    let mut buckets: BTreeMap<u64, Vec<usize>> = BTreeMap::new();
+    let mut distinct_columns = Vec::new();
+    let mut column_to_distinct = Vec::with_capacity(columns.len());
+    for (idx, column) in columns.iter().enumerate() {
+        let bucket = buckets.entry(column_hash(column)).or_default();
+        let duplicate = bucket
+            .iter()
+            .find(|&&distinct_idx| columns[distinct_columns[distinct_idx]] == *column);
+        match duplicate {
+            Some(&distinct_idx) => column_to_distinct.push(distinct_idx),
+            None => {
+                bucket.push(distinct_columns.len());
+                column_to_distinct.push(distinct_columns.len());
+                distinct_columns.push(idx);
+            }
+        }
+    }
+    (distinct_columns, column_to_distinct)
+}
+
 /// The functionality of a STARK prover providing methods to run the STARK Prove protocol
 /// https://lambdaclass.github.io/lambdaworks/starks/protocol.html
 /// The default implementation is complete and is compatible with Stone prover
 /// https://github.com/starkware-libs/stone-prover
-pub trait IsStarkProver<A: AIR> {
-    /// Returns the Merkle tree and the commitment to the vectors `vectors`.
-    fn batch_commit<E>(vectors: &[Vec<FieldElement<E>>]) -> (BatchedMerkleTree<E>, Commitment)
+/// The proofs commit with the vector commitment scheme `VC`, Merkle trees by default.
+pub trait IsStarkProver<A: AIR, VC: IsStarkVectorCommitment = MerkleVectorCommitment> {
+    /// Returns the committed vector `vectors` and the commitment to it, with the vector
+    /// commitment scheme `VC`.
+    fn batch_commit<E>(vectors: &[Vec<FieldElement<E>>]) -> (VC::Scheme<E>, VC::Commitment)
     where
         FieldElement<A::Field>: AsBytes + Sync + Send,
         FieldElement<A::FieldExtension>: AsBytes + Sync + Send,
@@ -178,13 +821,17 @@ pub trait IsStarkProver<A: AIR> {
         E: IsSubFieldOf<A::FieldExtension>,
         A::Field: IsSubFieldOf<E>,
     {
-        let tree = BatchedMerkleTree::<E>::build(vectors);
-        let commitment = tree.root;
-        (tree, commitment)
+        VC::Scheme::<E>::commit(vectors)
     }
 
     /// Given a `TraceTable`, this method interpolates its columns, computes the commitment to the
-    /// table and appends it to the transcript.
+    /// table and appends it to the transcript. If `masking` is given, the interpolating
+    /// polynomials are masked before being committed, see the `zk` module.
+    /// Identical columns, such as the constant ones of padded traces, are interpolated and
+    /// extended only once, but they are still committed as separate columns.
+    /// All the columns are committed in a single Merkle tree whose leaves are the rows of the
+    /// bit-reversed LDE, so every query opens one authentication path exposing a whole row,
+    /// whatever the width of the trace.
     /// Output: a touple of length 4 with the following:
     /// • The polynomials interpolating the columns of `trace`.
     /// • The evaluations of the above polynomials over the domain `domain`.
@@ -195,11 +842,12 @@ pub trait IsStarkProver<A: AIR> {
         trace: &TraceTable<E>,
         domain: &Domain<A::Field>,
         transcript: &mut impl IsTranscript<A::FieldExtension>,
+        masking: Option<&mut ZkMasking>,
     ) -> (
         Vec<Polynomial<FieldElement<E>>>,
         Vec<Vec<FieldElement<E>>>,
-        BatchedMerkleTree<E>,
-        Commitment,
+        VC::Scheme<E>,
+        VC::Commitment,
     )
     where
         FieldElement<A::Field>: AsBytes + Send + Sync,
@@ -208,16 +856,53 @@ pub trait IsStarkProver<A: AIR> {
         E: IsSubFieldOf<A::FieldExtension>,
         A::Field: IsSubFieldOf<E>,
     {
-        // Interpolate columns of `trace`.
-        let trace_polys = trace.compute_trace_polys::<A::Field>();
+        // Interpolate the distinct columns of `trace`.
+        let columns = trace.columns();
+        let (distinct_columns, column_to_distinct) = group_duplicate_columns(&columns);
+
+        #[cfg(not(feature = "parallel"))]
+        let distinct_columns_iter = distinct_columns.iter();
+        #[cfg(feature = "parallel")]
+        let distinct_columns_iter = distinct_columns.par_iter();
+
+        let distinct_trace_polys = distinct_columns_iter
+            .map(|idx| match &domain.twiddle_cache {
+                Some(twiddle_cache) => Polynomial::interpolate_offset_fft_with_twiddles(
+                    &columns[*idx],
+                    &FieldElement::<A::Field>::one(),
+                    &twiddle_cache.inverse_twiddles,
+                ),
+                None => Polynomial::interpolate_fft::<A::Field>(&columns[*idx]),
+            })
+            .collect::<Result<Vec<Polynomial<FieldElement<E>>>, FFTError>>()
+            .unwrap();
+        let mut trace_polys: Vec<_> = column_to_distinct
+            .iter()
+            .map(|distinct_idx| distinct_trace_polys[*distinct_idx].clone())
+            .collect();
 
-        // Evaluate those polynomials t_j on the large domain D_LDE.
-        let lde_trace_evaluations = Self::compute_lde_trace_evaluations(&trace_polys, domain);
+        // Evaluate those polynomials t_j on the large domain D_LDE. Masked polynomials are all
+        // different, even if they interpolate identical columns.
+        let lde_trace_evaluations = if let Some(masking) = masking {
+            trace_polys = trace_polys
+                .iter()
+                .map(|poly| masking.mask_trace_poly(poly, domain.interpolation_domain_size))
+                .collect();
+            Self::compute_lde_trace_evaluations(&trace_polys, domain)
+        } else {
+            let distinct_lde_trace_evaluations =
+                Self::compute_lde_trace_evaluations(&distinct_trace_polys, domain);
+            column_to_distinct
+                .iter()
+                .map(|distinct_idx| distinct_lde_trace_evaluations[*distinct_idx].clone())
+                .collect()
+        };
 
-        let mut lde_trace_permuted = lde_trace_evaluations.clone();
-        for col in lde_trace_permuted.iter_mut() {
-            in_place_bit_reverse_permute(col);
-        }
+        let lde_trace_permuted: Vec<_> = lde_trace_evaluations
+            .iter()
+            .map(Vec::as_slice)
+            .map(bit_reverse_permute)
+            .collect();
 
         // Compute commitment.
         let lde_trace_permuted_rows = columns2rows(lde_trace_permuted);
@@ -225,7 +910,7 @@ pub trait IsStarkProver<A: AIR> {
             Self::batch_commit(&lde_trace_permuted_rows);
 
         // >>>> Send commitment.
-        transcript.append_bytes(&lde_trace_merkle_root);
+        transcript.append_bytes(lde_trace_merkle_root.as_ref());
 
         (
             trace_polys,
@@ -259,27 +944,48 @@ pub trait IsStarkProver<A: AIR> {
                     domain.blowup_factor,
                     domain.interpolation_domain_size,
                     &domain.coset_offset,
+                    domain.twiddle_cache.as_ref(),
                 )
             })
             .collect::<Result<Vec<Vec<FieldElement<E>>>, FFTError>>()
             .unwrap()
     }
 
+    /// Absorbs the initialization data of the AIR into the transcript, if there is any,
+    /// prefixed with its length so that it is not confused with what is appended after it.
+    /// Nothing is appended for AIRs without initialization data, so their transcripts stay
+    /// the ones of the Stone prover.
+    fn round_0_transcript_initialization(
+        air: &A,
+        transcript: &mut impl IsTranscript<A::FieldExtension>,
+    ) {
+        let init_data = air.transcript_init_data();
+        if !init_data.is_empty() {
+            transcript.append_many(&[&init_data]);
+        }
+    }
+
     /// Returns the result of the first round of the STARK Prove protocol.
     fn round_1_randomized_air_with_preprocessing(
         air: &A,
         main_trace: &TraceTable<A::Field>,
         domain: &Domain<A::Field>,
         transcript: &mut impl IsTranscript<A::FieldExtension>,
-    ) -> Result<Round1<A>, ProvingError>
+        mut masking: Option<&mut ZkMasking>,
+    ) -> Result<Round1<A, VC>, ProvingError>
     where
         FieldElement<A::Field>: AsBytes + Send + Sync,
         FieldElement<A::FieldExtension>: AsBytes + Send + Sync,
     {
         let (trace_polys, evaluations, main_merkle_tree, main_merkle_root) =
-            Self::interpolate_and_commit::<A::Field>(main_trace, domain, transcript);
+            Self::interpolate_and_commit::<A::Field>(
+                main_trace,
+                domain,
+                transcript,
+                masking.as_deref_mut(),
+            );
 
-        let main = Round1CommitmentData::<A::Field> {
+        let main = Round1CommitmentData::<A::Field, VC> {
             trace_polys,
             lde_trace_merkle_tree: main_merkle_tree,
             lde_trace_merkle_root: main_merkle_root,
@@ -290,9 +996,9 @@ pub trait IsStarkProver<A: AIR> {
         let aux_trace = air.build_auxiliary_trace(main_trace, &rap_challenges);
         let (aux, aux_evaluations) = if !aux_trace.is_empty() {
             let (aux_trace_polys, aux_trace_polys_evaluations, aux_merkle_tree, aux_merkle_root) =
-                Self::interpolate_and_commit(&aux_trace, domain, transcript);
+                Self::interpolate_and_commit(&aux_trace, domain, transcript, masking);
             let aux_evaluations = aux_trace_polys_evaluations;
-            let aux = Some(Round1CommitmentData::<A::FieldExtension> {
+            let aux = Some(Round1CommitmentData::<A::FieldExtension, VC> {
                 trace_polys: aux_trace_polys,
                 lde_trace_merkle_tree: aux_merkle_tree,
                 lde_trace_merkle_root: aux_merkle_root,
@@ -318,32 +1024,33 @@ pub trait IsStarkProver<A: AIR> {
     }
 
     /// Returns the Merkle tree and the commitment to the evaluations of the parts of the
-    /// composition polynomial.
+    /// composition polynomial. The evaluations at a point of the LDE domain and at its
+    /// symmetric point are consecutive in bit-reversed order and share a leaf, so all the
+    /// parts are committed with a single root and one authentication path opens both
+    /// evaluations of a query.
     fn commit_composition_polynomial(
         lde_composition_poly_parts_evaluations: &[Vec<FieldElement<A::FieldExtension>>],
-    ) -> (BatchedMerkleTree<A::FieldExtension>, Commitment)
+    ) -> (VC::Scheme<A::FieldExtension>, VC::Commitment)
     where
         FieldElement<A::Field>: AsBytes + Sync + Send,
         FieldElement<A::FieldExtension>: AsBytes + Sync + Send,
     {
-        // TODO: Remove clones
-        let mut lde_composition_poly_evaluations = Vec::new();
-        for i in 0..lde_composition_poly_parts_evaluations[0].len() {
-            let mut row = Vec::new();
-            for evaluation in lde_composition_poly_parts_evaluations.iter() {
-                row.push(evaluation[i].clone());
-            }
-            lde_composition_poly_evaluations.push(row);
-        }
-
-        in_place_bit_reverse_permute(&mut lde_composition_poly_evaluations);
-
-        let mut lde_composition_poly_evaluations_merged = Vec::new();
-        for chunk in lde_composition_poly_evaluations.chunks(2) {
-            let (mut chunk0, chunk1) = (chunk[0].clone(), &chunk[1]);
-            chunk0.extend_from_slice(chunk1);
-            lde_composition_poly_evaluations_merged.push(chunk0);
-        }
+        // The leaf `k` holds the evaluations of every part at the positions `2k` and `2k + 1`
+        // of the bit reversed LDE domain, which are symmetric points.
+        let domain_size = lde_composition_poly_parts_evaluations[0].len();
+        let lde_composition_poly_evaluations_merged: Vec<Vec<_>> = (0..domain_size / 2)
+            .map(|k| {
+                [2 * k, 2 * k + 1]
+                    .into_iter()
+                    .flat_map(|position| {
+                        let i = reverse_index(position, domain_size as u64);
+                        lde_composition_poly_parts_evaluations
+                            .iter()
+                            .map(move |evaluations| evaluations[i].clone())
+                    })
+                    .collect()
+            })
+            .collect();
 
         Self::batch_commit(&lde_composition_poly_evaluations_merged)
     }
@@ -352,42 +1059,72 @@ pub trait IsStarkProver<A: AIR> {
     fn round_2_compute_composition_polynomial(
         air: &A,
         domain: &Domain<A::Field>,
-        round_1_result: &Round1<A>,
+        round_1_result: &Round1<A, VC>,
         transition_coefficients: &[FieldElement<A::FieldExtension>],
         boundary_coefficients: &[FieldElement<A::FieldExtension>],
-    ) -> Round2<A::FieldExtension>
+        masking: Option<&mut ZkMasking>,
+    ) -> Result<Round2<A::FieldExtension, VC>, ProvingError>
     where
         A: Send + Sync,
         FieldElement<A::Field>: AsBytes + Send + Sync,
         FieldElement<A::FieldExtension>: AsBytes + Send + Sync,
     {
-        // Compute the evaluations of the composition polynomial on the LDE domain.
+        // Compute the evaluations of the composition polynomial on the LDE domain, or on the
+        // smaller domain given by the composition blowup factor, which is enough to interpolate
+        // it since its degree is known. Its LDE trace is a subset of the one of the LDE domain.
+        let composition_domain;
+        let composition_lde_trace;
+        let (evaluation_domain, evaluation_lde_trace) =
+            if domain.composition_blowup_factor == domain.blowup_factor {
+                (domain, &round_1_result.lde_trace)
+            } else {
+                composition_domain = domain.composition_domain();
+                composition_lde_trace = round_1_result
+                    .lde_trace
+                    .subsample(domain.blowup_factor / domain.composition_blowup_factor);
+                (&composition_domain, &composition_lde_trace)
+            };
+
         let evaluator = ConstraintEvaluator::new(air, &round_1_result.rap_challenges);
-        let constraint_evaluations = evaluator.evaluate(
-            air,
-            &round_1_result.lde_trace,
-            domain,
-            transition_coefficients,
-            boundary_coefficients,
-            &round_1_result.rap_challenges,
-        );
+        let constraint_evaluations = evaluator
+            .evaluate(
+                air,
+                evaluation_lde_trace,
+                evaluation_domain,
+                transition_coefficients,
+                boundary_coefficients,
+                &round_1_result.rap_challenges,
+            )
+            .map_err(ProvingError::FieldError)?;
 
         // Get coefficients of the composition poly H
-        let composition_poly =
-            Polynomial::interpolate_offset_fft(&constraint_evaluations, &domain.coset_offset)
-                .unwrap();
+        let composition_poly = match &domain.twiddle_cache {
+            Some(twiddle_cache) => Polynomial::interpolate_offset_fft_with_twiddles(
+                &constraint_evaluations,
+                &domain.coset_offset,
+                &twiddle_cache.inverse_twiddles,
+            ),
+            None => {
+                Polynomial::interpolate_offset_fft(&constraint_evaluations, &domain.coset_offset)
+            }
+        }
+        .unwrap();
 
-        let number_of_parts = air.composition_poly_degree_bound() / air.trace_length();
+        let number_of_parts = zk::number_of_composition_parts(air);
         let composition_poly_parts = composition_poly.break_in_parts(number_of_parts);
+        let composition_poly_blinding =
+            masking.map(|masking| masking.random_polynomial(domain.interpolation_domain_size));
 
         let lde_composition_poly_parts_evaluations: Vec<_> = composition_poly_parts
             .iter()
+            .chain(composition_poly_blinding.iter())
             .map(|part| {
                 evaluate_polynomial_on_lde_domain(
                     part,
                     domain.blowup_factor,
                     domain.interpolation_domain_size,
                     &domain.coset_offset,
+                    domain.twiddle_cache.as_ref(),
                 )
                 .unwrap()
             })
@@ -396,20 +1133,21 @@ pub trait IsStarkProver<A: AIR> {
         let (composition_poly_merkle_tree, composition_poly_root) =
             Self::commit_composition_polynomial(&lde_composition_poly_parts_evaluations);
 
-        Round2 {
+        Ok(Round2 {
             lde_composition_poly_evaluations: lde_composition_poly_parts_evaluations,
             composition_poly_parts,
+            composition_poly_blinding,
             composition_poly_merkle_tree,
             composition_poly_root,
-        }
+        })
     }
 
     /// Returns the result of the third round of the STARK Prove protocol.
     fn round_3_evaluate_polynomials_in_out_of_domain_element(
         air: &A,
         domain: &Domain<A::Field>,
-        round_1_result: &Round1<A>,
-        round_2_result: &Round2<A::FieldExtension>,
+        round_1_result: &Round1<A, VC>,
+        round_2_result: &Round2<A::FieldExtension, VC>,
         z: &FieldElement<A::FieldExtension>,
     ) -> Round3<A::FieldExtension>
     where
@@ -423,6 +1161,7 @@ pub trait IsStarkProver<A: AIR> {
         let composition_poly_parts_ood_evaluation: Vec<_> = round_2_result
             .composition_poly_parts
             .iter()
+            .chain(round_2_result.composition_poly_blinding.iter())
             .map(|part| part.evaluate(&z_power))
             .collect();
 
@@ -454,15 +1193,18 @@ pub trait IsStarkProver<A: AIR> {
     }
 
     /// Returns the result of the fourth round of the STARK Prove protocol.
+    #[allow(clippy::too_many_arguments)]
     fn round_4_compute_and_run_fri_on_the_deep_composition_polynomial(
         air: &A,
         domain: &Domain<A::Field>,
-        round_1_result: &Round1<A>,
-        round_2_result: &Round2<A::FieldExtension>,
+        round_1_result: &Round1<A, VC>,
+        round_2_result: &Round2<A::FieldExtension, VC>,
         round_3_result: &Round3<A::FieldExtension>,
         z: &FieldElement<A::FieldExtension>,
         transcript: &mut impl IsTranscript<A::FieldExtension>,
-    ) -> Round4<A::Field, A::FieldExtension>
+        compact_fri: bool,
+        mut on_fri_layer: impl FnMut(usize, usize),
+    ) -> Result<Round4<A::Field, A::FieldExtension, VC>, ProvingError>
     where
         FieldElement<A::Field>: AsBytes + Send + Sync,
         FieldElement<A::FieldExtension>: AsBytes + Send + Sync,
@@ -470,15 +1212,14 @@ pub trait IsStarkProver<A: AIR> {
         let coset_offset_u64 = air.context().proof_options.coset_offset;
         let coset_offset = FieldElement::<A::Field>::from(coset_offset_u64);
 
-        let gamma = transcript.sample_field_element();
         let n_terms_composition_poly = round_2_result.lde_composition_poly_evaluations.len();
         let n_terms_trace = air.context().transition_offsets.len() * air.context().trace_columns;
 
         // <<<< Receive challenges: 𝛾, 𝛾'
-        let mut deep_composition_coefficients: Vec<_> =
-            core::iter::successors(Some(FieldElement::one()), |x| Some(x * &gamma))
-                .take(n_terms_composition_poly + n_terms_trace)
-                .collect();
+        let mut deep_composition_coefficients = air
+            .options()
+            .deep_combination
+            .sample_coefficients(n_terms_composition_poly + n_terms_trace, transcript);
 
         let trace_poly_coeffients: Vec<_> = deep_composition_coefficients
             .drain(..n_terms_trace)
@@ -501,14 +1242,36 @@ pub trait IsStarkProver<A: AIR> {
 
         let domain_size = domain.lde_roots_of_unity_coset.len();
 
-        // FRI commit and query phases
-        let (fri_last_value, fri_layers) = fri::commit_phase::<A::Field, A::FieldExtension>(
-            domain.root_order as usize,
-            deep_composition_poly,
-            transcript,
-            &coset_offset,
-            domain_size,
+        // FRI commit and query phases. The masked trace polynomials of the zero-knowledge mode
+        // have degree less than twice the trace length, which needs an additional folding.
+        let (number_layers, final_poly_len) = fri::fold_schedule(
+            air.trace_length() << usize::from(air.options().zero_knowledge),
+            air.options().fri_final_poly_degree,
         );
+        let on_layer = |layer| on_fri_layer(layer, number_layers);
+        let (fri_final_poly, fri_layers) = if compact_fri {
+            let (fri_final_poly, fri_layers) = fri::commit_phase_compact::<A::Field, _, VC>(
+                number_layers,
+                final_poly_len,
+                deep_composition_poly,
+                transcript,
+                &coset_offset,
+                domain_size,
+                on_layer,
+            );
+            (fri_final_poly, FriLayers::Compact(fri_layers))
+        } else {
+            let (fri_final_poly, fri_layers) = fri::commit_phase_with_progress::<A::Field, _, VC>(
+                number_layers,
+                final_poly_len,
+                deep_composition_poly,
+                transcript,
+                &coset_offset,
+                domain_size,
+                on_layer,
+            );
+            (fri_final_poly, FriLayers::Full(fri_layers))
+        };
 
         // grinding: generate nonce and append it to the transcript
         let security_bits = air.context().proof_options.grinding_factor;
@@ -521,24 +1284,34 @@ pub trait IsStarkProver<A: AIR> {
         }
 
         let number_of_queries = air.options().fri_number_of_queries;
-        let iotas = Self::sample_query_indexes(number_of_queries, domain, transcript);
-        let query_list = fri::query_phase(&fri_layers, &iotas);
-
-        let fri_layers_merkle_roots: Vec<_> = fri_layers
-            .iter()
-            .map(|layer| layer.merkle_tree.root)
-            .collect();
+        let iotas = if air.options().distinct_queries {
+            fri::sample_distinct_query_indexes(number_of_queries, domain, transcript)
+        } else {
+            Self::sample_query_indexes(number_of_queries, domain, transcript)
+        };
+        let (query_list, fri_layers_merkle_roots) = match fri_layers {
+            FriLayers::Full(fri_layers) => (
+                fri::query_phase(&fri_layers, &iotas),
+                fri_layers.iter().map(|layer| layer.root.clone()).collect(),
+            ),
+            FriLayers::Compact(fri_layers) => (
+                fri::query_phase_compact(&fri_layers, &iotas),
+                fri_layers.iter().map(|layer| layer.root.clone()).collect(),
+            ),
+        };
 
         let deep_poly_openings =
-            Self::open_deep_composition_poly(domain, round_1_result, round_2_result, &iotas);
+            Self::open_deep_composition_poly(domain, round_1_result, round_2_result, &iotas)?;
 
-        Round4 {
-            fri_last_value,
+        Ok(Round4 {
+            fri_final_poly,
             fri_layers_merkle_roots,
             deep_poly_openings,
             query_list,
             nonce,
-        }
+            #[cfg(any(debug_assertions, feature = "self_check"))]
+            query_indexes: iotas,
+        })
     }
 
     fn sample_query_indexes(
@@ -559,7 +1332,7 @@ pub trait IsStarkProver<A: AIR> {
     fn compute_deep_composition_poly(
         air: &A,
         trace_polys: &[Polynomial<FieldElement<A::FieldExtension>>],
-        round_2_result: &Round2<A::FieldExtension>,
+        round_2_result: &Round2<A::FieldExtension, VC>,
         round_3_result: &Round3<A::FieldExtension>,
         z: &FieldElement<A::FieldExtension>,
         primitive_root: &FieldElement<A::Field>,
@@ -572,20 +1345,30 @@ pub trait IsStarkProver<A: AIR> {
     {
         let z_power = z.pow(round_2_result.composition_poly_parts.len());
 
-        // ∑ᵢ 𝛾ᵢ ( Hᵢ − Hᵢ(z^N) ) / ( X − z^N )
+        // ∑ᵢ 𝛾ᵢ ( Hᵢ − Hᵢ(z^N) ) / ( X − z^N ), including the blinding polynomial if there is one
         let mut h_terms = Polynomial::zero();
-        for (i, part) in round_2_result.composition_poly_parts.iter().enumerate() {
+        for (i, part) in round_2_result
+            .composition_poly_parts
+            .iter()
+            .chain(round_2_result.composition_poly_blinding.iter())
+            .enumerate()
+        {
             // h_i_eval is the evaluation of the i-th part of the composition polynomial at z^N,
             // where N is the number of parts of the composition polynomial.
             let h_i_eval = &round_3_result.composition_poly_parts_ood_evaluation[i];
-            let h_i_term = &composition_poly_gammas[i] * (part - h_i_eval);
-            h_terms = h_terms + h_i_term;
+            let h_i_numerator = part - h_i_eval;
+            if composition_poly_gammas[i] == FieldElement::zero()
+                || h_i_numerator.coefficients().is_empty()
+            {
+                continue;
+            }
+            h_terms = h_terms + &composition_poly_gammas[i] * h_i_numerator;
         }
         assert_eq!(h_terms.evaluate(&z_power), FieldElement::zero());
         h_terms.ruffini_division_inplace(&z_power);
 
         // Get trace evaluations needed for the trace terms of the deep composition polynomial
-        let transition_offsets = &air.context().transition_offsets;
+        let frame_exponents = &frame_row_exponents(&air.context().transition_offsets, A::STEP_SIZE);
         let trace_frame_evaluations = &round_3_result.trace_ood_evaluations;
 
         // Compute the sum of all the trace terms of the deep composition polynomial.
@@ -606,7 +1389,7 @@ pub trait IsStarkProver<A: AIR> {
                     trace_frame_length,
                     trace_terms_gammas,
                     &trace_frame_evaluations.columns(),
-                    transition_offsets,
+                    frame_exponents,
                     (z, primitive_root),
                 )
             })
@@ -624,7 +1407,7 @@ pub trait IsStarkProver<A: AIR> {
                         trace_frame_length,
                         trace_terms_gammas,
                         &trace_frame_evaluations.columns(),
-                        transition_offsets,
+                        frame_exponents,
                         (z, primitive_root),
                     )
                 });
@@ -635,13 +1418,15 @@ pub trait IsStarkProver<A: AIR> {
     /// Adds to `accumulator` the term corresponding to the trace polynomial `t_j` of the Deep
     /// composition polynomial. That is, returns `accumulator + \sum_i \gamma_i \frac{ t_j - t_j(zg^i) }{ X - zg^i }`,
     /// where `i` ranges from `T * j` to `T * j + T - 1`, where `T` is the number of offsets in every frame.
+    /// The `i`-th row of the frame is the evaluation at `zgᵉ`, where `e` is the `i`-th of
+    /// `frame_exponents`.
     fn compute_trace_term(
         accumulator: &Polynomial<FieldElement<A::FieldExtension>>,
         (j, t_j): (usize, &Polynomial<FieldElement<A::FieldExtension>>),
         trace_frame_length: usize,
         trace_terms_gammas: &[FieldElement<A::FieldExtension>],
         trace_frame_evaluations: &[Vec<FieldElement<A::FieldExtension>>],
-        transition_offsets: &[usize],
+        frame_exponents: &[usize],
         (z, primitive_root): (&FieldElement<A::FieldExtension>, &FieldElement<A::Field>),
     ) -> Polynomial<FieldElement<A::FieldExtension>>
     where
@@ -651,16 +1436,17 @@ pub trait IsStarkProver<A: AIR> {
         let iter_trace_gammas = trace_terms_gammas.iter().skip(j * trace_frame_length);
         let trace_int = trace_frame_evaluations[j]
             .iter()
-            .zip(transition_offsets)
+            .zip(frame_exponents)
             .zip(iter_trace_gammas)
             .fold(
                 Polynomial::zero(),
-                |trace_agg, ((t_j_z, offset), trace_gamma)| {
+                |trace_agg, ((t_j_z, exponent), trace_gamma)| {
                     // @@@ this can be pre-computed
-                    let z_shifted = primitive_root.pow(*offset) * z;
-                    let mut poly = t_j - t_j_z;
-                    poly.ruffini_division_inplace(&z_shifted);
-                    trace_agg + poly * trace_gamma
+                    let z_shifted = primitive_root.pow(*exponent) * z;
+                    match deep_quotient_term(t_j, t_j_z, &z_shifted, trace_gamma) {
+                        Some(term) => trace_agg + term,
+                        None => trace_agg,
+                    }
                 },
             );
 
@@ -671,17 +1457,17 @@ pub trait IsStarkProver<A: AIR> {
     /// at the domain value corresponding to the FRI query challenge `index` and its symmetric
     /// element.
     fn open_composition_poly(
-        composition_poly_merkle_tree: &BatchedMerkleTree<A::FieldExtension>,
+        composition_poly_merkle_tree: &VC::Scheme<A::FieldExtension>,
         lde_composition_poly_evaluations: &[Vec<FieldElement<A::FieldExtension>>],
         index: usize,
-    ) -> PolynomialOpenings<A::FieldExtension>
+    ) -> Result<PolynomialOpenings<A::FieldExtension, VC>, ProvingError>
     where
         FieldElement<A::Field>: AsBytes + Sync + Send,
         FieldElement<A::FieldExtension>: AsBytes + Sync + Send,
     {
         let proof = composition_poly_merkle_tree
-            .get_proof_by_pos(index)
-            .unwrap();
+            .open(index)
+            .map_err(Into::into)?;
 
         let lde_composition_poly_parts_evaluation: Vec<_> = lde_composition_poly_evaluations
             .iter()
@@ -693,7 +1479,7 @@ pub trait IsStarkProver<A: AIR> {
             })
             .collect();
 
-        PolynomialOpenings {
+        Ok(PolynomialOpenings {
             proof: proof.clone(),
             proof_sym: proof,
             evaluations: lde_composition_poly_parts_evaluation
@@ -706,7 +1492,7 @@ pub trait IsStarkProver<A: AIR> {
                 .skip(1)
                 .step_by(2)
                 .collect(),
-        }
+        })
     }
 
     /// Computes values and validity proofs of the evaluations of the trace polynomials
@@ -714,10 +1500,10 @@ pub trait IsStarkProver<A: AIR> {
     /// element.
     fn open_trace_polys<E>(
         domain: &Domain<A::Field>,
-        tree: &BatchedMerkleTree<E>,
+        tree: &VC::Scheme<E>,
         lde_trace: &Table<E>,
         challenge: usize,
-    ) -> PolynomialOpenings<E>
+    ) -> Result<PolynomialOpenings<E, VC>, ProvingError>
     where
         FieldElement<A::Field>: AsBytes + Sync + Send,
         FieldElement<E>: AsBytes + Sync + Send,
@@ -728,25 +1514,27 @@ pub trait IsStarkProver<A: AIR> {
 
         let index = challenge * 2;
         let index_sym = challenge * 2 + 1;
-        PolynomialOpenings {
-            proof: tree.get_proof_by_pos(index).unwrap(),
-            proof_sym: tree.get_proof_by_pos(index_sym).unwrap(),
+        Ok(PolynomialOpenings {
+            proof: tree.open(index).map_err(Into::into)?,
+            proof_sym: tree.open(index_sym).map_err(Into::into)?,
             evaluations: lde_trace
-                .get_row(reverse_index(index, domain_size as u64))
+                .try_get_row(reverse_index(index, domain_size as u64))
+                .map_err(ProvingError::TraceIndexOutOfRange)?
                 .to_vec(),
             evaluations_sym: lde_trace
-                .get_row(reverse_index(index_sym, domain_size as u64))
+                .try_get_row(reverse_index(index_sym, domain_size as u64))
+                .map_err(ProvingError::TraceIndexOutOfRange)?
                 .to_vec(),
-        }
+        })
     }
 
     /// Open the deep composition polynomial on a list of indexes and their symmetric elements.
     fn open_deep_composition_poly(
         domain: &Domain<A::Field>,
-        round_1_result: &Round1<A>,
-        round_2_result: &Round2<A::FieldExtension>,
+        round_1_result: &Round1<A, VC>,
+        round_2_result: &Round2<A::FieldExtension, VC>,
         indexes_to_open: &[usize],
-    ) -> DeepPolynomialOpenings<A::Field, A::FieldExtension>
+    ) -> Result<DeepPolynomialOpenings<A::Field, A::FieldExtension, VC>, ProvingError>
     where
         FieldElement<A::Field>: AsBytes + Send + Sync,
         FieldElement<A::FieldExtension>: AsBytes + Send + Sync,
@@ -759,22 +1547,26 @@ pub trait IsStarkProver<A: AIR> {
                 &round_1_result.main.lde_trace_merkle_tree,
                 &round_1_result.lde_trace.main_table,
                 *index,
-            );
+            )?;
 
             let composition_openings = Self::open_composition_poly(
                 &round_2_result.composition_poly_merkle_tree,
                 &round_2_result.lde_composition_poly_evaluations,
                 *index,
-            );
+            )?;
 
-            let aux_trace_polys = round_1_result.aux.as_ref().map(|aux| {
-                Self::open_trace_polys::<A::FieldExtension>(
-                    domain,
-                    &aux.lde_trace_merkle_tree,
-                    &round_1_result.lde_trace.aux_table,
-                    *index,
-                )
-            });
+            let aux_trace_polys = round_1_result
+                .aux
+                .as_ref()
+                .map(|aux| {
+                    Self::open_trace_polys::<A::FieldExtension>(
+                        domain,
+                        &aux.lde_trace_merkle_tree,
+                        &round_1_result.lde_trace.aux_table,
+                        *index,
+                    )
+                })
+                .transpose()?;
 
             openings.push(DeepPolynomialOpening {
                 composition_poly: composition_openings,
@@ -783,55 +1575,293 @@ pub trait IsStarkProver<A: AIR> {
             });
         }
 
-        openings
+        Ok(openings)
     }
 
-    // FIXME remove unwrap() calls and return errors
     /// Generates a STARK proof for the trace `main_trace` with public inputs `pub_inputs`.
+    /// In zero-knowledge mode, the masking randomness is seeded from the operating system,
+    /// which needs the `std` feature; without it, use `prove_with_zk_seed`.
     /// Warning: the transcript must be safely initializated before passing it to this method.
     fn prove(
         main_trace: &TraceTable<A::Field>,
         pub_inputs: &A::PublicInputs,
         proof_options: &ProofOptions,
-        mut transcript: impl IsTranscript<A::FieldExtension>,
-    ) -> Result<StarkProof<A::Field, A::FieldExtension>, ProvingError>
+        transcript: impl IsTranscript<A::FieldExtension>,
+    ) -> Result<StarkProof<A::Field, A::FieldExtension, VC>, ProvingError>
     where
         A: Send + Sync,
         FieldElement<A::Field>: AsBytes + Send + Sync,
         FieldElement<A::FieldExtension>: AsBytes + Send + Sync,
     {
-        info!("Started proof generation...");
-        #[cfg(feature = "instruments")]
-        println!("- Started round 0: Air Initialization");
-        #[cfg(feature = "instruments")]
-        let timer0 = Instant::now();
-
-        let air = A::new(main_trace.n_rows(), pub_inputs, proof_options);
-        let domain = Domain::new(&air);
-
-        #[cfg(feature = "instruments")]
-        let elapsed0 = timer0.elapsed();
-        #[cfg(feature = "instruments")]
-        println!("  Time spent: {:?}", elapsed0);
-
-        // ===================================
-        // ==========|   Round 1   |==========
-        // ===================================
-
-        #[cfg(feature = "instruments")]
-        println!("- Started round 1: RAP");
-        #[cfg(feature = "instruments")]
-        let timer1 = Instant::now();
-
-        let round_1_result = Self::round_1_randomized_air_with_preprocessing(
-            &air,
+        Self::prove_and_measure(
             main_trace,
-            &domain,
-            &mut transcript,
-        )?;
+            pub_inputs,
+            proof_options,
+            transcript,
+            None,
+            None,
+            None,
+            None,
+            false,
+        )
+    }
 
-        #[cfg(debug_assertions)]
-        validate_trace(
+    /// Same as `prove`, with a `TestTranscript` returning `challenges` and then the query
+    /// `indexes` instead of sampling them, so that the challenges of the proof are known in
+    /// advance. The proof is not sound, it only verifies with `verify_with_test_transcript`
+    /// given the same challenges. For testing only.
+    #[cfg(feature = "test_fiat_shamir")]
+    fn prove_with_test_transcript(
+        main_trace: &TraceTable<A::Field>,
+        pub_inputs: &A::PublicInputs,
+        proof_options: &ProofOptions,
+        challenges: &[FieldElement<A::FieldExtension>],
+        indexes: &[u64],
+    ) -> Result<StarkProof<A::Field, A::FieldExtension, VC>, ProvingError>
+    where
+        A: Send + Sync,
+        FieldElement<A::Field>: AsBytes + Send + Sync,
+        FieldElement<A::FieldExtension>: AsBytes + Send + Sync,
+    {
+        Self::prove(
+            main_trace,
+            pub_inputs,
+            proof_options,
+            crate::transcript::test_transcript(challenges, indexes),
+        )
+    }
+
+    /// Same as `prove`, with the masking randomness of the zero-knowledge mode derived from
+    /// `zk_seed`, so that the same seed gives the same proof. The seed is not used unless
+    /// `proof_options.zero_knowledge` is set.
+    /// Warning: the transcript must be safely initializated before passing it to this method.
+    fn prove_with_zk_seed(
+        main_trace: &TraceTable<A::Field>,
+        pub_inputs: &A::PublicInputs,
+        proof_options: &ProofOptions,
+        transcript: impl IsTranscript<A::FieldExtension>,
+        zk_seed: [u8; 32],
+    ) -> Result<StarkProof<A::Field, A::FieldExtension, VC>, ProvingError>
+    where
+        A: Send + Sync,
+        FieldElement<A::Field>: AsBytes + Send + Sync,
+        FieldElement<A::FieldExtension>: AsBytes + Send + Sync,
+    {
+        Self::prove_and_measure(
+            main_trace,
+            pub_inputs,
+            proof_options,
+            transcript,
+            Some(zk_seed),
+            None,
+            None,
+            None,
+            false,
+        )
+    }
+
+    /// Same as `prove`, over a domain built beforehand, for instance with `Domain::for_params`,
+    /// so that it can be shared by several proofs of the same size. The domain must be the one
+    /// `Domain::new` would build for the AIR.
+    /// Warning: the transcript must be safely initializated before passing it to this method.
+    fn prove_with_domain(
+        main_trace: &TraceTable<A::Field>,
+        pub_inputs: &A::PublicInputs,
+        proof_options: &ProofOptions,
+        domain: &Domain<A::Field>,
+        transcript: impl IsTranscript<A::FieldExtension>,
+    ) -> Result<StarkProof<A::Field, A::FieldExtension, VC>, ProvingError>
+    where
+        A: Send + Sync,
+        FieldElement<A::Field>: AsBytes + Send + Sync,
+        FieldElement<A::FieldExtension>: AsBytes + Send + Sync,
+    {
+        Self::prove_and_measure(
+            main_trace,
+            pub_inputs,
+            proof_options,
+            transcript,
+            None,
+            Some(domain),
+            None,
+            None,
+            false,
+        )
+    }
+
+    /// Same as `prove`, also returning the time spent in each round of the protocol.
+    /// Warning: the transcript must be safely initializated before passing it to this method.
+    #[allow(clippy::type_complexity)]
+    fn prove_with_timings(
+        main_trace: &TraceTable<A::Field>,
+        pub_inputs: &A::PublicInputs,
+        proof_options: &ProofOptions,
+        transcript: impl IsTranscript<A::FieldExtension>,
+    ) -> Result<(StarkProof<A::Field, A::FieldExtension, VC>, ProvingTimings), ProvingError>
+    where
+        A: Send + Sync,
+        FieldElement<A::Field>: AsBytes + Send + Sync,
+        FieldElement<A::FieldExtension>: AsBytes + Send + Sync,
+    {
+        let mut timings = ProvingTimings::default();
+        let proof = Self::prove_and_measure(
+            main_trace,
+            pub_inputs,
+            proof_options,
+            transcript,
+            None,
+            None,
+            Some(&mut timings),
+            None,
+            false,
+        )?;
+        Ok((proof, timings))
+    }
+
+    /// Same as `prove`, calling `progress` with each stage of the protocol as it is reached:
+    /// at the start of each round, after each FRI layer is folded, and once the proof is
+    /// built. `ProveStage::percentage` gives an estimate of the progress of each stage.
+    /// Warning: the transcript must be safely initializated before passing it to this method.
+    fn prove_with_progress(
+        main_trace: &TraceTable<A::Field>,
+        pub_inputs: &A::PublicInputs,
+        proof_options: &ProofOptions,
+        transcript: impl IsTranscript<A::FieldExtension>,
+        progress: &mut dyn FnMut(ProveStage),
+    ) -> Result<StarkProof<A::Field, A::FieldExtension, VC>, ProvingError>
+    where
+        A: Send + Sync,
+        FieldElement<A::Field>: AsBytes + Send + Sync,
+        FieldElement<A::FieldExtension>: AsBytes + Send + Sync,
+    {
+        Self::prove_and_measure(
+            main_trace,
+            pub_inputs,
+            proof_options,
+            transcript,
+            None,
+            None,
+            None,
+            Some(progress),
+            false,
+        )
+    }
+
+    // FIXME remove unwrap() calls and return errors
+    /// Runs the STARK Prove protocol over `domain`, or over the domain of the AIR if not
+    /// given. In zero-knowledge mode, the masking randomness is derived from `zk_seed`, or
+    /// seeded from the operating system if not given; otherwise no randomness is drawn. If
+    /// `timings` is given, the duration of each round is recorded in it; otherwise the clock is
+    /// never read. If `progress` is given, it is called with each stage of the protocol. If
+    /// `compact_fri` is set, the FRI layers are committed with `fri::commit_phase_compact`.
+    #[allow(clippy::too_many_arguments)]
+    fn prove_and_measure(
+        main_trace: &TraceTable<A::Field>,
+        pub_inputs: &A::PublicInputs,
+        proof_options: &ProofOptions,
+        mut transcript: impl IsTranscript<A::FieldExtension>,
+        zk_seed: Option<[u8; 32]>,
+        domain: Option<&Domain<A::Field>>,
+        timings: Option<&mut ProvingTimings>,
+        mut progress: Option<&mut dyn FnMut(ProveStage)>,
+        compact_fri: bool,
+    ) -> Result<StarkProof<A::Field, A::FieldExtension, VC>, ProvingError>
+    where
+        A: Send + Sync,
+        FieldElement<A::Field>: AsBytes + Send + Sync,
+        FieldElement<A::FieldExtension>: AsBytes + Send + Sync,
+    {
+        info!("Started proof generation...");
+        report_progress(&mut progress, ProveStage::Initialization);
+        let mut timer = timings.is_some().then(RoundTimer::start);
+        let mut lap = || timer.as_mut().map(RoundTimer::lap).unwrap_or_default();
+
+        #[cfg(feature = "instruments")]
+        println!("- Started round 0: Air Initialization");
+        #[cfg(feature = "instruments")]
+        let timer0 = Instant::now();
+
+        let air = A::new(main_trace.n_rows(), pub_inputs, proof_options);
+        check_trace_shape(&air, main_trace)?;
+        check_trace_length(&air)?;
+        check_transition_exemptions(&air)?;
+        check_blowup_factor(&air)?;
+        check_composition_blowup_factor(&air)?;
+        check_two_adicity(&air)?;
+        if debug_enabled!() {
+            debug!(
+                "Interpolation degrees of the {} trace columns, for a trace of length {}: {:?}",
+                main_trace.n_cols(),
+                air.trace_length(),
+                main_trace.column_interpolation_degrees()
+            );
+        }
+        let air_domain;
+        let domain = match domain {
+            Some(domain) => {
+                check_domain(&air, domain)?;
+                domain
+            }
+            None => {
+                // With Metal, the FFTs run on the GPU, which computes its own twiddle factors.
+                air_domain = if cfg!(feature = "metal") {
+                    Domain::new(&air)
+                } else {
+                    Domain::new(&air).with_twiddle_cache()
+                };
+                &air_domain
+            }
+        };
+        check_distinct_queries(&air, domain)?;
+        let round_fields = RoundFields::new(&air, domain);
+
+        let mut masking = if proof_options.zero_knowledge {
+            zk::check_zero_knowledge_parameters(&air)?;
+            let zk_seed = match zk_seed {
+                Some(zk_seed) => zk_seed,
+                None => random_zk_seed()?,
+            };
+            Some(ZkMasking::new(
+                zk_seed,
+                zk::num_trace_masking_coefficients(&air),
+            ))
+        } else {
+            None
+        };
+
+        // The transcript is copied before it receives anything, to replay it as the verifier.
+        #[cfg(any(debug_assertions, feature = "self_check"))]
+        let initial_transcript = transcript.try_fork();
+
+        Self::round_0_transcript_initialization(&air, &mut transcript);
+        let initialization_time = lap();
+
+        #[cfg(feature = "instruments")]
+        let elapsed0 = timer0.elapsed();
+        #[cfg(feature = "instruments")]
+        println!("  Time spent: {:?}", elapsed0);
+
+        // ===================================
+        // ==========|   Round 1   |==========
+        // ===================================
+
+        report_progress(&mut progress, ProveStage::Round1);
+        let round_span = RoundSpan::enter("round_1", round_fields);
+        #[cfg(feature = "instruments")]
+        println!("- Started round 1: RAP");
+        #[cfg(feature = "instruments")]
+        let timer1 = Instant::now();
+
+        let round_1_result = Self::round_1_randomized_air_with_preprocessing(
+            &air,
+            main_trace,
+            domain,
+            &mut transcript,
+            masking.as_mut(),
+        )?;
+
+        #[cfg(debug_assertions)]
+        validate_trace(
             &air,
             &round_1_result.main.trace_polys,
             round_1_result
@@ -839,9 +1869,11 @@ pub trait IsStarkProver<A: AIR> {
                 .as_ref()
                 .map(|a| &a.trace_polys)
                 .unwrap_or(&vec![]),
-            &domain,
+            domain,
             &round_1_result.rap_challenges,
         );
+        let round_1_time = lap();
+        round_span.finish();
 
         #[cfg(feature = "instruments")]
         let elapsed1 = timer1.elapsed();
@@ -852,24 +1884,22 @@ pub trait IsStarkProver<A: AIR> {
         // ==========|   Round 2   |==========
         // ===================================
 
+        report_progress(&mut progress, ProveStage::Round2);
+        let round_span = RoundSpan::enter("round_2", round_fields);
         #[cfg(feature = "instruments")]
         println!("- Started round 2: Compute composition polynomial");
         #[cfg(feature = "instruments")]
         let timer2 = Instant::now();
 
-        // <<<< Receive challenge: 𝛽
-        let beta = transcript.sample_field_element();
-        let num_boundary_constraints = air
-            .boundary_constraints(&round_1_result.rap_challenges)
-            .constraints
-            .len();
+        let num_boundary_constraints = air.num_boundary_constraints(&round_1_result.rap_challenges);
 
         let num_transition_constraints = air.context().num_transition_constraints;
 
-        let mut coefficients: Vec<_> =
-            core::iter::successors(Some(FieldElement::one()), |x| Some(x * &beta))
-                .take(num_boundary_constraints + num_transition_constraints)
-                .collect();
+        // <<<< Receive challenges: 𝛽ⱼ
+        let mut coefficients = air.options().constraint_combination.sample_coefficients(
+            num_boundary_constraints + num_transition_constraints,
+            &mut transcript,
+        );
 
         let transition_coefficients: Vec<_> =
             coefficients.drain(..num_transition_constraints).collect();
@@ -877,14 +1907,17 @@ pub trait IsStarkProver<A: AIR> {
 
         let round_2_result = Self::round_2_compute_composition_polynomial(
             &air,
-            &domain,
+            domain,
             &round_1_result,
             &transition_coefficients,
             &boundary_coefficients,
-        );
+            masking.as_mut(),
+        )?;
 
         // >>>> Send commitments: [H₁], [H₂]
-        transcript.append_bytes(&round_2_result.composition_poly_root);
+        transcript.append_bytes(round_2_result.composition_poly_root.as_ref());
+        let round_2_time = lap();
+        round_span.finish();
 
         #[cfg(feature = "instruments")]
         let elapsed2 = timer2.elapsed();
@@ -895,6 +1928,8 @@ pub trait IsStarkProver<A: AIR> {
         // ==========|   Round 3   |==========
         // ===================================
 
+        report_progress(&mut progress, ProveStage::Round3);
+        let round_span = RoundSpan::enter("round_3", round_fields);
         #[cfg(feature = "instruments")]
         println!("- Started round 3: Evaluate polynomial in out of domain elements");
         #[cfg(feature = "instruments")]
@@ -905,8 +1940,1065 @@ pub trait IsStarkProver<A: AIR> {
             &domain.lde_roots_of_unity_coset,
             &domain.trace_roots_of_unity,
         );
-
-        let round_3_result = Self::round_3_evaluate_polynomials_in_out_of_domain_element(
+
+        let round_3_result = Self::round_3_evaluate_polynomials_in_out_of_domain_element(
+            &air,
+            domain,
+            &round_1_result,
+            &round_2_result,
+            &z,
+        );
+
+        // >>>> Send values: tⱼ(zgᵏ)
+        let trace_ood_evaluations_columns = round_3_result.trace_ood_evaluations.columns();
+        for col in trace_ood_evaluations_columns.iter() {
+            for elem in col.iter() {
+                transcript.append_field_element(elem);
+            }
+        }
+
+        // >>>> Send values: Hᵢ(z^N)
+        for element in round_3_result.composition_poly_parts_ood_evaluation.iter() {
+            transcript.append_field_element(element);
+        }
+        let round_3_time = lap();
+        round_span.finish();
+
+        #[cfg(feature = "instruments")]
+        let elapsed3 = timer3.elapsed();
+        #[cfg(feature = "instruments")]
+        println!("  Time spent: {:?}", elapsed3);
+
+        // ===================================
+        // ==========|   Round 4   |==========
+        // ===================================
+
+        report_progress(&mut progress, ProveStage::Round4);
+        let round_span = RoundSpan::enter("round_4", round_fields);
+        #[cfg(feature = "instruments")]
+        println!("- Started round 4: FRI");
+        #[cfg(feature = "instruments")]
+        let timer4 = Instant::now();
+
+        // Part of this round is running FRI, which is an interactive
+        // protocol on its own. Therefore we pass it the transcript
+        // to simulate the interactions with the verifier.
+        let round_4_result = Self::round_4_compute_and_run_fri_on_the_deep_composition_polynomial(
+            &air,
+            domain,
+            &round_1_result,
+            &round_2_result,
+            &round_3_result,
+            &z,
+            &mut transcript,
+            compact_fri,
+            |layer, layers| {
+                report_progress(&mut progress, ProveStage::FriFolding { layer, layers })
+            },
+        )?;
+        let round_4_time = lap();
+        round_span.finish();
+
+        if let (Some(timings), Some(timer)) = (timings, timer) {
+            *timings = ProvingTimings {
+                initialization: initialization_time,
+                round_1: round_1_time,
+                round_2: round_2_time,
+                round_3: round_3_time,
+                round_4: round_4_time,
+                total: timer.round_start.duration_since(&timer.start),
+            };
+        }
+
+        #[cfg(feature = "instruments")]
+        let elapsed4 = timer4.elapsed();
+        #[cfg(feature = "instruments")]
+        println!("  Time spent: {:?}", elapsed4);
+
+        #[cfg(feature = "instruments")]
+        {
+            let total_time = elapsed1 + elapsed2 + elapsed3 + elapsed4;
+            println!(
+                " Fraction of proving time per round: {:.4} {:.4} {:.4} {:.4} {:.4}",
+                elapsed0.as_nanos() as f64 / total_time.as_nanos() as f64,
+                elapsed1.as_nanos() as f64 / total_time.as_nanos() as f64,
+                elapsed2.as_nanos() as f64 / total_time.as_nanos() as f64,
+                elapsed3.as_nanos() as f64 / total_time.as_nanos() as f64,
+                elapsed4.as_nanos() as f64 / total_time.as_nanos() as f64
+            );
+        }
+
+        info!("End proof generation");
+
+        let proof = StarkProof::<A::Field, A::FieldExtension, VC> {
+            proof_version: PROOF_VERSION,
+            parameter_hash: air.parameter_hash(),
+            // [t]
+            lde_trace_main_merkle_root: round_1_result.main.lde_trace_merkle_root,
+            // [t]
+            lde_trace_aux_merkle_root: round_1_result.aux.map(|x| x.lde_trace_merkle_root),
+            // tⱼ(zgᵏ)
+            trace_ood_evaluations: round_3_result.trace_ood_evaluations,
+            // [H₁] and [H₂]
+            composition_poly_root: round_2_result.composition_poly_root,
+            // Hᵢ(z^N)
+            composition_poly_parts_ood_evaluation: round_3_result
+                .composition_poly_parts_ood_evaluation,
+            // [pₖ]
+            fri_layers_merkle_roots: round_4_result.fri_layers_merkle_roots,
+            // pₙ
+            fri_final_poly: round_4_result.fri_final_poly,
+            // Open(p₀(D₀), 𝜐ₛ), Open(pₖ(Dₖ), −𝜐ₛ^(2ᵏ))
+            query_list: round_4_result.query_list,
+            // Open(H₁(D_LDE, 𝜐₀), Open(H₂(D_LDE, 𝜐₀), Open(tⱼ(D_LDE), 𝜐₀)
+            // Open(H₁(D_LDE, -𝜐ᵢ), Open(H₂(D_LDE, -𝜐ᵢ), Open(tⱼ(D_LDE), -𝜐ᵢ)
+            deep_poly_openings: round_4_result.deep_poly_openings,
+            // nonce obtained from grinding
+            nonce: round_4_result.nonce,
+
+            trace_length: air.trace_length(),
+        };
+
+        #[cfg(any(debug_assertions, feature = "self_check"))]
+        if let Some(initial_transcript) = initial_transcript {
+            check_query_indexes(
+                &air,
+                &proof,
+                &round_4_result.query_indexes,
+                domain,
+                initial_transcript,
+            );
+        }
+
+        report_progress(&mut progress, ProveStage::Done);
+        Ok(proof)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "tracing")]
+    use std::collections::HashMap;
+    use std::num::ParseIntError;
+
+    fn decode_hex(s: &str) -> Result<Vec<u8>, ParseIntError> {
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16))
+            .collect()
+    }
+
+    use crate::{
+        config::BatchedMerkleTree,
+        constraints::{
+            boundary::{BoundaryConstraint, BoundaryConstraints},
+            transition::TransitionConstraint,
+        },
+        context::AirContext,
+        examples::{
+            bit_column::{bit_column_trace, BitColumnAIR},
+            fibonacci_2_cols_shifted::{self, Fibonacci2ColsShifted},
+            fibonacci_2_columns::Fibonacci2ColsAIR,
+            simple_fibonacci::{self, FibonacciAIR, FibonacciPublicInputs},
+        },
+        frame::Frame,
+        proof::options::{CombinationMode, ProofOptions},
+        transcript::StoneProverTranscript,
+        verifier::{Challenges, IsStarkVerifier, Verifier},
+        Felt252,
+    };
+
+    use super::*;
+    use lambdaworks_math::{
+        fft::cpu::bit_reversing::in_place_bit_reverse_permute,
+        field::{
+            element::FieldElement,
+            fields::{
+                fft_friendly::stark_252_prime_field::Stark252PrimeField,
+                u64_prime_field::{F17, FE17},
+            },
+            traits::IsFFTField,
+        },
+        polynomial::Polynomial,
+    };
+
+    #[test]
+    fn duplicate_trace_columns_are_committed_as_if_interpolated_independently() {
+        type P = Prover<FibonacciAIR<Stark252PrimeField>>;
+
+        let trace_length = 8;
+        let column: Vec<_> = (0..trace_length as u64).map(Felt252::from).collect();
+        let constant = vec![Felt252::from(5u64); trace_length];
+        let trace = TraceTable::from_columns_main(
+            vec![column.clone(), constant.clone(), column, constant],
+            1,
+        );
+        assert_eq!(
+            group_duplicate_columns(&trace.columns()),
+            (vec![0, 1], vec![0, 1, 0, 1])
+        );
+
+        let pub_inputs = FibonacciPublicInputs {
+            a0: Felt252::one(),
+            a1: Felt252::one(),
+        };
+        let air = FibonacciAIR::new(
+            trace_length,
+            &pub_inputs,
+            &ProofOptions::default_test_options(),
+        );
+        let domain = Domain::new(&air);
+
+        let (trace_polys, lde_trace_evaluations, _, root) =
+            P::interpolate_and_commit(&trace, &domain, &mut StoneProverTranscript::new(&[]), None);
+
+        let independent_trace_polys = trace.compute_trace_polys::<Stark252PrimeField>();
+        let independent_lde_trace_evaluations =
+            P::compute_lde_trace_evaluations(&independent_trace_polys, &domain);
+        let mut independent_lde_trace_permuted = independent_lde_trace_evaluations.clone();
+        for col in independent_lde_trace_permuted.iter_mut() {
+            in_place_bit_reverse_permute(col);
+        }
+        let (_, independent_root) = P::batch_commit(&columns2rows(independent_lde_trace_permuted));
+
+        assert_eq!(trace_polys, independent_trace_polys);
+        assert_eq!(lde_trace_evaluations, independent_lde_trace_evaluations);
+        assert_eq!(root, independent_root);
+    }
+
+    #[test]
+    fn opening_a_query_index_out_of_the_lde_domain_fails() {
+        type P = Prover<FibonacciAIR<Stark252PrimeField>>;
+
+        let trace = simple_fibonacci::fibonacci_trace([Felt252::from(1), Felt252::from(1)], 8);
+        let pub_inputs = FibonacciPublicInputs {
+            a0: Felt252::one(),
+            a1: Felt252::one(),
+        };
+        let air = FibonacciAIR::new(8, &pub_inputs, &ProofOptions::default_test_options());
+        let domain = Domain::new(&air);
+        let (_, lde_trace_evaluations, tree, _) =
+            P::interpolate_and_commit(&trace, &domain, &mut StoneProverTranscript::new(&[]), None);
+        let lde_trace = Table::from_columns(lde_trace_evaluations);
+
+        let domain_size = domain.lde_roots_of_unity_coset.len();
+        let last_query_index = domain_size / 2 - 1;
+        assert!(P::open_trace_polys(&domain, &tree, &lde_trace, last_query_index).is_ok());
+        assert!(matches!(
+            P::open_trace_polys(&domain, &tree, &lde_trace, last_query_index + 1),
+            Err(ProvingError::MerkleError(MerkleError::IndexOutOfRange { index, len }))
+                if index == domain_size && len == domain_size
+        ));
+    }
+
+    #[test]
+    fn opening_a_row_out_of_the_lde_trace_fails() {
+        type P = Prover<FibonacciAIR<Stark252PrimeField>>;
+
+        let trace = simple_fibonacci::fibonacci_trace([Felt252::from(1), Felt252::from(1)], 8);
+        let pub_inputs = FibonacciPublicInputs {
+            a0: Felt252::one(),
+            a1: Felt252::one(),
+        };
+        let air = FibonacciAIR::new(8, &pub_inputs, &ProofOptions::default_test_options());
+        let domain = Domain::new(&air);
+        let (_, lde_trace_evaluations, tree, _) =
+            P::interpolate_and_commit(&trace, &domain, &mut StoneProverTranscript::new(&[]), None);
+
+        // The symmetric point of the first query is the row in the middle of the LDE, which is
+        // missing from a table with only its first half.
+        let domain_size = domain.lde_roots_of_unity_coset.len();
+        let lde_trace = Table::from_columns(
+            lde_trace_evaluations
+                .into_iter()
+                .map(|column| column[..domain_size / 2].to_vec())
+                .collect(),
+        );
+        assert!(matches!(
+            P::open_trace_polys(&domain, &tree, &lde_trace, 0),
+            Err(ProvingError::TraceIndexOutOfRange(TraceError::RowOutOfRange { row_idx, n_rows }))
+                if row_idx == domain_size / 2 && n_rows == domain_size / 2
+        ));
+    }
+
+    #[test]
+    fn proving_over_a_coset_offset_in_the_trace_domain_fails() {
+        let trace = simple_fibonacci::fibonacci_trace([Felt252::from(1), Felt252::from(1)], 8);
+        let pub_inputs = FibonacciPublicInputs {
+            a0: Felt252::one(),
+            a1: Felt252::one(),
+        };
+        let mut proof_options = ProofOptions::default_test_options();
+        // The LDE domain contains the trace domain, where the boundary zerofiers vanish.
+        proof_options.coset_offset = 1;
+
+        let result = Prover::<FibonacciAIR<Stark252PrimeField>>::prove(
+            &trace,
+            &pub_inputs,
+            &proof_options,
+            StoneProverTranscript::new(&[]),
+        );
+        assert!(matches!(
+            result,
+            Err(ProvingError::FieldError(FieldError::InvZeroError))
+        ));
+    }
+
+    #[test]
+    fn composition_domain_is_a_subset_of_the_lde_domain() {
+        let pub_inputs = FibonacciPublicInputs {
+            a0: Felt252::one(),
+            a1: Felt252::one(),
+        };
+        let proof_options = ProofOptions {
+            composition_blowup_factor: Some(2),
+            fri_final_poly_degree: 0,
+            ..ProofOptions::default_test_options()
+        };
+        let air = FibonacciAIR::new(16, &pub_inputs, &proof_options);
+        let domain = Domain::new(&air);
+        let composition_domain = domain.composition_domain();
+
+        // The constraints are evaluated over a domain of size 16 * 2 instead of 16 * 4.
+        let step = domain.blowup_factor / 2;
+        assert_eq!(composition_domain.lde_roots_of_unity_coset.len(), 16 * 2);
+        for (i, element) in composition_domain
+            .lde_roots_of_unity_coset
+            .iter()
+            .enumerate()
+        {
+            assert_eq!(element, &domain.lde_roots_of_unity_coset[i * step]);
+        }
+    }
+
+    #[test]
+    fn prove_rejects_trace_with_too_few_columns() {
+        let trace = simple_fibonacci::fibonacci_trace([Felt252::from(1), Felt252::from(1)], 8);
+        let pub_inputs = FibonacciPublicInputs {
+            a0: Felt252::one(),
+            a1: Felt252::one(),
+        };
+
+        let result = Prover::<Fibonacci2ColsAIR<Stark252PrimeField>>::prove(
+            &trace,
+            &pub_inputs,
+            &ProofOptions::default_test_options(),
+            StoneProverTranscript::new(&[]),
+        );
+
+        assert!(matches!(
+            result,
+            Err(ProvingError::TraceShapeMismatch {
+                expected: (8, 2),
+                got: (8, 1)
+            })
+        ));
+    }
+
+    #[test]
+    fn prove_rejects_trace_with_wrong_length() {
+        let trace = simple_fibonacci::fibonacci_trace([Felt252::from(1), Felt252::from(1)], 6);
+        let pub_inputs = FibonacciPublicInputs {
+            a0: Felt252::one(),
+            a1: Felt252::one(),
+        };
+
+        let result = Prover::<FibonacciAIR<Stark252PrimeField>>::prove(
+            &trace,
+            &pub_inputs,
+            &ProofOptions::default_test_options(),
+            StoneProverTranscript::new(&[]),
+        );
+
+        assert!(matches!(
+            result,
+            Err(ProvingError::TraceShapeMismatch {
+                expected: (8, 1),
+                got: (6, 1)
+            })
+        ));
+    }
+
+    #[test]
+    fn builder_with_defaults_gives_the_proof_of_prove() {
+        let trace = simple_fibonacci::fibonacci_trace([Felt252::from(1), Felt252::from(1)], 8);
+        let pub_inputs = FibonacciPublicInputs {
+            a0: Felt252::one(),
+            a1: Felt252::one(),
+        };
+        let proof_options = ProofOptions::default_test_options();
+
+        let proof = Prover::<FibonacciAIR<Stark252PrimeField>>::prove(
+            &trace,
+            &pub_inputs,
+            &proof_options,
+            StoneProverTranscript::new(&[]),
+        )
+        .unwrap();
+        let built_proof = Prover::<FibonacciAIR<Stark252PrimeField>>::builder(
+            &pub_inputs,
+            &proof_options,
+            StoneProverTranscript::new(&[]),
+        )
+        .prove(&trace)
+        .unwrap();
+
+        assert_eq!(proof, built_proof);
+    }
+
+    #[test]
+    fn builder_knobs_take_effect() {
+        type P = Prover<FibonacciAIR<Stark252PrimeField>>;
+
+        let trace = simple_fibonacci::fibonacci_trace([Felt252::from(1), Felt252::from(1)], 32);
+        let pub_inputs = FibonacciPublicInputs {
+            a0: Felt252::one(),
+            a1: Felt252::one(),
+        };
+        let proof_options = ProofOptions {
+            zero_knowledge: true,
+            ..ProofOptions::default_test_options()
+        };
+
+        // The transcript.
+        let proof = P::builder(&pub_inputs, &proof_options, StoneProverTranscript::new(&[]))
+            .with_transcript(StoneProverTranscript::new(&[1]))
+            .with_zk_seed([1; 32])
+            .prove(&trace)
+            .unwrap();
+        assert!(Verifier::<FibonacciAIR<Stark252PrimeField>>::verify(
+            &proof,
+            &pub_inputs,
+            &proof_options,
+            StoneProverTranscript::new(&[1]),
+        ));
+        assert!(!Verifier::<FibonacciAIR<Stark252PrimeField>>::verify(
+            &proof,
+            &pub_inputs,
+            &proof_options,
+            StoneProverTranscript::new(&[]),
+        ));
+
+        // The seed of the zero-knowledge masking.
+        let prove_with_seed = |zk_seed| {
+            P::builder(&pub_inputs, &proof_options, StoneProverTranscript::new(&[]))
+                .with_zk_seed(zk_seed)
+                .prove(&trace)
+                .unwrap()
+        };
+        assert_eq!(prove_with_seed([1; 32]), prove_with_seed([1; 32]));
+        assert_ne!(prove_with_seed([1; 32]), prove_with_seed([2; 32]));
+
+        // The domain.
+        let domain = Domain::for_params(64, 4, 3);
+        let result = P::builder(&pub_inputs, &proof_options, StoneProverTranscript::new(&[]))
+            .with_domain(&domain)
+            .prove(&trace);
+        assert!(matches!(result, Err(ProvingError::WrongParameter(_))));
+
+        // The timings and the progress callback.
+        let mut timings = ProvingTimings::default();
+        let mut stages = Vec::new();
+        let mut progress = |stage: ProveStage| stages.push(stage);
+        P::builder(&pub_inputs, &proof_options, StoneProverTranscript::new(&[]))
+            .with_timings(&mut timings)
+            .with_progress(&mut progress)
+            .prove(&trace)
+            .unwrap();
+        assert!(timings.total > Duration::ZERO);
+        assert_eq!(stages.last(), Some(&ProveStage::Done));
+    }
+
+    #[test]
+    fn compact_fri_layers_give_the_same_proof() {
+        type P = Prover<FibonacciAIR<Stark252PrimeField>>;
+
+        let trace = simple_fibonacci::fibonacci_trace([Felt252::from(1), Felt252::from(1)], 32);
+        let pub_inputs = FibonacciPublicInputs {
+            a0: Felt252::one(),
+            a1: Felt252::one(),
+        };
+        let proof_options = ProofOptions::default_test_options();
+
+        let prove = |compact_fri| {
+            let mut stages = Vec::new();
+            let mut progress = |stage: ProveStage| stages.push(stage);
+            let proof = P::builder(&pub_inputs, &proof_options, StoneProverTranscript::new(&[]))
+                .with_compact_fri(compact_fri)
+                .with_progress(&mut progress)
+                .prove(&trace)
+                .unwrap();
+            (proof, stages)
+        };
+        let (proof, stages) = prove(false);
+        let (compact_proof, compact_stages) = prove(true);
+
+        assert!(!proof.query_list[0].layers_auth_paths.is_empty());
+        assert_eq!(proof, compact_proof);
+        assert_eq!(stages, compact_stages);
+    }
+
+    #[test]
+    fn progress_is_reported_once_per_round_in_order() {
+        let trace = simple_fibonacci::fibonacci_trace([Felt252::from(1), Felt252::from(1)], 8);
+        let pub_inputs = FibonacciPublicInputs {
+            a0: Felt252::one(),
+            a1: Felt252::one(),
+        };
+        let proof_options = ProofOptions::default_test_options();
+
+        let mut stages = Vec::new();
+        let proof = Prover::<FibonacciAIR<Stark252PrimeField>>::prove_with_progress(
+            &trace,
+            &pub_inputs,
+            &proof_options,
+            StoneProverTranscript::new(&[]),
+            &mut |stage| stages.push(stage),
+        )
+        .unwrap();
+
+        let rounds: Vec<_> = stages
+            .iter()
+            .filter(|stage| !matches!(stage, ProveStage::FriFolding { .. }))
+            .copied()
+            .collect();
+        assert_eq!(
+            rounds,
+            vec![
+                ProveStage::Initialization,
+                ProveStage::Round1,
+                ProveStage::Round2,
+                ProveStage::Round3,
+                ProveStage::Round4,
+                ProveStage::Done,
+            ]
+        );
+
+        let (number_layers, _) = fri::fold_schedule(8, proof_options.fri_final_poly_degree);
+        let fri_stages: Vec<_> = (1..=number_layers)
+            .map(|layer| ProveStage::FriFolding {
+                layer,
+                layers: number_layers,
+            })
+            .collect();
+        assert_eq!(&stages[5..stages.len() - 1], fri_stages.as_slice());
+
+        assert!(stages
+            .windows(2)
+            .all(|pair| pair[0].percentage() <= pair[1].percentage()));
+
+        assert!(Verifier::<FibonacciAIR<Stark252PrimeField>>::verify(
+            &proof,
+            &pub_inputs,
+            &proof_options,
+            StoneProverTranscript::new(&[]),
+        ));
+    }
+
+    /// Records the name and the fields of every span, to test the spans of the `tracing`
+    /// feature.
+    #[cfg(feature = "tracing")]
+    #[derive(Clone, Default)]
+    struct SpanRecorder {
+        spans: std::sync::Arc<std::sync::Mutex<Vec<(&'static str, HashMap<&'static str, String>)>>>,
+    }
+
+    #[cfg(feature = "tracing")]
+    struct FieldRecorder<'a>(&'a mut HashMap<&'static str, String>);
+
+    #[cfg(feature = "tracing")]
+    impl tracing::field::Visit for FieldRecorder<'_> {
+        fn record_str(&mut self, field: &tracing::field::Field, value: &str) {
+            self.0.insert(field.name(), value.to_string());
+        }
+
+        fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn core::fmt::Debug) {
+            self.0.insert(field.name(), format!("{:?}", value));
+        }
+    }
+
+    #[cfg(feature = "tracing")]
+    impl tracing::Subscriber for SpanRecorder {
+        fn enabled(&self, _metadata: &tracing::Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, span: &tracing::span::Attributes<'_>) -> tracing::span::Id {
+            let mut fields = HashMap::new();
+            span.record(&mut FieldRecorder(&mut fields));
+            let mut spans = self.spans.lock().unwrap();
+            spans.push((span.metadata().name(), fields));
+            tracing::span::Id::from_u64(spans.len() as u64)
+        }
+
+        fn record(&self, span: &tracing::span::Id, values: &tracing::span::Record<'_>) {
+            let mut spans = self.spans.lock().unwrap();
+            let (_, fields) = &mut spans[span.into_u64() as usize - 1];
+            values.record(&mut FieldRecorder(fields));
+        }
+
+        fn record_follows_from(&self, _span: &tracing::span::Id, _follows: &tracing::span::Id) {}
+
+        fn event(&self, _event: &tracing::Event<'_>) {}
+
+        fn enter(&self, _span: &tracing::span::Id) {}
+
+        fn exit(&self, _span: &tracing::span::Id) {}
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn every_round_is_a_span_with_the_dimensions_of_the_proof() {
+        let trace = simple_fibonacci::fibonacci_trace([Felt252::from(1), Felt252::from(1)], 8);
+        let pub_inputs = FibonacciPublicInputs {
+            a0: Felt252::one(),
+            a1: Felt252::one(),
+        };
+        let proof_options = ProofOptions::default_test_options();
+
+        let recorder = SpanRecorder::default();
+        tracing::subscriber::with_default(recorder.clone(), || {
+            Prover::<FibonacciAIR<Stark252PrimeField>>::prove(
+                &trace,
+                &pub_inputs,
+                &proof_options,
+                StoneProverTranscript::new(&[]),
+            )
+            .unwrap()
+        });
+
+        let spans = recorder.spans.lock().unwrap();
+        let rounds: Vec<_> = spans
+            .iter()
+            .filter(|(name, _)| *name == "prover_round")
+            .map(|(_, fields)| fields)
+            .collect();
+        assert_eq!(
+            rounds
+                .iter()
+                .map(|fields| fields["round"].as_str())
+                .collect::<Vec<_>>(),
+            ["round_1", "round_2", "round_3", "round_4"]
+        );
+        for fields in rounds {
+            assert_eq!(fields["trace_length"], "8");
+            assert_eq!(fields["trace_columns"], "1");
+            assert_eq!(fields["blowup_factor"], "4");
+            assert_eq!(fields["lde_domain_size"], "32");
+            assert!(fields.contains_key("duration_us"));
+        }
+    }
+
+    #[test]
+    fn round_timings_add_up_to_the_total_proving_time() {
+        let trace = simple_fibonacci::fibonacci_trace([Felt252::from(1), Felt252::from(1)], 8);
+        let pub_inputs = FibonacciPublicInputs {
+            a0: Felt252::one(),
+            a1: Felt252::one(),
+        };
+        let proof_options = ProofOptions::default_test_options();
+
+        let (proof, timings) = Prover::<FibonacciAIR<Stark252PrimeField>>::prove_with_timings(
+            &trace,
+            &pub_inputs,
+            &proof_options,
+            StoneProverTranscript::new(&[]),
+        )
+        .unwrap();
+
+        let rounds = timings.initialization
+            + timings.round_1
+            + timings.round_2
+            + timings.round_3
+            + timings.round_4;
+        assert!(rounds <= timings.total);
+        assert!(timings.total - rounds <= timings.total / 10);
+
+        assert!(Verifier::<FibonacciAIR<Stark252PrimeField>>::verify(
+            &proof,
+            &pub_inputs,
+            &proof_options,
+            StoneProverTranscript::new(&[]),
+        ));
+    }
+
+    #[test]
+    fn deep_quotient_term_matches_the_division_by_the_linear_factor() {
+        let point = Felt252::from(5);
+        let gamma = Felt252::from(7);
+        let coefficients: Vec<_> = (0..8)
+            .map(|_| Felt252::from(rand::random::<u64>()))
+            .collect();
+
+        let poly = Polynomial::new(&coefficients);
+        let eval = poly.evaluate(&point);
+        let linear_factor = Polynomial::new(&[-&point, Felt252::one()]);
+        let expected = ((&poly - eval) / linear_factor) * gamma;
+        assert_eq!(
+            deep_quotient_term(&poly, &eval, &point, &gamma),
+            Some(expected)
+        );
+
+        let constant_poly = Polynomial::new(&coefficients[..1]);
+        let constant_eval = constant_poly.evaluate(&point);
+        assert!(deep_quotient_term(&constant_poly, &constant_eval, &point, &gamma).is_none());
+
+        assert!(deep_quotient_term(&poly, &eval, &point, &Felt252::zero()).is_none());
+    }
+
+    #[test]
+    fn trace_with_a_constant_column_is_proven() {
+        let trace = bit_column_trace(&[true; 8]);
+        let proof_options = ProofOptions::default_test_options();
+
+        let proof = Prover::<BitColumnAIR<Stark252PrimeField>>::prove(
+            &trace,
+            &(),
+            &proof_options,
+            StoneProverTranscript::new(&[]),
+        )
+        .unwrap();
+
+        assert!(Verifier::<BitColumnAIR<Stark252PrimeField>>::verify(
+            &proof,
+            &(),
+            &proof_options,
+            StoneProverTranscript::new(&[]),
+        ));
+    }
+
+    #[test]
+    fn prove_rejects_traces_shorter_than_the_minimum_trace_length() {
+        for trace_length in [1, 2] {
+            let trace = bit_column_trace(&vec![true; trace_length]);
+
+            let result = Prover::<BitColumnAIR<Stark252PrimeField>>::prove(
+                &trace,
+                &(),
+                &ProofOptions::default_test_options(),
+                StoneProverTranscript::new(&[]),
+            );
+
+            assert!(matches!(
+                result,
+                Err(ProvingError::TraceTooShort { minimum: 4 })
+            ));
+        }
+    }
+
+    #[test]
+    fn prove_with_shared_domain_gives_the_same_proof_as_prove() {
+        type P = Prover<FibonacciAIR<Stark252PrimeField>>;
+
+        let trace = simple_fibonacci::fibonacci_trace([Felt252::one(), Felt252::one()], 16);
+        let pub_inputs = FibonacciPublicInputs {
+            a0: Felt252::one(),
+            a1: Felt252::one(),
+        };
+        let proof_options = ProofOptions::default_test_options();
+        let domain = Domain::for_params(16, 4, 3);
+
+        let proof = P::prove(
+            &trace,
+            &pub_inputs,
+            &proof_options,
+            StoneProverTranscript::new(&[]),
+        )
+        .unwrap();
+        let proof_with_domain = P::prove_with_domain(
+            &trace,
+            &pub_inputs,
+            &proof_options,
+            &domain,
+            StoneProverTranscript::new(&[]),
+        )
+        .unwrap();
+
+        let config = bincode::config::standard();
+        assert_eq!(
+            bincode::serde::encode_to_vec(&proof, config).unwrap(),
+            bincode::serde::encode_to_vec(&proof_with_domain, config).unwrap()
+        );
+    }
+
+    #[test]
+    fn prove_with_domain_rejects_a_domain_of_another_size() {
+        let trace = simple_fibonacci::fibonacci_trace([Felt252::one(), Felt252::one()], 16);
+        let pub_inputs = FibonacciPublicInputs {
+            a0: Felt252::one(),
+            a1: Felt252::one(),
+        };
+
+        let result = Prover::<FibonacciAIR<Stark252PrimeField>>::prove_with_domain(
+            &trace,
+            &pub_inputs,
+            &ProofOptions::default_test_options(),
+            &Domain::for_params(32, 4, 3),
+            StoneProverTranscript::new(&[]),
+        );
+
+        assert!(matches!(result, Err(ProvingError::WrongParameter(_))));
+    }
+
+    #[test]
+    fn trace_of_the_minimum_trace_length_is_proven() {
+        let trace = bit_column_trace(&[true, false, false, true]);
+        let proof_options = ProofOptions::default_test_options();
+
+        let proof = Prover::<BitColumnAIR<Stark252PrimeField>>::prove(
+            &trace,
+            &(),
+            &proof_options,
+            StoneProverTranscript::new(&[]),
+        )
+        .unwrap();
+
+        assert!(Verifier::<BitColumnAIR<Stark252PrimeField>>::verify(
+            &proof,
+            &(),
+            &proof_options,
+            StoneProverTranscript::new(&[]),
+        ));
+    }
+
+    /// Constrains each row to be the cube of the previous one.
+    struct CubeConstraint;
+
+    impl TransitionConstraint<Stark252PrimeField, Stark252PrimeField> for CubeConstraint {
+        fn degree(&self) -> usize {
+            3
+        }
+
+        fn constraint_idx(&self) -> usize {
+            0
+        }
+
+        fn end_exemptions(&self) -> usize {
+            1
+        }
+
+        fn evaluate(
+            &self,
+            frame: &Frame<Stark252PrimeField, Stark252PrimeField>,
+            transition_evaluations: &mut [Felt252],
+            _periodic_values: &[Felt252],
+            _rap_challenges: &[Felt252],
+        ) {
+            let x = frame
+                .get_evaluation_step(0)
+                .get_main_evaluation_element(0, 0);
+            let x_next = frame
+                .get_evaluation_step(1)
+                .get_main_evaluation_element(0, 0);
+            transition_evaluations[0] = x_next - x * x * x;
+        }
+    }
+
+    /// An AIR of repeated cubing, with the default composition polynomial degree bound.
+    struct CubeAIR {
+        context: AirContext,
+        trace_length: usize,
+        constraints: Vec<Box<dyn TransitionConstraint<Stark252PrimeField, Stark252PrimeField>>>,
+    }
+
+    impl AIR for CubeAIR {
+        type Field = Stark252PrimeField;
+        type FieldExtension = Stark252PrimeField;
+        type PublicInputs = ();
+
+        const STEP_SIZE: usize = 1;
+
+        fn new(trace_length: usize, _pub_inputs: &(), proof_options: &ProofOptions) -> Self {
+            Self {
+                context: AirContext {
+                    proof_options: proof_options.clone(),
+                    trace_columns: 1,
+                    transition_offsets: vec![0, 1],
+                    transition_exemptions: vec![1],
+                    num_transition_constraints: 1,
+                },
+                trace_length,
+                constraints: vec![Box::new(CubeConstraint)],
+            }
+        }
+
+        fn trace_layout(&self) -> (usize, usize) {
+            (1, 0)
+        }
+
+        fn boundary_constraints(
+            &self,
+            _rap_challenges: &[Felt252],
+        ) -> BoundaryConstraints<Stark252PrimeField> {
+            BoundaryConstraints::from_constraints(vec![BoundaryConstraint::new_main(
+                0,
+                0,
+                Felt252::from(2),
+            )])
+        }
+
+        fn transition_constraints(
+            &self,
+        ) -> &Vec<Box<dyn TransitionConstraint<Stark252PrimeField, Stark252PrimeField>>> {
+            &self.constraints
+        }
+
+        fn compute_transition_verifier(
+            &self,
+            frame: &Frame<Stark252PrimeField, Stark252PrimeField>,
+            periodic_values: &[Felt252],
+            rap_challenges: &[Felt252],
+        ) -> Vec<Felt252> {
+            self.compute_transition_prover(frame, periodic_values, rap_challenges)
+        }
+
+        fn context(&self) -> &AirContext {
+            &self.context
+        }
+
+        fn trace_length(&self) -> usize {
+            self.trace_length
+        }
+
+        fn pub_inputs(&self) -> &Self::PublicInputs {
+            &()
+        }
+    }
+
+    fn cube_trace(trace_length: usize) -> TraceTable<Stark252PrimeField> {
+        let column: Vec<_> = core::iter::successors(Some(Felt252::from(2)), |x| Some(x * x * x))
+            .take(trace_length)
+            .collect();
+        TraceTable::from_columns_main(vec![column], 1)
+    }
+
+    #[test]
+    fn default_composition_poly_degree_bound_is_proven() {
+        let trace = cube_trace(8);
+        let proof_options = ProofOptions::default_test_options();
+
+        let air = CubeAIR::new(8, &(), &proof_options);
+        assert_eq!(air.composition_poly_degree_bound(), 4 * 8);
+
+        let proof =
+            Prover::<CubeAIR>::prove(&trace, &(), &proof_options, StoneProverTranscript::new(&[]))
+                .unwrap();
+        assert!(Verifier::<CubeAIR>::verify(
+            &proof,
+            &(),
+            &proof_options,
+            StoneProverTranscript::new(&[]),
+        ));
+    }
+
+    #[test]
+    fn prove_rejects_blowup_factor_smaller_than_the_composition_parts() {
+        let trace = cube_trace(8);
+        let proof_options = ProofOptions {
+            blowup_factor: 2,
+            ..ProofOptions::default_test_options()
+        };
+
+        let result =
+            Prover::<CubeAIR>::prove(&trace, &(), &proof_options, StoneProverTranscript::new(&[]));
+
+        assert!(matches!(
+            result,
+            Err(ProvingError::BlowupTooSmall { needed: 4, got: 2 })
+        ));
+    }
+
+    /// A cubing constraint exempted on more rows than a trace of length 8 has.
+    struct OverExemptedCubeConstraint;
+
+    impl TransitionConstraint<Stark252PrimeField, Stark252PrimeField> for OverExemptedCubeConstraint {
+        fn degree(&self) -> usize {
+            3
+        }
+
+        fn constraint_idx(&self) -> usize {
+            0
+        }
+
+        fn end_exemptions(&self) -> usize {
+            9
+        }
+
+        fn evaluate(
+            &self,
+            frame: &Frame<Stark252PrimeField, Stark252PrimeField>,
+            transition_evaluations: &mut [Felt252],
+            periodic_values: &[Felt252],
+            rap_challenges: &[Felt252],
+        ) {
+            CubeConstraint.evaluate(
+                frame,
+                transition_evaluations,
+                periodic_values,
+                rap_challenges,
+            );
+        }
+    }
+
+    #[test]
+    fn domain_larger_than_the_two_adic_subgroup_is_rejected() {
+        // The LDE domain of 32 elements does not fit in the 16 roots of unity of F17.
+        let pub_inputs = FibonacciPublicInputs {
+            a0: FE17::one(),
+            a1: FE17::one(),
+        };
+        let air = FibonacciAIR::<F17>::new(8, &pub_inputs, &ProofOptions::default_test_options());
+
+        assert!(matches!(
+            check_two_adicity(&air),
+            Err(ProvingError::InsufficientTwoAdicity {
+                needed: 5,
+                available: 4,
+            })
+        ));
+
+        let air = FibonacciAIR::<F17>::new(4, &pub_inputs, &ProofOptions::default_test_options());
+        assert!(check_two_adicity(&air).is_ok());
+    }
+
+    #[test]
+    fn composition_poly_reconstructed_from_its_parts_is_the_composition_poly() {
+        type P = Prover<CubeAIR>;
+
+        let trace = cube_trace(8);
+        let air = CubeAIR::new(8, &(), &ProofOptions::default_test_options());
+        let domain = Domain::new(&air);
+        let mut transcript = StoneProverTranscript::new(&[]);
+
+        P::round_0_transcript_initialization(&air, &mut transcript);
+        let round_1_result = P::round_1_randomized_air_with_preprocessing(
+            &air,
+            &trace,
+            &domain,
+            &mut transcript,
+            None,
+        )
+        .unwrap();
+        let beta: Felt252 = transcript.sample_field_element();
+        let coefficients: Vec<_> = core::iter::successors(Some(Felt252::one()), |x| Some(x * beta))
+            .take(air.num_boundary_constraints(&[]) + air.context().num_transition_constraints)
+            .collect();
+        let (transition_coefficients, boundary_coefficients) =
+            coefficients.split_at(air.context().num_transition_constraints);
+        let round_2_result = P::round_2_compute_composition_polynomial(
+            &air,
+            &domain,
+            &round_1_result,
+            transition_coefficients,
+            boundary_coefficients,
+            None,
+        )
+        .unwrap();
+        let z = transcript.sample_z_ood(
+            &domain.lde_roots_of_unity_coset,
+            &domain.trace_roots_of_unity,
+        );
+        let round_3_result = P::round_3_evaluate_polynomials_in_out_of_domain_element(
             &air,
             &domain,
             &round_1_result,
@@ -914,125 +3006,272 @@ pub trait IsStarkProver<A: AIR> {
             &z,
         );
 
-        // >>>> Send values: tⱼ(zgᵏ)
-        let trace_ood_evaluations_columns = round_3_result.trace_ood_evaluations.columns();
-        for col in trace_ood_evaluations_columns.iter() {
-            for elem in col.iter() {
-                transcript.append_field_element(elem);
+        // H = ∑ᵢ Xⁱ Hᵢ(Xᵈ), so the j-th coefficient of Hᵢ is the (jd + i)-th one of H.
+        let parts = &round_2_result.composition_poly_parts;
+        let number_of_parts = parts.len();
+        let mut coefficients =
+            vec![Felt252::zero(); number_of_parts * domain.interpolation_domain_size];
+        for (i, part) in parts.iter().enumerate() {
+            for (j, coefficient) in part.coefficients().iter().enumerate() {
+                coefficients[j * number_of_parts + i] = *coefficient;
             }
         }
+        let composition_poly = Polynomial::new(&coefficients);
 
-        // >>>> Send values: Hᵢ(z^N)
-        for element in round_3_result.composition_poly_parts_ood_evaluation.iter() {
-            transcript.append_field_element(element);
+        assert_eq!(number_of_parts, 4);
+        assert_eq!(
+            zk::reconstruct_composition_poly_evaluation(
+                &air,
+                &round_3_result.composition_poly_parts_ood_evaluation,
+                &z,
+            ),
+            composition_poly.evaluate(&z)
+        );
+    }
+
+    #[test]
+    fn exemptions_within_the_trace_are_accepted() {
+        let air = CubeAIR::new(8, &(), &ProofOptions::default_test_options());
+
+        assert!(check_transition_exemptions(&air).is_ok());
+    }
+
+    #[test]
+    fn exemptions_beyond_the_start_of_the_trace_are_rejected() {
+        let air = CubeAIR {
+            constraints: vec![Box::new(OverExemptedCubeConstraint)],
+            ..CubeAIR::new(8, &(), &ProofOptions::default_test_options())
+        };
+
+        assert!(matches!(
+            check_transition_exemptions(&air),
+            Err(ProvingError::ExemptionsOutOfTrace {
+                constraint_idx: 0,
+                exempted_rows: 9,
+                trace_length: 8,
+            })
+        ));
+    }
+
+    /// A Fibonacci AIR that absorbs a label from its public inputs into the transcript.
+    struct LabeledFibonacciAIR {
+        inner: FibonacciAIR<Stark252PrimeField>,
+        pub_inputs: LabeledFibonacciPublicInputs,
+    }
+
+    #[derive(Clone, Debug)]
+    struct LabeledFibonacciPublicInputs {
+        fibonacci: FibonacciPublicInputs<Stark252PrimeField>,
+        label: Vec<u8>,
+    }
+
+    impl AIR for LabeledFibonacciAIR {
+        type Field = Stark252PrimeField;
+        type FieldExtension = Stark252PrimeField;
+        type PublicInputs = LabeledFibonacciPublicInputs;
+
+        const STEP_SIZE: usize = 1;
+
+        fn new(
+            trace_length: usize,
+            pub_inputs: &Self::PublicInputs,
+            proof_options: &ProofOptions,
+        ) -> Self {
+            Self {
+                inner: FibonacciAIR::new(trace_length, &pub_inputs.fibonacci, proof_options),
+                pub_inputs: pub_inputs.clone(),
+            }
         }
 
-        #[cfg(feature = "instruments")]
-        let elapsed3 = timer3.elapsed();
-        #[cfg(feature = "instruments")]
-        println!("  Time spent: {:?}", elapsed3);
+        fn transcript_init_data(&self) -> Vec<u8> {
+            self.pub_inputs.label.clone()
+        }
 
-        // ===================================
-        // ==========|   Round 4   |==========
-        // ===================================
+        fn trace_layout(&self) -> (usize, usize) {
+            self.inner.trace_layout()
+        }
 
-        #[cfg(feature = "instruments")]
-        println!("- Started round 4: FRI");
-        #[cfg(feature = "instruments")]
-        let timer4 = Instant::now();
+        fn composition_poly_degree_bound(&self) -> usize {
+            self.inner.composition_poly_degree_bound()
+        }
 
-        // Part of this round is running FRI, which is an interactive
-        // protocol on its own. Therefore we pass it the transcript
-        // to simulate the interactions with the verifier.
-        let round_4_result = Self::round_4_compute_and_run_fri_on_the_deep_composition_polynomial(
+        fn boundary_constraints(
+            &self,
+            rap_challenges: &[Felt252],
+        ) -> BoundaryConstraints<Stark252PrimeField> {
+            self.inner.boundary_constraints(rap_challenges)
+        }
+
+        fn transition_constraints(
+            &self,
+        ) -> &Vec<Box<dyn TransitionConstraint<Stark252PrimeField, Stark252PrimeField>>> {
+            self.inner.transition_constraints()
+        }
+
+        fn compute_transition_verifier(
+            &self,
+            frame: &Frame<Stark252PrimeField, Stark252PrimeField>,
+            periodic_values: &[Felt252],
+            rap_challenges: &[Felt252],
+        ) -> Vec<Felt252> {
+            self.inner
+                .compute_transition_verifier(frame, periodic_values, rap_challenges)
+        }
+
+        fn context(&self) -> &AirContext {
+            self.inner.context()
+        }
+
+        fn trace_length(&self) -> usize {
+            self.inner.trace_length()
+        }
+
+        fn pub_inputs(&self) -> &Self::PublicInputs {
+            &self.pub_inputs
+        }
+    }
+
+    fn labeled_fibonacci_pub_inputs(label: &[u8]) -> LabeledFibonacciPublicInputs {
+        LabeledFibonacciPublicInputs {
+            fibonacci: FibonacciPublicInputs {
+                a0: Felt252::one(),
+                a1: Felt252::one(),
+            },
+            label: label.to_vec(),
+        }
+    }
+
+    #[test]
+    fn transcript_init_data_changes_the_first_challenge() {
+        let proof_options = ProofOptions::default_test_options();
+        let air_a =
+            LabeledFibonacciAIR::new(8, &labeled_fibonacci_pub_inputs(b"air a"), &proof_options);
+        let air_b =
+            LabeledFibonacciAIR::new(8, &labeled_fibonacci_pub_inputs(b"air b"), &proof_options);
+
+        let mut transcript_a = StoneProverTranscript::new(&[]);
+        let mut transcript_b = StoneProverTranscript::new(&[]);
+        Prover::<LabeledFibonacciAIR>::round_0_transcript_initialization(&air_a, &mut transcript_a);
+        Prover::<LabeledFibonacciAIR>::round_0_transcript_initialization(&air_b, &mut transcript_b);
+
+        assert_ne!(
+            transcript_a.sample_field_element(),
+            transcript_b.sample_field_element()
+        );
+    }
+
+    #[test]
+    fn transcript_init_data_is_absorbed_with_its_length() {
+        let proof_options = ProofOptions::default_test_options();
+        let air =
+            LabeledFibonacciAIR::new(8, &labeled_fibonacci_pub_inputs(b"air a"), &proof_options);
+
+        let mut transcript = StoneProverTranscript::new(&[]);
+        Prover::<LabeledFibonacciAIR>::round_0_transcript_initialization(&air, &mut transcript);
+        let mut expected_transcript = StoneProverTranscript::new(&[]);
+        expected_transcript.append_bytes(&[0, 0, 0, 0, 0, 0, 0, 5, b'a', b'i', b'r', b' ', b'a']);
+        assert_eq!(transcript.state(), expected_transcript.state());
+
+        // AIRs without initialization data leave the transcript untouched.
+        let air = FibonacciAIR::<Stark252PrimeField>::new(
+            8,
+            &labeled_fibonacci_pub_inputs(b"").fibonacci,
+            &proof_options,
+        );
+        let mut transcript = StoneProverTranscript::new(&[]);
+        Prover::<FibonacciAIR<Stark252PrimeField>>::round_0_transcript_initialization(
             &air,
-            &domain,
-            &round_1_result,
-            &round_2_result,
-            &round_3_result,
-            &z,
             &mut transcript,
         );
+        assert_eq!(transcript.state(), StoneProverTranscript::new(&[]).state());
+    }
 
-        #[cfg(feature = "instruments")]
-        let elapsed4 = timer4.elapsed();
-        #[cfg(feature = "instruments")]
-        println!("  Time spent: {:?}", elapsed4);
+    #[test]
+    fn proof_does_not_verify_with_a_different_transcript_init_data() {
+        let trace = simple_fibonacci::fibonacci_trace([Felt252::from(1), Felt252::from(1)], 8);
+        let proof_options = ProofOptions::default_test_options();
 
-        #[cfg(feature = "instruments")]
-        {
-            let total_time = elapsed1 + elapsed2 + elapsed3 + elapsed4;
-            println!(
-                " Fraction of proving time per round: {:.4} {:.4} {:.4} {:.4} {:.4}",
-                elapsed0.as_nanos() as f64 / total_time.as_nanos() as f64,
-                elapsed1.as_nanos() as f64 / total_time.as_nanos() as f64,
-                elapsed2.as_nanos() as f64 / total_time.as_nanos() as f64,
-                elapsed3.as_nanos() as f64 / total_time.as_nanos() as f64,
-                elapsed4.as_nanos() as f64 / total_time.as_nanos() as f64
-            );
-        }
+        let proof = Prover::<LabeledFibonacciAIR>::prove(
+            &trace,
+            &labeled_fibonacci_pub_inputs(b"air a"),
+            &proof_options,
+            StoneProverTranscript::new(&[]),
+        )
+        .unwrap();
 
-        info!("End proof generation");
+        assert!(Verifier::<LabeledFibonacciAIR>::verify(
+            &proof,
+            &labeled_fibonacci_pub_inputs(b"air a"),
+            &proof_options,
+            StoneProverTranscript::new(&[]),
+        ));
+        assert!(!Verifier::<LabeledFibonacciAIR>::verify(
+            &proof,
+            &labeled_fibonacci_pub_inputs(b"air b"),
+            &proof_options,
+            StoneProverTranscript::new(&[]),
+        ));
+    }
 
-        Ok(StarkProof::<A::Field, A::FieldExtension> {
-            // [t]
-            lde_trace_main_merkle_root: round_1_result.main.lde_trace_merkle_root,
-            // [t]
-            lde_trace_aux_merkle_root: round_1_result.aux.map(|x| x.lde_trace_merkle_root),
-            // tⱼ(zgᵏ)
-            trace_ood_evaluations: round_3_result.trace_ood_evaluations,
-            // [H₁] and [H₂]
-            composition_poly_root: round_2_result.composition_poly_root,
-            // Hᵢ(z^N)
-            composition_poly_parts_ood_evaluation: round_3_result
-                .composition_poly_parts_ood_evaluation,
-            // [pₖ]
-            fri_layers_merkle_roots: round_4_result.fri_layers_merkle_roots,
-            // pₙ
-            fri_last_value: round_4_result.fri_last_value,
-            // Open(p₀(D₀), 𝜐ₛ), Open(pₖ(Dₖ), −𝜐ₛ^(2ᵏ))
-            query_list: round_4_result.query_list,
-            // Open(H₁(D_LDE, 𝜐₀), Open(H₂(D_LDE, 𝜐₀), Open(tⱼ(D_LDE), 𝜐₀)
-            // Open(H₁(D_LDE, -𝜐ᵢ), Open(H₂(D_LDE, -𝜐ᵢ), Open(tⱼ(D_LDE), -𝜐ᵢ)
-            deep_poly_openings: round_4_result.deep_poly_openings,
-            // nonce obtained from grinding
-            nonce: round_4_result.nonce,
+    #[test]
+    fn verifier_replays_one_query_index_per_fri_query() {
+        let trace = simple_fibonacci::fibonacci_trace([Felt252::from(1), Felt252::from(1)], 8);
+        let pub_inputs = FibonacciPublicInputs {
+            a0: Felt252::one(),
+            a1: Felt252::one(),
+        };
+        let proof_options = ProofOptions::default_test_options();
 
-            trace_length: air.trace_length(),
-        })
+        let proof = Prover::<FibonacciAIR<Stark252PrimeField>>::prove(
+            &trace,
+            &pub_inputs,
+            &proof_options,
+            StoneProverTranscript::new(&[]),
+        )
+        .unwrap();
+
+        let query_indexes = Verifier::<FibonacciAIR<Stark252PrimeField>>::replay_query_indexes(
+            &proof,
+            &pub_inputs,
+            &proof_options,
+            StoneProverTranscript::new(&[]),
+        );
+        assert_eq!(query_indexes.len(), proof_options.fri_number_of_queries);
+        let number_of_pairs = proof.trace_length * proof_options.blowup_factor as usize / 2;
+        assert!(query_indexes.iter().all(|iota| *iota < number_of_pairs));
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use std::num::ParseIntError;
+    #[test]
+    fn proof_with_corrupted_trace_opening_at_second_query_does_not_verify() {
+        let trace = simple_fibonacci::fibonacci_trace([Felt252::from(1), Felt252::from(1)], 8);
+        let pub_inputs = FibonacciPublicInputs {
+            a0: Felt252::one(),
+            a1: Felt252::one(),
+        };
+        let proof_options = ProofOptions::default_test_options();
 
-    fn decode_hex(s: &str) -> Result<Vec<u8>, ParseIntError> {
-        (0..s.len())
-            .step_by(2)
-            .map(|i| u8::from_str_radix(&s[i..i + 2], 16))
-            .collect()
-    }
+        let mut proof = Prover::<FibonacciAIR<Stark252PrimeField>>::prove(
+            &trace,
+            &pub_inputs,
+            &proof_options,
+            StoneProverTranscript::new(&[]),
+        )
+        .unwrap();
 
-    use crate::{
-        examples::{
-            fibonacci_2_cols_shifted::{self, Fibonacci2ColsShifted},
-            simple_fibonacci::{self, FibonacciPublicInputs},
-        },
-        proof::options::ProofOptions,
-        transcript::StoneProverTranscript,
-        verifier::{Challenges, IsStarkVerifier, Verifier},
-        Felt252,
-    };
+        // The trace and composition polynomials are opened at every query index
+        assert_eq!(
+            proof.deep_poly_openings.len(),
+            proof_options.fri_number_of_queries
+        );
 
-    use super::*;
-    use lambdaworks_math::{
-        field::{
-            element::FieldElement, fields::fft_friendly::stark_252_prime_field::Stark252PrimeField,
-            traits::IsFFTField,
-        },
-        polynomial::Polynomial,
-    };
+        proof.deep_poly_openings[1].main_trace_polys.evaluations[0] += Felt252::one();
+        assert!(!Verifier::<FibonacciAIR<Stark252PrimeField>>::verify(
+            &proof,
+            &pub_inputs,
+            &proof_options,
+            StoneProverTranscript::new(&[]),
+        ));
+    }
 
     #[test]
     fn test_domain_constructor() {
@@ -1051,6 +3290,12 @@ mod tests {
             fri_number_of_queries: 1,
             coset_offset,
             grinding_factor,
+            zero_knowledge: false,
+            composition_blowup_factor: None,
+            fri_final_poly_degree: 0,
+            deep_combination: CombinationMode::PowersOfGamma,
+            constraint_combination: CombinationMode::PowersOfGamma,
+            distinct_queries: false,
         };
 
         let domain = Domain::new(&simple_fibonacci::FibonacciAIR::new(
@@ -1095,9 +3340,14 @@ mod tests {
         .unwrap();
 
         for poly in trace_polys.iter() {
-            let lde_evaluation =
-                evaluate_polynomial_on_lde_domain(poly, blowup_factor, domain_size, &coset_offset)
-                    .unwrap();
+            let lde_evaluation = evaluate_polynomial_on_lde_domain(
+                poly,
+                blowup_factor,
+                domain_size,
+                &coset_offset,
+                None,
+            )
+            .unwrap();
             assert_eq!(lde_evaluation.len(), trace_length * blowup_factor);
             for (i, evaluation) in lde_evaluation.iter().enumerate() {
                 assert_eq!(
@@ -1108,6 +3358,64 @@ mod tests {
         }
     }
 
+    #[test]
+    fn opened_trace_evaluations_are_the_evaluations_at_the_bit_reversed_query_positions() {
+        let trace = simple_fibonacci::fibonacci_trace([Felt252::from(1), Felt252::from(1)], 8);
+        let pub_inputs = FibonacciPublicInputs {
+            a0: Felt252::one(),
+            a1: Felt252::one(),
+        };
+        let proof_options = ProofOptions::default_test_options();
+        let proof = Prover::<FibonacciAIR<Stark252PrimeField>>::prove(
+            &trace,
+            &pub_inputs,
+            &proof_options,
+            StoneProverTranscript::new(&[]),
+        )
+        .unwrap();
+
+        let air = FibonacciAIR::new(trace.n_rows(), &pub_inputs, &proof_options);
+        let domain = Domain::new(&air);
+        let challenges = Verifier::step_1_replay_rounds_and_recover_challenges(
+            &air,
+            &proof,
+            &domain,
+            &mut StoneProverTranscript::new(&[]),
+        );
+
+        // The leaves of the trace tree are the LDE evaluations in bit-reversed order, and the
+        // query `iota` opens the leaves `2 * iota` and `2 * iota + 1`, which hold the
+        // evaluations at a point of the LDE coset and at its symmetric point.
+        let trace_poly = &trace.compute_trace_polys::<Stark252PrimeField>()[0];
+        let lde_domain_size = domain.lde_roots_of_unity_coset.len();
+        let lde_primitive_root = Stark252PrimeField::get_primitive_root_of_unity(
+            lde_domain_size.trailing_zeros() as u64,
+        )
+        .unwrap();
+        let evaluation_at_leaf = |leaf: usize| {
+            let exponent = reverse_index(leaf, lde_domain_size as u64);
+            trace_poly.evaluate(&(domain.coset_offset * lde_primitive_root.pow(exponent)))
+        };
+        assert!(!challenges.iotas.is_empty());
+        for (iota, opening) in challenges.iotas.iter().zip(&proof.deep_poly_openings) {
+            assert_eq!(
+                opening.main_trace_polys.evaluations,
+                vec![evaluation_at_leaf(2 * iota)]
+            );
+            assert_eq!(
+                opening.main_trace_polys.evaluations_sym,
+                vec![evaluation_at_leaf(2 * iota + 1)]
+            );
+            assert_eq!(
+                evaluation_at_leaf(2 * iota + 1),
+                trace_poly.evaluate(
+                    &-(domain.coset_offset
+                        * lde_primitive_root.pow(reverse_index(2 * iota, lde_domain_size as u64)))
+                )
+            );
+        }
+    }
+
     #[test]
     fn test_evaluate_polynomial_on_lde_domain_edge_case() {
         let poly = Polynomial::new_monomial(Felt252::one(), 8);
@@ -1115,7 +3423,8 @@ mod tests {
         let domain_size: usize = 8;
         let offset = Felt252::from(3);
         let evaluations =
-            evaluate_polynomial_on_lde_domain(&poly, blowup_factor, domain_size, &offset).unwrap();
+            evaluate_polynomial_on_lde_domain(&poly, blowup_factor, domain_size, &offset, None)
+                .unwrap();
         assert_eq!(evaluations.len(), domain_size * blowup_factor);
 
         let primitive_root: Felt252 = Stark252PrimeField::get_primitive_root_of_unity(
@@ -1340,10 +3649,10 @@ mod tests {
         let proof = stone_compatibility_case_1_proof();
 
         assert_eq!(
-            proof.fri_last_value,
-            FieldElement::from_hex_unchecked(
+            proof.fri_final_poly,
+            vec![FieldElement::from_hex_unchecked(
                 "43fedf9f9e3d1469309862065c7d7ca0e7e9ce451906e9c01553056f695aec9"
-            )
+            )]
         );
     }
 
@@ -1480,6 +3789,44 @@ mod tests {
         );
     }
 
+    #[test]
+    fn composition_polynomial_openings_share_a_leaf_with_their_symmetric_point() {
+        let trace = simple_fibonacci::fibonacci_trace([Felt252::from(1), Felt252::from(1)], 8);
+        let pub_inputs = FibonacciPublicInputs {
+            a0: Felt252::one(),
+            a1: Felt252::one(),
+        };
+        let proof = Prover::<FibonacciAIR<Stark252PrimeField>>::prove(
+            &trace,
+            &pub_inputs,
+            &ProofOptions::default_test_options(),
+            StoneProverTranscript::new(&[]),
+        )
+        .unwrap();
+
+        let query_indexes = Verifier::<FibonacciAIR<Stark252PrimeField>>::replay_query_indexes(
+            &proof,
+            &pub_inputs,
+            &ProofOptions::default_test_options(),
+            StoneProverTranscript::new(&[]),
+        );
+        for (iota, opening) in query_indexes.iter().zip(&proof.deep_poly_openings) {
+            let composition_poly = &opening.composition_poly;
+            let mut leaf = composition_poly.evaluations.clone();
+            leaf.extend_from_slice(&composition_poly.evaluations_sym);
+
+            assert!(
+                <BatchedMerkleTree<Stark252PrimeField> as VectorCommitment>::verify(
+                    &proof.composition_poly_root,
+                    *iota,
+                    &leaf,
+                    &composition_poly.proof,
+                )
+            );
+            assert_eq!(composition_poly.proof_sym, composition_poly.proof);
+        }
+    }
+
     #[test]
     fn stone_compatibility_case_1_fri_query_phase_query_lengths() {
         let proof = stone_compatibility_case_1_proof();
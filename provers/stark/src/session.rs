@@ -0,0 +1,269 @@
+//! Sessions that prove and verify a sequence of proofs, possibly of different AIRs, bound
+//! to each other.
+//!
+//! Each proof is made with a fresh transcript, as with `IsStarkProver::prove`, to which the
+//! session first appends its state. The state starts as the hash of a seed and, after each
+//! proof, absorbs its commitments. The challenges of every proof therefore depend on all the
+//! proofs before it, so a verifier session given the same proofs in another order, or with
+//! one of them missing, samples different challenges and rejects them.
+
+use lambdaworks_crypto::fiat_shamir::is_transcript::IsTranscript;
+use lambdaworks_math::{
+    field::{
+        element::FieldElement,
+        traits::{IsField, IsSubFieldOf},
+    },
+    traits::AsBytes,
+};
+use sha3::{Digest, Keccak256};
+
+use crate::{
+    proof::{options::ProofOptions, stark::StarkProof},
+    prover::{IsStarkProver, Prover, ProvingError},
+    trace::TraceTable,
+    traits::AIR,
+    verifier::{IsStarkVerifier, Verifier},
+};
+
+/// Proves a sequence of statements whose proofs are bound to each other. The proofs must be
+/// checked in the same order by a [`VerifierSession`] built from the same seed.
+pub struct ProverSession {
+    state: [u8; 32],
+}
+
+impl ProverSession {
+    /// Starts a session from `seed`, which the verifier session must also start from.
+    pub fn new(seed: &[u8]) -> Self {
+        Self {
+            state: Keccak256::digest(seed).into(),
+        }
+    }
+
+    /// Proves the next statement of the session with a fresh `transcript`, bound to the
+    /// proofs made before it.
+    pub fn prove_next<A: AIR + Send + Sync>(
+        &mut self,
+        main_trace: &TraceTable<A::Field>,
+        pub_inputs: &A::PublicInputs,
+        proof_options: &ProofOptions,
+        mut transcript: impl IsTranscript<A::FieldExtension>,
+    ) -> Result<StarkProof<A::Field, A::FieldExtension>, ProvingError>
+    where
+        FieldElement<A::Field>: AsBytes + Send + Sync,
+        FieldElement<A::FieldExtension>: AsBytes + Send + Sync,
+    {
+        transcript.append_bytes(&self.state);
+        let proof = Prover::<A>::prove(main_trace, pub_inputs, proof_options, transcript)?;
+        self.state = next_state(&self.state, &proof);
+        Ok(proof)
+    }
+
+    /// Returns the state the next proof will be bound to.
+    pub fn state(&self) -> [u8; 32] {
+        self.state
+    }
+}
+
+/// Verifies the proofs made by a [`ProverSession`], in the order they were made.
+pub struct VerifierSession {
+    state: [u8; 32],
+}
+
+impl VerifierSession {
+    /// Starts a session from `seed`, the one the prover session started from.
+    pub fn new(seed: &[u8]) -> Self {
+        Self {
+            state: Keccak256::digest(seed).into(),
+        }
+    }
+
+    /// Verifies the next proof of the session with a fresh `transcript`. The proof is
+    /// absorbed whether it is accepted or not, so once a proof is rejected the proofs after
+    /// it are rejected too.
+    pub fn verify_next<A: AIR>(
+        &mut self,
+        proof: &StarkProof<A::Field, A::FieldExtension>,
+        pub_inputs: &A::PublicInputs,
+        proof_options: &ProofOptions,
+        mut transcript: impl IsTranscript<A::FieldExtension>,
+    ) -> bool
+    where
+        FieldElement<A::Field>: AsBytes + Sync + Send,
+        FieldElement<A::FieldExtension>: AsBytes + Sync + Send,
+    {
+        transcript.append_bytes(&self.state);
+        let accepted = Verifier::<A>::verify(proof, pub_inputs, proof_options, transcript);
+        self.state = next_state(&self.state, proof);
+        accepted
+    }
+
+    /// Returns the state the next proof must be bound to.
+    pub fn state(&self) -> [u8; 32] {
+        self.state
+    }
+}
+
+/// Absorbs into `state` the parameter hash of `proof`, which identifies its AIR and options,
+/// and everything the transcript of the proof absorbs: its commitments, out of domain
+/// evaluations, final FRI polynomial and grinding nonce.
+fn next_state<F, E>(state: &[u8; 32], proof: &StarkProof<F, E>) -> [u8; 32]
+where
+    F: IsSubFieldOf<E>,
+    E: IsField,
+    FieldElement<E>: AsBytes,
+{
+    let mut hasher = Keccak256::new();
+    hasher.update(state);
+    hasher.update(proof.parameter_hash);
+    hasher.update((proof.trace_length as u64).to_be_bytes());
+    hasher.update(proof.lde_trace_main_merkle_root);
+    if let Some(root) = &proof.lde_trace_aux_merkle_root {
+        hasher.update(root);
+    }
+    for evaluation in &proof.trace_ood_evaluations.data {
+        hasher.update(evaluation.as_bytes());
+    }
+    hasher.update(proof.composition_poly_root);
+    for evaluation in &proof.composition_poly_parts_ood_evaluation {
+        hasher.update(evaluation.as_bytes());
+    }
+    for root in &proof.fri_layers_merkle_roots {
+        hasher.update(root);
+    }
+    for coefficient in &proof.fri_final_poly {
+        hasher.update(coefficient.as_bytes());
+    }
+    if let Some(nonce) = proof.nonce {
+        hasher.update(nonce.to_be_bytes());
+    }
+    hasher.finalize().into()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        examples::{
+            quadratic_air::{quadratic_trace, QuadraticAIR, QuadraticPublicInputs},
+            simple_fibonacci::{fibonacci_trace, FibonacciAIR, FibonacciPublicInputs},
+        },
+        transcript::StoneProverTranscript,
+        Felt252,
+    };
+    use lambdaworks_math::field::fields::fft_friendly::stark_252_prime_field::Stark252PrimeField;
+
+    type Fibonacci = FibonacciAIR<Stark252PrimeField>;
+    type Quadratic = QuadraticAIR<Stark252PrimeField>;
+
+    #[test]
+    fn swapping_two_proofs_of_a_session_fails_verification() {
+        let proof_options = ProofOptions::default_test_options();
+        let fibonacci_inputs = FibonacciPublicInputs {
+            a0: Felt252::one(),
+            a1: Felt252::one(),
+        };
+        let quadratic_inputs = QuadraticPublicInputs {
+            a0: Felt252::from(3),
+        };
+
+        let mut prover = ProverSession::new(b"batch");
+        let fibonacci_proof = prover
+            .prove_next::<Fibonacci>(
+                &fibonacci_trace([Felt252::one(), Felt252::one()], 8),
+                &fibonacci_inputs,
+                &proof_options,
+                StoneProverTranscript::new(&[]),
+            )
+            .unwrap();
+        let quadratic_proof = prover
+            .prove_next::<Quadratic>(
+                &quadratic_trace(Felt252::from(3), 8),
+                &quadratic_inputs,
+                &proof_options,
+                StoneProverTranscript::new(&[]),
+            )
+            .unwrap();
+
+        let mut verifier = VerifierSession::new(b"batch");
+        assert!(verifier.verify_next::<Fibonacci>(
+            &fibonacci_proof,
+            &fibonacci_inputs,
+            &proof_options,
+            StoneProverTranscript::new(&[]),
+        ));
+        assert!(verifier.verify_next::<Quadratic>(
+            &quadratic_proof,
+            &quadratic_inputs,
+            &proof_options,
+            StoneProverTranscript::new(&[]),
+        ));
+        assert_eq!(verifier.state(), prover.state());
+
+        let mut verifier = VerifierSession::new(b"batch");
+        assert!(!verifier.verify_next::<Quadratic>(
+            &quadratic_proof,
+            &quadratic_inputs,
+            &proof_options,
+            StoneProverTranscript::new(&[]),
+        ));
+        assert!(!verifier.verify_next::<Fibonacci>(
+            &fibonacci_proof,
+            &fibonacci_inputs,
+            &proof_options,
+            StoneProverTranscript::new(&[]),
+        ));
+    }
+
+    #[test]
+    fn proofs_of_a_session_are_not_valid_on_their_own() {
+        let proof_options = ProofOptions::default_test_options();
+        let pub_inputs = FibonacciPublicInputs {
+            a0: Felt252::one(),
+            a1: Felt252::one(),
+        };
+        let trace = fibonacci_trace([Felt252::one(), Felt252::one()], 8);
+
+        let mut prover = ProverSession::new(b"batch");
+        let first_proof = prover
+            .prove_next::<Fibonacci>(
+                &trace,
+                &pub_inputs,
+                &proof_options,
+                StoneProverTranscript::new(&[]),
+            )
+            .unwrap();
+        let second_proof = prover
+            .prove_next::<Fibonacci>(
+                &trace,
+                &pub_inputs,
+                &proof_options,
+                StoneProverTranscript::new(&[]),
+            )
+            .unwrap();
+
+        // Dropping the first proof changes the challenges of the second one.
+        let mut verifier = VerifierSession::new(b"batch");
+        assert!(!verifier.verify_next::<Fibonacci>(
+            &second_proof,
+            &pub_inputs,
+            &proof_options,
+            StoneProverTranscript::new(&[]),
+        ));
+
+        // A proof of the session is bound to its seed.
+        assert!(!Verifier::<Fibonacci>::verify(
+            &first_proof,
+            &pub_inputs,
+            &proof_options,
+            StoneProverTranscript::new(&[]),
+        ));
+        assert!(
+            !VerifierSession::new(b"another batch").verify_next::<Fibonacci>(
+                &first_proof,
+                &pub_inputs,
+                &proof_options,
+                StoneProverTranscript::new(&[]),
+            )
+        );
+    }
+}
@@ -1,4 +1,9 @@
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use core::hash::{Hash, Hasher};
+
 use crate::frame::Frame;
+use crate::trace::TraceError;
 use lambdaworks_math::field::{
     element::FieldElement,
     traits::{IsField, IsSubFieldOf},
@@ -9,13 +14,34 @@ use lambdaworks_math::field::{
 /// the STARK protocol implementation, such as the `TraceTable` and the `EvaluationFrame`.
 /// Since this struct is a representation of a two-dimensional table, all rows should have the same
 /// length.
-#[derive(Clone, Default, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Default, Debug, serde::Serialize, serde::Deserialize)]
 pub struct Table<F: IsField> {
     pub data: Vec<FieldElement<F>>,
     pub width: usize,
     pub height: usize,
 }
 
+// Implemented by hand, since deriving them would require the field type itself to implement
+// them, which most fields do not.
+impl<F: IsField> PartialEq for Table<F> {
+    fn eq(&self, other: &Self) -> bool {
+        self.data == other.data && self.width == other.width && self.height == other.height
+    }
+}
+
+impl<F: IsField> Eq for Table<F> {}
+
+impl<F: IsField> Hash for Table<F>
+where
+    FieldElement<F>: Hash,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.data.hash(state);
+        self.width.hash(state);
+        self.height.hash(state);
+    }
+}
+
 impl<'t, F: IsField> Table<F> {
     /// Crates a new Table instance from a one-dimensional array in row major order
     /// and the intended width of the table.
@@ -62,6 +88,15 @@ impl<'t, F: IsField> Table<F> {
         Self::new(data, width)
     }
 
+    /// Returns the table made of the rows of `self` whose index is a multiple of `step`.
+    pub fn subsample_rows(&self, step: usize) -> Self {
+        let data = (0..self.height)
+            .step_by(step)
+            .flat_map(|row_idx| self.get_row(row_idx).iter().cloned())
+            .collect();
+        Self::new(data, self.width)
+    }
+
     /// Returns a vector of vectors of field elements representing the table rows
     pub fn rows(&self) -> Vec<Vec<FieldElement<F>>> {
         self.data.chunks(self.width).map(|r| r.to_vec()).collect()
@@ -73,6 +108,18 @@ impl<'t, F: IsField> Table<F> {
         &self.data[row_offset..row_offset + self.width]
     }
 
+    /// Given a row index, returns a reference to that row, or `TraceError::RowOutOfRange` if the
+    /// table has no such row.
+    pub fn try_get_row(&self, row_idx: usize) -> Result<&[FieldElement<F>], TraceError> {
+        if row_idx >= self.height {
+            return Err(TraceError::RowOutOfRange {
+                row_idx,
+                n_rows: self.height,
+            });
+        }
+        Ok(self.get_row(row_idx))
+    }
+
     /// Given a row index, returns a mutable reference to that row as a slice of field elements.
     pub fn get_row_mut(&mut self, row_idx: usize) -> &mut [FieldElement<F>] {
         let n_cols = self.width;
@@ -111,6 +158,18 @@ impl<'t, F: IsField> Table<F> {
         &self.data[idx]
     }
 
+    /// Given row and column indexes, returns the stored field element in that position of the
+    /// table, or the `TraceError` naming the index out of range.
+    pub fn try_get(&self, row: usize, col: usize) -> Result<&FieldElement<F>, TraceError> {
+        if col >= self.width {
+            return Err(TraceError::ColumnOutOfRange {
+                col_idx: col,
+                n_cols: self.width,
+            });
+        }
+        Ok(&self.try_get_row(row)?[col])
+    }
+
     /// Given a step size, converts the given table into a `Frame`.
     pub fn into_frame(&'t self, main_trace_columns: usize, step_size: usize) -> Frame<'t, F, F> {
         debug_assert!(self.height % step_size == 0);
@@ -137,7 +196,7 @@ impl<'t, F: IsField> Table<F> {
 }
 
 /// A view of a contiguos subset of rows of a table.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug)]
 pub struct TableView<'t, F, E>
 where
     E: IsField,
@@ -147,6 +206,36 @@ where
     pub aux_data: Vec<&'t [FieldElement<E>]>,
 }
 
+impl<F, E> PartialEq for TableView<'_, F, E>
+where
+    E: IsField,
+    F: IsSubFieldOf<F>,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.data == other.data && self.aux_data == other.aux_data
+    }
+}
+
+impl<F, E> Eq for TableView<'_, F, E>
+where
+    E: IsField,
+    F: IsSubFieldOf<F>,
+{
+}
+
+impl<F, E> Hash for TableView<'_, F, E>
+where
+    E: IsField,
+    F: IsSubFieldOf<F>,
+    FieldElement<F>: Hash,
+    FieldElement<E>: Hash,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.data.hash(state);
+        self.aux_data.hash(state);
+    }
+}
+
 impl<'t, F, E> TableView<'t, F, E>
 where
     E: IsField,
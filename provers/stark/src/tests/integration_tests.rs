@@ -1,22 +1,51 @@
-use lambdaworks_math::field::{
-    element::FieldElement, fields::fft_friendly::stark_252_prime_field::Stark252PrimeField,
+use std::collections::HashSet;
+
+use lambdaworks_crypto::fiat_shamir::{
+    default_transcript::{DefaultTranscript, TranscriptHasher},
+    test_transcript::TestTranscript,
+};
+use lambdaworks_math::{
+    field::{
+        element::FieldElement,
+        fields::fft_friendly::{
+            babybear::Babybear31PrimeField, quadratic_babybear::QuadraticBabybearField,
+            stark_252_prime_field::Stark252PrimeField,
+        },
+    },
+    traits::ByteOrder,
 };
+use sha3::{Keccak256, Sha3_256};
 
 use crate::{
+    config::MerkleVectorCommitment,
+    domain::Domain,
     examples::{
+        bit_column::{bit_column_trace, BitColumnAIR},
         bit_flags::{self, BitFlagsAIR},
         dummy_air::{self, DummyAIR},
         fibonacci_2_cols_shifted::{self, Fibonacci2ColsShifted},
         fibonacci_2_columns::{self, Fibonacci2ColsAIR},
+        fibonacci_extension::FibonacciExtensionAIR,
         fibonacci_rap::{fibonacci_rap_trace, FibonacciRAP, FibonacciRAPPublicInputs},
+        logup_range_check::{
+            logup_range_check_trace, LogUpRangeCheckAIR, LogUpRangeCheckPublicInputs,
+        },
+        offset_counter::{offset_counter_trace, OffsetCounterAIR},
         quadratic_air::{self, QuadraticAIR, QuadraticPublicInputs},
+        range_check::{range_check_trace, RangeCheckAIR, RangeCheckPublicInputs},
         simple_fibonacci::{self, FibonacciAIR, FibonacciPublicInputs},
         simple_periodic_cols::{self, SimplePeriodicAIR, SimplePeriodicPublicInputs},
+        unit_fractions::{unit_fractions_trace, UnitFractionsAIR},
+    },
+    proof::{
+        options::{CombinationMode, ProofOptions},
+        stark::{SelfDescribingProof, StarkProof, PROOF_VERSION},
     },
-    proof::options::ProofOptions,
-    prover::{IsStarkProver, Prover},
-    transcript::StoneProverTranscript,
-    verifier::{IsStarkVerifier, Verifier},
+    prover::{IsStarkProver, Prover, ProvingError},
+    trace::TraceTable,
+    traits::AIR,
+    transcript::{Challenge, RecordingTranscript, StoneProverTranscript},
+    verifier::{IsStarkVerifier, VerificationError, Verifier},
     Felt252,
 };
 
@@ -46,6 +75,355 @@ fn test_prove_fib() {
     ));
 }
 
+#[test_log::test]
+fn test_prove_fib_with_the_merkle_vector_commitment_is_the_default() {
+    let trace = simple_fibonacci::fibonacci_trace([Felt252::from(1), Felt252::from(1)], 16);
+    let proof_options = ProofOptions::default_test_options();
+    let pub_inputs = FibonacciPublicInputs {
+        a0: Felt252::one(),
+        a1: Felt252::one(),
+    };
+
+    let proof = Prover::<FibonacciAIR<Stark252PrimeField>, MerkleVectorCommitment>::prove(
+        &trace,
+        &pub_inputs,
+        &proof_options,
+        StoneProverTranscript::new(&[]),
+    )
+    .unwrap();
+    let default_proof = Prover::<FibonacciAIR<Stark252PrimeField>>::prove(
+        &trace,
+        &pub_inputs,
+        &proof_options,
+        StoneProverTranscript::new(&[]),
+    )
+    .unwrap();
+    assert_eq!(proof, default_proof);
+    assert!(Verifier::<
+        FibonacciAIR<Stark252PrimeField>,
+        MerkleVectorCommitment,
+    >::verify(
+        &proof,
+        &pub_inputs,
+        &proof_options,
+        StoneProverTranscript::new(&[]),
+    ));
+}
+
+#[test_log::test]
+fn test_prove_fib_zero_knowledge() {
+    let trace = simple_fibonacci::fibonacci_trace([Felt252::from(1), Felt252::from(1)], 32);
+
+    let proof_options = ProofOptions {
+        blowup_factor: 4,
+        fri_number_of_queries: 3,
+        coset_offset: 3,
+        grinding_factor: 1,
+        zero_knowledge: true,
+        composition_blowup_factor: None,
+        fri_final_poly_degree: 0,
+        deep_combination: CombinationMode::PowersOfGamma,
+        constraint_combination: CombinationMode::PowersOfGamma,
+        distinct_queries: false,
+    };
+
+    let pub_inputs = FibonacciPublicInputs {
+        a0: Felt252::one(),
+        a1: Felt252::one(),
+    };
+
+    let proof = Prover::<FibonacciAIR<Stark252PrimeField>>::prove_with_zk_seed(
+        &trace,
+        &pub_inputs,
+        &proof_options,
+        StoneProverTranscript::new(&[]),
+        [1; 32],
+    )
+    .unwrap();
+    assert!(Verifier::<FibonacciAIR<Stark252PrimeField>>::verify(
+        &proof,
+        &pub_inputs,
+        &proof_options,
+        StoneProverTranscript::new(&[]),
+    ));
+
+    // The proof only verifies with the options it was generated with.
+    let non_zk_proof_options = ProofOptions {
+        zero_knowledge: false,
+        ..proof_options.clone()
+    };
+    assert!(!Verifier::<FibonacciAIR<Stark252PrimeField>>::verify(
+        &proof,
+        &pub_inputs,
+        &non_zk_proof_options,
+        StoneProverTranscript::new(&[]),
+    ));
+
+    // A different seed masks the trace differently.
+    let other_proof = Prover::<FibonacciAIR<Stark252PrimeField>>::prove_with_zk_seed(
+        &trace,
+        &pub_inputs,
+        &proof_options,
+        StoneProverTranscript::new(&[]),
+        [2; 32],
+    )
+    .unwrap();
+    assert_ne!(
+        proof.lde_trace_main_merkle_root,
+        other_proof.lde_trace_main_merkle_root
+    );
+}
+
+#[test_log::test]
+fn test_prove_rap_fib_babybear_zero_knowledge() {
+    type FE = FieldElement<Babybear31PrimeField>;
+    let steps = 16;
+    let trace = fibonacci_rap_trace([FE::from(1), FE::from(1)], steps);
+
+    let proof_options = ProofOptions {
+        blowup_factor: 4,
+        fri_number_of_queries: 3,
+        coset_offset: 3,
+        grinding_factor: 1,
+        zero_knowledge: true,
+        composition_blowup_factor: None,
+        fri_final_poly_degree: 0,
+        deep_combination: CombinationMode::PowersOfGamma,
+        constraint_combination: CombinationMode::PowersOfGamma,
+        distinct_queries: false,
+    };
+
+    let pub_inputs = FibonacciRAPPublicInputs {
+        steps,
+        a0: FE::one(),
+        a1: FE::one(),
+    };
+
+    let proof = Prover::<FibonacciRAP<Babybear31PrimeField>>::prove(
+        &trace,
+        &pub_inputs,
+        &proof_options,
+        DefaultTranscript::<Babybear31PrimeField>::new(&[]),
+    )
+    .unwrap();
+    assert!(Verifier::<FibonacciRAP<Babybear31PrimeField>>::verify(
+        &proof,
+        &pub_inputs,
+        &proof_options,
+        DefaultTranscript::<Babybear31PrimeField>::new(&[]),
+    ));
+}
+
+#[test_log::test]
+fn test_prove_fib_with_smaller_composition_blowup_factor() {
+    let trace = simple_fibonacci::fibonacci_trace([Felt252::from(1), Felt252::from(1)], 32);
+
+    let proof_options = ProofOptions::default_test_options();
+    let composition_proof_options = ProofOptions {
+        composition_blowup_factor: Some(2),
+        fri_final_poly_degree: 0,
+        ..proof_options.clone()
+    };
+    assert!(proof_options.blowup_factor > 2);
+
+    let pub_inputs = FibonacciPublicInputs {
+        a0: Felt252::one(),
+        a1: Felt252::one(),
+    };
+
+    let proof = Prover::<FibonacciAIR<Stark252PrimeField>>::prove(
+        &trace,
+        &pub_inputs,
+        &composition_proof_options,
+        StoneProverTranscript::new(&[]),
+    )
+    .unwrap();
+    assert!(Verifier::<FibonacciAIR<Stark252PrimeField>>::verify(
+        &proof,
+        &pub_inputs,
+        &composition_proof_options,
+        StoneProverTranscript::new(&[]),
+    ));
+
+    // The composition polynomial is the same, so the proof is the same.
+    let default_proof = Prover::<FibonacciAIR<Stark252PrimeField>>::prove(
+        &trace,
+        &pub_inputs,
+        &proof_options,
+        StoneProverTranscript::new(&[]),
+    )
+    .unwrap();
+    assert_eq!(
+        proof.composition_poly_root,
+        default_proof.composition_poly_root
+    );
+    assert_eq!(proof.fri_final_poly, default_proof.fri_final_poly);
+
+    let too_large_proof_options = ProofOptions {
+        composition_blowup_factor: Some(2 * proof_options.blowup_factor),
+        fri_final_poly_degree: 0,
+        ..proof_options
+    };
+    assert!(Prover::<FibonacciAIR<Stark252PrimeField>>::prove(
+        &trace,
+        &pub_inputs,
+        &too_large_proof_options,
+        StoneProverTranscript::new(&[]),
+    )
+    .is_err());
+}
+
+#[test_log::test]
+fn test_prove_fib_babybear() {
+    type FE = FieldElement<Babybear31PrimeField>;
+    let trace = simple_fibonacci::fibonacci_trace([FE::from(1), FE::from(1)], 1024);
+
+    let proof_options = ProofOptions::default_test_options();
+
+    let pub_inputs = FibonacciPublicInputs {
+        a0: FE::one(),
+        a1: FE::one(),
+    };
+
+    let proof = Prover::<FibonacciAIR<Babybear31PrimeField>>::prove(
+        &trace,
+        &pub_inputs,
+        &proof_options,
+        DefaultTranscript::<Babybear31PrimeField>::new(&[]),
+    )
+    .unwrap();
+    assert!(Verifier::<FibonacciAIR<Babybear31PrimeField>>::verify(
+        &proof,
+        &pub_inputs,
+        &proof_options,
+        DefaultTranscript::<Babybear31PrimeField>::new(&[]),
+    ));
+}
+
+#[test_log::test]
+fn test_prove_fib_babybear_over_the_quadratic_extension() {
+    type FE = FieldElement<Babybear31PrimeField>;
+    type FibAIR = FibonacciExtensionAIR<Babybear31PrimeField, QuadraticBabybearField>;
+    let trace = simple_fibonacci::fibonacci_trace([FE::from(1), FE::from(1)], 64);
+
+    let proof_options = ProofOptions::default_test_options();
+
+    let pub_inputs = FibonacciPublicInputs {
+        a0: FE::one(),
+        a1: FE::one(),
+    };
+
+    let proof = Prover::<FibAIR>::prove(
+        &trace,
+        &pub_inputs,
+        &proof_options,
+        DefaultTranscript::<QuadraticBabybearField>::new(&[]),
+    )
+    .unwrap();
+    assert!(Verifier::<FibAIR>::verify(
+        &proof,
+        &pub_inputs,
+        &proof_options,
+        DefaultTranscript::<QuadraticBabybearField>::new(&[]),
+    ));
+
+    let wrong_pub_inputs = FibonacciPublicInputs {
+        a0: FE::one(),
+        a1: FE::from(2),
+    };
+    assert!(!Verifier::<FibAIR>::verify(
+        &proof,
+        &wrong_pub_inputs,
+        &proof_options,
+        DefaultTranscript::<QuadraticBabybearField>::new(&[]),
+    ));
+}
+
+#[test_log::test]
+fn test_prove_fib_babybear_over_the_quadratic_extension_rejects_zero_knowledge() {
+    type FE = FieldElement<Babybear31PrimeField>;
+    type FibAIR = FibonacciExtensionAIR<Babybear31PrimeField, QuadraticBabybearField>;
+    let trace = simple_fibonacci::fibonacci_trace([FE::from(1), FE::from(1)], 64);
+
+    let proof_options = ProofOptions {
+        zero_knowledge: true,
+        ..ProofOptions::default_test_options()
+    };
+
+    let pub_inputs = FibonacciPublicInputs {
+        a0: FE::one(),
+        a1: FE::one(),
+    };
+
+    let result = Prover::<FibAIR>::prove(
+        &trace,
+        &pub_inputs,
+        &proof_options,
+        DefaultTranscript::<QuadraticBabybearField>::new(&[]),
+    );
+    assert!(matches!(result, Err(ProvingError::WrongParameter(_))));
+}
+
+#[test_log::test]
+fn test_prove_fib_babybear_with_each_transcript_hasher() {
+    type FE = FieldElement<Babybear31PrimeField>;
+
+    fn prove_and_verify<H: TranscriptHasher>() {
+        let trace = simple_fibonacci::fibonacci_trace([FE::from(1), FE::from(1)], 16);
+        let proof_options = ProofOptions::default_test_options();
+        let pub_inputs = FibonacciPublicInputs {
+            a0: FE::one(),
+            a1: FE::one(),
+        };
+
+        let proof = Prover::<FibonacciAIR<Babybear31PrimeField>>::prove(
+            &trace,
+            &pub_inputs,
+            &proof_options,
+            DefaultTranscript::<Babybear31PrimeField, H>::new(&[]),
+        )
+        .unwrap();
+        assert!(Verifier::<FibonacciAIR<Babybear31PrimeField>>::verify(
+            &proof,
+            &pub_inputs,
+            &proof_options,
+            DefaultTranscript::<Babybear31PrimeField, H>::new(&[]),
+        ));
+    }
+
+    prove_and_verify::<Keccak256>();
+    prove_and_verify::<Sha3_256>();
+}
+
+#[test_log::test]
+fn test_prove_rap_fib_babybear() {
+    type FE = FieldElement<Babybear31PrimeField>;
+    let steps = 16;
+    let trace = fibonacci_rap_trace([FE::from(1), FE::from(1)], steps);
+
+    let proof_options = ProofOptions::default_test_options();
+
+    let pub_inputs = FibonacciRAPPublicInputs {
+        steps,
+        a0: FE::one(),
+        a1: FE::one(),
+    };
+
+    let proof = Prover::<FibonacciRAP<Babybear31PrimeField>>::prove(
+        &trace,
+        &pub_inputs,
+        &proof_options,
+        DefaultTranscript::<Babybear31PrimeField>::new(&[]),
+    )
+    .unwrap();
+    assert!(Verifier::<FibonacciRAP<Babybear31PrimeField>>::verify(
+        &proof,
+        &pub_inputs,
+        &proof_options,
+        DefaultTranscript::<Babybear31PrimeField>::new(&[]),
+    ));
+}
+
 #[test_log::test]
 fn test_prove_fib17() {
     type FE = FieldElement<Stark252PrimeField>;
@@ -56,6 +434,12 @@ fn test_prove_fib17() {
         fri_number_of_queries: 7,
         coset_offset: 3,
         grinding_factor: 1,
+        zero_knowledge: false,
+        composition_blowup_factor: None,
+        fri_final_poly_degree: 0,
+        deep_combination: CombinationMode::PowersOfGamma,
+        constraint_combination: CombinationMode::PowersOfGamma,
+        distinct_queries: false,
     };
 
     let pub_inputs = FibonacciPublicInputs {
@@ -237,26 +621,390 @@ fn test_prove_rap_fib() {
 }
 
 #[test_log::test]
-fn test_prove_dummy() {
-    let trace_length = 16;
-    let trace = dummy_air::dummy_trace(trace_length);
-
+fn test_prove_rap_fib_commits_and_opens_the_auxiliary_trace() {
+    let steps = 16;
+    let trace = fibonacci_rap_trace([Felt252::from(1), Felt252::from(1)], steps);
     let proof_options = ProofOptions::default_test_options();
+    let pub_inputs = FibonacciRAPPublicInputs {
+        steps,
+        a0: Felt252::one(),
+        a1: Felt252::one(),
+    };
+    let air = FibonacciRAP::<Stark252PrimeField>::new(trace.n_rows(), &pub_inputs, &proof_options);
+    let (_, num_aux_columns) = air.trace_layout();
 
-    let proof =
-        Prover::<DummyAIR>::prove(&trace, &(), &proof_options, StoneProverTranscript::new(&[]))
-            .unwrap();
+    let mut proof = Prover::<FibonacciRAP<Stark252PrimeField>>::prove(
+        &trace,
+        &pub_inputs,
+        &proof_options,
+        StoneProverTranscript::new(&[]),
+    )
+    .unwrap();
 
-    assert!(Verifier::<DummyAIR>::verify(
+    assert!(num_aux_columns > 0);
+    assert!(proof.lde_trace_aux_merkle_root.is_some());
+    for opening in proof.deep_poly_openings.iter() {
+        let aux_trace_polys = opening.aux_trace_polys.as_ref().unwrap();
+        assert_eq!(aux_trace_polys.evaluations.len(), num_aux_columns);
+        assert_eq!(aux_trace_polys.evaluations_sym.len(), num_aux_columns);
+    }
+
+    // A tampered opening of the auxiliary trace does not match its commitment.
+    let aux_evaluation = &mut proof.deep_poly_openings[0]
+        .aux_trace_polys
+        .as_mut()
+        .unwrap()
+        .evaluations[0];
+    let original_evaluation = *aux_evaluation;
+    *aux_evaluation += Felt252::one();
+    assert!(!Verifier::<FibonacciRAP<Stark252PrimeField>>::verify(
         &proof,
-        &(),
+        &pub_inputs,
+        &proof_options,
+        StoneProverTranscript::new(&[])
+    ));
+    proof.deep_poly_openings[0]
+        .aux_trace_polys
+        .as_mut()
+        .unwrap()
+        .evaluations[0] = original_evaluation;
+    assert!(Verifier::<FibonacciRAP<Stark252PrimeField>>::verify(
+        &proof,
+        &pub_inputs,
+        &proof_options,
+        StoneProverTranscript::new(&[])
+    ));
+
+    // The commitment to the auxiliary trace is appended to the transcript after the RAP
+    // challenges are sampled, and the openings are checked against it.
+    proof.lde_trace_aux_merkle_root.as_mut().unwrap()[0] ^= 1;
+    assert!(!Verifier::<FibonacciRAP<Stark252PrimeField>>::verify(
+        &proof,
+        &pub_inputs,
         &proof_options,
         StoneProverTranscript::new(&[])
     ));
 }
 
 #[test_log::test]
-fn test_prove_bit_flags() {
+fn test_structural_check_rejects_each_malformed_part_of_a_proof() {
+    type RapProof = StarkProof<Stark252PrimeField, Stark252PrimeField>;
+
+    let steps = 16;
+    let trace = fibonacci_rap_trace([Felt252::from(1), Felt252::from(1)], steps);
+    let proof_options = ProofOptions::default_test_options();
+    let pub_inputs = FibonacciRAPPublicInputs {
+        steps,
+        a0: Felt252::one(),
+        a1: Felt252::one(),
+    };
+    let air = FibonacciRAP::<Stark252PrimeField>::new(trace.n_rows(), &pub_inputs, &proof_options);
+    let prove = || {
+        Prover::<FibonacciRAP<Stark252PrimeField>>::prove(
+            &trace,
+            &pub_inputs,
+            &proof_options,
+            StoneProverTranscript::new(&[]),
+        )
+        .unwrap()
+    };
+    assert!(prove().structural_check(&air).is_ok());
+
+    type Malformation = (&'static str, fn(&mut RapProof));
+    let malformations: Vec<Malformation> = vec![
+        ("trace_length", |proof| proof.trace_length *= 2),
+        ("fri_final_poly", |proof| {
+            proof.fri_final_poly.push(Felt252::one())
+        }),
+        ("trace_ood_evaluations.width", |proof| {
+            proof.trace_ood_evaluations.width += 1
+        }),
+        ("trace_ood_evaluations.height", |proof| {
+            proof.trace_ood_evaluations.height -= 1
+        }),
+        ("trace_ood_evaluations.data", |proof| {
+            proof.trace_ood_evaluations.data.pop();
+        }),
+        ("lde_trace_aux_merkle_root", |proof| {
+            proof.lde_trace_aux_merkle_root = None
+        }),
+        ("composition_poly_parts_ood_evaluation", |proof| {
+            proof.composition_poly_parts_ood_evaluation.pop();
+        }),
+        ("query_list", |proof| {
+            proof.query_list.pop();
+        }),
+        ("deep_poly_openings", |proof| {
+            proof.deep_poly_openings.pop();
+        }),
+        ("query_list.layers_auth_paths", |proof| {
+            proof.query_list[0].layers_auth_paths.pop();
+        }),
+        ("query_list.layers_evaluations_sym", |proof| {
+            proof.query_list[0].layers_evaluations_sym.pop();
+        }),
+        ("query_list.layers_auth_paths.merkle_path", |proof| {
+            proof.query_list[0].layers_auth_paths[0].merkle_path.pop();
+        }),
+        ("deep_poly_openings.composition_poly", |proof| {
+            proof.deep_poly_openings[0]
+                .composition_poly
+                .evaluations_sym
+                .pop();
+        }),
+        ("deep_poly_openings.main_trace_polys", |proof| {
+            proof.deep_poly_openings[0]
+                .main_trace_polys
+                .proof
+                .merkle_path
+                .pop();
+        }),
+        ("deep_poly_openings.aux_trace_polys", |proof| {
+            proof.deep_poly_openings[0].aux_trace_polys = None
+        }),
+    ];
+    for (field, malform) in malformations {
+        let mut proof = prove();
+        malform(&mut proof);
+        assert!(
+            matches!(
+                proof.structural_check(&air),
+                Err(VerificationError::MalformedProof { field: got, .. }) if got == field
+            ),
+            "{}",
+            field
+        );
+        assert!(!Verifier::<FibonacciRAP<Stark252PrimeField>>::verify(
+            &proof,
+            &pub_inputs,
+            &proof_options,
+            StoneProverTranscript::new(&[])
+        ));
+    }
+
+    let mut proof = prove();
+    proof.fri_layers_merkle_roots.pop();
+    assert!(matches!(
+        proof.structural_check(&air),
+        Err(VerificationError::WrongFriLayerCount { .. })
+    ));
+
+    // A trace length whose LDE domain overflows a `usize` is rejected before any size is
+    // computed from it.
+    let mut proof = prove();
+    proof.trace_length = 1 << (usize::BITS - 1);
+    let huge_air =
+        FibonacciRAP::<Stark252PrimeField>::new(proof.trace_length, &pub_inputs, &proof_options);
+    assert!(matches!(
+        proof.structural_check(&huge_air),
+        Err(VerificationError::InvalidProof)
+    ));
+    assert!(!Verifier::<FibonacciRAP<Stark252PrimeField>>::verify(
+        &proof,
+        &pub_inputs,
+        &proof_options,
+        StoneProverTranscript::new(&[])
+    ));
+}
+
+#[test_log::test]
+fn test_prove_logup_range_check() {
+    let witness = (0..16u64).map(|i| Felt252::from((i * 7) % 16)).collect();
+    let trace = logup_range_check_trace(witness);
+
+    let proof_options = ProofOptions::default_test_options();
+
+    let proof = Prover::<LogUpRangeCheckAIR<Stark252PrimeField>>::prove(
+        &trace,
+        &LogUpRangeCheckPublicInputs,
+        &proof_options,
+        StoneProverTranscript::new(&[]),
+    )
+    .unwrap();
+    assert!(Verifier::<LogUpRangeCheckAIR<Stark252PrimeField>>::verify(
+        &proof,
+        &LogUpRangeCheckPublicInputs,
+        &proof_options,
+        StoneProverTranscript::new(&[])
+    ));
+}
+
+#[test_log::test]
+fn test_prove_logup_range_check_out_of_range_fails() {
+    let mut witness: Vec<_> = (0..16u64).map(Felt252::from).collect();
+    witness[5] = Felt252::from(16);
+    let trace = logup_range_check_trace(witness);
+
+    let proof_options = ProofOptions::default_test_options();
+
+    let proof = Prover::<LogUpRangeCheckAIR<Stark252PrimeField>>::prove(
+        &trace,
+        &LogUpRangeCheckPublicInputs,
+        &proof_options,
+        StoneProverTranscript::new(&[]),
+    )
+    .unwrap();
+    assert!(!Verifier::<LogUpRangeCheckAIR<Stark252PrimeField>>::verify(
+        &proof,
+        &LogUpRangeCheckPublicInputs,
+        &proof_options,
+        StoneProverTranscript::new(&[])
+    ));
+}
+
+#[test_log::test]
+fn test_prove_range_check() {
+    let trace = range_check_trace::<Stark252PrimeField>(0b1011_0110, 8);
+    let pub_inputs = RangeCheckPublicInputs {
+        value: Felt252::from(0b1011_0110),
+        num_bits: 8,
+    };
+
+    let proof_options = ProofOptions::default_test_options();
+
+    let proof = Prover::<RangeCheckAIR<Stark252PrimeField>>::prove(
+        &trace,
+        &pub_inputs,
+        &proof_options,
+        StoneProverTranscript::new(&[]),
+    )
+    .unwrap();
+    assert!(Verifier::<RangeCheckAIR<Stark252PrimeField>>::verify(
+        &proof,
+        &pub_inputs,
+        &proof_options,
+        StoneProverTranscript::new(&[])
+    ));
+}
+
+#[test_log::test]
+fn test_prove_range_check_does_not_enforce_the_exempted_last_row() {
+    let trace = range_check_trace::<Stark252PrimeField>(0b1011_0110, 8);
+    let pub_inputs = RangeCheckPublicInputs {
+        value: Felt252::from(0b1011_0110),
+        num_bits: 8,
+    };
+
+    // The frame of the last row wraps around to the first one, where the accumulation
+    // `acc_0 = 2 * acc_last + b_0` does not hold. The accumulation constraint has one end
+    // exemption, so that row is left out of its zerofier.
+    let first_row = trace.get_row(0);
+    let last_row = trace.last_row();
+    assert_ne!(first_row[1], last_row[1] + last_row[1] + first_row[0]);
+
+    let proof_options = ProofOptions::default_test_options();
+
+    let proof = Prover::<RangeCheckAIR<Stark252PrimeField>>::prove(
+        &trace,
+        &pub_inputs,
+        &proof_options,
+        StoneProverTranscript::new(&[]),
+    )
+    .unwrap();
+    assert!(Verifier::<RangeCheckAIR<Stark252PrimeField>>::verify(
+        &proof,
+        &pub_inputs,
+        &proof_options,
+        StoneProverTranscript::new(&[])
+    ));
+}
+
+#[test_log::test]
+fn test_prove_range_check_with_a_non_bit_fails() {
+    // Claims that 5 fits in 2 bits by decomposing it as 1 * 2 + 3.
+    let mut bits = vec![Felt252::zero(); 8];
+    let mut accumulator = vec![Felt252::zero(); 8];
+    bits[6] = Felt252::from(1);
+    bits[7] = Felt252::from(3);
+    accumulator[6] = Felt252::from(1);
+    accumulator[7] = Felt252::from(5);
+    let trace = TraceTable::from_columns_main(vec![bits, accumulator], 1);
+    let pub_inputs = RangeCheckPublicInputs {
+        value: Felt252::from(5),
+        num_bits: 2,
+    };
+
+    let proof_options = ProofOptions::default_test_options();
+
+    let proof = Prover::<RangeCheckAIR<Stark252PrimeField>>::prove(
+        &trace,
+        &pub_inputs,
+        &proof_options,
+        StoneProverTranscript::new(&[]),
+    )
+    .unwrap();
+    assert!(!Verifier::<RangeCheckAIR<Stark252PrimeField>>::verify(
+        &proof,
+        &pub_inputs,
+        &proof_options,
+        StoneProverTranscript::new(&[])
+    ));
+}
+
+#[test_log::test]
+fn test_prove_dummy() {
+    let trace_length = 16;
+    let trace = dummy_air::dummy_trace(trace_length);
+
+    let proof_options = ProofOptions::default_test_options();
+
+    let proof =
+        Prover::<DummyAIR>::prove(&trace, &(), &proof_options, StoneProverTranscript::new(&[]))
+            .unwrap();
+
+    assert!(Verifier::<DummyAIR>::verify(
+        &proof,
+        &(),
+        &proof_options,
+        StoneProverTranscript::new(&[])
+    ));
+}
+
+#[test_log::test]
+fn test_prove_bit_column_without_boundary_constraints() {
+    let bits: Vec<_> = (0..32).map(|i| i % 3 == 0).collect();
+    let trace = bit_column_trace::<Stark252PrimeField>(&bits);
+    let proof_options = ProofOptions::default_test_options();
+
+    let proof = Prover::<BitColumnAIR<Stark252PrimeField>>::prove(
+        &trace,
+        &(),
+        &proof_options,
+        StoneProverTranscript::new(&[]),
+    )
+    .unwrap();
+
+    assert!(Verifier::<BitColumnAIR<Stark252PrimeField>>::verify(
+        &proof,
+        &(),
+        &proof_options,
+        StoneProverTranscript::new(&[])
+    ));
+}
+
+#[test_log::test]
+fn test_prove_unit_fractions_with_a_rational_constraint() {
+    let trace = unit_fractions_trace::<Stark252PrimeField>(32);
+    let proof_options = ProofOptions::default_test_options();
+
+    let proof = Prover::<UnitFractionsAIR<Stark252PrimeField>>::prove(
+        &trace,
+        &(),
+        &proof_options,
+        StoneProverTranscript::new(&[]),
+    )
+    .unwrap();
+
+    assert!(Verifier::<UnitFractionsAIR<Stark252PrimeField>>::verify(
+        &proof,
+        &(),
+        &proof_options,
+        StoneProverTranscript::new(&[])
+    ));
+}
+
+#[test_log::test]
+fn test_prove_bit_flags() {
     let trace = bit_flags::bit_prefix_flag_trace(32);
     let proof_options = ProofOptions::default_test_options();
 
@@ -271,3 +1019,1061 @@ fn test_prove_bit_flags() {
         StoneProverTranscript::new(&[]),
     ));
 }
+
+/// Path of the golden proof of `test_prove_fib_reproduces_golden_proof`.
+const GOLDEN_FIBONACCI_PROOF: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/src/tests/fixtures/fibonacci_16_proof.bin"
+);
+
+/// Checks that proving a fixed Fibonacci trace gives the same proof bytes as the checked in
+/// fixture, to catch accidental changes to the transcript or to the proof layout. If a
+/// change is intended, the fixture has to be replaced with the new proof bytes in the same
+/// commit.
+#[test_log::test]
+fn test_prove_fib_reproduces_golden_proof() {
+    let trace = simple_fibonacci::fibonacci_trace([Felt252::from(1), Felt252::from(1)], 16);
+    let proof_options = ProofOptions::default_test_options();
+    let pub_inputs = FibonacciPublicInputs {
+        a0: Felt252::one(),
+        a1: Felt252::one(),
+    };
+
+    let proof = Prover::<FibonacciAIR<Stark252PrimeField>>::prove(
+        &trace,
+        &pub_inputs,
+        &proof_options,
+        StoneProverTranscript::new(&[]),
+    )
+    .unwrap();
+    let proof_bytes = bincode::serde::encode_to_vec(&proof, bincode::config::standard()).unwrap();
+
+    let golden_proof_bytes =
+        std::fs::read(GOLDEN_FIBONACCI_PROOF).expect("the golden proof fixture is missing");
+    assert_eq!(proof_bytes, golden_proof_bytes);
+
+    let (golden_proof, _) =
+        bincode::serde::decode_from_slice(&golden_proof_bytes, bincode::config::standard())
+            .unwrap();
+    assert!(Verifier::<FibonacciAIR<Stark252PrimeField>>::verify(
+        &golden_proof,
+        &pub_inputs,
+        &proof_options,
+        StoneProverTranscript::new(&[]),
+    ));
+}
+
+#[test_log::test]
+fn test_prove_fib_verifies_from_self_describing_proof_bytes() {
+    let trace = simple_fibonacci::fibonacci_trace([Felt252::from(1), Felt252::from(1)], 16);
+    let proof_options = ProofOptions::default_test_options();
+    let pub_inputs = FibonacciPublicInputs {
+        a0: Felt252::one(),
+        a1: Felt252::one(),
+    };
+
+    let proof = Prover::<FibonacciAIR<Stark252PrimeField>>::prove(
+        &trace,
+        &pub_inputs,
+        &proof_options,
+        StoneProverTranscript::new(&[]),
+    )
+    .unwrap();
+    let mut self_describing_proof = SelfDescribingProof::new::<FibonacciAIR<Stark252PrimeField>>(
+        proof,
+        pub_inputs,
+        &proof_options,
+    );
+    let proof_bytes = self_describing_proof.to_bytes().unwrap();
+
+    assert!(
+        Verifier::<FibonacciAIR<Stark252PrimeField>>::verify_self_describing(
+            &proof_bytes,
+            &proof_options,
+            StoneProverTranscript::new(&[]),
+        )
+        .is_ok()
+    );
+
+    self_describing_proof.context.trace_columns += 1;
+    let tampered_bytes = self_describing_proof.to_bytes().unwrap();
+    assert!(matches!(
+        Verifier::<FibonacciAIR<Stark252PrimeField>>::verify_self_describing(
+            &tampered_bytes,
+            &proof_options,
+            StoneProverTranscript::new(&[]),
+        ),
+        Err(VerificationError::ContextMismatch)
+    ));
+}
+
+#[test_log::test]
+fn test_self_describing_proof_with_weaker_options_is_rejected() {
+    let trace = simple_fibonacci::fibonacci_trace([Felt252::from(1), Felt252::from(1)], 16);
+    let proof_options = ProofOptions::default_test_options();
+    let weak_options = ProofOptions {
+        fri_number_of_queries: 1,
+        grinding_factor: 0,
+        ..proof_options.clone()
+    };
+    let pub_inputs = FibonacciPublicInputs {
+        a0: Felt252::one(),
+        a1: Felt252::one(),
+    };
+
+    // A proof made with weaker options, that verifies under them.
+    let weak_proof = Prover::<FibonacciAIR<Stark252PrimeField>>::prove(
+        &trace,
+        &pub_inputs,
+        &weak_options,
+        StoneProverTranscript::new(&[]),
+    )
+    .unwrap();
+    let weak_proof_bytes = SelfDescribingProof::new::<FibonacciAIR<Stark252PrimeField>>(
+        weak_proof,
+        pub_inputs.clone(),
+        &weak_options,
+    )
+    .to_bytes()
+    .unwrap();
+    assert!(
+        Verifier::<FibonacciAIR<Stark252PrimeField>>::verify_self_describing(
+            &weak_proof_bytes,
+            &weak_options,
+            StoneProverTranscript::new(&[]),
+        )
+        .is_ok()
+    );
+    assert!(matches!(
+        Verifier::<FibonacciAIR<Stark252PrimeField>>::verify_self_describing(
+            &weak_proof_bytes,
+            &proof_options,
+            StoneProverTranscript::new(&[]),
+        ),
+        Err(VerificationError::ProofOptionsMismatch)
+    ));
+
+    // A proof made with the expected options, re-serialized with weaker ones.
+    let proof = Prover::<FibonacciAIR<Stark252PrimeField>>::prove(
+        &trace,
+        &pub_inputs,
+        &proof_options,
+        StoneProverTranscript::new(&[]),
+    )
+    .unwrap();
+    let mut self_describing_proof = SelfDescribingProof::new::<FibonacciAIR<Stark252PrimeField>>(
+        proof,
+        pub_inputs,
+        &proof_options,
+    );
+    self_describing_proof.context.proof_options.blowup_factor /= 2;
+    let tampered_bytes = self_describing_proof.to_bytes().unwrap();
+    assert!(matches!(
+        Verifier::<FibonacciAIR<Stark252PrimeField>>::verify_self_describing(
+            &tampered_bytes,
+            &proof_options,
+            StoneProverTranscript::new(&[]),
+        ),
+        Err(VerificationError::ProofOptionsMismatch)
+    ));
+}
+
+#[test_log::test]
+fn test_prove_fib_with_one_fewer_fri_layer_is_rejected() {
+    let trace = simple_fibonacci::fibonacci_trace([Felt252::from(1), Felt252::from(1)], 16);
+    let proof_options = ProofOptions::default_test_options();
+    let pub_inputs = FibonacciPublicInputs {
+        a0: Felt252::one(),
+        a1: Felt252::one(),
+    };
+
+    let mut proof = Prover::<FibonacciAIR<Stark252PrimeField>>::prove(
+        &trace,
+        &pub_inputs,
+        &proof_options,
+        StoneProverTranscript::new(&[]),
+    )
+    .unwrap();
+    proof.fri_layers_merkle_roots.pop();
+    for decommitment in proof.query_list.iter_mut() {
+        decommitment.layers_auth_paths.pop();
+        decommitment.layers_evaluations_sym.pop();
+    }
+
+    assert!(!Verifier::<FibonacciAIR<Stark252PrimeField>>::verify(
+        &proof,
+        &pub_inputs,
+        &proof_options,
+        StoneProverTranscript::new(&[]),
+    ));
+
+    let proof_bytes = SelfDescribingProof::new::<FibonacciAIR<Stark252PrimeField>>(
+        proof,
+        pub_inputs,
+        &proof_options,
+    )
+    .to_bytes()
+    .unwrap();
+    assert!(matches!(
+        Verifier::<FibonacciAIR<Stark252PrimeField>>::verify_self_describing(
+            &proof_bytes,
+            &proof_options,
+            StoneProverTranscript::new(&[]),
+        ),
+        Err(VerificationError::WrongFriLayerCount {
+            expected: 3,
+            got: 2
+        })
+    ));
+}
+
+#[test_log::test]
+fn test_prove_fib_with_a_trace_length_of_one_is_rejected() {
+    let trace = simple_fibonacci::fibonacci_trace([Felt252::from(1), Felt252::from(1)], 16);
+    let proof_options = ProofOptions::default_test_options();
+    let pub_inputs = FibonacciPublicInputs {
+        a0: Felt252::one(),
+        a1: Felt252::one(),
+    };
+
+    let mut proof = Prover::<FibonacciAIR<Stark252PrimeField>>::prove(
+        &trace,
+        &pub_inputs,
+        &proof_options,
+        StoneProverTranscript::new(&[]),
+    )
+    .unwrap();
+    // A trace of a single row leaves no fold to FRI.
+    proof.trace_length = 1;
+
+    let air = FibonacciAIR::new(proof.trace_length, &pub_inputs, &proof_options);
+    assert!(matches!(
+        proof.structural_check(&air),
+        Err(VerificationError::InvalidProof)
+    ));
+    assert!(!Verifier::<FibonacciAIR<Stark252PrimeField>>::verify(
+        &proof,
+        &pub_inputs,
+        &proof_options,
+        StoneProverTranscript::new(&[]),
+    ));
+
+    let proof_bytes = SelfDescribingProof::new::<FibonacciAIR<Stark252PrimeField>>(
+        proof,
+        pub_inputs,
+        &proof_options,
+    )
+    .to_bytes()
+    .unwrap();
+    assert!(
+        Verifier::<FibonacciAIR<Stark252PrimeField>>::verify_self_describing(
+            &proof_bytes,
+            &proof_options,
+            StoneProverTranscript::new(&[]),
+        )
+        .is_err()
+    );
+}
+
+#[test_log::test]
+fn test_prove_fib_twice_gives_equal_proofs() {
+    let trace = simple_fibonacci::fibonacci_trace([Felt252::from(1), Felt252::from(1)], 16);
+    let proof_options = ProofOptions::default_test_options();
+    let pub_inputs = FibonacciPublicInputs {
+        a0: Felt252::one(),
+        a1: Felt252::one(),
+    };
+
+    let prove = || {
+        Prover::<FibonacciAIR<Stark252PrimeField>>::prove(
+            &trace,
+            &pub_inputs,
+            &proof_options,
+            StoneProverTranscript::new(&[]),
+        )
+        .unwrap()
+    };
+    let proof = prove();
+    let other_proof = prove();
+    assert_eq!(proof, other_proof);
+
+    let other_pub_inputs = FibonacciPublicInputs {
+        a0: Felt252::from(2),
+        a1: Felt252::from(3),
+    };
+    let other_trace = simple_fibonacci::fibonacci_trace([Felt252::from(2), Felt252::from(3)], 16);
+    let different_proof = Prover::<FibonacciAIR<Stark252PrimeField>>::prove(
+        &other_trace,
+        &other_pub_inputs,
+        &proof_options,
+        StoneProverTranscript::new(&[]),
+    )
+    .unwrap();
+    assert_ne!(proof, different_proof);
+
+    let proofs: HashSet<_> = [proof, other_proof, different_proof].into_iter().collect();
+    assert_eq!(proofs.len(), 2);
+}
+
+#[test_log::test]
+fn test_prove_fib_with_each_deep_combination_mode() {
+    let trace = simple_fibonacci::fibonacci_trace([Felt252::from(1), Felt252::from(1)], 16);
+    let pub_inputs = FibonacciPublicInputs {
+        a0: Felt252::one(),
+        a1: Felt252::one(),
+    };
+
+    for deep_combination in [CombinationMode::PowersOfGamma, CombinationMode::PerTerm] {
+        let proof_options = ProofOptions {
+            deep_combination,
+            ..ProofOptions::default_test_options()
+        };
+
+        let proof = Prover::<FibonacciAIR<Stark252PrimeField>>::prove(
+            &trace,
+            &pub_inputs,
+            &proof_options,
+            StoneProverTranscript::new(&[]),
+        )
+        .unwrap();
+        assert!(Verifier::<FibonacciAIR<Stark252PrimeField>>::verify(
+            &proof,
+            &pub_inputs,
+            &proof_options,
+            StoneProverTranscript::new(&[]),
+        ));
+    }
+}
+
+#[test_log::test]
+fn test_prove_fib_with_each_constraint_combination_mode() {
+    let trace = simple_fibonacci::fibonacci_trace([Felt252::from(1), Felt252::from(1)], 16);
+    let pub_inputs = FibonacciPublicInputs {
+        a0: Felt252::one(),
+        a1: Felt252::one(),
+    };
+
+    let mut number_of_challenges = Vec::new();
+    for constraint_combination in [CombinationMode::PowersOfGamma, CombinationMode::PerTerm] {
+        let proof_options = ProofOptions {
+            constraint_combination,
+            ..ProofOptions::default_test_options()
+        };
+
+        let mut prover_transcript = RecordingTranscript::new(StoneProverTranscript::new(&[]));
+        let proof = Prover::<FibonacciAIR<Stark252PrimeField>>::prove(
+            &trace,
+            &pub_inputs,
+            &proof_options,
+            &mut prover_transcript,
+        )
+        .unwrap();
+        assert!(Verifier::<FibonacciAIR<Stark252PrimeField>>::verify(
+            &proof,
+            &pub_inputs,
+            &proof_options,
+            StoneProverTranscript::new(&[]),
+        ));
+        number_of_challenges.push(prover_transcript.challenges().len());
+    }
+
+    // The AIR has one transition and two boundary constraints, whose three coefficients are
+    // the powers of a single challenge or three challenges.
+    assert_eq!(number_of_challenges[1], number_of_challenges[0] + 2);
+}
+
+#[test_log::test]
+fn test_prove_fib_with_another_proof_version_is_rejected() {
+    let trace = simple_fibonacci::fibonacci_trace([Felt252::from(1), Felt252::from(1)], 16);
+    let proof_options = ProofOptions::default_test_options();
+    let pub_inputs = FibonacciPublicInputs {
+        a0: Felt252::one(),
+        a1: Felt252::one(),
+    };
+
+    let mut proof = Prover::<FibonacciAIR<Stark252PrimeField>>::prove(
+        &trace,
+        &pub_inputs,
+        &proof_options,
+        StoneProverTranscript::new(&[]),
+    )
+    .unwrap();
+    assert_eq!(proof.proof_version, PROOF_VERSION);
+    proof.proof_version += 1;
+
+    assert!(!Verifier::<FibonacciAIR<Stark252PrimeField>>::verify(
+        &proof,
+        &pub_inputs,
+        &proof_options,
+        StoneProverTranscript::new(&[]),
+    ));
+
+    let proof_bytes = SelfDescribingProof::new::<FibonacciAIR<Stark252PrimeField>>(
+        proof,
+        pub_inputs,
+        &proof_options,
+    )
+    .to_bytes()
+    .unwrap();
+    assert!(matches!(
+        Verifier::<FibonacciAIR<Stark252PrimeField>>::verify_self_describing(
+            &proof_bytes,
+            &proof_options,
+            StoneProverTranscript::new(&[]),
+        ),
+        Err(VerificationError::VersionMismatch { expected, got })
+            if expected == PROOF_VERSION && got == PROOF_VERSION + 1
+    ));
+}
+
+#[test_log::test]
+fn test_prove_fib_with_other_proof_options_is_rejected_by_its_parameter_hash() {
+    let trace = simple_fibonacci::fibonacci_trace([Felt252::from(1), Felt252::from(1)], 16);
+    let proof_options = ProofOptions::default_test_options();
+    let pub_inputs = FibonacciPublicInputs {
+        a0: Felt252::one(),
+        a1: Felt252::one(),
+    };
+
+    let proof = Prover::<FibonacciAIR<Stark252PrimeField>>::prove(
+        &trace,
+        &pub_inputs,
+        &proof_options,
+        StoneProverTranscript::new(&[]),
+    )
+    .unwrap();
+
+    // More queries than needed would otherwise pass the check on the number of queries.
+    let other_options = proof_options.clone().with_fri_number_of_queries(2);
+    assert!(!Verifier::<FibonacciAIR<Stark252PrimeField>>::verify(
+        &proof,
+        &pub_inputs,
+        &other_options,
+        StoneProverTranscript::new(&[]),
+    ));
+
+    let proof_bytes = SelfDescribingProof::new::<FibonacciAIR<Stark252PrimeField>>(
+        proof,
+        pub_inputs,
+        &other_options,
+    )
+    .to_bytes()
+    .unwrap();
+    // The context claims the options of the verifier, but the proof was made with others.
+    assert!(matches!(
+        Verifier::<FibonacciAIR<Stark252PrimeField>>::verify_self_describing(
+            &proof_bytes,
+            &other_options,
+            StoneProverTranscript::new(&[]),
+        ),
+        Err(VerificationError::ParameterHashMismatch)
+    ));
+}
+
+#[test_log::test]
+fn test_prove_fib_with_little_endian_transcript_needs_a_little_endian_verifier() {
+    let trace = simple_fibonacci::fibonacci_trace([Felt252::from(1), Felt252::from(1)], 16);
+    let proof_options = ProofOptions::default_test_options();
+    let pub_inputs = FibonacciPublicInputs {
+        a0: Felt252::one(),
+        a1: Felt252::one(),
+    };
+    let transcript =
+        |byte_order| DefaultTranscript::<Stark252PrimeField>::new(&[]).with_byte_order(byte_order);
+
+    let proof = Prover::<FibonacciAIR<Stark252PrimeField>>::prove(
+        &trace,
+        &pub_inputs,
+        &proof_options,
+        transcript(ByteOrder::LittleEndian),
+    )
+    .unwrap();
+
+    assert!(Verifier::<FibonacciAIR<Stark252PrimeField>>::verify(
+        &proof,
+        &pub_inputs,
+        &proof_options,
+        transcript(ByteOrder::LittleEndian),
+    ));
+    assert!(!Verifier::<FibonacciAIR<Stark252PrimeField>>::verify(
+        &proof,
+        &pub_inputs,
+        &proof_options,
+        transcript(ByteOrder::BigEndian),
+    ));
+
+    let proof_bytes = SelfDescribingProof::new::<FibonacciAIR<Stark252PrimeField>>(
+        proof,
+        pub_inputs,
+        &proof_options,
+    )
+    .with_byte_order(ByteOrder::LittleEndian)
+    .to_bytes()
+    .unwrap();
+    assert!(
+        Verifier::<FibonacciAIR<Stark252PrimeField>>::verify_self_describing(
+            &proof_bytes,
+            &proof_options,
+            transcript(ByteOrder::LittleEndian),
+        )
+        .is_ok()
+    );
+    assert!(matches!(
+        Verifier::<FibonacciAIR<Stark252PrimeField>>::verify_self_describing(
+            &proof_bytes,
+            &proof_options,
+            transcript(ByteOrder::BigEndian),
+        ),
+        Err(VerificationError::ByteOrderMismatch)
+    ));
+}
+
+#[test_log::test]
+fn test_prove_fib_with_a_corrupted_fri_layer_is_rejected_by_fri_only() {
+    let trace = simple_fibonacci::fibonacci_trace([Felt252::from(1), Felt252::from(1)], 16);
+    let proof_options = ProofOptions::default_test_options();
+    let pub_inputs = FibonacciPublicInputs {
+        a0: Felt252::one(),
+        a1: Felt252::one(),
+    };
+
+    let mut proof = Prover::<FibonacciAIR<Stark252PrimeField>>::prove(
+        &trace,
+        &pub_inputs,
+        &proof_options,
+        StoneProverTranscript::new(&[]),
+    )
+    .unwrap();
+    proof.query_list[0].layers_evaluations_sym[0] += Felt252::one();
+
+    assert!(!Verifier::<FibonacciAIR<Stark252PrimeField>>::verify(
+        &proof,
+        &pub_inputs,
+        &proof_options,
+        StoneProverTranscript::new(&[]),
+    ));
+
+    let air = FibonacciAIR::new(proof.trace_length, &pub_inputs, &proof_options);
+    let domain = Domain::new(&air);
+    let challenges =
+        Verifier::<FibonacciAIR<Stark252PrimeField>>::step_1_replay_rounds_and_recover_challenges(
+            &air,
+            &proof,
+            &domain,
+            &mut StoneProverTranscript::new(&[]),
+        );
+    assert!(
+        Verifier::<FibonacciAIR<Stark252PrimeField>>::step_4_verify_trace_and_composition_openings(
+            &proof,
+            &challenges,
+        )
+    );
+    assert!(
+        !Verifier::<FibonacciAIR<Stark252PrimeField>>::step_3_verify_fri(
+            &air,
+            &proof,
+            &domain,
+            &challenges,
+        )
+    );
+}
+
+#[test_log::test]
+fn test_prove_fib_with_a_mutated_fri_last_value_is_rejected() {
+    let trace = simple_fibonacci::fibonacci_trace([Felt252::from(1), Felt252::from(1)], 16);
+    let proof_options = ProofOptions::default_test_options();
+    let pub_inputs = FibonacciPublicInputs {
+        a0: Felt252::one(),
+        a1: Felt252::one(),
+    };
+
+    let mut proof = Prover::<FibonacciAIR<Stark252PrimeField>>::prove(
+        &trace,
+        &pub_inputs,
+        &proof_options,
+        StoneProverTranscript::new(&[]),
+    )
+    .unwrap();
+
+    // Replay the challenges of the honest proof, so the mutated last value is caught by the
+    // folds of the queries rather than by the query indexes drawn from the transcript.
+    let air = FibonacciAIR::new(proof.trace_length, &pub_inputs, &proof_options);
+    let domain = Domain::new(&air);
+    let challenges =
+        Verifier::<FibonacciAIR<Stark252PrimeField>>::step_1_replay_rounds_and_recover_challenges(
+            &air,
+            &proof,
+            &domain,
+            &mut StoneProverTranscript::new(&[]),
+        );
+    proof.fri_final_poly[0] += Felt252::one();
+
+    assert!(
+        !Verifier::<FibonacciAIR<Stark252PrimeField>>::step_3_verify_fri(
+            &air,
+            &proof,
+            &domain,
+            &challenges,
+        )
+    );
+    assert!(!Verifier::<FibonacciAIR<Stark252PrimeField>>::verify(
+        &proof,
+        &pub_inputs,
+        &proof_options,
+        StoneProverTranscript::new(&[]),
+    ));
+}
+
+#[test_log::test]
+fn test_prove_fib_with_a_final_fri_polynomial_gives_smaller_proofs() {
+    let trace = simple_fibonacci::fibonacci_trace([Felt252::from(1), Felt252::from(1)], 256);
+    let pub_inputs = FibonacciPublicInputs {
+        a0: Felt252::one(),
+        a1: Felt252::one(),
+    };
+
+    let prove_and_verify = |fri_final_poly_degree: usize| {
+        let proof_options = ProofOptions {
+            fri_final_poly_degree,
+            ..ProofOptions::default_test_options()
+        };
+        let proof = Prover::<FibonacciAIR<Stark252PrimeField>>::prove(
+            &trace,
+            &pub_inputs,
+            &proof_options,
+            StoneProverTranscript::new(&[]),
+        )
+        .unwrap();
+
+        let timer = std::time::Instant::now();
+        assert!(Verifier::<FibonacciAIR<Stark252PrimeField>>::verify(
+            &proof,
+            &pub_inputs,
+            &proof_options,
+            StoneProverTranscript::new(&[]),
+        ));
+        let verify_time = timer.elapsed();
+        let proof_size = bincode::serde::encode_to_vec(&proof, bincode::config::standard())
+            .unwrap()
+            .len();
+        log::info!(
+            "fri_final_poly_degree {}: {} bytes, verified in {:?}",
+            fri_final_poly_degree,
+            proof_size,
+            verify_time
+        );
+        (proof, proof_size)
+    };
+
+    let (folded_proof, folded_proof_size) = prove_and_verify(0);
+    let (final_poly_proof, final_poly_proof_size) = prove_and_verify(16);
+
+    assert_eq!(folded_proof.fri_final_poly.len(), 1);
+    assert_eq!(final_poly_proof.fri_final_poly.len(), 16);
+    assert_eq!(
+        final_poly_proof.fri_layers_merkle_roots.len() + 4,
+        folded_proof.fri_layers_merkle_roots.len()
+    );
+    assert!(final_poly_proof_size < folded_proof_size);
+}
+
+#[test_log::test]
+fn test_proof_with_a_final_fri_polynomial_is_rejected_with_other_final_degree() {
+    let trace = simple_fibonacci::fibonacci_trace([Felt252::from(1), Felt252::from(1)], 32);
+    let pub_inputs = FibonacciPublicInputs {
+        a0: Felt252::one(),
+        a1: Felt252::one(),
+    };
+    let proof_options = ProofOptions {
+        fri_final_poly_degree: 3,
+        ..ProofOptions::default_test_options()
+    };
+
+    let proof = Prover::<FibonacciAIR<Stark252PrimeField>>::prove(
+        &trace,
+        &pub_inputs,
+        &proof_options,
+        StoneProverTranscript::new(&[]),
+    )
+    .unwrap();
+
+    assert!(Verifier::<FibonacciAIR<Stark252PrimeField>>::verify(
+        &proof,
+        &pub_inputs,
+        &proof_options,
+        StoneProverTranscript::new(&[]),
+    ));
+    assert!(!Verifier::<FibonacciAIR<Stark252PrimeField>>::verify(
+        &proof,
+        &pub_inputs,
+        &ProofOptions::default_test_options(),
+        StoneProverTranscript::new(&[]),
+    ));
+}
+
+#[test_log::test]
+fn test_prove_offset_counter_with_non_adjacent_frame_rows() {
+    let start = Felt252::from(5);
+    let trace = offset_counter_trace(start, 32);
+    let proof_options = ProofOptions::default_test_options();
+
+    let proof = Prover::<OffsetCounterAIR<Stark252PrimeField>>::prove(
+        &trace,
+        &start,
+        &proof_options,
+        StoneProverTranscript::new(&[]),
+    )
+    .unwrap();
+    assert_eq!(proof.trace_ood_evaluations.height, 2);
+    assert!(Verifier::<OffsetCounterAIR<Stark252PrimeField>>::verify(
+        &proof,
+        &start,
+        &proof_options,
+        StoneProverTranscript::new(&[]),
+    ));
+    assert!(!Verifier::<OffsetCounterAIR<Stark252PrimeField>>::verify(
+        &proof,
+        &Felt252::from(6),
+        &proof_options,
+        StoneProverTranscript::new(&[]),
+    ));
+}
+
+#[test_log::test]
+fn test_verify_batch_reports_only_the_corrupted_proof() {
+    let proof_options = ProofOptions::default_test_options();
+    let mut instances: Vec<_> = [1u64, 2, 3]
+        .into_iter()
+        .map(|a0| {
+            let pub_inputs = FibonacciPublicInputs {
+                a0: Felt252::from(a0),
+                a1: Felt252::one(),
+            };
+            let trace = simple_fibonacci::fibonacci_trace([pub_inputs.a0, pub_inputs.a1], 16);
+            let proof = Prover::<FibonacciAIR<Stark252PrimeField>>::prove(
+                &trace,
+                &pub_inputs,
+                &proof_options,
+                StoneProverTranscript::new(&[]),
+            )
+            .unwrap();
+            (proof, pub_inputs)
+        })
+        .collect();
+    instances[1].0.fri_final_poly[0] += Felt252::one();
+
+    let batch: Vec<_> = instances
+        .iter()
+        .map(|(proof, pub_inputs)| (proof, pub_inputs))
+        .collect();
+    let results =
+        Verifier::<FibonacciAIR<Stark252PrimeField>>::verify_batch(&batch, &proof_options, || {
+            StoneProverTranscript::new(&[])
+        });
+
+    assert_eq!(results.len(), 3);
+    assert!(results[0].is_ok());
+    assert!(matches!(results[1], Err(VerificationError::InvalidProof)));
+    assert!(results[2].is_ok());
+}
+
+#[test_log::test]
+fn test_prove_fib_zero_knowledge_is_reproducible_from_its_seed() {
+    let trace = simple_fibonacci::fibonacci_trace([Felt252::from(1), Felt252::from(1)], 32);
+    let proof_options = ProofOptions {
+        zero_knowledge: true,
+        ..ProofOptions::default_test_options()
+    };
+    let pub_inputs = FibonacciPublicInputs {
+        a0: Felt252::one(),
+        a1: Felt252::one(),
+    };
+    let prove = |zk_seed| {
+        let proof = Prover::<FibonacciAIR<Stark252PrimeField>>::prove_with_zk_seed(
+            &trace,
+            &pub_inputs,
+            &proof_options,
+            StoneProverTranscript::new(&[]),
+            zk_seed,
+        )
+        .unwrap();
+        assert!(Verifier::<FibonacciAIR<Stark252PrimeField>>::verify(
+            &proof,
+            &pub_inputs,
+            &proof_options,
+            StoneProverTranscript::new(&[]),
+        ));
+        bincode::serde::encode_to_vec(&proof, bincode::config::standard()).unwrap()
+    };
+
+    let proof_bytes = prove([7; 32]);
+    assert_eq!(proof_bytes, prove([7; 32]));
+    assert_ne!(proof_bytes, prove([8; 32]));
+}
+
+#[test_log::test]
+fn test_prove_fib_with_distinct_queries() {
+    let trace = simple_fibonacci::fibonacci_trace([Felt252::from(1), Felt252::from(1)], 16);
+    let mut proof_options = ProofOptions::default_test_options();
+    proof_options.distinct_queries = true;
+    let pub_inputs = FibonacciPublicInputs {
+        a0: Felt252::one(),
+        a1: Felt252::one(),
+    };
+
+    let proof = Prover::<FibonacciAIR<Stark252PrimeField>>::prove(
+        &trace,
+        &pub_inputs,
+        &proof_options,
+        StoneProverTranscript::new(&[]),
+    )
+    .unwrap();
+    let query_indexes = Verifier::<FibonacciAIR<Stark252PrimeField>>::replay_query_indexes(
+        &proof,
+        &pub_inputs,
+        &proof_options,
+        StoneProverTranscript::new(&[]),
+    );
+    for (i, iota) in query_indexes.iter().enumerate() {
+        assert!(!query_indexes[..i].contains(iota));
+    }
+    assert!(Verifier::<FibonacciAIR<Stark252PrimeField>>::verify(
+        &proof,
+        &pub_inputs,
+        &proof_options,
+        StoneProverTranscript::new(&[]),
+    ));
+}
+
+#[test_log::test]
+fn test_prove_fib_with_more_distinct_queries_than_pairs_fails() {
+    let trace = simple_fibonacci::fibonacci_trace([Felt252::from(1), Felt252::from(1)], 8);
+    let mut proof_options = ProofOptions::default_test_options();
+    proof_options.distinct_queries = true;
+    // The LDE domain has `8 * blowup_factor` points, so half as many pairs.
+    proof_options.fri_number_of_queries = 4 * proof_options.blowup_factor as usize + 1;
+    let pub_inputs = FibonacciPublicInputs {
+        a0: Felt252::one(),
+        a1: Felt252::one(),
+    };
+
+    assert!(matches!(
+        Prover::<FibonacciAIR<Stark252PrimeField>>::prove(
+            &trace,
+            &pub_inputs,
+            &proof_options,
+            StoneProverTranscript::new(&[]),
+        ),
+        Err(ProvingError::WrongParameter(_))
+    ));
+}
+
+/// The challenges that `fibonacci_challenges_transcript` returns, in the order the prover and
+/// the verifier of an 8 row Fibonacci proof with the default test options sample them: the
+/// composition coefficient β, the out of domain point z, the DEEP coefficient γ, the three
+/// FRI folding challenges 𝜁ₖ and the three query indexes 𝜄ₛ.
+fn fibonacci_golden_challenges() -> Vec<Challenge<Stark252PrimeField>> {
+    let mut challenges: Vec<_> = [5u64, 7, 11, 13, 17, 19]
+        .into_iter()
+        .map(|value| Challenge::FieldElement(Felt252::from(value)))
+        .collect();
+    challenges.extend([1, 2, 3].map(Challenge::Index));
+    challenges
+}
+
+fn fibonacci_challenges_transcript() -> TestTranscript<Stark252PrimeField> {
+    let mut transcript =
+        TestTranscript::with_field_elements(&[5u64, 7, 11, 13, 17, 19].map(Felt252::from));
+    for index in [1, 2, 3] {
+        transcript.push_u64(index);
+    }
+    transcript
+}
+
+#[test_log::test]
+fn test_prove_fib_samples_the_golden_challenge_sequence() {
+    let trace = simple_fibonacci::fibonacci_trace([Felt252::from(1), Felt252::from(1)], 8);
+    let proof_options = ProofOptions::default_test_options();
+    let pub_inputs = FibonacciPublicInputs {
+        a0: Felt252::one(),
+        a1: Felt252::one(),
+    };
+
+    let mut prover_transcript = RecordingTranscript::new(fibonacci_challenges_transcript());
+    let proof = Prover::<FibonacciAIR<Stark252PrimeField>>::prove(
+        &trace,
+        &pub_inputs,
+        &proof_options,
+        &mut prover_transcript,
+    )
+    .unwrap();
+    assert_eq!(
+        prover_transcript.challenges(),
+        fibonacci_golden_challenges()
+    );
+    assert_eq!(
+        Verifier::<FibonacciAIR<Stark252PrimeField>>::replay_query_indexes(
+            &proof,
+            &pub_inputs,
+            &proof_options,
+            fibonacci_challenges_transcript(),
+        ),
+        vec![1, 2, 3]
+    );
+
+    let mut verifier_transcript = RecordingTranscript::new(fibonacci_challenges_transcript());
+    assert!(Verifier::<FibonacciAIR<Stark252PrimeField>>::verify(
+        &proof,
+        &pub_inputs,
+        &proof_options,
+        &mut verifier_transcript,
+    ));
+    assert_eq!(
+        verifier_transcript.challenges(),
+        fibonacci_golden_challenges()
+    );
+}
+
+/// The challenges that a `StoneProverTranscript` returns to the prover and the verifier of an
+/// 8 row Fibonacci proof with the default test options, in the same order as
+/// `fibonacci_golden_challenges`: β, z, γ, the three 𝜁ₖ and the three 𝜄ₛ.
+fn fibonacci_stone_golden_challenges() -> Vec<Challenge<Stark252PrimeField>> {
+    let mut challenges: Vec<_> = [
+        "7e34ffd5ae86b8932817a785cde161e30e9950c5766f4710e507a2daf798df9",
+        "a9d7976ac476a45245394ce6020ba4866cc74d6d434be61246f55ae3f1918c",
+        "6401b352d3c4fdb89df476d8d719bb6ec5018e85fab8ecd00bd5466b2083232",
+        "11be1805705ad03fb3af5db63db09730b30d7a766793225ad0713ac1277a109",
+        "57f12625760a40b91449ac2873ed9acdc774282bfc78a5b005744de202625bb",
+        "3e2cbd3bd08bdd8ab2cf71fcfbf67462ad9fb0220325f042e64e1a72087c372",
+    ]
+    .into_iter()
+    .map(|value| Challenge::FieldElement(Felt252::from_hex_unchecked(value)))
+    .collect();
+    challenges.extend([13, 6, 11].map(Challenge::Index));
+    challenges
+}
+
+#[test_log::test]
+fn test_prove_fib_with_stone_transcript_samples_the_golden_challenge_sequence() {
+    let trace = simple_fibonacci::fibonacci_trace([Felt252::from(1), Felt252::from(1)], 8);
+    let proof_options = ProofOptions::default_test_options();
+    let pub_inputs = FibonacciPublicInputs {
+        a0: Felt252::one(),
+        a1: Felt252::one(),
+    };
+
+    let mut prover_transcript = RecordingTranscript::new(StoneProverTranscript::new(&[]));
+    let proof = Prover::<FibonacciAIR<Stark252PrimeField>>::prove(
+        &trace,
+        &pub_inputs,
+        &proof_options,
+        &mut prover_transcript,
+    )
+    .unwrap();
+    assert_eq!(
+        prover_transcript.challenges(),
+        fibonacci_stone_golden_challenges()
+    );
+
+    let mut verifier_transcript = RecordingTranscript::new(StoneProverTranscript::new(&[]));
+    assert!(Verifier::<FibonacciAIR<Stark252PrimeField>>::verify(
+        &proof,
+        &pub_inputs,
+        &proof_options,
+        &mut verifier_transcript,
+    ));
+    assert_eq!(
+        verifier_transcript.challenges(),
+        fibonacci_stone_golden_challenges()
+    );
+}
+
+#[cfg(feature = "test_fiat_shamir")]
+#[test_log::test]
+fn test_prove_fib_with_test_transcript() {
+    let trace = simple_fibonacci::fibonacci_trace([Felt252::from(1), Felt252::from(1)], 8);
+    let proof_options = ProofOptions::default_test_options();
+    let pub_inputs = FibonacciPublicInputs {
+        a0: Felt252::one(),
+        a1: Felt252::one(),
+    };
+    let challenges = [5u64, 7, 11, 13, 17, 19].map(Felt252::from);
+    let indexes = [1, 2, 3];
+
+    let proof = Prover::<FibonacciAIR<Stark252PrimeField>>::prove_with_test_transcript(
+        &trace,
+        &pub_inputs,
+        &proof_options,
+        &challenges,
+        &indexes,
+    )
+    .unwrap();
+    assert_eq!(
+        Verifier::<FibonacciAIR<Stark252PrimeField>>::replay_query_indexes(
+            &proof,
+            &pub_inputs,
+            &proof_options,
+            crate::transcript::test_transcript(&challenges, &indexes),
+        ),
+        vec![1, 2, 3]
+    );
+
+    assert!(
+        Verifier::<FibonacciAIR<Stark252PrimeField>>::verify_with_test_transcript(
+            &proof,
+            &pub_inputs,
+            &proof_options,
+            &challenges,
+            &indexes,
+        )
+    );
+    assert!(
+        !Verifier::<FibonacciAIR<Stark252PrimeField>>::verify_with_test_transcript(
+            &proof,
+            &pub_inputs,
+            &proof_options,
+            &[5u64, 7, 11, 13, 17, 23].map(Felt252::from),
+            &indexes,
+        )
+    );
+}
+
+#[cfg(feature = "unsafe_bench")]
+#[test_log::test]
+fn test_verify_only_fri_accepts_a_proof_of_other_public_inputs() {
+    let trace = simple_fibonacci::fibonacci_trace([Felt252::from(1), Felt252::from(1)], 8);
+    let proof_options = ProofOptions::default_test_options();
+    let pub_inputs = FibonacciPublicInputs {
+        a0: Felt252::one(),
+        a1: Felt252::one(),
+    };
+    // The public inputs are not part of the transcript, so the FRI checks do not depend on
+    // them, while the boundary constraints do not hold for them.
+    let wrong_pub_inputs = FibonacciPublicInputs {
+        a0: Felt252::one(),
+        a1: Felt252::from(2),
+    };
+
+    let proof = Prover::<FibonacciAIR<Stark252PrimeField>>::prove(
+        &trace,
+        &pub_inputs,
+        &proof_options,
+        StoneProverTranscript::new(&[]),
+    )
+    .unwrap();
+
+    assert!(!Verifier::<FibonacciAIR<Stark252PrimeField>>::verify(
+        &proof,
+        &wrong_pub_inputs,
+        &proof_options,
+        StoneProverTranscript::new(&[]),
+    ));
+    assert!(
+        Verifier::<FibonacciAIR<Stark252PrimeField>>::verify_only_fri(
+            &proof,
+            &wrong_pub_inputs,
+            &proof_options,
+            StoneProverTranscript::new(&[]),
+        )
+    );
+}
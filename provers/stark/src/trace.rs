@@ -1,4 +1,9 @@
+use crate::debug::{for_each_lde_trace_violation, ConstraintViolation};
 use crate::table::Table;
+use crate::traits::AIR;
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, string::ToString, vec::Vec};
+use core::ops::ControlFlow;
 use itertools::Itertools;
 use lambdaworks_math::fft::errors::FFTError;
 use lambdaworks_math::field::traits::{IsField, IsSubFieldOf};
@@ -22,6 +27,52 @@ pub struct TraceTable<F: IsField> {
     pub step_size: usize,
     pub num_main_columns: usize,
     pub num_aux_columns: usize,
+    /// Optional labels of the columns, in the same order as the columns of the table.
+    pub column_names: Option<Vec<String>>,
+}
+
+/// Errors that can happen when accessing the columns of a `TraceTable` by name.
+#[derive(Debug, PartialEq, Eq)]
+pub enum ColumnNameError {
+    /// The trace has no column with the given name, or its columns are not named.
+    UnknownColumn(String),
+    /// The number of names does not match the number of columns of the trace.
+    WrongNumberOfNames { expected: usize, got: usize },
+}
+
+/// An index out of the bounds of a table, as returned by [`TraceTable::try_get_row`] and
+/// [`TraceTable::try_get_cell`].
+#[derive(Debug, PartialEq, Eq)]
+pub enum TraceError {
+    /// The table has `n_rows` rows, so it has no row `row_idx`.
+    RowOutOfRange { row_idx: usize, n_rows: usize },
+    /// The table has `n_cols` columns, so it has no column `col_idx`.
+    ColumnOutOfRange { col_idx: usize, n_cols: usize },
+}
+
+/// The rows given to [`TraceTable::from_rows`] do not make a trace.
+#[derive(Debug, PartialEq, Eq)]
+pub enum FromRowsError {
+    /// The row `row_idx` is not as wide as the first one.
+    RowWidthMismatch {
+        row_idx: usize,
+        expected: usize,
+        got: usize,
+    },
+    /// The rows are narrower than the number of main columns. Having no rows at all counts
+    /// as a width of zero.
+    TooFewColumns {
+        num_main_columns: usize,
+        width: usize,
+    },
+}
+
+/// The trace given to [`TraceTable::augment`] does not have as many rows as the trace it
+/// is appended to.
+#[derive(Debug, PartialEq, Eq)]
+pub struct RowCountMismatch {
+    pub expected: usize,
+    pub got: usize,
 }
 
 impl<F: IsField> TraceTable<F> {
@@ -38,6 +89,7 @@ impl<F: IsField> TraceTable<F> {
             num_main_columns,
             num_aux_columns,
             step_size,
+            column_names: None,
         }
     }
 
@@ -46,8 +98,6 @@ impl<F: IsField> TraceTable<F> {
         num_main_columns: usize,
         step_size: usize,
     ) -> Self {
-        println!("COLUMNS LEN: {}", columns.len());
-        println!("NUM MAIN COLUMNS: {}", num_main_columns);
         let num_aux_columns = columns.len() - num_main_columns;
         let table = Table::from_columns(columns);
         Self {
@@ -55,6 +105,7 @@ impl<F: IsField> TraceTable<F> {
             num_main_columns,
             num_aux_columns,
             step_size,
+            column_names: None,
         }
     }
 
@@ -68,7 +119,41 @@ impl<F: IsField> TraceTable<F> {
             num_main_columns,
             num_aux_columns,
             step_size,
+            column_names: None,
+        }
+    }
+
+    /// Creates a trace from its rows, as they are produced when executing a computation.
+    /// All the rows should have the same width, which is the number of columns of the trace,
+    /// and at least `num_main_columns`, the columns after them being the auxiliary ones.
+    pub fn from_rows(
+        rows: &[Vec<FieldElement<F>>],
+        num_main_columns: usize,
+        step_size: usize,
+    ) -> Result<Self, FromRowsError> {
+        let width = rows.first().map_or(0, Vec::len);
+        if let Some((row_idx, row)) = rows.iter().find_position(|row| row.len() != width) {
+            return Err(FromRowsError::RowWidthMismatch {
+                row_idx,
+                expected: width,
+                got: row.len(),
+            });
         }
+        let num_aux_columns =
+            width
+                .checked_sub(num_main_columns)
+                .ok_or(FromRowsError::TooFewColumns {
+                    num_main_columns,
+                    width,
+                })?;
+
+        let data = rows.iter().flatten().cloned().collect();
+        Ok(Self::new(
+            data,
+            num_main_columns,
+            num_aux_columns,
+            step_size,
+        ))
     }
 
     pub fn empty() -> Self {
@@ -110,6 +195,23 @@ impl<F: IsField> TraceTable<F> {
         self.table.get_row_mut(row_idx)
     }
 
+    /// Returns the row `row_idx`, or `TraceError::RowOutOfRange` if the trace has no such row.
+    /// Unlike `get_row`, it does not panic, so it is meant for indexes computed from untrusted
+    /// values rather than for hot loops.
+    pub fn try_get_row(&self, row_idx: usize) -> Result<&[FieldElement<F>], TraceError> {
+        self.table.try_get_row(row_idx)
+    }
+
+    /// Returns the element at row `row_idx` and column `col_idx`, or the `TraceError` naming
+    /// the index out of range.
+    pub fn try_get_cell(
+        &self,
+        row_idx: usize,
+        col_idx: usize,
+    ) -> Result<&FieldElement<F>, TraceError> {
+        self.table.try_get(row_idx, col_idx)
+    }
+
     pub fn last_row(&self) -> &[FieldElement<F>] {
         self.get_row(self.n_rows() - 1)
     }
@@ -118,6 +220,74 @@ impl<F: IsField> TraceTable<F> {
         self.table.columns()
     }
 
+    /// Labels the columns of the trace. There should be exactly one name per column.
+    pub fn with_column_names(mut self, names: Vec<String>) -> Result<Self, ColumnNameError> {
+        if names.len() != self.n_cols() {
+            return Err(ColumnNameError::WrongNumberOfNames {
+                expected: self.n_cols(),
+                got: names.len(),
+            });
+        }
+        self.column_names = Some(names);
+        Ok(self)
+    }
+
+    /// Returns the index of the column labeled `name`.
+    pub fn column_index(&self, name: &str) -> Result<usize, ColumnNameError> {
+        self.column_names
+            .as_ref()
+            .and_then(|names| names.iter().position(|column_name| column_name == name))
+            .ok_or_else(|| ColumnNameError::UnknownColumn(name.to_string()))
+    }
+
+    /// Returns the values of the column labeled `name`.
+    pub fn get_col_by_name(&self, name: &str) -> Result<Vec<FieldElement<F>>, ColumnNameError> {
+        let col = self.column_index(name)?;
+        Ok((0..self.n_rows())
+            .map(|row| self.table.get(row, col).clone())
+            .collect())
+    }
+
+    /// Places the columns of `other` to the right of the ones of this trace, so that an AIR
+    /// can constrain two computations together. The main columns of `other` go after the
+    /// main columns of this trace, and its auxiliary columns after the auxiliary ones.
+    /// Returns the index of the first main column of `other` in the augmented trace.
+    /// Both traces should have the same number of rows. The column names are kept only if
+    /// both traces have them.
+    pub fn augment(&mut self, other: &TraceTable<F>) -> Result<usize, RowCountMismatch> {
+        if other.n_rows() != self.n_rows() {
+            return Err(RowCountMismatch {
+                expected: self.n_rows(),
+                got: other.n_rows(),
+            });
+        }
+
+        let offset = self.num_main_columns;
+        let mut columns = self.columns();
+        let mut other_columns = other.columns();
+        let aux_columns = columns.split_off(self.num_main_columns);
+        let other_aux_columns = other_columns.split_off(other.num_main_columns);
+        columns.extend(other_columns);
+        columns.extend(aux_columns);
+        columns.extend(other_aux_columns);
+
+        self.column_names = match (self.column_names.take(), &other.column_names) {
+            (Some(mut names), Some(other_names)) => {
+                let aux_names = names.split_off(self.num_main_columns);
+                names.extend_from_slice(&other_names[..other.num_main_columns]);
+                names.extend(aux_names);
+                names.extend_from_slice(&other_names[other.num_main_columns..]);
+                Some(names)
+            }
+            _ => None,
+        };
+        self.table = Table::from_columns(columns);
+        self.num_main_columns += other.num_main_columns;
+        self.num_aux_columns += other.num_aux_columns;
+
+        Ok(offset)
+    }
+
     /// Given a slice of integer numbers representing column indexes, merge these columns into
     /// a one-dimensional vector.
     ///
@@ -184,6 +354,103 @@ impl<F: IsField> TraceTable<F> {
         }
     }
 }
+
+impl<F: IsFFTField> TraceTable<F> {
+    /// Checks that the trace satisfies the boundary and transition constraints of `air`,
+    /// stopping at the first row where one of them does not hold.
+    ///
+    /// The auxiliary trace is built from this main trace and the given `rap_challenges`.
+    /// Unlike the validation done by the prover in debug builds, the constraints are
+    /// evaluated directly over the trace, without interpolating it.
+    pub fn validate<A>(
+        &self,
+        air: &A,
+        rap_challenges: &[FieldElement<A::FieldExtension>],
+    ) -> Result<(), ConstraintViolation<A::FieldExtension>>
+    where
+        F: IsSubFieldOf<A::FieldExtension>,
+        A: AIR<Field = F>,
+    {
+        let mut first_violation = None;
+        self.for_each_violation(air, rap_challenges, |violation| {
+            first_violation = Some(violation);
+            ControlFlow::Break(())
+        });
+
+        first_violation.map_or(Ok(()), Err)
+    }
+
+    /// Returns every boundary and transition constraint of `air` that does not hold over the
+    /// trace, boundary constraints first and then transition constraints row by row. See
+    /// [`TraceTable::validate`].
+    pub fn constraint_violations<A>(
+        &self,
+        air: &A,
+        rap_challenges: &[FieldElement<A::FieldExtension>],
+    ) -> Vec<ConstraintViolation<A::FieldExtension>>
+    where
+        F: IsSubFieldOf<A::FieldExtension>,
+        A: AIR<Field = F>,
+    {
+        let mut violations = Vec::new();
+        self.for_each_violation(air, rap_challenges, |violation| {
+            violations.push(violation);
+            ControlFlow::Continue(())
+        });
+
+        violations
+    }
+
+    fn for_each_violation<A>(
+        &self,
+        air: &A,
+        rap_challenges: &[FieldElement<A::FieldExtension>],
+        on_violation: impl FnMut(ConstraintViolation<A::FieldExtension>) -> ControlFlow<()>,
+    ) where
+        F: IsSubFieldOf<A::FieldExtension>,
+        A: AIR<Field = F>,
+    {
+        let aux_trace = air.build_auxiliary_trace(self, rap_challenges);
+        let lde_trace =
+            LDETraceTable::from_columns(self.columns(), aux_trace.columns(), A::STEP_SIZE, 1);
+
+        let periodic_columns: Vec<_> = air
+            .get_periodic_column_values()
+            .iter()
+            .map(|values| {
+                values
+                    .iter()
+                    .cycle()
+                    .take(air.trace_length())
+                    .cloned()
+                    .collect()
+            })
+            .collect();
+
+        for_each_lde_trace_violation(
+            air,
+            &lde_trace,
+            &periodic_columns,
+            rap_challenges,
+            on_violation,
+        )
+    }
+
+    /// Returns the degree of the polynomial interpolating each column of the trace over the
+    /// trace domain, in the order of the columns. Every degree is below the number of rows, and
+    /// a degree close to it means the column looks random to the constraints, which helps to
+    /// check the degrees of the transition constraints and the blowup factor.
+    pub fn column_interpolation_degrees(&self) -> Vec<usize>
+    where
+        FieldElement<F>: Send + Sync,
+    {
+        self.compute_trace_polys::<F>()
+            .iter()
+            .map(Polynomial::degree)
+            .collect()
+    }
+}
+
 pub struct LDETraceTable<F, E>
 where
     E: IsField,
@@ -241,6 +508,18 @@ where
         self.main_table.width + self.aux_table.width
     }
 
+    /// Returns the table with one of every `step` rows of `self`. The LDE of a trace over a
+    /// coset in natural order becomes its LDE over the coset with the same offset and a
+    /// blowup factor `step` times smaller, see [`crate::domain::Domain::composition_domain`].
+    pub(crate) fn subsample(&self, step: usize) -> Self {
+        Self {
+            main_table: self.main_table.subsample_rows(step),
+            aux_table: self.aux_table.subsample_rows(step),
+            lde_step_size: self.lde_step_size / step,
+            blowup_factor: self.blowup_factor / step,
+        }
+    }
+
     pub fn num_rows(&self) -> usize {
         self.main_table.height
     }
@@ -271,6 +550,16 @@ where
     }
 }
 
+/// Returns the exponents `e` of the points `x * gᵉ` at which the trace polynomials are
+/// evaluated to build a frame, one per row of the frame: the `step_size` rows of the step at
+/// every offset in `frame_offsets`. The offsets need not be contiguous.
+pub fn frame_row_exponents(frame_offsets: &[usize], step_size: usize) -> Vec<usize> {
+    frame_offsets
+        .iter()
+        .flat_map(|offset| offset * step_size..(offset + 1) * step_size)
+        .collect()
+}
+
 /// Given a slice of trace polynomials, an evaluation point `x`, the frame offsets
 /// corresponding to the computation of the transitions, and a primitive root,
 /// outputs the trace evaluations of each trace polynomial over the values used to
@@ -289,13 +578,8 @@ where
     F: IsSubFieldOf<E>,
     E: IsField,
 {
-    let evaluation_points = frame_offsets
-        .iter()
-        .flat_map(|offset| {
-            let exponents_range_start = offset * step_size;
-            let exponents_range_end = (offset + 1) * step_size;
-            (exponents_range_start..exponents_range_end).collect_vec()
-        })
+    let evaluation_points = frame_row_exponents(frame_offsets, step_size)
+        .into_iter()
         .map(|exponent| primitive_root.pow(exponent) * x)
         .collect_vec();
 
@@ -349,10 +633,69 @@ pub fn columns2rows<F: IsField>(columns: Vec<Vec<FieldElement<F>>>) -> Vec<Vec<F
 
 #[cfg(test)]
 mod test {
-    use super::TraceTable;
-    use lambdaworks_math::field::{element::FieldElement, fields::u64_prime_field::F17};
+    use super::{
+        columns2rows, ColumnNameError, FromRowsError, RowCountMismatch, TraceError, TraceTable,
+    };
+    use crate::{
+        debug::ConstraintViolation,
+        examples::simple_fibonacci::{fibonacci_trace, FibonacciAIR, FibonacciPublicInputs},
+        proof::options::ProofOptions,
+        traits::AIR,
+    };
+    use lambdaworks_math::field::{
+        element::FieldElement, fields::u64_prime_field::F17, traits::IsFFTField,
+    };
     type FE = FieldElement<F17>;
 
+    #[test]
+    fn try_get_row_and_cell_name_the_index_out_of_range() {
+        let trace = TraceTable::<F17>::from_columns_main(
+            vec![
+                [1u64, 2, 3, 4].map(FE::from).to_vec(),
+                [5u64, 6, 7, 8].map(FE::from).to_vec(),
+            ],
+            1,
+        );
+
+        assert_eq!(trace.try_get_row(3), Ok(&[FE::from(4), FE::from(8)][..]));
+        assert_eq!(trace.try_get_cell(2, 1), Ok(&FE::from(7)));
+        assert_eq!(
+            trace.try_get_row(4),
+            Err(TraceError::RowOutOfRange {
+                row_idx: 4,
+                n_rows: 4
+            })
+        );
+        assert_eq!(
+            trace.try_get_cell(4, 0),
+            Err(TraceError::RowOutOfRange {
+                row_idx: 4,
+                n_rows: 4
+            })
+        );
+        assert_eq!(
+            trace.try_get_cell(0, 2),
+            Err(TraceError::ColumnOutOfRange {
+                col_idx: 2,
+                n_cols: 2
+            })
+        );
+    }
+
+    #[test]
+    fn column_interpolation_degrees_of_constant_and_ramp_columns() {
+        // The columns are interpolated over the powers of a primitive root of unity, so the
+        // linear ramp takes the values 1 + 3x at x = 1, ω, ω², ...
+        let omega = F17::get_primitive_root_of_unity(3).unwrap();
+        let constant = vec![FE::from(5); 8];
+        let ramp: Vec<_> = (0..8u64)
+            .map(|i| FE::one() + FE::from(3) * omega.pow(i))
+            .collect();
+        let trace = TraceTable::from_columns_main(vec![constant, ramp], 1);
+
+        assert_eq!(trace.column_interpolation_degrees(), vec![0, 1]);
+    }
+
     #[test]
     fn test_cols() {
         let col_1 = vec![FE::from(1), FE::from(2), FE::from(5), FE::from(13)];
@@ -363,4 +706,197 @@ mod test {
 
         assert_eq!(res_cols, vec![col_1, col_2]);
     }
+
+    #[test]
+    fn trace_from_transposed_columns_is_the_trace_from_the_columns() {
+        let col_1 = vec![FE::from(1), FE::from(2), FE::from(5), FE::from(13)];
+        let col_2 = vec![FE::from(1), FE::from(3), FE::from(8), FE::from(21)];
+        let col_3 = vec![FE::from(4), FE::from(0), FE::from(7), FE::from(9)];
+        let columns = vec![col_1, col_2, col_3];
+
+        let from_rows = TraceTable::from_rows(&columns2rows(columns.clone()), 2, 1).unwrap();
+
+        assert_eq!(from_rows, TraceTable::from_columns(columns, 2, 1));
+        assert_eq!(from_rows.num_aux_columns, 1);
+    }
+
+    #[test]
+    fn rows_of_different_widths_are_rejected() {
+        let rows = vec![vec![FE::one(); 2], vec![FE::one(); 2], vec![FE::one(); 3]];
+
+        assert_eq!(
+            TraceTable::from_rows(&rows, 2, 1),
+            Err(FromRowsError::RowWidthMismatch {
+                row_idx: 2,
+                expected: 2,
+                got: 3
+            })
+        );
+    }
+
+    #[test]
+    fn rows_narrower_than_the_main_columns_are_rejected() {
+        let rows = vec![vec![FE::one(); 2]; 4];
+
+        assert_eq!(
+            TraceTable::from_rows(&rows, 3, 1),
+            Err(FromRowsError::TooFewColumns {
+                num_main_columns: 3,
+                width: 2
+            })
+        );
+        assert_eq!(
+            TraceTable::<F17>::from_rows(&[], 1, 1),
+            Err(FromRowsError::TooFewColumns {
+                num_main_columns: 1,
+                width: 0
+            })
+        );
+    }
+
+    #[test]
+    fn named_columns_are_read_by_position_and_by_name() {
+        let col_1 = vec![FE::from(1), FE::from(2), FE::from(5), FE::from(13)];
+        let col_2 = vec![FE::from(1), FE::from(3), FE::from(8), FE::from(21)];
+
+        let trace_table = TraceTable::from_columns_main(vec![col_1.clone(), col_2.clone()], 1)
+            .with_column_names(vec!["a".to_string(), "b".to_string()])
+            .unwrap();
+
+        assert_eq!(trace_table.columns(), vec![col_1.clone(), col_2.clone()]);
+        assert_eq!(trace_table.get_col_by_name("a"), Ok(col_1));
+        assert_eq!(trace_table.get_col_by_name("b"), Ok(col_2));
+        assert_eq!(
+            trace_table.get_col_by_name("c"),
+            Err(ColumnNameError::UnknownColumn("c".to_string()))
+        );
+    }
+
+    #[test]
+    fn column_names_must_match_the_number_of_columns() {
+        let trace_table = TraceTable::from_columns_main(vec![vec![FE::one(); 4]; 2], 1);
+
+        assert_eq!(
+            trace_table.with_column_names(vec!["a".to_string()]),
+            Err(ColumnNameError::WrongNumberOfNames {
+                expected: 2,
+                got: 1
+            })
+        );
+    }
+
+    #[test]
+    fn augmented_fibonacci_traces_can_be_constrained_together() {
+        let mut trace = fibonacci_trace([FE::one(), FE::one()], 8);
+        let doubled_trace = fibonacci_trace([FE::from(2), FE::from(2)], 8);
+
+        let offset = trace.augment(&doubled_trace).unwrap();
+
+        assert_eq!(offset, 1);
+        assert_eq!(trace.n_cols(), 2);
+        assert_eq!(trace.num_main_columns, 2);
+        // Cross constraint: the second sequence is twice the first one.
+        for row in trace.rows() {
+            assert_eq!(row[offset], FE::from(2) * row[0]);
+        }
+    }
+
+    #[test]
+    fn augmenting_keeps_main_columns_before_auxiliary_ones() {
+        let column = |value: u64| vec![FE::from(value); 4];
+        let mut trace = TraceTable::from_columns(vec![column(1), column(2)], 1, 1)
+            .with_column_names(vec!["a".to_string(), "a_aux".to_string()])
+            .unwrap();
+        let other = TraceTable::from_columns(vec![column(3), column(4)], 1, 1)
+            .with_column_names(vec!["b".to_string(), "b_aux".to_string()])
+            .unwrap();
+
+        assert_eq!(trace.augment(&other), Ok(1));
+        assert_eq!(
+            trace.columns(),
+            vec![column(1), column(3), column(2), column(4)]
+        );
+        assert_eq!((trace.num_main_columns, trace.num_aux_columns), (2, 2));
+        assert_eq!(trace.get_col_by_name("b_aux"), Ok(column(4)));
+    }
+
+    #[test]
+    fn augmenting_with_a_trace_of_another_length_is_rejected() {
+        let mut trace = fibonacci_trace([FE::one(), FE::one()], 8);
+        let other = fibonacci_trace([FE::one(), FE::one()], 4);
+
+        assert_eq!(
+            trace.augment(&other),
+            Err(RowCountMismatch {
+                expected: 8,
+                got: 4
+            })
+        );
+        assert_eq!(trace, fibonacci_trace([FE::one(), FE::one()], 8));
+    }
+
+    #[test]
+    fn validate_accepts_a_valid_fibonacci_trace() {
+        let trace = fibonacci_trace([FE::one(), FE::one()], 8);
+        let pub_inputs = FibonacciPublicInputs {
+            a0: FE::one(),
+            a1: FE::one(),
+        };
+        let air = FibonacciAIR::new(
+            trace.n_rows(),
+            &pub_inputs,
+            &ProofOptions::default_test_options(),
+        );
+
+        assert_eq!(trace.validate(&air, &[]), Ok(()));
+    }
+
+    #[test]
+    fn validate_reports_first_violating_row_of_corrupted_fibonacci_trace() {
+        let mut trace = fibonacci_trace([FE::one(), FE::one()], 8);
+        // Rows hold 1, 1, 2, 3, 5, 8, 13, 21. Corrupting the fifth row breaks the
+        // transition evaluated at row 3, the first one reading it.
+        trace.get_row_mut(5)[0] = FE::from(9);
+        let pub_inputs = FibonacciPublicInputs {
+            a0: FE::one(),
+            a1: FE::one(),
+        };
+        let air = FibonacciAIR::new(
+            trace.n_rows(),
+            &pub_inputs,
+            &ProofOptions::default_test_options(),
+        );
+
+        assert_eq!(
+            trace.validate(&air, &[]),
+            Err(ConstraintViolation::Transition {
+                row: 3,
+                constraint_idx: 0,
+                value: FE::from(1),
+            })
+        );
+    }
+
+    #[test]
+    fn validate_reports_boundary_violation() {
+        let trace = fibonacci_trace([FE::one(), FE::from(2)], 8);
+        let pub_inputs = FibonacciPublicInputs {
+            a0: FE::one(),
+            a1: FE::one(),
+        };
+        let air = FibonacciAIR::new(
+            trace.n_rows(),
+            &pub_inputs,
+            &ProofOptions::default_test_options(),
+        );
+
+        assert_eq!(
+            trace.validate(&air, &[]),
+            Err(ConstraintViolation::Boundary {
+                row: 1,
+                constraint_idx: 1,
+                value: FE::one(),
+            })
+        );
+    }
 }
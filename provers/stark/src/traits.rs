@@ -1,4 +1,6 @@
-use std::collections::HashMap;
+use alloc::collections::BTreeMap;
+#[cfg(not(feature = "std"))]
+use alloc::{boxed::Box, vec::Vec};
 
 use lambdaworks_crypto::fiat_shamir::is_transcript::IsTranscript;
 use lambdaworks_math::{
@@ -8,6 +10,7 @@ use lambdaworks_math::{
     },
     polynomial::Polynomial,
 };
+use sha3::{Digest, Keccak256};
 
 use crate::{constraints::transition::TransitionConstraint, domain::Domain};
 
@@ -32,6 +35,9 @@ pub trait AIR {
         proof_options: &ProofOptions,
     ) -> Self;
 
+    /// Builds the auxiliary segment of the trace from the main trace and the challenges
+    /// sampled by `build_rap_challenges` once the main trace is committed. It is used by
+    /// randomized AIRs, for example for permutation or lookup arguments.
     fn build_auxiliary_trace(
         &self,
         _main_trace: &TraceTable<Self::Field>,
@@ -40,6 +46,8 @@ pub trait AIR {
         TraceTable::empty()
     }
 
+    /// Samples the challenges the auxiliary trace depends on. The verifier calls this method
+    /// at the same point of the transcript as the prover, after absorbing the main trace root.
     fn build_rap_challenges(
         &self,
         _transcript: &mut impl IsTranscript<Self::FieldExtension>,
@@ -47,13 +55,79 @@ pub trait AIR {
         Vec::new()
     }
 
+    /// Returns bytes that the prover and the verifier absorb into the transcript before
+    /// anything else, such as a configuration or version label, to bind them to the proof.
+    /// They are absorbed with `IsTranscript::append_many`, prefixed with their length. By
+    /// default nothing is absorbed.
+    fn transcript_init_data(&self) -> Vec<u8> {
+        Vec::new()
+    }
+
+    /// Returns a hash of the parameters of the AIR and of the proof options, which the prover
+    /// embeds in the proof so that the verifier rejects proofs made for other parameters with
+    /// a clear error. AIRs with parameters that are not part of their context, such as
+    /// constants of their constraints, should override it to hash them too.
+    fn parameter_hash(&self) -> [u8; 32] {
+        let context = self.context();
+        let options = self.options();
+        let (main_columns, aux_columns) = self.trace_layout();
+
+        let mut hasher = Keccak256::new();
+        for value in [
+            self.trace_length(),
+            context.trace_columns,
+            main_columns,
+            aux_columns,
+            context.num_transition_constraints,
+            self.composition_poly_degree_bound(),
+            Self::STEP_SIZE,
+        ] {
+            hasher.update((value as u64).to_be_bytes());
+        }
+        for offsets in [&context.transition_offsets, &context.transition_exemptions] {
+            hasher.update((offsets.len() as u64).to_be_bytes());
+            for offset in offsets {
+                hasher.update((*offset as u64).to_be_bytes());
+            }
+        }
+
+        hasher.update([
+            options.blowup_factor,
+            options.grinding_factor,
+            u8::from(options.zero_knowledge),
+            options.composition_blowup_factor.unwrap_or(0),
+            options.deep_combination as u8,
+            options.constraint_combination as u8,
+            u8::from(options.distinct_queries),
+        ]);
+        hasher.update((options.fri_number_of_queries as u64).to_be_bytes());
+        hasher.update(options.coset_offset.to_be_bytes());
+        hasher.update((options.fri_final_poly_degree as u64).to_be_bytes());
+
+        hasher.finalize().into()
+    }
+
     fn trace_layout(&self) -> (usize, usize);
 
     fn num_auxiliary_rap_columns(&self) -> usize {
         self.trace_layout().1
     }
 
-    fn composition_poly_degree_bound(&self) -> usize;
+    /// Returns a bound on the degree of the composition polynomial, which is split in
+    /// `composition_poly_degree_bound() / trace_length()` parts. The default is `d * N`, where
+    /// `d` is the maximum degree of the transition constraints rounded up to a power of two
+    /// and `N` is the trace length. This holds for any constraints, but AIRs whose zerofiers
+    /// lower the degree can override it with a tighter bound, which needs a smaller blowup
+    /// factor.
+    fn composition_poly_degree_bound(&self) -> usize {
+        let max_degree = self
+            .transition_constraints()
+            .iter()
+            .map(|constraint| constraint.degree())
+            .max()
+            .unwrap_or(1);
+        max_degree.next_power_of_two() * self.trace_length()
+    }
 
     /// The method called by the prover to evaluate the transitions corresponding to an evaluation frame.
     /// In the case of the prover, the main evaluation table of the frame takes values in
@@ -103,6 +177,12 @@ pub trait AIR {
         self.options().blowup_factor
     }
 
+    fn composition_blowup_factor(&self) -> u8 {
+        self.options()
+            .composition_blowup_factor
+            .unwrap_or(self.blowup_factor())
+    }
+
     fn coset_offset(&self) -> FieldElement<Self::Field> {
         FieldElement::from(self.options().coset_offset)
     }
@@ -118,12 +198,53 @@ pub trait AIR {
         self.context().num_transition_constraints
     }
 
+    /// Returns the number of boundary constraints, which may be zero for AIRs made only of
+    /// transition constraints. One coefficient is sampled for each of them in round 2.
+    fn num_boundary_constraints(
+        &self,
+        rap_challenges: &[FieldElement<Self::FieldExtension>],
+    ) -> usize {
+        self.boundary_constraints(rap_challenges).constraints.len()
+    }
+
+    /// Returns the smallest trace length that can be proven with this AIR. It is the smallest
+    /// power of two such that:
+    /// - FRI commits to at least one folded layer, which needs a trace of at least 4 rows.
+    ///   With fewer rows the verifier would never check the final value of FRI.
+    /// - The rows of the evaluation frame, given by the transition offsets, fit in the trace.
+    /// - Every transition constraint applies to at least one row, after its end exemptions.
+    ///
+    /// It does not depend on the blowup factor, which only sizes the LDE domain. The
+    /// zero-knowledge mode further requires the trace to be at least as long as the number of
+    /// masking coefficients.
+    fn minimum_trace_length(&self) -> usize {
+        let frame_rows = self
+            .context()
+            .transition_offsets
+            .iter()
+            .max()
+            .map_or(1, |offset| offset + 1);
+        let exempted_rows = self
+            .transition_constraints()
+            .iter()
+            .map(|constraint| constraint.end_exemptions())
+            .max()
+            .unwrap_or(0);
+        4.max(frame_rows).max(exempted_rows + 1).next_power_of_two()
+    }
+
     fn pub_inputs(&self) -> &Self::PublicInputs;
 
+    /// Returns the values of the periodic columns of the AIR, one cycle per column.
+    /// Periodic columns are fixed public columns, such as round constants, that the
+    /// constraints can reference without them being committed: both the prover and the
+    /// verifier interpolate them independently and pass their values to the transition
+    /// constraints as `periodic_values`. The length of each cycle must divide the trace length.
     fn get_periodic_column_values(&self) -> Vec<Vec<FieldElement<Self::Field>>> {
         vec![]
     }
 
+    /// Returns the polynomials interpolating the periodic columns over the trace domain.
     fn get_periodic_column_polynomials(&self) -> Vec<Polynomial<FieldElement<Self::Field>>> {
         let mut result = Vec::new();
         for periodic_column in self.get_periodic_column_values() {
@@ -153,8 +274,8 @@ pub trait AIR {
     ) -> Vec<Vec<FieldElement<Self::Field>>> {
         let mut evals = vec![Vec::new(); self.num_transition_constraints()];
 
-        let mut zerofier_groups: HashMap<ZerofierGroupKey, Vec<FieldElement<Self::Field>>> =
-            HashMap::new();
+        let mut zerofier_groups: BTreeMap<ZerofierGroupKey, Vec<FieldElement<Self::Field>>> =
+            BTreeMap::new();
 
         self.transition_constraints().iter().for_each(|c| {
             let period = c.period();
@@ -1,15 +1,21 @@
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
 use lambdaworks_crypto::fiat_shamir::is_transcript::IsTranscript;
+#[cfg(feature = "test_fiat_shamir")]
+use lambdaworks_crypto::fiat_shamir::test_transcript::TestTranscript;
 use lambdaworks_math::{
     field::{
-        element::FieldElement, fields::fft_friendly::stark_252_prime_field::Stark252PrimeField,
-        traits::IsFFTField,
+        element::FieldElement,
+        fields::fft_friendly::stark_252_prime_field::Stark252PrimeField,
+        traits::{IsFFTField, IsField, IsSubFieldOf},
     },
-    traits::{AsBytes, ByteConversion},
+    traits::{AsBytes, ByteConversion, ByteOrder},
     unsigned_integer::element::U256,
 };
 use sha3::{Digest, Keccak256};
 
 /// A transcript implementing `IsStarkTranscript` and compatible with Stone (https://github.com/starkware-libs/stone-prover).
+#[derive(Clone)]
 pub struct StoneProverTranscript {
     state: [u8; 32],
     seed_increment: U256,
@@ -114,6 +120,10 @@ impl IsTranscript<Stark252PrimeField> for StoneProverTranscript {
         self.state
     }
 
+    fn try_fork(&self) -> Option<Self> {
+        Some(self.clone())
+    }
+
     fn sample_field_element(&mut self) -> FieldElement<Stark252PrimeField> {
         let mut result = self.sample_big_int();
         while result >= Self::MODULUS_MAX_MULTIPLE {
@@ -131,7 +141,110 @@ impl IsTranscript<Stark252PrimeField> for StoneProverTranscript {
     }
 }
 
+/// A challenge sampled from a transcript, as recorded by `RecordingTranscript`.
+#[derive(Clone, Debug)]
+pub enum Challenge<F: IsField> {
+    /// A field element returned by `sample_field_element` or `sample_z_ood`.
+    FieldElement(FieldElement<F>),
+    /// An index returned by `sample_u64`.
+    Index(u64),
+}
+
+impl<F: IsField> PartialEq for Challenge<F> {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::FieldElement(a), Self::FieldElement(b)) => a == b,
+            (Self::Index(a), Self::Index(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+impl<F: IsField> Eq for Challenge<F> {}
+
+/// A transcript that forwards everything to `inner` and records the challenges it returns, in
+/// order. Proving or verifying with it gives the full challenge sequence of the protocol, so
+/// tests can pin it and changes of the transcript become visible.
+pub struct RecordingTranscript<F: IsField, T: IsTranscript<F>> {
+    inner: T,
+    challenges: Vec<Challenge<F>>,
+}
+
+impl<F: IsField, T: IsTranscript<F>> RecordingTranscript<F, T> {
+    pub fn new(inner: T) -> Self {
+        Self {
+            inner,
+            challenges: Vec::new(),
+        }
+    }
+
+    /// Returns the challenges sampled so far, in order.
+    pub fn challenges(&self) -> &[Challenge<F>] {
+        &self.challenges
+    }
+}
+
+impl<F: IsField, T: IsTranscript<F>> IsTranscript<F> for &mut RecordingTranscript<F, T> {
+    fn append_field_element(&mut self, element: &FieldElement<F>) {
+        self.inner.append_field_element(element);
+    }
+
+    fn append_bytes(&mut self, new_bytes: &[u8]) {
+        self.inner.append_bytes(new_bytes);
+    }
+
+    fn byte_order(&self) -> ByteOrder {
+        self.inner.byte_order()
+    }
+
+    fn state(&self) -> [u8; 32] {
+        self.inner.state()
+    }
+
+    fn sample_field_element(&mut self) -> FieldElement<F> {
+        let element = self.inner.sample_field_element();
+        self.challenges
+            .push(Challenge::FieldElement(element.clone()));
+        element
+    }
+
+    fn sample_u64(&mut self, upper_bound: u64) -> u64 {
+        let index = self.inner.sample_u64(upper_bound);
+        self.challenges.push(Challenge::Index(index));
+        index
+    }
+
+    fn sample_z_ood<S: IsSubFieldOf<F>>(
+        &mut self,
+        lde_roots_of_unity_coset: &[FieldElement<S>],
+        trace_roots_of_unity: &[FieldElement<S>],
+    ) -> FieldElement<F> {
+        let z = self
+            .inner
+            .sample_z_ood(lde_roots_of_unity_coset, trace_roots_of_unity);
+        self.challenges.push(Challenge::FieldElement(z.clone()));
+        z
+    }
+}
+
+/// Returns a `TestTranscript` that returns the `field_elements` and then the `indexes`, reduced
+/// modulo the requested upper bound, as its challenges. Proving and verifying with the same
+/// ones gives proofs whose challenges are known in advance.
+#[cfg(feature = "test_fiat_shamir")]
+pub fn test_transcript<F: IsField>(
+    field_elements: &[FieldElement<F>],
+    indexes: &[u64],
+) -> TestTranscript<F> {
+    let mut transcript = TestTranscript::with_field_elements(field_elements);
+    for index in indexes {
+        transcript.push_u64(*index);
+    }
+    transcript
+}
+
 /// Returns a batch of size `size` of field elements sampled from the transcript `transcript`.
+/// Any field element can be returned, including zero. AIRs can use it to sample their RAP
+/// challenges in `AIR::build_rap_challenges`.
 pub fn batch_sample_challenges<F: IsFFTField>(
     size: usize,
     transcript: &mut impl IsTranscript<F>,
@@ -144,13 +257,38 @@ where
         .collect()
 }
 
+/// Same as `batch_sample_challenges`, but zero is never returned: every zero sampled from the
+/// transcript is discarded and sampled again. This is needed for challenges that have to be
+/// inverted, such as the ones used as denominators. Since the discarded samples are also
+/// taken from the transcript, the prover and the verifier stay in sync.
+pub fn batch_sample_challenges_non_zero<F: IsFFTField>(
+    size: usize,
+    transcript: &mut impl IsTranscript<F>,
+) -> Vec<FieldElement<F>>
+where
+    FieldElement<F>: AsBytes,
+{
+    (0..size)
+        .map(|_| loop {
+            let challenge = transcript.sample_field_element();
+            if challenge != FieldElement::zero() {
+                break challenge;
+            }
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use lambdaworks_math::field::{
         element::FieldElement, fields::fft_friendly::stark_252_prime_field::Stark252PrimeField,
     };
 
-    use crate::transcript::{IsTranscript, StoneProverTranscript};
+    use crate::transcript::{
+        batch_sample_challenges, batch_sample_challenges_non_zero, IsTranscript,
+        StoneProverTranscript,
+    };
+    use lambdaworks_crypto::fiat_shamir::test_transcript::TestTranscript;
 
     use std::num::ParseIntError;
 
@@ -163,6 +301,46 @@ mod tests {
             .collect()
     }
 
+    #[test]
+    fn forked_transcripts_produce_the_same_challenges() {
+        let mut transcript = StoneProverTranscript::new(&[0x01, 0x02]);
+        transcript.append_bytes(&[0x03]);
+        // Sample once so that the transcript has spare bytes to copy.
+        transcript.sample_u64(16);
+        let mut fork = transcript.try_fork().unwrap();
+
+        assert_eq!(transcript.sample_u64(1 << 20), fork.sample_u64(1 << 20));
+        assert_eq!(
+            transcript.sample_field_element(),
+            fork.sample_field_element()
+        );
+    }
+
+    #[test]
+    fn batch_sample_challenges_non_zero_resamples_zeros() {
+        let mut transcript = TestTranscript::with_field_elements(&[
+            FE::zero(),
+            FE::from(3),
+            FE::zero(),
+            FE::zero(),
+            FE::from(5),
+        ]);
+
+        let challenges = batch_sample_challenges_non_zero(2, &mut transcript);
+
+        assert_eq!(challenges, vec![FE::from(3), FE::from(5)]);
+        assert!(transcript.remaining_field_elements().is_empty());
+    }
+
+    #[test]
+    fn batch_sample_challenges_may_return_zero() {
+        let mut transcript = TestTranscript::with_field_elements(&[FE::zero(), FE::from(3)]);
+
+        let challenges = batch_sample_challenges(2, &mut transcript);
+
+        assert_eq!(challenges, vec![FE::zero(), FE::from(3)]);
+    }
+
     #[test]
     fn sample_bytes_from_stone_prover_channel() {
         let mut transcript = StoneProverTranscript::new(&[0x01, 0x02, 0x03]);
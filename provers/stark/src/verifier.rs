@@ -1,13 +1,25 @@
 use super::{
-    config::BatchedMerkleTreeBackend,
+    config::{IsStarkVectorCommitment, MerkleVectorCommitment},
     domain::Domain,
-    fri::fri_decommit::FriDecommitment,
-    grinding,
-    proof::{options::ProofOptions, stark::StarkProof},
+    fri, grinding,
+    proof::{
+        options::ProofOptions,
+        stark::{StarkProof, PROOF_VERSION},
+    },
+    trace::frame_row_exponents,
     traits::AIR,
+    zerofier::linear_zerofier,
+    zk,
+};
+use crate::proof::stark::DeepPolynomialOpening;
+#[cfg(feature = "std")]
+use crate::proof::stark::SelfDescribingProof;
+#[cfg(not(feature = "std"))]
+use alloc::{borrow::ToOwned, string::String, vec::Vec};
+use core::marker::PhantomData;
+use lambdaworks_crypto::{
+    fiat_shamir::is_transcript::IsTranscript, merkle_tree::traits::VectorCommitment,
 };
-use crate::{config::Commitment, proof::stark::DeepPolynomialOpening};
-use lambdaworks_crypto::{fiat_shamir::is_transcript::IsTranscript, merkle_tree::proof::Proof};
 use lambdaworks_math::{
     fft::cpu::bit_reversing::reverse_index,
     field::{
@@ -16,18 +28,18 @@ use lambdaworks_math::{
     },
     traits::AsBytes,
 };
-#[cfg(not(feature = "test_fiat_shamir"))]
-use log::error;
-use std::marker::PhantomData;
+#[cfg(feature = "parallel")]
+use rayon::prelude::{IntoParallelRefIterator, ParallelIterator};
 #[cfg(feature = "instruments")]
 use std::time::Instant;
 
-/// A default STARK verifier implementing `IsStarkVerifier`.
-pub struct Verifier<A: AIR> {
-    phantom: PhantomData<A>,
+/// A default STARK verifier implementing `IsStarkVerifier`, for proofs committed with the
+/// vector commitment scheme `VC`.
+pub struct Verifier<A: AIR, VC: IsStarkVectorCommitment = MerkleVectorCommitment> {
+    phantom: PhantomData<(A, VC)>,
 }
 
-impl<A: AIR> IsStarkVerifier<A> for Verifier<A> {}
+impl<A: AIR, VC: IsStarkVectorCommitment> IsStarkVerifier<A, VC> for Verifier<A, VC> {}
 
 /// A container holding the complete list of challenges sent to the prover along with the seed used
 /// to validate the proof-of-work nonce.
@@ -55,26 +67,57 @@ where
     pub grinding_seed: [u8; 32],
 }
 
+/// The reasons a self describing proof can be rejected, see
+/// `IsStarkVerifier::verify_self_describing`.
+#[derive(Debug)]
+pub enum VerificationError {
+    /// The bytes are not a serialized self describing proof.
+    Deserialization(String),
+    /// The embedded context differs from the one of the AIR built from the embedded metadata.
+    ContextMismatch,
+    /// The embedded proof options differ from the ones the verifier expects.
+    ProofOptionsMismatch,
+    /// The proof was produced with a transcript appending field elements in another byte
+    /// order than the one of the verifier transcript.
+    ByteOrderMismatch,
+    /// The number of committed FRI layers is not the one needed to fold the DEEP composition
+    /// polynomial down to the final polynomial, see [`fri::fold_schedule`].
+    WrongFriLayerCount { expected: usize, got: usize },
+    /// A part of the proof does not have the length fixed by the AIR and the proof options,
+    /// see [`StarkProof::structural_check`].
+    MalformedProof {
+        field: &'static str,
+        expected: usize,
+        got: usize,
+    },
+    /// The proof was produced for another version of the proof format, see
+    /// [`crate::proof::stark::PROOF_VERSION`].
+    VersionMismatch { expected: u16, got: u16 },
+    /// The proof was produced for other AIR parameters or proof options than the ones of the
+    /// verifier, see [`AIR::parameter_hash`].
+    ParameterHashMismatch,
+    /// The proof does not verify.
+    InvalidProof,
+}
+
 pub type DeepPolynomialEvaluations<F> = (Vec<FieldElement<F>>, Vec<FieldElement<F>>);
 
 /// The functionality of a STARK verifier providing methods to run the STARK Verify protocol
 /// https://lambdaclass.github.io/lambdaworks/starks/protocol.html
-pub trait IsStarkVerifier<A: AIR> {
+/// The proofs are committed with the vector commitment scheme `VC`, Merkle trees by default.
+pub trait IsStarkVerifier<A: AIR, VC: IsStarkVectorCommitment = MerkleVectorCommitment> {
     fn sample_query_indexes(
         number_of_queries: usize,
         domain: &Domain<A::Field>,
         transcript: &mut impl IsTranscript<A::FieldExtension>,
     ) -> Vec<usize> {
-        let domain_size = domain.lde_roots_of_unity_coset.len() as u64;
-        (0..number_of_queries)
-            .map(|_| (transcript.sample_u64(domain_size >> 1)) as usize)
-            .collect::<Vec<usize>>()
+        fri::sample_query_indexes(number_of_queries, domain, transcript)
     }
 
     /// Returns the list of challenges sent to the prover.
     fn step_1_replay_rounds_and_recover_challenges(
         air: &A,
-        proof: &StarkProof<A::Field, A::FieldExtension>,
+        proof: &StarkProof<A::Field, A::FieldExtension, VC>,
         domain: &Domain<A::Field>,
         transcript: &mut impl IsTranscript<A::FieldExtension>,
     ) -> Challenges<A>
@@ -82,38 +125,48 @@ pub trait IsStarkVerifier<A: AIR> {
         FieldElement<A::Field>: AsBytes,
         FieldElement<A::FieldExtension>: AsBytes,
     {
+        // ===================================
+        // ==========|   Round 0   |==========
+        // ===================================
+
+        // As in `IsStarkProver::round_0_transcript_initialization`
+        let init_data = air.transcript_init_data();
+        if !init_data.is_empty() {
+            transcript.append_many(&[&init_data]);
+        }
+
         // ===================================
         // ==========|   Round 1   |==========
         // ===================================
 
         // <<<< Receive commitments:[tⱼ]
-        transcript.append_bytes(&proof.lde_trace_main_merkle_root);
+        transcript.append_bytes(proof.lde_trace_main_merkle_root.as_ref());
 
         let rap_challenges = air.build_rap_challenges(transcript);
 
-        if let Some(root) = proof.lde_trace_aux_merkle_root {
-            transcript.append_bytes(&root);
+        if let Some(root) = &proof.lde_trace_aux_merkle_root {
+            transcript.append_bytes(root.as_ref());
         }
 
         // ===================================
         // ==========|   Round 2   |==========
         // ===================================
 
-        // <<<< Receive challenge: 𝛽
-        let beta = transcript.sample_field_element();
-        let num_boundary_constraints = air.boundary_constraints(&rap_challenges).constraints.len();
+        let num_boundary_constraints = air.num_boundary_constraints(&rap_challenges);
 
         let num_transition_constraints = air.context().num_transition_constraints;
 
-        let mut coefficients: Vec<_> = (0..num_boundary_constraints + num_transition_constraints)
-            .map(|i| beta.pow(i))
-            .collect();
+        // <<<< Receive challenges: 𝛽ⱼ
+        let mut coefficients = air.options().constraint_combination.sample_coefficients(
+            num_boundary_constraints + num_transition_constraints,
+            transcript,
+        );
 
         let transition_coeffs: Vec<_> = coefficients.drain(..num_transition_constraints).collect();
         let boundary_coeffs = coefficients;
 
         // <<<< Receive commitments: [H₁], [H₂]
-        transcript.append_bytes(&proof.composition_poly_root);
+        transcript.append_bytes(proof.composition_poly_root.as_ref());
 
         // ===================================
         // ==========|   Round 3   |==========
@@ -143,13 +196,12 @@ pub trait IsStarkVerifier<A: AIR> {
 
         let n_terms_composition_poly = proof.composition_poly_parts_ood_evaluation.len();
         let n_terms_trace = air.context().transition_offsets.len() * air.context().trace_columns;
-        let gamma = transcript.sample_field_element();
 
         // <<<< Receive challenges: 𝛾, 𝛾'
-        let mut deep_composition_coefficients: Vec<_> =
-            core::iter::successors(Some(FieldElement::one()), |x| Some(x * &gamma))
-                .take(n_terms_composition_poly + n_terms_trace)
-                .collect();
+        let mut deep_composition_coefficients = air
+            .options()
+            .deep_combination
+            .sample_coefficients(n_terms_composition_poly + n_terms_trace, transcript);
 
         let trace_term_coeffs: Vec<_> = deep_composition_coefficients
             .drain(..n_terms_trace)
@@ -162,23 +214,11 @@ pub trait IsStarkVerifier<A: AIR> {
         let gammas = deep_composition_coefficients;
 
         // FRI commit phase
-        let merkle_roots = &proof.fri_layers_merkle_roots;
-        let mut zetas = merkle_roots
-            .iter()
-            .map(|root| {
-                // >>>> Send challenge 𝜁ₖ
-                let element = transcript.sample_field_element();
-                // <<<< Receive commitment: [pₖ] (the first one is [p₀])
-                transcript.append_bytes(root);
-                element
-            })
-            .collect::<Vec<FieldElement<A::FieldExtension>>>();
-
-        // >>>> Send challenge 𝜁ₙ₋₁
-        zetas.push(transcript.sample_field_element());
-
-        // <<<< Receive value: pₙ
-        transcript.append_field_element(&proof.fri_last_value);
+        let zetas = fri::replay_commit_phase(
+            &proof.fri_layers_merkle_roots,
+            &proof.fri_final_poly,
+            transcript,
+        );
 
         // Receive grinding value
         let security_bits = air.context().proof_options.grinding_factor;
@@ -193,7 +233,11 @@ pub trait IsStarkVerifier<A: AIR> {
         // FRI query phase
         // <<<< Send challenges 𝜄ₛ (iota_s)
         let number_of_queries = air.options().fri_number_of_queries;
-        let iotas = Self::sample_query_indexes(number_of_queries, domain, transcript);
+        let iotas = if air.options().distinct_queries {
+            fri::sample_distinct_query_indexes(number_of_queries, domain, transcript)
+        } else {
+            Self::sample_query_indexes(number_of_queries, domain, transcript)
+        };
 
         Challenges {
             z,
@@ -213,7 +257,7 @@ pub trait IsStarkVerifier<A: AIR> {
     /// See https://lambdaclass.github.io/lambdaworks/starks/protocol.html#step-2-verify-claimed-composition-polynomial
     fn step_2_verify_claimed_composition_polynomial(
         air: &A,
-        proof: &StarkProof<A::Field, A::FieldExtension>,
+        proof: &StarkProof<A::Field, A::FieldExtension, VC>,
         domain: &Domain<A::Field>,
         challenges: &Challenges<A>,
     ) -> bool {
@@ -238,7 +282,8 @@ pub trait IsStarkVerifier<A: AIR> {
                 } else {
                     &proof.trace_ood_evaluations.get_row(0)[column_idx]
                 };
-                let boundary_zerofier_challenges_z_den = -point + &challenges.z;
+                let boundary_zerofier_challenges_z_den =
+                    linear_zerofier(point.clone()).evaluate(&challenges.z);
 
                 let boundary_quotient_ood_evaluation_num =
                     -&boundary_constraints.constraints[index].value + trace_evaluation;
@@ -252,7 +297,11 @@ pub trait IsStarkVerifier<A: AIR> {
             .into_iter()
             .unzip();
 
-        FieldElement::inplace_batch_inverse(&mut boundary_c_i_evaluations_den).unwrap();
+        // `z` is sampled outside of the trace domain, so the boundary zerofiers do not vanish
+        // on it.
+        if FieldElement::inplace_batch_inverse(&mut boundary_c_i_evaluations_den).is_err() {
+            return false;
+        }
 
         let boundary_quotient_ood_evaluation: FieldElement<A::FieldExtension> =
             boundary_c_i_evaluations_num
@@ -268,11 +317,7 @@ pub trait IsStarkVerifier<A: AIR> {
             .map(|poly| poly.evaluate(&challenges.z))
             .collect::<Vec<FieldElement<A::FieldExtension>>>();
 
-        let num_main_trace_columns =
-            proof.trace_ood_evaluations.width - air.num_auxiliary_rap_columns();
-
-        let ood_frame =
-            (proof.trace_ood_evaluations).into_frame(num_main_trace_columns, A::STEP_SIZE);
+        let ood_frame = proof.ood_frame(air);
         let transition_ood_frame_evaluations = air.compute_transition_verifier(
             &ood_frame,
             &periodic_values,
@@ -298,13 +343,11 @@ pub trait IsStarkVerifier<A: AIR> {
         let composition_poly_ood_evaluation =
             &boundary_quotient_ood_evaluation + transition_c_i_evaluations_sum;
 
-        let composition_poly_claimed_ood_evaluation = proof
-            .composition_poly_parts_ood_evaluation
-            .iter()
-            .rev()
-            .fold(FieldElement::zero(), |acc, coeff| {
-                acc * &challenges.z + coeff
-            });
+        let composition_poly_claimed_ood_evaluation = zk::reconstruct_composition_poly_evaluation(
+            air,
+            &proof.composition_poly_parts_ood_evaluation,
+            &challenges.z,
+        );
 
         composition_poly_claimed_ood_evaluation == composition_poly_ood_evaluation
     }
@@ -313,7 +356,8 @@ pub trait IsStarkVerifier<A: AIR> {
     /// openings of the trace polynomials and the composition polynomial parts. It then uses these to verify that the
     /// FRI decommitments are valid and correspond to the Deep composition polynomial.
     fn step_3_verify_fri(
-        proof: &StarkProof<A::Field, A::FieldExtension>,
+        air: &A,
+        proof: &StarkProof<A::Field, A::FieldExtension, VC>,
         domain: &Domain<A::Field>,
         challenges: &Challenges<A>,
     ) -> bool
@@ -323,35 +367,27 @@ pub trait IsStarkVerifier<A: AIR> {
     {
         let (deep_poly_evaluations, deep_poly_evaluations_sym) =
             Self::reconstruct_deep_composition_poly_evaluations_for_all_queries(
-                challenges, domain, proof,
+                air, challenges, domain, proof,
             );
 
-        // verify FRI
-        let mut evaluation_point_inverse = challenges
-            .iotas
-            .iter()
-            .map(|iota| Self::query_challenge_to_evaluation_point(*iota, domain))
-            .collect::<Vec<FieldElement<A::Field>>>();
-        FieldElement::inplace_batch_inverse(&mut evaluation_point_inverse).unwrap();
+        let first_layer_evaluations: Vec<_> = deep_poly_evaluations
+            .into_iter()
+            .zip(deep_poly_evaluations_sym)
+            .collect();
 
-        proof
-            .query_list
-            .iter()
-            .zip(&challenges.iotas)
-            .zip(evaluation_point_inverse)
-            .enumerate()
-            .fold(true, |mut result, (i, ((proof_s, iota_s), eval))| {
-                result &= Self::verify_query_and_sym_openings(
-                    proof,
-                    &challenges.zetas,
-                    *iota_s,
-                    proof_s,
-                    eval,
-                    &deep_poly_evaluations[i],
-                    &deep_poly_evaluations_sym[i],
-                );
-                result
-            })
+        let result = fri::verify_query_phase(
+            &proof.fri_layers_merkle_roots,
+            &proof.fri_final_poly,
+            &proof.query_list,
+            &challenges.zetas,
+            &challenges.iotas,
+            &first_layer_evaluations,
+            domain,
+        );
+        if let Err(error) = &result {
+            error!("FRI query rejected: {:?}", error);
+        }
+        result.is_ok()
     }
 
     /// Returns the field element element of the domain `domain` corresponding to the given FRI query index challenge `iota`.
@@ -376,8 +412,8 @@ pub trait IsStarkVerifier<A: AIR> {
 
     /// Verifies the validity of the opening proof.
     fn verify_opening<E>(
-        proof: &Proof<Commitment>,
-        root: &Commitment,
+        proof: &VC::Opening,
+        root: &VC::Commitment,
         index: usize,
         value: &[FieldElement<E>],
     ) -> bool
@@ -387,14 +423,14 @@ pub trait IsStarkVerifier<A: AIR> {
         E: IsField,
         A::Field: IsSubFieldOf<E>,
     {
-        proof.verify::<BatchedMerkleTreeBackend<E>>(root, index, &value.to_owned())
+        VC::Scheme::<E>::verify(root, index, &value.to_owned(), proof)
     }
 
     /// Verify opening Open(tⱼ(D_LDE), 𝜐) and Open(tⱼ(D_LDE), -𝜐) for all trace polynomials tⱼ,
     /// where 𝜐 and -𝜐 are the elements corresponding to the index challenge `iota`.
     fn verify_trace_openings(
-        proof: &StarkProof<A::Field, A::FieldExtension>,
-        deep_poly_openings: &DeepPolynomialOpening<A::Field, A::FieldExtension>,
+        proof: &StarkProof<A::Field, A::FieldExtension, VC>,
+        deep_poly_openings: &DeepPolynomialOpening<A::Field, A::FieldExtension, VC>,
         iota: usize,
     ) -> bool
     where
@@ -419,7 +455,7 @@ pub trait IsStarkVerifier<A: AIR> {
         );
 
         match (
-            proof.lde_trace_aux_merkle_root,
+            &proof.lde_trace_aux_merkle_root,
             &deep_poly_openings.aux_trace_polys,
         ) {
             (None, Some(_)) => result = false,
@@ -427,13 +463,13 @@ pub trait IsStarkVerifier<A: AIR> {
             (Some(aux_root), Some(aux_trace_polys_opening)) => {
                 result &= Self::verify_opening::<A::FieldExtension>(
                     &aux_trace_polys_opening.proof,
-                    &aux_root,
+                    aux_root,
                     index,
                     &aux_trace_polys_opening.evaluations,
                 );
                 result &= Self::verify_opening::<A::FieldExtension>(
                     &aux_trace_polys_opening.proof_sym,
-                    &aux_root,
+                    aux_root,
                     index_sym,
                     &aux_trace_polys_opening.evaluations_sym,
                 );
@@ -447,8 +483,8 @@ pub trait IsStarkVerifier<A: AIR> {
     /// Verify opening Open(Hᵢ(D_LDE), 𝜐) and Open(Hᵢ(D_LDE), -𝜐) for all parts Hᵢof the composition
     /// polynomial, where 𝜐 and -𝜐 are the elements corresponding to the index challenge `iota`.
     fn verify_composition_poly_opening(
-        deep_poly_openings: &DeepPolynomialOpening<A::Field, A::FieldExtension>,
-        composition_poly_merkle_root: &Commitment,
+        deep_poly_openings: &DeepPolynomialOpening<A::Field, A::FieldExtension, VC>,
+        composition_poly_merkle_root: &VC::Commitment,
         iota: &usize,
     ) -> bool
     where
@@ -458,21 +494,19 @@ pub trait IsStarkVerifier<A: AIR> {
         let mut value = deep_poly_openings.composition_poly.evaluations.clone();
         value.extend_from_slice(&deep_poly_openings.composition_poly.evaluations_sym);
 
-        deep_poly_openings
-            .composition_poly
-            .proof
-            .verify::<BatchedMerkleTreeBackend<A::FieldExtension>>(
-                composition_poly_merkle_root,
-                *iota,
-                &value,
-            )
+        VC::Scheme::<A::FieldExtension>::verify(
+            composition_poly_merkle_root,
+            *iota,
+            &value,
+            &deep_poly_openings.composition_poly.proof,
+        )
     }
 
     /// Verifies the validity of the purported values of the trace polynomials and the composition polynomial
     /// parts at the domain elements and their symmetric counterparts corresponding to all the FRI query
     /// index challenges.
     fn step_4_verify_trace_and_composition_openings(
-        proof: &StarkProof<A::Field, A::FieldExtension>,
+        proof: &StarkProof<A::Field, A::FieldExtension, VC>,
         challenges: &Challenges<A>,
     ) -> bool
     where
@@ -494,124 +528,23 @@ pub trait IsStarkVerifier<A: AIR> {
         )
     }
 
-    /// Verifies the openings of a fold polynomial of an inner layer of FRI.
-    fn verify_fri_layer_openings(
-        merkle_root: &Commitment,
-        auth_path_sym: &Proof<Commitment>,
-        evaluation: &FieldElement<A::FieldExtension>,
-        evaluation_sym: &FieldElement<A::FieldExtension>,
-        iota: usize,
-    ) -> bool
-    where
-        FieldElement<A::Field>: AsBytes + Sync + Send,
-        FieldElement<A::FieldExtension>: AsBytes + Sync + Send,
-    {
-        let evaluations = if iota % 2 == 1 {
-            vec![evaluation_sym.clone(), evaluation.clone()]
-        } else {
-            vec![evaluation.clone(), evaluation_sym.clone()]
-        };
-
-        auth_path_sym.verify::<BatchedMerkleTreeBackend<A::FieldExtension>>(
-            merkle_root,
-            iota >> 1,
-            &evaluations,
-        )
-    }
-
-    /// Verify a single FRI query
-    /// `zetas`: the vector of all challenges sent by the verifier to the prover at the commit
-    /// phase to fold polynomials.
-    /// `iota`: the index challenge of this FRI query. This index uniquely determines two elements 𝜐 and -𝜐
-    /// of the evaluation domain of FRI layer 0.
-    /// `evaluation_point_inv`: precomputed value of 𝜐⁻¹.
-    /// `deep_composition_evaluation`: precomputed value of p₀(𝜐), where p₀ is the deep composition polynomial.
-    /// `deep_composition_evaluation_sym`: precomputed value of p₀(-𝜐), where p₀ is the deep composition polynomial.
-    fn verify_query_and_sym_openings(
-        proof: &StarkProof<A::Field, A::FieldExtension>,
-        zetas: &[FieldElement<A::FieldExtension>],
-        iota: usize,
-        fri_decommitment: &FriDecommitment<A::FieldExtension>,
-        evaluation_point_inv: FieldElement<A::Field>,
-        deep_composition_evaluation: &FieldElement<A::FieldExtension>,
-        deep_composition_evaluation_sym: &FieldElement<A::FieldExtension>,
-    ) -> bool
-    where
-        FieldElement<A::Field>: AsBytes + Sync + Send,
-        FieldElement<A::FieldExtension>: AsBytes + Sync + Send,
-    {
-        let fri_layers_merkle_roots = &proof.fri_layers_merkle_roots;
-        let evaluation_point_vec: Vec<FieldElement<A::Field>> =
-            core::iter::successors(Some(evaluation_point_inv.square()), |evaluation_point| {
-                Some(evaluation_point.square())
-            })
-            .take(fri_layers_merkle_roots.len())
-            .collect();
-
-        let p0_eval = deep_composition_evaluation;
-        let p0_eval_sym = deep_composition_evaluation_sym;
-
-        // Reconstruct p₁(𝜐²)
-        let mut v =
-            (p0_eval + p0_eval_sym) + evaluation_point_inv * &zetas[0] * (p0_eval - p0_eval_sym);
-        let mut index = iota;
-
-        // For each FRI layer, starting from the layer 1: use the proof to verify the validity of values pᵢ(−𝜐^(2ⁱ)) (given by the prover) and
-        // pᵢ(𝜐^(2ⁱ)) (computed on the previous iteration by the verifier). Then use them to obtain pᵢ₊₁(𝜐^(2ⁱ⁺¹)).
-        // Finally, check that the final value coincides with the given by the prover.
-        fri_layers_merkle_roots
-            .iter()
-            .enumerate()
-            .zip(&fri_decommitment.layers_auth_paths)
-            .zip(&fri_decommitment.layers_evaluations_sym)
-            .zip(evaluation_point_vec)
-            .fold(
-                true,
-                |result,
-                 (
-                    (((i, merkle_root), auth_path_sym), evaluation_sym),
-                    evaluation_point_inv,
-                )| {
-                    // Verify opening Open(pᵢ(Dₖ), −𝜐^(2ⁱ)) and Open(pᵢ(Dₖ), 𝜐^(2ⁱ)).
-                    // `v` is pᵢ(𝜐^(2ⁱ)).
-                    // `evaluation_sym` is pᵢ(−𝜐^(2ⁱ)).
-                    let openings_ok = Self::verify_fri_layer_openings(
-                        merkle_root,
-                        auth_path_sym,
-                        &v,
-                        evaluation_sym,
-                        index,
-                    );
-
-                    // Update `v` with next value pᵢ₊₁(𝜐^(2ⁱ⁺¹)).
-                    v = (&v + evaluation_sym) + evaluation_point_inv * &zetas[i + 1] * (&v - evaluation_sym);
-
-                    // Update index for next iteration. The index of the squares in the next layer
-                    // is obtained by halving the current index. This is due to the bit-reverse
-                    // ordering of the elements in the Merkle tree.
-                    index >>= 1;
-
-                    if i < fri_decommitment.layers_evaluations_sym.len() - 1 {
-                        result & openings_ok
-                    } else {
-                        // Check that final value is the given by the prover
-                        result & (v == proof.fri_last_value) & openings_ok
-                    }
-                },
-            )
-    }
-
     fn reconstruct_deep_composition_poly_evaluations_for_all_queries(
+        air: &A,
         challenges: &Challenges<A>,
         domain: &Domain<A::Field>,
-        proof: &StarkProof<A::Field, A::FieldExtension>,
+        proof: &StarkProof<A::Field, A::FieldExtension, VC>,
     ) -> DeepPolynomialEvaluations<A::FieldExtension> {
+        let number_of_parts = zk::number_of_composition_parts(air);
         let mut deep_poly_evaluations = Vec::new();
         let mut deep_poly_evaluations_sym = Vec::new();
+        let primitive_root =
+            &A::Field::get_primitive_root_of_unity(domain.root_order as u64).unwrap();
+        let frame_shifts: Vec<_> =
+            frame_row_exponents(&air.context().transition_offsets, A::STEP_SIZE)
+                .into_iter()
+                .map(|exponent| primitive_root.pow(exponent))
+                .collect();
         for (i, iota) in challenges.iotas.iter().enumerate() {
-            let primitive_root =
-                &A::Field::get_primitive_root_of_unity(domain.root_order as u64).unwrap();
-
             let mut evaluations: Vec<FieldElement<A::FieldExtension>> = proof.deep_poly_openings[i]
                 .main_trace_polys
                 .evaluations
@@ -627,10 +560,11 @@ pub trait IsStarkVerifier<A: AIR> {
             deep_poly_evaluations.push(Self::reconstruct_deep_composition_poly_evaluation(
                 proof,
                 &evaluation_point,
-                primitive_root,
+                &frame_shifts,
                 challenges,
                 &evaluations,
                 &proof.deep_poly_openings[i].composition_poly.evaluations,
+                number_of_parts,
             ));
 
             let mut evaluations_sym: Vec<FieldElement<A::FieldExtension>> = proof
@@ -649,25 +583,35 @@ pub trait IsStarkVerifier<A: AIR> {
             deep_poly_evaluations_sym.push(Self::reconstruct_deep_composition_poly_evaluation(
                 proof,
                 &evaluation_point,
-                primitive_root,
+                &frame_shifts,
                 challenges,
                 &evaluations_sym,
                 &proof.deep_poly_openings[i].composition_poly.evaluations_sym,
+                number_of_parts,
             ));
         }
         (deep_poly_evaluations, deep_poly_evaluations_sym)
     }
 
+    /// Computes the evaluation of the DEEP composition polynomial at `evaluation_point`.
+    /// `lde_composition_poly_parts_evaluation` holds the evaluations of the `number_of_parts`
+    /// parts of the composition polynomial, followed in zero-knowledge mode by the one of the
+    /// blinding polynomial, which is also opened at `z^number_of_parts`.
+    #[allow(clippy::too_many_arguments)]
     fn reconstruct_deep_composition_poly_evaluation(
-        proof: &StarkProof<A::Field, A::FieldExtension>,
+        proof: &StarkProof<A::Field, A::FieldExtension, VC>,
         evaluation_point: &FieldElement<A::Field>,
-        primitive_root: &FieldElement<A::Field>,
+        frame_shifts: &[FieldElement<A::Field>],
         challenges: &Challenges<A>,
         lde_trace_evaluations: &[FieldElement<A::FieldExtension>],
         lde_composition_poly_parts_evaluation: &[FieldElement<A::FieldExtension>],
+        number_of_parts: usize,
     ) -> FieldElement<A::FieldExtension> {
-        let mut denoms_trace = (0..proof.trace_ood_evaluations.height)
-            .map(|row_idx| evaluation_point - primitive_root.pow(row_idx as u64) * &challenges.z)
+        let evaluation_point = evaluation_point.clone().to_extension::<A::FieldExtension>();
+        let mut denoms_trace = frame_shifts
+            .iter()
+            .map(|frame_shift| linear_zerofier(frame_shift * &challenges.z))
+            .map(|zerofier| zerofier.evaluate(&evaluation_point))
             .collect::<Vec<FieldElement<A::FieldExtension>>>();
         FieldElement::inplace_batch_inverse(&mut denoms_trace).unwrap();
 
@@ -686,10 +630,11 @@ pub trait IsStarkVerifier<A: AIR> {
                 trace_terms + trace_i
             });
 
-        let number_of_parts = lde_composition_poly_parts_evaluation.len();
         let z_pow = &challenges.z.pow(number_of_parts);
 
-        let denom_composition = (evaluation_point - z_pow).inv().unwrap();
+        let denom_composition = linear_zerofier(z_pow.clone())
+            .evaluate(&evaluation_point)
+            .inv_unchecked();
         let mut h_terms = FieldElement::zero();
         for (j, h_i_upsilon) in lde_composition_poly_parts_evaluation.iter().enumerate() {
             let h_i_zpower = &proof.composition_poly_parts_ood_evaluation[j];
@@ -701,114 +646,376 @@ pub trait IsStarkVerifier<A: AIR> {
         trace_term + h_terms
     }
 
+    /// Checks that the dimensions implied by the proof match the trace layout of the AIR and
+    /// the proof options, see [`StarkProof::structural_check`].
+    fn check_proof_shape(air: &A, proof: &StarkProof<A::Field, A::FieldExtension, VC>) -> bool {
+        proof.structural_check(air).is_ok()
+    }
+
+    /// Checks that the proof commits to as many FRI layers as folding the DEEP composition
+    /// polynomial needs to reach a final polynomial of degree `fri_final_poly_degree`. The
+    /// degree bound of the DEEP composition polynomial is the trace length, doubled in
+    /// zero-knowledge mode, so there should be log2(degree bound) - log2(final length) folds,
+    /// one less committed layer as the last fold is sent as the final polynomial. A prover
+    /// folding less could hide a polynomial above the degree bound in the final one.
+    fn check_fri_layer_count(
+        air: &A,
+        proof: &StarkProof<A::Field, A::FieldExtension, VC>,
+    ) -> Result<(), VerificationError> {
+        let (number_of_folds, _) = fri::fold_schedule(
+            air.trace_length() << usize::from(air.options().zero_knowledge),
+            air.options().fri_final_poly_degree,
+        );
+        // A degree bound of 1 leaves nothing to fold, which no prover produces.
+        let expected = number_of_folds
+            .checked_sub(1)
+            .ok_or(VerificationError::InvalidProof)?;
+        let got = proof.fri_layers_merkle_roots.len();
+        if got != expected {
+            return Err(VerificationError::WrongFriLayerCount { expected, got });
+        }
+        Ok(())
+    }
+
+    /// Checks that the proof was produced for the proof format version of the verifier, and for
+    /// the same AIR parameters and proof options as `air`.
+    fn check_version_and_parameters(
+        air: &A,
+        proof: &StarkProof<A::Field, A::FieldExtension, VC>,
+    ) -> Result<(), VerificationError> {
+        if proof.proof_version != PROOF_VERSION {
+            return Err(VerificationError::VersionMismatch {
+                expected: PROOF_VERSION,
+                got: proof.proof_version,
+            });
+        }
+        if proof.parameter_hash != air.parameter_hash() {
+            return Err(VerificationError::ParameterHashMismatch);
+        }
+        Ok(())
+    }
+
+    /// Returns the FRI query indexes 𝜄ₛ of `proof`, sampled from `transcript` as the verifier
+    /// does. They are not part of the proof: the verifier derives them again from the
+    /// commitments, so this is the way to inspect which positions a proof opens.
+    /// Warning: the transcript must be initialized as the one passed to `verify`.
+    fn replay_query_indexes(
+        proof: &StarkProof<A::Field, A::FieldExtension, VC>,
+        pub_input: &A::PublicInputs,
+        proof_options: &ProofOptions,
+        mut transcript: impl IsTranscript<A::FieldExtension>,
+    ) -> Vec<usize>
+    where
+        FieldElement<A::Field>: AsBytes,
+        FieldElement<A::FieldExtension>: AsBytes,
+    {
+        let air = A::new(proof.trace_length, pub_input, proof_options);
+        let domain = Domain::new(&air);
+        Self::step_1_replay_rounds_and_recover_challenges(&air, proof, &domain, &mut transcript)
+            .iotas
+    }
+
+    /// Returns the challenges of the auxiliary trace of `proof`, sampled from `transcript` as
+    /// the verifier does, to read its auxiliary public outputs with
+    /// `StarkProof::public_outputs`.
+    /// Warning: the transcript must be initialized as the one passed to `verify`.
+    fn replay_rap_challenges(
+        proof: &StarkProof<A::Field, A::FieldExtension, VC>,
+        pub_input: &A::PublicInputs,
+        proof_options: &ProofOptions,
+        mut transcript: impl IsTranscript<A::FieldExtension>,
+    ) -> Vec<FieldElement<A::FieldExtension>>
+    where
+        FieldElement<A::Field>: AsBytes,
+        FieldElement<A::FieldExtension>: AsBytes,
+    {
+        let air = A::new(proof.trace_length, pub_input, proof_options);
+        let domain = Domain::new(&air);
+        Self::step_1_replay_rounds_and_recover_challenges(&air, proof, &domain, &mut transcript)
+            .rap_challenges
+    }
+
+    /// Verifies a proof of `IsStarkProver::prove_with_test_transcript`, replaying the
+    /// `challenges` and query `indexes` it was produced with. For testing only.
+    #[cfg(feature = "test_fiat_shamir")]
+    fn verify_with_test_transcript(
+        proof: &StarkProof<A::Field, A::FieldExtension, VC>,
+        pub_input: &A::PublicInputs,
+        proof_options: &ProofOptions,
+        challenges: &[FieldElement<A::FieldExtension>],
+        indexes: &[u64],
+    ) -> bool
+    where
+        FieldElement<A::Field>: AsBytes + Sync + Send,
+        FieldElement<A::FieldExtension>: AsBytes + Sync + Send,
+    {
+        Self::verify(
+            proof,
+            pub_input,
+            proof_options,
+            crate::transcript::test_transcript(challenges, indexes),
+        )
+    }
+
     /// Verifies a STARK proof with public inputs `pub_inputs`.
     /// Warning: the transcript must be safely initializated before passing it to this method.
     fn verify(
-        proof: &StarkProof<A::Field, A::FieldExtension>,
+        proof: &StarkProof<A::Field, A::FieldExtension, VC>,
         pub_input: &A::PublicInputs,
         proof_options: &ProofOptions,
-        mut transcript: impl IsTranscript<A::FieldExtension>,
+        transcript: impl IsTranscript<A::FieldExtension>,
     ) -> bool
     where
         FieldElement<A::Field>: AsBytes + Sync + Send,
         FieldElement<A::FieldExtension>: AsBytes + Sync + Send,
     {
-        // Verify there are enough queries
-        if proof.query_list.len() < proof_options.fri_number_of_queries {
-            return false;
-        }
-
-        #[cfg(feature = "instruments")]
-        println!("- Started step 1: Recover challenges");
-        #[cfg(feature = "instruments")]
-        let timer1 = Instant::now();
+        verify_rounds::<A, VC, Self>(proof, pub_input, proof_options, transcript, false)
+    }
 
-        let air = A::new(proof.trace_length, pub_input, proof_options);
-        let domain = Domain::new(&air);
+    /// INSECURE, for benchmarking only. Same as `verify`, running the FRI checks alone: the
+    /// check of the composition polynomial against the constraints at the out of domain point
+    /// and the openings of the trace and the composition polynomial are skipped, so it accepts
+    /// proofs of false statements.
+    #[cfg(feature = "unsafe_bench")]
+    fn verify_only_fri(
+        proof: &StarkProof<A::Field, A::FieldExtension, VC>,
+        pub_input: &A::PublicInputs,
+        proof_options: &ProofOptions,
+        transcript: impl IsTranscript<A::FieldExtension>,
+    ) -> bool
+    where
+        FieldElement<A::Field>: AsBytes + Sync + Send,
+        FieldElement<A::FieldExtension>: AsBytes + Sync + Send,
+    {
+        verify_rounds::<A, VC, Self>(proof, pub_input, proof_options, transcript, true)
+    }
 
-        let challenges = Self::step_1_replay_rounds_and_recover_challenges(
-            &air,
+    /// Verifies a proof serialized with `SelfDescribingProof::to_bytes`. The AIR is rebuilt from
+    /// the public inputs and the trace length carried by the proof, and its context has to match
+    /// the embedded one, so only the type of the AIR has to be known. The proof options set the
+    /// security of the proof, so they are not taken from the proof: the embedded ones have to
+    /// be `proof_options`, the ones the verifier expects.
+    /// Warning: the transcript must be safely initializated before passing it to this method.
+    #[cfg(feature = "std")]
+    fn verify_self_describing(
+        proof_bytes: &[u8],
+        proof_options: &ProofOptions,
+        transcript: impl IsTranscript<A::FieldExtension>,
+    ) -> Result<(), VerificationError>
+    where
+        FieldElement<A::Field>: AsBytes + Sync + Send,
+        FieldElement<A::FieldExtension>: AsBytes + Sync + Send,
+        SelfDescribingProof<A::Field, A::FieldExtension, A::PublicInputs, VC>:
+            serde::de::DeserializeOwned,
+    {
+        let self_describing_proof =
+            SelfDescribingProof::<A::Field, A::FieldExtension, A::PublicInputs, VC>::from_bytes(
+                proof_bytes,
+            )
+            .map_err(|error| VerificationError::Deserialization(format!("{:?}", error)))?;
+        let SelfDescribingProof {
+            context,
+            public_inputs,
             proof,
-            &domain,
-            &mut transcript,
-        );
+            byte_order,
+        } = self_describing_proof;
 
-        // verify grinding
-        let security_bits = air.context().proof_options.grinding_factor;
-        if security_bits > 0 {
-            let nonce_is_valid = proof.nonce.map_or(false, |nonce_value| {
-                grinding::is_valid_nonce(&challenges.grinding_seed, nonce_value, security_bits)
-            });
+        if context.proof_options != *proof_options {
+            return Err(VerificationError::ProofOptionsMismatch);
+        }
 
-            if !nonce_is_valid {
-                error!("Grinding factor not satisfied");
-                return false;
-            }
+        if byte_order != transcript.byte_order() {
+            return Err(VerificationError::ByteOrderMismatch);
         }
 
-        #[cfg(feature = "instruments")]
-        let elapsed1 = timer1.elapsed();
-        #[cfg(feature = "instruments")]
-        println!("  Time spent: {:?}", elapsed1);
+        if !proof.trace_length.is_power_of_two() {
+            return Err(VerificationError::InvalidProof);
+        }
 
-        #[cfg(feature = "instruments")]
-        println!("- Started step 2: Verify claimed polynomial");
-        #[cfg(feature = "instruments")]
-        let timer2 = Instant::now();
+        let air = A::new(proof.trace_length, &public_inputs, proof_options);
+        if air.context() != &context {
+            return Err(VerificationError::ContextMismatch);
+        }
+        Self::check_version_and_parameters(&air, &proof)?;
+        Self::check_fri_layer_count(&air, &proof)?;
 
-        if !Self::step_2_verify_claimed_composition_polynomial(&air, proof, &domain, &challenges) {
-            error!("Composition Polynomial verification failed");
-            return false;
+        if Self::verify(&proof, &public_inputs, proof_options, transcript) {
+            Ok(())
+        } else {
+            Err(VerificationError::InvalidProof)
         }
+    }
 
-        #[cfg(feature = "instruments")]
-        let elapsed2 = timer2.elapsed();
-        #[cfg(feature = "instruments")]
-        println!("  Time spent: {:?}", elapsed2);
-        #[cfg(feature = "instruments")]
+    /// Verifies independent proofs, each with its public inputs, in parallel when the
+    /// `parallel` feature is enabled. Every proof is verified fully, with a transcript of its
+    /// own built by `new_transcript`. The results are in the order of `proofs`.
+    #[allow(clippy::type_complexity)]
+    fn verify_batch<T>(
+        proofs: &[(
+            &StarkProof<A::Field, A::FieldExtension, VC>,
+            &A::PublicInputs,
+        )],
+        proof_options: &ProofOptions,
+        new_transcript: impl Fn() -> T + Sync,
+    ) -> Vec<Result<(), VerificationError>>
+    where
+        T: IsTranscript<A::FieldExtension>,
+        FieldElement<A::Field>: AsBytes + Sync + Send,
+        FieldElement<A::FieldExtension>: AsBytes + Sync + Send,
+        A::PublicInputs: Sync,
+    {
+        #[cfg(feature = "parallel")]
+        let proofs_iter = proofs.par_iter();
+        #[cfg(not(feature = "parallel"))]
+        let proofs_iter = proofs.iter();
+
+        proofs_iter
+            .map(|(proof, pub_inputs)| {
+                if Self::verify(proof, pub_inputs, proof_options, new_transcript()) {
+                    Ok(())
+                } else {
+                    Err(VerificationError::InvalidProof)
+                }
+            })
+            .collect()
+    }
+}
 
-        println!("- Started step 3: Verify FRI");
-        #[cfg(feature = "instruments")]
-        let timer3 = Instant::now();
+/// Runs the STARK Verify protocol for `V::verify`. If `only_fri` is set, only the FRI checks
+/// are run, see `IsStarkVerifier::verify_only_fri`.
+fn verify_rounds<A: AIR, VC: IsStarkVectorCommitment, V: IsStarkVerifier<A, VC> + ?Sized>(
+    proof: &StarkProof<A::Field, A::FieldExtension, VC>,
+    pub_input: &A::PublicInputs,
+    proof_options: &ProofOptions,
+    mut transcript: impl IsTranscript<A::FieldExtension>,
+    only_fri: bool,
+) -> bool
+where
+    FieldElement<A::Field>: AsBytes + Sync + Send,
+    FieldElement<A::FieldExtension>: AsBytes + Sync + Send,
+{
+    #[cfg(feature = "instruments")]
+    println!("- Started step 1: Recover challenges");
+    #[cfg(feature = "instruments")]
+    let timer1 = Instant::now();
+
+    if !proof.trace_length.is_power_of_two() {
+        error!("Trace length {} is not a power of two", proof.trace_length);
+        return false;
+    }
 
-        if !Self::step_3_verify_fri(proof, &domain, &challenges) {
-            error!("FRI verification failed");
-            return false;
-        }
+    let air = A::new(proof.trace_length, pub_input, proof_options);
+
+    if proof.trace_length < air.minimum_trace_length() {
+        error!(
+            "Trace length {} is shorter than the minimum {}",
+            proof.trace_length,
+            air.minimum_trace_length()
+        );
+        return false;
+    }
+
+    // The lengths are checked before anything is hashed or computed from the proof.
+    if let Err(error) = proof.structural_check(&air) {
+        error!("Malformed proof rejected: {:?}", error);
+        return false;
+    }
 
-        #[cfg(feature = "instruments")]
-        let elapsed3 = timer3.elapsed();
-        #[cfg(feature = "instruments")]
-        println!("  Time spent: {:?}", elapsed3);
+    if let Err(error) = V::check_version_and_parameters(&air, proof) {
+        error!("Proof rejected: {:?}", error);
+        return false;
+    }
 
-        #[cfg(feature = "instruments")]
-        println!("- Started step 4: Verify deep composition polynomial");
-        #[cfg(feature = "instruments")]
-        let timer4 = Instant::now();
+    let domain = Domain::new(&air);
 
-        #[allow(clippy::let_and_return)]
-        if !Self::step_4_verify_trace_and_composition_openings(proof, &challenges) {
-            error!("DEEP Composition Polynomial verification failed");
+    // Sampling more distinct query indexes than there are pairs of points would not end.
+    if air.options().distinct_queries
+        && proof_options.fri_number_of_queries > domain.lde_roots_of_unity_coset.len() / 2
+    {
+        error!("Too many distinct FRI queries for the LDE domain");
+        return false;
+    }
+
+    let challenges =
+        V::step_1_replay_rounds_and_recover_challenges(&air, proof, &domain, &mut transcript);
+
+    // verify grinding
+    let security_bits = air.context().proof_options.grinding_factor;
+    if security_bits > 0 {
+        let nonce_is_valid = proof.nonce.map_or(false, |nonce_value| {
+            grinding::is_valid_nonce(&challenges.grinding_seed, nonce_value, security_bits)
+        });
+
+        if !nonce_is_valid {
+            error!("Grinding factor not satisfied");
             return false;
         }
+    }
 
-        #[cfg(feature = "instruments")]
-        let elapsed4 = timer4.elapsed();
-        #[cfg(feature = "instruments")]
-        println!("  Time spent: {:?}", elapsed4);
-
-        #[cfg(feature = "instruments")]
-        {
-            let total_time = elapsed1 + elapsed2 + elapsed3 + elapsed4;
-            println!(
-                " Fraction of verifying time per step: {:.4} {:.4} {:.4} {:.4}",
-                elapsed1.as_nanos() as f64 / total_time.as_nanos() as f64,
-                elapsed2.as_nanos() as f64 / total_time.as_nanos() as f64,
-                elapsed3.as_nanos() as f64 / total_time.as_nanos() as f64,
-                elapsed4.as_nanos() as f64 / total_time.as_nanos() as f64
-            );
-        }
+    #[cfg(feature = "instruments")]
+    let elapsed1 = timer1.elapsed();
+    #[cfg(feature = "instruments")]
+    println!("  Time spent: {:?}", elapsed1);
+
+    #[cfg(feature = "instruments")]
+    println!("- Started step 2: Verify claimed polynomial");
+    #[cfg(feature = "instruments")]
+    let timer2 = Instant::now();
+
+    if !only_fri
+        && !V::step_2_verify_claimed_composition_polynomial(&air, proof, &domain, &challenges)
+    {
+        error!("Composition Polynomial verification failed");
+        return false;
+    }
 
-        true
+    #[cfg(feature = "instruments")]
+    let elapsed2 = timer2.elapsed();
+    #[cfg(feature = "instruments")]
+    println!("  Time spent: {:?}", elapsed2);
+    #[cfg(feature = "instruments")]
+
+    println!("- Started step 3: Verify FRI");
+    #[cfg(feature = "instruments")]
+    let timer3 = Instant::now();
+
+    if !V::step_3_verify_fri(&air, proof, &domain, &challenges) {
+        error!("FRI verification failed");
+        return false;
+    }
+
+    #[cfg(feature = "instruments")]
+    let elapsed3 = timer3.elapsed();
+    #[cfg(feature = "instruments")]
+    println!("  Time spent: {:?}", elapsed3);
+
+    #[cfg(feature = "instruments")]
+    println!("- Started step 4: Verify deep composition polynomial");
+    #[cfg(feature = "instruments")]
+    let timer4 = Instant::now();
+
+    #[allow(clippy::let_and_return)]
+    if !only_fri && !V::step_4_verify_trace_and_composition_openings(proof, &challenges) {
+        error!("DEEP Composition Polynomial verification failed");
+        return false;
     }
+
+    #[cfg(feature = "instruments")]
+    let elapsed4 = timer4.elapsed();
+    #[cfg(feature = "instruments")]
+    println!("  Time spent: {:?}", elapsed4);
+
+    #[cfg(feature = "instruments")]
+    {
+        let total_time = elapsed1 + elapsed2 + elapsed3 + elapsed4;
+        println!(
+            " Fraction of verifying time per step: {:.4} {:.4} {:.4} {:.4}",
+            elapsed1.as_nanos() as f64 / total_time.as_nanos() as f64,
+            elapsed2.as_nanos() as f64 / total_time.as_nanos() as f64,
+            elapsed3.as_nanos() as f64 / total_time.as_nanos() as f64,
+            elapsed4.as_nanos() as f64 / total_time.as_nanos() as f64
+        );
+    }
+
+    true
 }
@@ -0,0 +1,84 @@
+//! Entry points to prove and verify Fibonacci computations from JavaScript, exchanging JSON
+//! strings. The trace is given as the list of its values, in hexadecimal, and the proof
+//! options as the JSON serialization of [`ProofOptions`].
+
+use lambdaworks_math::field::element::FieldElement;
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use crate::{
+    examples::simple_fibonacci::{FibonacciAIR, FibonacciPublicInputs},
+    proof::{options::ProofOptions, stark::StarkProof},
+    prover::{IsStarkProver, Prover},
+    trace::TraceTable,
+    transcript::StoneProverTranscript,
+    verifier::{IsStarkVerifier, Verifier},
+    PrimeField,
+};
+
+/// A proof along with the public inputs it was produced for, as returned by [`prove_wasm`].
+#[derive(serde::Serialize, serde::Deserialize)]
+struct WasmProof {
+    public_inputs: FibonacciPublicInputs<PrimeField>,
+    proof: StarkProof<PrimeField, PrimeField>,
+}
+
+/// Proves the Fibonacci sequence `trace_json`, a JSON list of hexadecimal values whose length
+/// is a power of two, with the options `config_json`. The public inputs are its first two
+/// values. Returns the JSON of the proof and its public inputs, to be checked with
+/// [`verify_wasm`].
+#[wasm_bindgen]
+pub fn prove_wasm(trace_json: &str, config_json: &str) -> Result<String, String> {
+    let values: Vec<String> = serde_json::from_str(trace_json).map_err(|e| e.to_string())?;
+    let column = values
+        .iter()
+        .map(|value| FieldElement::<PrimeField>::from_hex(value))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("{:?}", e))?;
+    if column.len() < 2 {
+        return Err("the trace should have at least two values".to_string());
+    }
+    let proof_options: ProofOptions =
+        serde_json::from_str(config_json).map_err(|e| e.to_string())?;
+
+    let public_inputs = FibonacciPublicInputs {
+        a0: column[0],
+        a1: column[1],
+    };
+    let trace = TraceTable::from_columns(vec![column], 1, 1);
+    let proof = Prover::<FibonacciAIR<PrimeField>>::prove(
+        &trace,
+        &public_inputs,
+        &proof_options,
+        StoneProverTranscript::new(&[]),
+    )
+    .map_err(|e| format!("{:?}", e))?;
+
+    serde_json::to_string(&WasmProof {
+        public_inputs,
+        proof,
+    })
+    .map_err(|e| e.to_string())
+}
+
+/// Verifies a proof returned by [`prove_wasm`] with the options `config_json`. Returns false
+/// if the proof or the options cannot be read.
+#[wasm_bindgen]
+pub fn verify_wasm(proof_json: &str, config_json: &str) -> bool {
+    let Ok(WasmProof {
+        public_inputs,
+        proof,
+    }) = serde_json::from_str(proof_json)
+    else {
+        return false;
+    };
+    let Ok(proof_options) = serde_json::from_str::<ProofOptions>(config_json) else {
+        return false;
+    };
+
+    Verifier::<FibonacciAIR<PrimeField>>::verify(
+        &proof,
+        &public_inputs,
+        &proof_options,
+        StoneProverTranscript::new(&[]),
+    )
+}
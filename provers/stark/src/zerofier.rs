@@ -0,0 +1,154 @@
+//! The vanishing polynomials the constraints are divided by.
+//!
+//! A transition constraint that holds on the rows `offset + k * period` of a trace of length
+//! `N` vanishes on the points `g^offset * (g^period)^k`, which form a coset of the subgroup of
+//! order `N / period`, so it is divisible by `X^(N / period) - g^(offset * N / period)`. A
+//! boundary constraint on row `i` vanishes on `g^i`, so it is divisible by `X - g^i`. Both are
+//! evaluated without building their coefficients, which for the former would be a vector of
+//! the size of the subgroup.
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use lambdaworks_math::{
+    field::{
+        element::FieldElement,
+        errors::FieldError,
+        traits::{IsField, IsSubFieldOf},
+    },
+    polynomial::Polynomial,
+};
+
+/// The polynomial `Xⁿ - cⁿ`, which vanishes exactly on the coset `c⟨ω⟩` of the subgroup of
+/// order `n`, where `ω` is a primitive `n`-th root of unity.
+#[derive(Clone, Debug)]
+pub struct VanishingPolynomial<F: IsField> {
+    domain_size: usize,
+    /// `cⁿ`, the independent term with its sign changed.
+    offset_power: FieldElement<F>,
+}
+
+/// Returns `Xⁿ - 1`, the vanishing polynomial of the subgroup of order `n = domain_size`.
+pub fn vanishing_polynomial<F: IsField>(domain_size: usize) -> VanishingPolynomial<F> {
+    VanishingPolynomial {
+        domain_size,
+        offset_power: FieldElement::one(),
+    }
+}
+
+/// Returns `Xⁿ - offsetⁿ`, the vanishing polynomial of the coset `offset * H` of the subgroup
+/// `H` of order `n = domain_size`.
+pub fn coset_vanishing_polynomial<F: IsField>(
+    domain_size: usize,
+    offset: &FieldElement<F>,
+) -> VanishingPolynomial<F> {
+    VanishingPolynomial {
+        domain_size,
+        offset_power: offset.pow(domain_size),
+    }
+}
+
+impl<F: IsField> VanishingPolynomial<F> {
+    /// Evaluates the polynomial at `x`, which can be in an extension of `F`, with a single
+    /// exponentiation.
+    pub fn evaluate<E: IsField>(&self, x: &FieldElement<E>) -> FieldElement<E>
+    where
+        F: IsSubFieldOf<E>,
+    {
+        -&self.offset_power + x.pow(self.domain_size)
+    }
+
+    /// Returns the inverses of the evaluations of the polynomial at `points`, inverted all at
+    /// once. Fails if the polynomial vanishes at one of the points.
+    pub fn inverse_evaluations(
+        &self,
+        points: &[FieldElement<F>],
+    ) -> Result<Vec<FieldElement<F>>, FieldError> {
+        let mut evaluations: Vec<_> = points.iter().map(|x| self.evaluate(x)).collect();
+        FieldElement::inplace_batch_inverse(&mut evaluations)?;
+        Ok(evaluations)
+    }
+}
+
+/// The polynomial `X - root`.
+#[derive(Clone, Debug)]
+pub struct LinearZerofier<F: IsField> {
+    root: FieldElement<F>,
+}
+
+/// Returns `X - root`, the zerofier of the single point `root`.
+pub fn linear_zerofier<F: IsField>(root: FieldElement<F>) -> LinearZerofier<F> {
+    LinearZerofier { root }
+}
+
+impl<F: IsField> LinearZerofier<F> {
+    /// Evaluates the polynomial at `x`, which can be in an extension of `F`.
+    pub fn evaluate<E: IsField>(&self, x: &FieldElement<E>) -> FieldElement<E>
+    where
+        F: IsSubFieldOf<E>,
+    {
+        -&self.root + x
+    }
+
+    /// Returns the inverses of the evaluations of the polynomial at `points`, inverted all at
+    /// once. Fails if one of the points is the root.
+    pub fn inverse_evaluations(
+        &self,
+        points: &[FieldElement<F>],
+    ) -> Result<Vec<FieldElement<F>>, FieldError> {
+        let mut evaluations: Vec<_> = points.iter().map(|x| self.evaluate(x)).collect();
+        FieldElement::inplace_batch_inverse(&mut evaluations)?;
+        Ok(evaluations)
+    }
+
+    /// Returns the coefficients of the polynomial, `[-root, 1]`.
+    pub fn to_polynomial(&self) -> Polynomial<FieldElement<F>> {
+        Polynomial::new(&[-&self.root, FieldElement::one()])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lambdaworks_math::field::{
+        fields::u64_prime_field::{F17, FE17},
+        traits::IsFFTField,
+    };
+
+    #[test]
+    fn vanishing_polynomial_is_zero_exactly_on_the_roots_of_unity() {
+        let omega = F17::get_primitive_root_of_unity(3).unwrap();
+        let roots_of_unity: Vec<FE17> = (0..8u64).map(|i| omega.pow(i)).collect();
+        let zerofier = vanishing_polynomial::<F17>(8);
+
+        for x in (0..17u64).map(FE17::from) {
+            assert_eq!(
+                zerofier.evaluate(&x) == FE17::zero(),
+                roots_of_unity.contains(&x)
+            );
+        }
+    }
+
+    #[test]
+    fn coset_vanishing_polynomial_is_zero_exactly_on_the_coset() {
+        let omega = F17::get_primitive_root_of_unity(2).unwrap();
+        let offset = FE17::from(3);
+        let coset: Vec<FE17> = (0..4u64).map(|i| offset * omega.pow(i)).collect();
+        let zerofier = coset_vanishing_polynomial(4, &offset);
+
+        for x in (0..17u64).map(FE17::from) {
+            assert_eq!(zerofier.evaluate(&x) == FE17::zero(), coset.contains(&x));
+        }
+    }
+
+    #[test]
+    fn inverse_evaluations_fail_on_a_root() {
+        let zerofier = linear_zerofier(FE17::from(5));
+        let points = [2u64, 3, 4].map(FE17::from);
+
+        let inverses = zerofier.inverse_evaluations(&points).unwrap();
+        for (x, inverse) in points.iter().zip(inverses) {
+            assert_eq!(zerofier.to_polynomial().evaluate(x) * inverse, FE17::one());
+        }
+        assert!(zerofier.inverse_evaluations(&[FE17::from(5)]).is_err());
+    }
+}
@@ -0,0 +1,220 @@
+//! Zero-knowledge masking of the trace and the composition polynomial.
+//!
+//! Without masking, the openings of a proof reveal evaluations of the trace polynomials at
+//! the queried points, which leak information about the witness. When
+//! `ProofOptions::zero_knowledge` is set, the prover replaces each trace polynomial `t` by
+//!
+//!   t'(X) = t(X) + (Xᴺ - 1) r(X),
+//!
+//! where `N` is the trace length and `r` is a random polynomial with `k` coefficients. Since
+//! `t'` coincides with `t` on the trace domain the constraints still hold, and this is the
+//! same as extending each column with `k` random rows before interpolating. Any `k`
+//! evaluations of `t'` outside the trace domain are independent and uniformly distributed,
+//! so `k` must be at least the number of evaluations of each trace polynomial that the proof
+//! reveals: two per FRI query, at 𝜐 and -𝜐, and one per row of the out of domain frame.
+//! See [`num_trace_masking_coefficients`].
+//!
+//! The prover also commits, together with the parts of the composition polynomial, a random
+//! polynomial of degree less than `N` that enters the DEEP composition polynomial with its
+//! own coefficient. This blinds the evaluations revealed by the inner layers of FRI.
+//!
+//! The masked trace polynomials have degree `N + k - 1`, so FRI is run with a degree bound of
+//! `2N`, folding once more than without masking, and the composition polynomial is split in
+//! more parts. Proving requires `k <= N`, and as always a blowup factor at least as large as
+//! the number of parts of the composition polynomial. Since the degree bound doubles, the
+//! effective blowup factor for the FRI soundness is half of `ProofOptions::blowup_factor`.
+//!
+//! The masking coefficients are sampled in the base field, which would not hide evaluations
+//! over a proper extension, so zero knowledge is only supported for AIRs whose
+//! `FieldExtension` is their `Field`.
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use lambdaworks_math::{
+    field::{
+        element::FieldElement,
+        traits::{IsField, IsSubFieldOf},
+    },
+    polynomial::Polynomial,
+};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+
+use crate::{prover::ProvingError, traits::AIR};
+
+/// Returns the number of random coefficients added to each trace polynomial in zero-knowledge
+/// mode, which is the number of evaluations of each trace polynomial revealed by a proof:
+/// `2 * fri_number_of_queries` openings plus the number of rows of the out of domain frame.
+pub fn num_trace_masking_coefficients<A: AIR>(air: &A) -> usize {
+    2 * air.options().fri_number_of_queries + air.context().transition_offsets.len()
+}
+
+/// Returns the number of parts the composition polynomial is split in, not counting the
+/// blinding polynomial committed with them in zero-knowledge mode.
+pub(crate) fn number_of_composition_parts<A: AIR>(air: &A) -> usize {
+    let trace_length = air.trace_length();
+    if !air.options().zero_knowledge {
+        return air.composition_poly_degree_bound() / trace_length;
+    }
+
+    // Masking increases the degree of the trace polynomials by `k`, and so the degree of
+    // the composition polynomial by at most `k` times the degree of the constraints.
+    let max_constraint_degree = air
+        .transition_constraints()
+        .iter()
+        .map(|constraint| constraint.degree())
+        .max()
+        .unwrap_or(1);
+    let degree_bound = air.composition_poly_degree_bound()
+        + max_constraint_degree * num_trace_masking_coefficients(air);
+    (degree_bound + trace_length - 1) / trace_length
+}
+
+/// Returns the evaluation of the composition polynomial at the out of domain point `z`,
+/// given the evaluations `Hᵢ(zᵈ)` of its `d` parts, as `H(z) = ∑ᵢ zⁱ Hᵢ(zᵈ)`. In zero-knowledge
+/// mode, the evaluation of the blinding polynomial follows the ones of the parts and is not
+/// part of the composition polynomial, so it is skipped.
+pub(crate) fn reconstruct_composition_poly_evaluation<A: AIR>(
+    air: &A,
+    parts_ood_evaluation: &[FieldElement<A::FieldExtension>],
+    z: &FieldElement<A::FieldExtension>,
+) -> FieldElement<A::FieldExtension> {
+    Polynomial::evaluate_from_parts(&parts_ood_evaluation[..number_of_composition_parts(air)], z)
+}
+
+/// Returns the degree of `E` as an extension of `F`, the number of coordinates of its elements.
+fn extension_degree<F: IsSubFieldOf<E>, E: IsField>() -> usize {
+    FieldElement::<E>::zero().to_subfield_vec::<F>().len()
+}
+
+/// Checks that the proof options of `air` allow masking its trace.
+pub(crate) fn check_zero_knowledge_parameters<A: AIR>(air: &A) -> Result<(), ProvingError> {
+    let degree = extension_degree::<A::Field, A::FieldExtension>();
+    if degree > 1 {
+        return Err(ProvingError::WrongParameter(format!(
+            "zero knowledge is not supported over an extension of degree {} of the field of the AIR",
+            degree
+        )));
+    }
+    let num_coefficients = num_trace_masking_coefficients(air);
+    if num_coefficients > air.trace_length() {
+        return Err(ProvingError::WrongParameter(format!(
+            "zero knowledge needs {} masking coefficients, more than the trace length {}",
+            num_coefficients,
+            air.trace_length()
+        )));
+    }
+    Ok(())
+}
+
+/// The source of randomness of the prover in zero-knowledge mode. It is a ChaCha20 generator,
+/// whose output for a given seed does not change across platforms and versions, so that a
+/// seed always reproduces the same proof.
+pub struct ZkMasking {
+    rng: ChaCha20Rng,
+    num_trace_coefficients: usize,
+}
+
+impl ZkMasking {
+    /// Creates a masking whose randomness is derived from `seed`, adding
+    /// `num_trace_coefficients` random coefficients to each trace polynomial.
+    pub fn new(seed: [u8; 32], num_trace_coefficients: usize) -> Self {
+        Self {
+            rng: ChaCha20Rng::from_seed(seed),
+            num_trace_coefficients,
+        }
+    }
+
+    /// Returns a random field element, obtained by reducing 512 random bits.
+    pub fn random_field_element<E: IsField>(&mut self) -> FieldElement<E> {
+        let two_to_64 = FieldElement::<E>::from(u64::MAX) + FieldElement::<E>::one();
+        (0..8).fold(FieldElement::zero(), |acc, _| {
+            acc * &two_to_64 + FieldElement::from(self.rng.gen::<u64>())
+        })
+    }
+
+    /// Returns a polynomial with `num_coefficients` random coefficients.
+    pub fn random_polynomial<E: IsField>(
+        &mut self,
+        num_coefficients: usize,
+    ) -> Polynomial<FieldElement<E>> {
+        let coefficients: Vec<_> = (0..num_coefficients)
+            .map(|_| self.random_field_element())
+            .collect();
+        Polynomial::new(&coefficients)
+    }
+
+    /// Returns `poly + (Xᴺ - 1) r`, where `N` is `trace_length` and `r` is a random polynomial.
+    /// The result coincides with `poly` on the trace domain.
+    pub fn mask_trace_poly<E: IsField>(
+        &mut self,
+        poly: &Polynomial<FieldElement<E>>,
+        trace_length: usize,
+    ) -> Polynomial<FieldElement<E>> {
+        let num_coefficients = self.num_trace_coefficients;
+        let mask = self.random_polynomial::<E>(num_coefficients);
+
+        let mut coefficients = vec![FieldElement::zero(); trace_length + num_coefficients];
+        for (i, coefficient) in mask.coefficients().iter().enumerate() {
+            coefficients[i] = &coefficients[i] - coefficient;
+            coefficients[i + trace_length] = &coefficients[i + trace_length] + coefficient;
+        }
+
+        poly.clone() + Polynomial::new(&coefficients)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lambdaworks_math::field::{
+        fields::fft_friendly::{
+            babybear::Babybear31PrimeField, quadratic_babybear::QuadraticBabybearField,
+            stark_252_prime_field::Stark252PrimeField,
+        },
+        traits::IsFFTField,
+    };
+
+    type FE = FieldElement<Stark252PrimeField>;
+
+    #[test]
+    fn masked_trace_poly_coincides_with_trace_poly_on_trace_domain() {
+        let poly = Polynomial::new(&[FE::from(1u64), FE::from(2u64), FE::from(3u64)]);
+        let mut masking = ZkMasking::new([7; 32], 5);
+        let masked_poly = masking.mask_trace_poly(&poly, 8);
+
+        assert_eq!(masked_poly.degree(), 8 + 5 - 1);
+
+        let root = Stark252PrimeField::get_primitive_root_of_unity(3).unwrap();
+        for i in 0..8u64 {
+            let x = root.pow(i);
+            assert_eq!(masked_poly.evaluate(&x), poly.evaluate(&x));
+        }
+        let x = FE::from(3u64);
+        assert_ne!(masked_poly.evaluate(&x), poly.evaluate(&x));
+    }
+
+    #[test]
+    fn extension_degree_counts_the_coordinates_of_the_extension() {
+        assert_eq!(
+            extension_degree::<Stark252PrimeField, Stark252PrimeField>(),
+            1
+        );
+        assert_eq!(
+            extension_degree::<Babybear31PrimeField, QuadraticBabybearField>(),
+            2
+        );
+    }
+
+    #[test]
+    fn masking_is_determined_by_the_seed() {
+        let poly = Polynomial::new(&[FE::from(1u64), FE::from(2u64), FE::from(3u64)]);
+
+        let masked_poly_1 = ZkMasking::new([7; 32], 5).mask_trace_poly(&poly, 8);
+        let masked_poly_2 = ZkMasking::new([7; 32], 5).mask_trace_poly(&poly, 8);
+        let masked_poly_3 = ZkMasking::new([8; 32], 5).mask_trace_poly(&poly, 8);
+
+        assert_eq!(masked_poly_1, masked_poly_2);
+        assert_ne!(masked_poly_1, masked_poly_3);
+    }
+}
@@ -0,0 +1,77 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use lambdaworks_math::field::{
+    element::FieldElement, fields::fft_friendly::stark_252_prime_field::Stark252PrimeField,
+};
+use stark_platinum_prover::{
+    examples::simple_fibonacci::{fibonacci_trace, FibonacciAIR, FibonacciPublicInputs},
+    proof::options::ProofOptions,
+    prover::{IsStarkProver, Prover},
+    transcript::StoneProverTranscript,
+};
+
+/// Keeps track of the bytes currently allocated, and of the peak since it was last reset.
+struct CountingAllocator;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            let allocated = ALLOCATED.fetch_add(layout.size(), Ordering::SeqCst) + layout.size();
+            PEAK.fetch_max(allocated, Ordering::SeqCst);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        ALLOCATED.fetch_sub(layout.size(), Ordering::SeqCst);
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+type F = Stark252PrimeField;
+type FE = FieldElement<F>;
+
+#[test]
+#[cfg_attr(
+    debug_assertions,
+    ignore = "debug builds of the prover also validate the trace"
+)]
+fn memory_estimate_is_close_to_the_peak_memory_of_proving() {
+    let trace_length = 1 << 12;
+    let trace = fibonacci_trace([FE::one(), FE::one()], trace_length);
+    let pub_inputs = FibonacciPublicInputs {
+        a0: FE::one(),
+        a1: FE::one(),
+    };
+    let proof_options = ProofOptions::default_test_options();
+
+    let baseline = ALLOCATED.load(Ordering::SeqCst);
+    PEAK.store(baseline, Ordering::SeqCst);
+
+    let proof = Prover::<FibonacciAIR<F>>::prove(
+        &trace,
+        &pub_inputs,
+        &proof_options,
+        StoneProverTranscript::new(&[]),
+    )
+    .unwrap();
+    drop(proof);
+
+    let peak = (PEAK.load(Ordering::SeqCst) - baseline) as f64;
+    let estimate = proof_options
+        .estimate_memory::<F>(trace_length, trace.n_cols())
+        .total() as f64;
+
+    assert!(
+        (estimate - peak).abs() <= 0.3 * peak,
+        "estimated {estimate} bytes, but the peak was {peak} bytes"
+    );
+}
@@ -0,0 +1,34 @@
+use wasm_bindgen_test::wasm_bindgen_test_configure;
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[cfg(feature = "wasm")]
+use stark_platinum_prover::{
+    proof::options::ProofOptions,
+    wasm::{prove_wasm, verify_wasm},
+};
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen_test::*;
+
+#[cfg(feature = "wasm")]
+#[wasm_bindgen_test]
+#[test]
+fn test_prove_fibonacci_wasm_verifies_in_wasm() {
+    let mut sequence = vec![1u64, 1];
+    for i in 2..16 {
+        sequence.push(sequence[i - 1] + sequence[i - 2]);
+    }
+    let trace_json = serde_json::to_string(
+        &sequence
+            .iter()
+            .map(|value| format!("{:x}", value))
+            .collect::<Vec<_>>(),
+    )
+    .unwrap();
+    let config_json = serde_json::to_string(&ProofOptions::default_test_options()).unwrap();
+
+    let proof_json = prove_wasm(&trace_json, &config_json).unwrap();
+
+    assert!(verify_wasm(&proof_json, &config_json));
+    assert!(!verify_wasm(&proof_json[1..], &config_json));
+}